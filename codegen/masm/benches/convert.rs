@@ -0,0 +1,94 @@
+//! Compares the serial and parallel conversion of a program from HIR to MASM.
+//!
+//! The program is synthetic: it consists of a configurable number of modules, each containing
+//! the same handful of functions, which makes the amount of work per module uniform. The
+//! parallel conversion is expected to scale with the number of modules, up to the number of
+//! available threads, so the speedup over the serial conversion for a given number of modules can
+//! be read off by comparing the `serial/N` and `parallel/N` results.
+//!
+//! Run with `cargo bench -p miden-codegen-masm --bench convert`.
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use miden_codegen_masm::ConvertHirToMasm;
+use miden_hir::{
+    pass::{AnalysisManager, ConversionPass, ModuleRewritePassAdapter, RewritePass, RewriteSet},
+    testing::{self, TestContext},
+    Program, ProgramBuilder,
+};
+use miden_hir_transform as transforms;
+
+/// Build a program of `num_modules` modules, with the rewrites which are a prerequisite of the
+/// conversion to MASM already applied, so that only the conversion itself is measured
+fn synthetic_program(context: &TestContext, num_modules: usize) -> Box<Program> {
+    let mut builder = ProgramBuilder::new(&context.session.diagnostics);
+    for i in 0..num_modules {
+        let mut mb = builder.module(format!("bench{i}").as_str());
+        testing::fib1(mb.as_mut(), context);
+        testing::sum_matrix(mb.as_mut(), context);
+        mb.build()
+            .expect("unexpected error constructing benchmark module");
+    }
+    let mut program = builder.link().expect("failed to link program");
+
+    let mut rewrites = RewriteSet::default();
+    rewrites.push(ModuleRewritePassAdapter::new(transforms::LowerSwitch));
+    rewrites.push(ModuleRewritePassAdapter::new(
+        transforms::SplitCriticalEdges,
+    ));
+    rewrites.push(ModuleRewritePassAdapter::new(transforms::Treeify));
+    rewrites.push(ModuleRewritePassAdapter::new(transforms::InlineBlocks));
+
+    let mut analyses = AnalysisManager::new();
+    let modules = program.modules_mut().take();
+    for mut module in modules.into_iter() {
+        rewrites
+            .apply(&mut module, &mut analyses, &context.session)
+            .expect("failed to apply rewrites");
+        program.modules_mut().insert(module);
+    }
+    program
+}
+
+fn convert(c: &mut Criterion) {
+    let context = TestContext::default();
+    let mut group = c.benchmark_group("convert");
+    for num_modules in [1, 8, 64] {
+        group.bench_with_input(
+            BenchmarkId::new("serial", num_modules),
+            &num_modules,
+            |b, &num_modules| {
+                b.iter_batched(
+                    || synthetic_program(&context, num_modules),
+                    |program| {
+                        ConvertHirToMasm::<Program>::default()
+                            .convert(program, &mut AnalysisManager::new(), &context.session)
+                            .expect("conversion failed")
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parallel", num_modules),
+            &num_modules,
+            |b, &num_modules| {
+                b.iter_batched(
+                    || synthetic_program(&context, num_modules),
+                    |program| {
+                        ConvertHirToMasm::<Program>::default()
+                            .convert_parallel(
+                                program,
+                                &mut AnalysisManager::new(),
+                                &context.session,
+                            )
+                            .expect("conversion failed")
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, convert);
+criterion_main!(benches);
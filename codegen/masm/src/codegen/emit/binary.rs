@@ -167,6 +167,7 @@ impl<'a> OpEmitter<'a> {
             Type::U32 | Type::U16 | Type::U8 | Type::I1 => {
                 self.emit(Op::U32Gt);
             }
+            Type::U64 => self.gt_u64(),
             Type::I32 => self.emit(Op::Exec("intrinsics::i32::is_gt".parse().unwrap())),
             ty => unimplemented!("gt is not yet implemented for {ty}"),
         }
@@ -184,6 +185,10 @@ impl<'a> OpEmitter<'a> {
             Type::U32 | Type::U16 | Type::U8 | Type::I1 => {
                 self.emit_all(&[Op::PushU32(imm.as_u32().unwrap()), Op::U32Gt]);
             }
+            Type::U64 => {
+                self.push_immediate(imm);
+                self.gt_u64();
+            }
             Type::I32 => {
                 self.emit_all(&[
                     Op::PushU32(imm.as_i32().unwrap() as u32),
@@ -207,6 +212,7 @@ impl<'a> OpEmitter<'a> {
             Type::U32 | Type::U16 | Type::U8 | Type::I1 => {
                 self.emit(Op::U32Gte);
             }
+            Type::U64 => self.gte_u64(),
             Type::I32 => self.emit(Op::Exec("intrinsics::i32::is_gte".parse().unwrap())),
             ty => unimplemented!("gte is not yet implemented for {ty}"),
         }
@@ -224,6 +230,10 @@ impl<'a> OpEmitter<'a> {
             Type::U32 | Type::U16 | Type::U8 | Type::I1 => {
                 self.emit_all(&[Op::PushU32(imm.as_u32().unwrap()), Op::U32Gte]);
             }
+            Type::U64 => {
+                self.push_immediate(imm);
+                self.gte_u64();
+            }
             Type::I32 => {
                 self.emit_all(&[
                     Op::PushU32(imm.as_i32().unwrap() as u32),
@@ -247,6 +257,7 @@ impl<'a> OpEmitter<'a> {
             Type::U32 | Type::U16 | Type::U8 | Type::I1 => {
                 self.emit(Op::U32Lt);
             }
+            Type::U64 => self.lt_u64(),
             Type::I32 => self.emit(Op::Exec("intrinsics::i32::is_lt".parse().unwrap())),
             ty => unimplemented!("lt is not yet implemented for {ty}"),
         }
@@ -264,6 +275,10 @@ impl<'a> OpEmitter<'a> {
             Type::U32 | Type::U16 | Type::U8 | Type::I1 => {
                 self.emit_all(&[Op::PushU32(imm.as_u32().unwrap()), Op::U32Lt]);
             }
+            Type::U64 => {
+                self.push_immediate(imm);
+                self.lt_u64();
+            }
             Type::I32 => {
                 self.emit_all(&[
                     Op::PushU32(imm.as_i32().unwrap() as u32),
@@ -287,6 +302,7 @@ impl<'a> OpEmitter<'a> {
             Type::U32 | Type::U16 | Type::U8 | Type::I1 => {
                 self.emit(Op::U32Lte);
             }
+            Type::U64 => self.lte_u64(),
             Type::I32 => self.emit(Op::Exec("intrinsics::i32::is_lte".parse().unwrap())),
             ty => unimplemented!("lte is not yet implemented for {ty}"),
         }
@@ -304,6 +320,10 @@ impl<'a> OpEmitter<'a> {
             Type::U32 | Type::U16 | Type::U8 | Type::I1 => {
                 self.emit_all(&[Op::PushU32(imm.as_u32().unwrap()), Op::U32Lte]);
             }
+            Type::U64 => {
+                self.push_immediate(imm);
+                self.lte_u64();
+            }
             Type::I32 => {
                 self.emit_all(&[
                     Op::PushU32(imm.as_i32().unwrap() as u32),
@@ -327,6 +347,9 @@ impl<'a> OpEmitter<'a> {
             Type::U64 => {
                 self.add_u64(overflow);
             }
+            Type::I64 => {
+                self.add_i64(overflow);
+            }
             Type::U32 => {
                 self.add_u32(overflow);
             }
@@ -357,6 +380,10 @@ impl<'a> OpEmitter<'a> {
                 self.push_immediate(imm);
                 self.add_u64(overflow);
             }
+            Type::I64 => {
+                self.push_immediate(imm);
+                self.add_i64(overflow);
+            }
             Type::U32 => {
                 self.add_imm_u32(imm.as_u32().unwrap(), overflow);
             }
@@ -386,6 +413,9 @@ impl<'a> OpEmitter<'a> {
             Type::U64 => {
                 self.sub_u64(overflow);
             }
+            Type::I64 => {
+                self.sub_i64(overflow);
+            }
             Type::U32 => {
                 self.sub_u32(overflow);
             }
@@ -415,6 +445,10 @@ impl<'a> OpEmitter<'a> {
                 self.push_immediate(imm);
                 self.sub_u64(overflow);
             }
+            Type::I64 => {
+                self.push_immediate(imm);
+                self.sub_i64(overflow);
+            }
             Type::U32 => {
                 self.sub_imm_u32(imm.as_u32().unwrap(), overflow);
             }
@@ -454,6 +488,7 @@ impl<'a> OpEmitter<'a> {
                 todo!()
             }
             Type::U64 => self.mul_u64(overflow),
+            Type::I64 => self.mul_i64(overflow),
             Type::Felt => {
                 assert_matches!(
                     overflow,
@@ -487,6 +522,10 @@ impl<'a> OpEmitter<'a> {
                 self.push_immediate(imm);
                 self.mul_u64(overflow);
             }
+            Type::I64 => {
+                self.push_immediate(imm);
+                self.mul_i64(overflow);
+            }
             Type::Felt => {
                 assert_matches!(
                     overflow,
@@ -1027,7 +1066,12 @@ impl<'a> OpEmitter<'a> {
         let ty = lhs.ty();
         assert_eq!(ty, rhs.ty(), "expected shl operands to be the same type");
         match &ty {
-            Type::U64 => self.shl_u64(),
+            Type::U64 => {
+                // The shift amount is expected to be a u32, so drop the high limb, which is zero
+                // for any valid shift amount
+                self.trunc_int64(32);
+                self.shl_u64();
+            }
             Type::U32 | Type::I32 => self.shl_u32(),
             ty @ (Type::U16 | Type::U8) => {
                 self.shl_u32();
@@ -1047,11 +1091,9 @@ impl<'a> OpEmitter<'a> {
         assert_eq!(ty, imm.ty(), "expected shl operands to be the same type");
         match &ty {
             Type::U64 => {
-                assert!(
-                    imm.as_u64().unwrap() < 64,
-                    "invalid shift value: must be < 64"
-                );
-                self.push_immediate(imm);
+                let shift = imm.as_u64().unwrap();
+                assert!(shift < 64, "invalid shift value: must be < 64, got {shift}");
+                self.push_u32(shift as u32);
                 self.shl_u64();
             }
             Type::U32 => self.shl_imm_u32(imm.as_u32().unwrap()),
@@ -1074,7 +1116,10 @@ impl<'a> OpEmitter<'a> {
         let ty = lhs.ty();
         assert_eq!(ty, rhs.ty(), "expected shr operands to be the same type");
         match &ty {
-            Type::U64 => self.shr_u64(),
+            Type::U64 => {
+                self.trunc_int64(32);
+                self.shr_u64();
+            }
             Type::U32 | Type::U16 | Type::U8 => self.shr_u32(),
             Type::I32 => self.shr_i32(),
             ty if !ty.is_integer() => {
@@ -1093,7 +1138,7 @@ impl<'a> OpEmitter<'a> {
             Type::U64 => {
                 let shift = imm.as_u64().unwrap();
                 assert!(shift < 64, "invalid shift value: must be < 64, got {shift}");
-                self.push_immediate(imm);
+                self.push_u32(shift as u32);
                 self.shr_u64();
             }
             Type::U32 | Type::U16 | Type::U8 => self.shr_imm_u32(imm.as_u32().unwrap()),
@@ -1112,7 +1157,10 @@ impl<'a> OpEmitter<'a> {
         let ty = lhs.ty();
         assert_eq!(ty, rhs.ty(), "expected rotl operands to be the same type");
         match &ty {
-            Type::U64 => self.rotl_u64(),
+            Type::U64 => {
+                self.trunc_int64(32);
+                self.rotl_u64();
+            }
             Type::U32 => self.rotl_u32(),
             ty if !ty.is_integer() => {
                 panic!("invalid binary operand: rotl expects integer operands, got {ty}")
@@ -1128,7 +1176,7 @@ impl<'a> OpEmitter<'a> {
         assert_eq!(ty, imm.ty(), "expected rotl operands to be the same type");
         match &ty {
             Type::U64 => {
-                self.push_immediate(imm);
+                self.push_u32(imm.as_u64().unwrap() as u32);
                 self.rotl_u64();
             }
             Type::U32 => self.rotl_imm_u32(imm.as_u32().unwrap()),
@@ -1146,7 +1194,10 @@ impl<'a> OpEmitter<'a> {
         let ty = lhs.ty();
         assert_eq!(ty, rhs.ty(), "expected rotr operands to be the same type");
         match &ty {
-            Type::U64 => self.rotr_u64(),
+            Type::U64 => {
+                self.trunc_int64(32);
+                self.rotr_u64();
+            }
             Type::U32 => self.rotr_u32(),
             ty if !ty.is_integer() => {
                 panic!("invalid binary operand: rotr expects integer operands, got {ty}")
@@ -1162,7 +1213,7 @@ impl<'a> OpEmitter<'a> {
         assert_eq!(ty, imm.ty(), "expected rotr operands to be the same type");
         match &ty {
             Type::U64 => {
-                self.push_immediate(imm);
+                self.push_u32(imm.as_u64().unwrap() as u32);
                 self.rotr_u64();
             }
             Type::U32 => self.rotr_imm_u32(imm.as_u32().unwrap()),
@@ -382,7 +382,8 @@ impl<'a> OpEmitter<'a> {
     #[inline(always)]
     pub fn add_i32(&mut self, overflow: Overflow) {
         self.emit(match overflow {
-            Overflow::Unchecked | Overflow::Wrapping => Op::U32WrappingAdd,
+            Overflow::Unchecked => Op::Add,
+            Overflow::Wrapping => Op::U32WrappingAdd,
             Overflow::Checked => Op::Exec("intrinsics::i32::checked_add".parse().unwrap()),
             Overflow::Overflowing => Op::Exec("intrinsics::i32::overflowing_add".parse().unwrap()),
         })
@@ -1,4 +1,4 @@
-use miden_hir::{Felt, FieldElement, Overflow};
+use miden_hir::{assert_matches, Felt, FieldElement, Overflow};
 
 use crate::masm::{self as masm, Op};
 
@@ -319,7 +319,9 @@ impl<'a> OpEmitter<'a> {
     ///
     /// The semantics of this operation depend on the `overflow` setting:
     ///
-    /// * There is no unchecked variant for u64, so wrapping is used instead
+    /// * When unchecked, the carry out of the low limbs is propagated into the sum of the high
+    /// limbs, which is computed with field arithmetic, as for u32, so the result is only a valid
+    /// u64 value if the sum does not overflow.
     /// * When checked, both the operands and the result are validated to ensure
     /// they are valid u64 values.
     /// * Overflowing and wrapping variants follow the usual semantics, with the
@@ -334,7 +336,19 @@ impl<'a> OpEmitter<'a> {
             Overflow::Checked => {
                 self.emit(Op::Exec("std::math::u64::checked_add".parse().unwrap()));
             }
-            Overflow::Unchecked | Overflow::Wrapping => {
+            Overflow::Unchecked => {
+                // [b_hi, b_lo, a_hi, a_lo] => [carry, c_lo, b_hi, a_hi] => [c_hi, c_lo]
+                self.emit_all(&[
+                    Op::Swap(1),
+                    Op::Movup(3),
+                    Op::U32OverflowingAdd,
+                    Op::Movup(3),
+                    Op::Movup(3),
+                    Op::Add,
+                    Op::Add,
+                ]);
+            }
+            Overflow::Wrapping => {
                 self.emit(Op::Exec("std::math::u64::wrapping_add".parse().unwrap()));
             }
             Overflow::Overflowing => {
@@ -399,6 +413,52 @@ impl<'a> OpEmitter<'a> {
         }
     }
 
+    /// Pops two i64 values off the stack, `b` and `a`, and performs `a + b`.
+    ///
+    /// An i64 value is represented by the same two 32-bit limbs as the u64 value with the same
+    /// bits, so addition of i64 values is addition of u64 values: wrapping addition wraps around
+    /// modulo 2^64, rather than the field modulus, and unchecked addition is performed with field
+    /// arithmetic, as for i32, see [OpEmitter::add_u64].
+    ///
+    /// Detecting signed overflow is not yet supported, so only unchecked or wrapping semantics are.
+    #[inline]
+    pub fn add_i64(&mut self, overflow: Overflow) {
+        assert_matches!(
+            overflow,
+            Overflow::Unchecked | Overflow::Wrapping,
+            "only unchecked or wrapping semantics are supported for i64"
+        );
+        self.add_u64(overflow);
+    }
+
+    /// Pops two i64 values off the stack, `b` and `a`, and performs `a - b`.
+    ///
+    /// See [OpEmitter::add_i64] for details on the supported semantics.
+    #[inline]
+    pub fn sub_i64(&mut self, overflow: Overflow) {
+        assert_matches!(
+            overflow,
+            Overflow::Unchecked | Overflow::Wrapping,
+            "only unchecked or wrapping semantics are supported for i64"
+        );
+        self.sub_u64(overflow);
+    }
+
+    /// Pops two i64 values off the stack, `b` and `a`, and performs `a * b`.
+    ///
+    /// The low 64 bits of a product do not depend on the signedness of its operands, so this is
+    /// wrapping u64 multiplication. See [OpEmitter::add_i64] for details on the supported
+    /// semantics.
+    #[inline]
+    pub fn mul_i64(&mut self, overflow: Overflow) {
+        assert_matches!(
+            overflow,
+            Overflow::Unchecked | Overflow::Wrapping,
+            "only unchecked or wrapping semantics are supported for i64"
+        );
+        self.mul_u64(overflow);
+    }
+
     /// Pops two u64 values off the stack, `b` and `a`, and pushes the result of `a / b` on the stack.
     ///
     /// Both the operands and result are validated to ensure they are valid u64 values.
@@ -927,8 +927,19 @@ impl<'a> OpEmitter<'a> {
         todo!()
     }
 
-    fn store_word(&mut self, _ptr: Option<NativePtr>) {
-        todo!()
+    /// Stores a single 32-bit machine word, i.e. a single field element, not the Miden notion of a word
+    ///
+    /// Expects a native pointer triplet on the stack if an immediate address is not given.
+    fn store_word(&mut self, ptr: Option<NativePtr>) {
+        if let Some(imm) = ptr {
+            self.emit_all(&[
+                Op::PushU32(imm.offset as u32),
+                Op::PushU32(imm.index as u32),
+                Op::PushU32(imm.waddr),
+            ]);
+        }
+
+        self.emit(Op::Exec("intrinsics::mem::store_sw".parse().unwrap()));
     }
 
     fn store_felt(&mut self, _ptr: Option<NativePtr>) {
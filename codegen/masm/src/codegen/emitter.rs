@@ -539,6 +539,14 @@ impl<'b, 'f: 'b> BlockEmitter<'b, 'f> {
                 let dst_ty = emitter.value_type(result).clone();
                 emitter.cast(&dst_ty);
             }
+            // The representation of a value on the operand stack depends only on its size, so
+            // this is a no-op
+            hir::Opcode::Bitcast => {
+                let result_ty = emitter.value_type(result).clone();
+                let stack = emitter.stack_mut();
+                stack.pop().expect("operand stack is empty");
+                stack.push(result_ty);
+            }
             hir::Opcode::Trunc => {
                 let dst_ty = emitter.value_type(result).clone();
                 emitter.trunc(&dst_ty);
@@ -597,6 +605,32 @@ impl<'b, 'f: 'b> BlockEmitter<'b, 'f> {
     fn emit_binary_op(&mut self, inst_info: &InstInfo, op: &hir::BinaryOp) {
         use miden_hir::Overflow;
 
+        // When the operands are constants which were pushed on the stack just before this
+        // instruction, fold the operation if possible, or failing that, emit the immediate form
+        // of the operation, rather than materializing the right-hand operand on the stack.
+        let [rhs, lhs] = op.args;
+        if let Some(rhs_imm) = self.materialized_constant(rhs, 0) {
+            let folded = self
+                .materialized_constant(lhs, 1)
+                .and_then(|lhs_imm| fold_binary_op(op, lhs_imm, rhs_imm));
+            if let Some(result) = folded {
+                self.discard_materialized_constants(2);
+                let mut emitter = self.inst_emitter(inst_info.inst);
+                emitter.literal(result);
+                return;
+            }
+            if has_immediate_form(op.op) {
+                self.discard_materialized_constants(1);
+                let op = hir::BinaryOpImm {
+                    op: op.op,
+                    overflow: op.overflow,
+                    arg: lhs,
+                    imm: rhs_imm,
+                };
+                return self.emit_binary_imm_op(inst_info, &op);
+            }
+        }
+
         let mut emitter = self.inst_emitter(inst_info.inst);
         let overflow = op.overflow.unwrap_or(Overflow::Checked);
         match op.op {
@@ -632,6 +666,53 @@ impl<'b, 'f: 'b> BlockEmitter<'b, 'f> {
         }
     }
 
+    /// If `value` is the result of a constant instruction, is at `index` on the operand stack, and
+    /// was pushed there by the op emitted `index` ops before the end of the current block, i.e.
+    /// no other ops have been emitted since, return the constant.
+    ///
+    /// Only constants which are pushed with a single op are considered.
+    fn materialized_constant(&self, value: hir::Value, index: usize) -> Option<hir::Immediate> {
+        use miden_hir::Immediate;
+
+        let dfg = &self.function.f.dfg;
+        let imm = match dfg.value_data(value) {
+            hir::ValueData::Inst { inst, .. } => match dfg.inst(*inst) {
+                hir::Instruction::UnaryOpImm(hir::UnaryOpImm { imm, .. }) => *imm,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if self.stack.len() <= index || self.stack[index].as_value() != Some(value) {
+            return None;
+        }
+        let push = match imm {
+            Immediate::I1(i) => Op::PushU8(i as u8),
+            Immediate::I8(i) => Op::PushU8(i as u8),
+            Immediate::U8(i) => Op::PushU8(i),
+            Immediate::U16(i) => Op::PushU32(i as u32),
+            Immediate::I16(i) => Op::PushU32(i as u16 as u32),
+            Immediate::U32(i) => Op::PushU32(i),
+            Immediate::I32(i) => Op::PushU32(i as u32),
+            Immediate::Felt(i) => Op::Push(i),
+            _ => return None,
+        };
+        let ops = &self.function.f_prime.block(self.target).ops;
+        let pushed = ops.len() > index && ops[ops.len() - 1 - index] == push;
+        pushed.then_some(imm)
+    }
+
+    /// Remove the last `n` ops of the current block, along with the top `n` operands on the
+    /// stack, which must have been verified with [Self::materialized_constant] to be the pushes of
+    /// those operands.
+    fn discard_materialized_constants(&mut self, n: usize) {
+        let block = self.function.f_prime.block_mut(self.target);
+        let len = block.ops.len();
+        block.ops.truncate(len - n);
+        for _ in 0..n {
+            self.stack.pop().expect("operand stack is empty");
+        }
+    }
+
     fn emit_test_op(&mut self, _inst_info: &InstInfo, op: &hir::Test) {
         unimplemented!("unrecognized test opcode: '{}'", &op.op);
     }
@@ -1110,3 +1191,56 @@ impl<'b, 'f: 'b> BlockEmitter<'b, 'f> {
         OpEmitter::new(self.function.f_prime, self.target, &mut self.stack)
     }
 }
+
+/// Returns true if binary operations with opcode `op` can be emitted with an immediate right-hand
+/// operand of the same type as the left-hand operand
+fn has_immediate_form(op: hir::Opcode) -> bool {
+    matches!(
+        op,
+        hir::Opcode::Eq
+            | hir::Opcode::Neq
+            | hir::Opcode::Gt
+            | hir::Opcode::Gte
+            | hir::Opcode::Lt
+            | hir::Opcode::Lte
+            | hir::Opcode::Add
+            | hir::Opcode::Sub
+            | hir::Opcode::Mul
+            | hir::Opcode::Band
+            | hir::Opcode::Bor
+            | hir::Opcode::Bxor
+    )
+}
+
+/// Evaluate `lhs <op> rhs` at compile-time, for 32-bit integer operands, when the result does not
+/// depend on overflow checks
+fn fold_binary_op(
+    op: &hir::BinaryOp,
+    lhs: hir::Immediate,
+    rhs: hir::Immediate,
+) -> Option<hir::Immediate> {
+    use miden_hir::{Immediate, Opcode, Overflow};
+
+    let wrapping = op.overflow == Some(Overflow::Wrapping);
+    match (lhs, rhs) {
+        (Immediate::I32(a), Immediate::I32(b)) => Some(Immediate::I32(match op.op {
+            Opcode::Add if wrapping => a.wrapping_add(b),
+            Opcode::Sub if wrapping => a.wrapping_sub(b),
+            Opcode::Mul if wrapping => a.wrapping_mul(b),
+            Opcode::Band => a & b,
+            Opcode::Bor => a | b,
+            Opcode::Bxor => a ^ b,
+            _ => return None,
+        })),
+        (Immediate::U32(a), Immediate::U32(b)) => Some(Immediate::U32(match op.op {
+            Opcode::Add if wrapping => a.wrapping_add(b),
+            Opcode::Sub if wrapping => a.wrapping_sub(b),
+            Opcode::Mul if wrapping => a.wrapping_mul(b),
+            Opcode::Band => a & b,
+            Opcode::Bor => a | b,
+            Opcode::Bxor => a ^ b,
+            _ => return None,
+        })),
+        _ => None,
+    }
+}
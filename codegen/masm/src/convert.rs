@@ -1,10 +1,14 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, Context};
+use miden_diagnostics::Severity;
 use miden_hir::{
     self as hir,
-    pass::{AnalysisManager, ConversionPass, ConversionResult},
-    ConversionPassRegistration, PassInfo,
+    pass::{AnalysisManager, ConversionError, ConversionPass, ConversionResult},
+    symbols, ConversionPassRegistration, PassInfo,
 };
 use miden_hir_analysis as analysis;
-use midenc_session::Session;
+use midenc_session::{ProcedureOrder, Session};
 
 use crate::{
     codegen::{FunctionEmitter, OperandStack, Scheduler, TypedValue},
@@ -23,6 +27,7 @@ type ModuleGlobalVariableAnalysis = analysis::GlobalVariableAnalysis<hir::Module
 /// * If converting multiple modules, they must be linked into a [Program], in order to
 ///   ensure that there are no undefined symbols, and that the placement of global variables
 ///   in linear memory has been fixed.
+/// * There are no `switch` instructions, or the [LowerSwitch] rewrite has been applied.
 /// * There are no critical edges in the control flow graph, or the [SplitCriticalEdges]
 ///   rewrite has been applied.
 /// * The control flow graph is a tree, with the exception of loop header blocks. This
@@ -67,32 +72,153 @@ impl ConversionPass for ConvertHirToMasm<hir::Program> {
             let mut convert_to_masm = ConvertHirToMasm::<hir::Module>::default();
             let masm_module = convert_to_masm.convert(module, analyses, session)?;
 
-            // If this module makes use of any intrinsics modules, and those modules are not
-            // already present, add them to the program.
-            for import in masm_module
-                .imports
-                .iter()
-                .filter(|import| import.name.as_str().starts_with("intrinsics::"))
-            {
-                if masm_program.contains(import.name) {
-                    continue;
-                }
-                match masm::intrinsics::load(import.name.as_str(), &session.codemap) {
-                    Some(loaded) => {
-                        masm_program.insert(Box::new(loaded));
-                    }
-                    None => unimplemented!("unrecognized intrinsic module: '{}'", &import.name),
-                }
-            }
+            insert_module(&mut masm_program, masm_module, session)?;
+        }
+
+        Ok(masm_program)
+    }
+}
 
-            // Add to the final Miden Assembly program
-            masm_program.insert(masm_module);
+impl ConvertHirToMasm<hir::Program> {
+    /// Like [ConversionPass::convert], but the modules of `program` are converted in parallel,
+    /// split into contiguous chunks, one per available thread.
+    ///
+    /// Each thread has its own [AnalysisManager], so analyses are not shared between modules,
+    /// with the exception of the program-wide global variable layout. The resulting program is
+    /// identical to the one produced by [ConversionPass::convert].
+    pub fn convert_parallel(
+        &mut self,
+        mut program: Box<hir::Program>,
+        analyses: &mut AnalysisManager,
+        session: &Session,
+    ) -> ConversionResult<Box<masm::Program>> {
+        let mut masm_program = Box::new(masm::Program::from(program.as_ref()));
+
+        // Remove the set of modules to compile from the program
+        let modules = program.modules_mut().take().into_iter().collect::<Vec<_>>();
+
+        // The global variable layout is computed up front, as it must agree across all modules
+        let globals = analyses
+            .get_or_compute::<ProgramGlobalVariableAnalysis>(&program, session)?
+            .layout()
+            .clone();
+
+        let threads = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .clamp(1, modules.len().max(1));
+        let chunk_size = (modules.len() + threads - 1) / threads;
+        let mut chunks = (0..threads).map(|_| vec![]).collect::<Vec<_>>();
+        for (i, module) in modules.into_iter().enumerate() {
+            chunks[i / chunk_size].push(module);
+        }
+
+        let converted = std::thread::scope(|scope| {
+            let handles = chunks
+                .into_iter()
+                .map(|chunk| {
+                    let globals = globals.clone();
+                    scope.spawn(move || convert_modules(chunk, globals, session))
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // Modules are added in their original order, so that the output is deterministic
+        for masm_modules in converted {
+            for masm_module in masm_modules? {
+                insert_module(&mut masm_program, masm_module, session)?;
+            }
         }
 
         Ok(masm_program)
     }
 }
 
+/// Convert `modules` using a fresh [AnalysisManager], seeded with the program-wide layout of
+/// global variables, `globals`
+fn convert_modules(
+    modules: Vec<Box<hir::Module>>,
+    globals: analysis::GlobalVariableLayout,
+    session: &Session,
+) -> ConversionResult<Vec<Box<masm::Module>>> {
+    use miden_hir::ProgramAnalysisKey;
+
+    let mut analyses = AnalysisManager::new();
+    analyses.insert(
+        ProgramAnalysisKey,
+        ProgramGlobalVariableAnalysis::with_layout(globals),
+    );
+    modules
+        .into_iter()
+        .map(|module| {
+            let mut convert_to_masm = ConvertHirToMasm::<hir::Module>::default();
+            convert_to_masm.convert(module, &mut analyses, session)
+        })
+        .collect()
+}
+
+/// Add `masm_module` to the final Miden Assembly program, along with any intrinsics modules it
+/// makes use of which are not already present.
+///
+/// An imported module provided by the user, see [midenc_session::Options::intrinsic_modules], is
+/// linked in place of the built-in intrinsics module of the same name, if there is one.
+fn insert_module(
+    masm_program: &mut masm::Program,
+    masm_module: Box<masm::Module>,
+    session: &Session,
+) -> ConversionResult<()> {
+    for import in masm_module.imports.iter() {
+        let name = import.name.as_str();
+        if masm_program.contains(import.name) {
+            continue;
+        }
+        let loaded = masm::intrinsics::load_for_session(name, session)
+            .with_context(|| format!("failed to load the module imported as '{name}'"))?;
+        match loaded {
+            Some(loaded) => masm_program.insert(Box::new(loaded)),
+            None if name.starts_with("intrinsics::") => {
+                return Err(unrecognized_intrinsic_module(name, session))
+            }
+            // Any other module is expected to be provided by a library at assembly time
+            None => (),
+        }
+    }
+
+    masm_program.insert(masm_module);
+
+    Ok(())
+}
+
+/// Report an import of the intrinsics module `name`, which is neither defined by the compiler nor
+/// provided by the user, e.g. because the program was compiled against a different version of
+/// the intrinsics than the one known to the compiler.
+fn unrecognized_intrinsic_module(name: &str, session: &Session) -> ConversionError {
+    let known = masm::intrinsics::names()
+        .chain(session.options.intrinsic_modules.keys().map(String::as_str))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|name| format!("'{name}'"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    session
+        .diagnostics
+        .diagnostic(Severity::Error)
+        .with_message(format!("unrecognized intrinsic module '{name}'"))
+        .with_note(format!("the known intrinsic modules are: {known}"))
+        .with_note(format!(
+            "an implementation of this module can be provided with `--intrinsic {name}=PATH`"
+        ))
+        .emit();
+    anyhow!("unrecognized intrinsic module '{name}', expected one of: {known}").into()
+}
+
 impl ConversionPass for ConvertHirToMasm<hir::Module> {
     type From = Box<hir::Module>;
     type To = Box<masm::Module>;
@@ -119,7 +245,14 @@ impl ConversionPass for ConvertHirToMasm<hir::Module> {
         // the next function in the module. Once the end of the module
         // is reached, the cursor will point to the null object, and
         // `remove` will return `None`.
+        let mut functions = vec![];
         while let Some(function) = module.pop_front() {
+            functions.push(function);
+        }
+        if session.options.procedure_order == ProcedureOrder::Name {
+            functions.sort_by(|a, b| a.id.function.as_str().cmp(b.id.function.as_str()));
+        }
+        for function in functions {
             let mut convert_to_masm = ConvertHirToMasm::<&hir::Function>::default();
             let masm_function = convert_to_masm.convert(&function, analyses, session)?;
             masm_module.push_back(Box::new(masm_function));
@@ -142,6 +275,18 @@ impl<'a> ConversionPass for ConvertHirToMasm<&'a hir::Function> {
         use miden_hir::ProgramAnalysisKey;
 
         let mut f_prime = masm::Function::new(f.id, f.signature.clone());
+        // Carry over the attributes relating the function to the code it was translated from, as
+        // they are needed to produce debug info for the generated procedure
+        for name in [
+            symbols::WasmFuncIndex,
+            symbols::WasmOffset,
+            symbols::SourceFile,
+            symbols::SourceLine,
+        ] {
+            if let Some(value) = f.dfg.get_attribute(&name) {
+                f_prime.attrs.insert(name, *value);
+            }
+        }
 
         // Start at the function entry
         {
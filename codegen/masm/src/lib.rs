@@ -36,10 +36,56 @@ pub enum CompilerError {
     /// An error occurred during application of a conversion
     #[error(transparent)]
     Conversion(#[from] hir::pass::ConversionError),
+    /// A function was compiled on its own, as part of a module it does not belong to
+    #[error("expected {function} to be a function of module {module}")]
+    FunctionNotInModule {
+        function: hir::FunctionIdent,
+        module: hir::Ident,
+    },
 }
 
 pub type CompilerResult<T> = Result<T, CompilerError>;
 
+/// Compile a single [hir::Function] of `module` to a MASM procedure, without compiling the rest of
+/// the module, e.g. to compile functions on demand.
+///
+/// The addresses of the global variables referenced by `function` are taken from the
+/// program-wide global variable analysis, if `analyses` has one, otherwise they are computed from
+/// the global variable table of `module`. In either case, `function` must be one of the functions
+/// of `module`, otherwise [CompilerError::FunctionNotInModule] is returned.
+///
+/// The same invariants as [ConvertHirToMasm] apply to `function`, so the following rewrites must
+/// have been applied to it beforehand, in this order:
+///
+/// * [miden_hir_transform::LowerSwitch], unless it has no `switch` instructions
+/// * [miden_hir_transform::SplitCriticalEdges]
+/// * [miden_hir_transform::Treeify]
+///
+/// [miden_hir_transform::InlineBlocks] is optional, but is applied by [MasmCompiler].
+pub fn compile_function(
+    function: &hir::Function,
+    module: &hir::Module,
+    analyses: &mut hir::pass::AnalysisManager,
+    session: &Session,
+) -> CompilerResult<Function> {
+    use miden_hir::{pass::ConversionPass, ProgramAnalysisKey};
+    use miden_hir_analysis::GlobalVariableAnalysis;
+
+    if function.id.module != module.name {
+        return Err(CompilerError::FunctionNotInModule {
+            function: function.id,
+            module: module.name,
+        });
+    }
+
+    if !analyses.is_available::<GlobalVariableAnalysis<hir::Program>>(&ProgramAnalysisKey) {
+        analyses.get_or_compute::<GlobalVariableAnalysis<hir::Module>>(module, session)?;
+    }
+
+    let mut convert_to_masm = ConvertHirToMasm::<&hir::Function>::default();
+    Ok(convert_to_masm.convert(function, analyses, session)?)
+}
+
 /// [MasmCompiler] is a compiler from Miden IR to MASM IR, an intermediate representation
 /// of Miden Assembly which is used within the Miden compiler framework for various purposes,
 /// and can be emitted directly to textual Miden Assembly.
@@ -52,21 +98,31 @@ pub type CompilerResult<T> = Result<T, CompilerError>;
 pub struct MasmCompiler<'a> {
     session: &'a Session,
     analyses: hir::pass::AnalysisManager,
+    parallel: bool,
 }
 impl<'a> MasmCompiler<'a> {
     pub fn new(session: &'a Session) -> Self {
         Self {
             session,
             analyses: hir::pass::AnalysisManager::new(),
+            parallel: false,
         }
     }
 
+    /// Convert the modules of the program to MASM in parallel, see
+    /// [ConvertHirToMasm::convert_parallel]
+    pub fn with_parallel_conversion(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
     /// Compile an [hir::Program] that has been linked and is ready to be compiled.
     pub fn compile(&mut self, mut input: Box<hir::Program>) -> CompilerResult<Box<Program>> {
         use miden_hir::pass::{ConversionPass, ModuleRewritePassAdapter, RewritePass, RewriteSet};
         use miden_hir_transform as transforms;
 
         let mut rewrites = RewriteSet::default();
+        rewrites.push(ModuleRewritePassAdapter::new(transforms::LowerSwitch));
         rewrites.push(ModuleRewritePassAdapter::new(
             transforms::SplitCriticalEdges,
         ));
@@ -80,17 +136,22 @@ impl<'a> MasmCompiler<'a> {
         }
 
         let mut convert_to_masm = ConvertHirToMasm::<hir::Program>::default();
-        let mut program = convert_to_masm.convert(input, &mut self.analyses, self.session)?;
-
-        // Ensure intrinsics modules are linked
-        program.insert(Box::new(
-            intrinsics::load("intrinsics::mem", &self.session.codemap)
-                .expect("undefined intrinsics module"),
-        ));
-        program.insert(Box::new(
-            intrinsics::load("intrinsics::i32", &self.session.codemap)
-                .expect("undefined intrinsics module"),
-        ));
+        let mut program = if self.parallel {
+            convert_to_masm.convert_parallel(input, &mut self.analyses, self.session)?
+        } else {
+            convert_to_masm.convert(input, &mut self.analyses, self.session)?
+        };
+
+        // Ensure intrinsics modules are linked, unless already linked in by the conversion, e.g.
+        // as a user-provided replacement
+        for name in ["intrinsics::mem", "intrinsics::i32"] {
+            if program.get(name).is_none() {
+                program.insert(Box::new(
+                    intrinsics::load(name, &self.session.codemap)
+                        .expect("undefined intrinsics module"),
+                ));
+            }
+        }
 
         Ok(program)
     }
@@ -126,4 +187,19 @@ impl<'a> MasmCompiler<'a> {
 
         self.compile(program)
     }
+
+    /// Compile a [hir::Component] as a program.
+    ///
+    /// With [hir::ComponentEntrypoints::Exports], the result is a library in which each export of
+    /// the component is a public procedure the host can invoke, rather than a program with a single
+    /// entrypoint. The same invariants as [MasmCompiler::compile_modules] apply.
+    pub fn compile_component(
+        &mut self,
+        input: hir::Component,
+        entrypoints: hir::ComponentEntrypoints,
+    ) -> CompilerResult<Box<Program>> {
+        let program = input.link(entrypoints)?;
+
+        self.compile(program)
+    }
 }
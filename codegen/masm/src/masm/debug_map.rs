@@ -0,0 +1,165 @@
+use std::fmt;
+
+use miden_hir::{symbols, FunctionIdent, Symbol};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use super::{BlockId, Op, Program, Region};
+
+/// A [DebugMap] relates the procedures of a [Program] to the Wasm functions they were compiled
+/// from, and to the source code those functions were compiled from, where it is known.
+///
+/// It is emitted as a JSON sidecar alongside the compiled program, for use by debuggers.
+///
+/// NOTE: MASM ops do not carry source spans yet, so each entry describes a whole procedure,
+/// i.e. its MASM offset is that of the first op of the procedure, and its Wasm offset is that of
+/// the start of the function body.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct DebugMap {
+    /// The entries of this map, in the order of the modules and procedures of the program
+    pub entries: Vec<DebugMapEntry>,
+}
+
+/// A single entry of a [DebugMap]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugMapEntry {
+    /// The procedure this entry describes
+    pub procedure: FunctionIdent,
+    /// The offset, in ops, of the first op of the procedure from the start of its module, where
+    /// the ops of the module are counted in the order they are emitted, including those nested
+    /// in the blocks of control flow ops
+    pub masm_offset: u32,
+    /// The index of the Wasm function the procedure was compiled from
+    pub wasm_function: u32,
+    /// The offset of the Wasm code corresponding to `masm_offset`, relative to the start of the
+    /// code section, as in DWARF
+    pub wasm_offset: u32,
+    /// The source location of the Wasm code at `wasm_offset`, if known
+    pub source: Option<SourceLocation>,
+}
+
+/// A location in the source code a program was compiled from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceLocation {
+    /// The path of the source file
+    #[serde(serialize_with = "serialize_symbol")]
+    pub file: Symbol,
+    /// The 1-based line number in the source file
+    pub line: u32,
+}
+
+impl DebugMap {
+    /// Build the debug map of `program`, from the debug info attributes of its procedures.
+    ///
+    /// Procedures which were not compiled from Wasm with debug info enabled have no entries.
+    pub fn new(program: &Program) -> Self {
+        let mut entries = vec![];
+        for module in program.modules() {
+            let mut masm_offset = 0;
+            for function in module.functions() {
+                let offset = masm_offset;
+                masm_offset += num_ops(&function.body, function.body.id());
+                let attrs = &function.attrs;
+                let (Some(wasm_function), Some(wasm_offset)) = (
+                    attrs.get_int(&symbols::WasmFuncIndex),
+                    attrs.get_int(&symbols::WasmOffset),
+                ) else {
+                    continue;
+                };
+                let source = match (
+                    attrs.get_symbol(&symbols::SourceFile),
+                    attrs.get_int(&symbols::SourceLine),
+                ) {
+                    (Some(file), Some(line)) => Some(SourceLocation {
+                        file,
+                        line: line as u32,
+                    }),
+                    _ => None,
+                };
+                entries.push(DebugMapEntry {
+                    procedure: function.name,
+                    masm_offset: offset,
+                    wasm_function: wasm_function as u32,
+                    wasm_offset: wasm_offset as u32,
+                    source,
+                });
+            }
+        }
+        Self { entries }
+    }
+}
+
+/// The number of ops in `block` of `region`, including those in nested blocks
+fn num_ops(region: &Region, block: BlockId) -> u32 {
+    region
+        .block(block)
+        .ops
+        .iter()
+        .map(|op| match op {
+            Op::If(then_blk, else_blk) => {
+                1 + num_ops(region, *then_blk) + num_ops(region, *else_blk)
+            }
+            Op::While(body) | Op::Repeat(_, body) => 1 + num_ops(region, *body),
+            _ => 1,
+        })
+        .sum()
+}
+
+impl Serialize for DebugMapEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Masm<'a> {
+            module: &'a str,
+            procedure: String,
+            offset: u32,
+        }
+        #[derive(Serialize)]
+        struct Wasm {
+            function: u32,
+            offset: u32,
+        }
+
+        let mut entry = serializer.serialize_struct("DebugMapEntry", 3)?;
+        entry.serialize_field(
+            "masm",
+            &Masm {
+                module: self.procedure.module.as_str(),
+                // Procedures are named as they appear in the emitted Miden Assembly
+                procedure: miden_hir::mangle_procedure_name(self.procedure.function.as_str())
+                    .into_owned(),
+                offset: self.masm_offset,
+            },
+        )?;
+        entry.serialize_field(
+            "wasm",
+            &Wasm {
+                function: self.wasm_function,
+                offset: self.wasm_offset,
+            },
+        )?;
+        entry.serialize_field("source", &self.source)?;
+        entry.end()
+    }
+}
+
+fn serialize_symbol<S: Serializer>(symbol: &Symbol, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(symbol.as_str())
+}
+
+impl fmt::Display for DebugMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let json = serde_json::to_string_pretty(self).map_err(|_| fmt::Error)?;
+        writeln!(f, "{json}")
+    }
+}
+
+impl midenc_session::Emit for DebugMap {
+    fn name(&self) -> Option<Symbol> {
+        None
+    }
+    fn output_type(&self) -> midenc_session::OutputType {
+        midenc_session::OutputType::DebugMap
+    }
+    fn write_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_fmt(format_args!("{}", self))
+    }
+}
@@ -132,7 +132,9 @@ impl Function {
         use miden_hir::{Linkage, Symbol};
         let id = FunctionIdent {
             module,
-            function: Ident::with_empty_span(Symbol::intern(proc.name.as_ref())),
+            function: Ident::with_empty_span(Symbol::intern(&miden_hir::demangle_procedure_name(
+                proc.name.as_ref(),
+            ))),
         };
         let mut signature = Signature::new(vec![], vec![]);
         if !proc.is_export {
@@ -163,8 +165,8 @@ impl Function {
             ast::{ProcedureAst, SourceLocation},
         };
 
-        let name = masm::ProcedureName::try_from(self.name.function.as_str())
-            .expect("invalid function name");
+        let name = miden_hir::mangle_procedure_name(self.name.function.as_str());
+        let name = masm::ProcedureName::try_from(name.as_ref()).expect("invalid function name");
         let num_locals = u16::try_from(self.locals.len()).expect("too many locals");
         let start = codemap
             .location(self)
@@ -212,13 +214,13 @@ impl<'a> fmt::Display for DisplayMasmFunction<'a> {
         } else {
             "proc"
         };
-        let name = self.function.name;
+        let name = miden_hir::mangle_procedure_name(self.function.name.function.as_str());
         match self.function.locals.len() {
             0 => {
-                writeln!(f, "{visibility}.{}", &name.function)?;
+                writeln!(f, "{visibility}.{name}")?;
             }
             n => {
-                writeln!(f, "{visibility}.{}.{}", &name.function, n)?;
+                writeln!(f, "{visibility}.{name}.{n}")?;
             }
         }
 
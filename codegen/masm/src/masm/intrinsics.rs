@@ -1,6 +1,10 @@
+use std::collections::BTreeSet;
+
 use miden_diagnostics::{CodeMap, FileName};
+use miden_hir::{self as hir, Instruction, MasmOp, Opcode, Overflow, Type};
+use midenc_session::Session;
 
-use super::Module;
+use super::{LoadModuleError, Module};
 
 const I32_INTRINSICS: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/intrinsics/i32.masm"));
@@ -28,3 +32,121 @@ pub fn load<N: AsRef<str>>(name: N, codemap: &CodeMap) -> Option<Module> {
         }
     }
 }
+
+/// Returns the fully-qualified names of the intrinsics modules defined in this crate
+pub fn names() -> impl Iterator<Item = &'static str> {
+    INTRINSICS.iter().map(|(name, _, _)| *name)
+}
+
+/// Loads the module named `name` to satisfy an import of it, preferring the module provided for
+/// it by the user, see [midenc_session::Options::intrinsic_modules], to the intrinsics module of
+/// that name defined in this crate.
+///
+/// Returns `Ok(None)` if there is neither, and an error if the user-provided module could not be
+/// loaded.
+pub fn load_for_session(name: &str, session: &Session) -> Result<Option<Module>, LoadModuleError> {
+    let Some(path) = session.options.intrinsic_modules.get(name) else {
+        return Ok(load(name, &session.codemap));
+    };
+    if !path.is_file() {
+        return Err(LoadModuleError::InvalidPath(path.clone()));
+    }
+    let id = session.codemap.add_file(path)?;
+    let source_file = session.codemap.get(id).unwrap();
+    Module::parse_source_file(source_file, name, &session.codemap).map(Some)
+}
+
+/// Returns the names of the intrinsics modules, e.g. `intrinsics::i32`, which `program` will
+/// depend on once converted to Miden Assembly, in sorted order.
+///
+/// This is computed from the HIR alone, without performing the conversion, by determining which
+/// instructions may be lowered to a call to an intrinsic, along with any explicit calls to an
+/// intrinsic. Some of those instructions are lowered without an intrinsic in certain cases, e.g.
+/// a load from a constant address, so the result may include a module which the converted
+/// program does not end up using, but never omits one that it does.
+pub fn required_intrinsics(program: &hir::Program) -> Vec<String> {
+    let mut required = BTreeSet::new();
+    for module in program.modules().iter() {
+        for function in module.functions() {
+            let dfg = &function.dfg;
+            for (block, _) in dfg.blocks() {
+                for inst in dfg.block_insts(block) {
+                    add_required_intrinsics(dfg, dfg.inst(inst), &mut required);
+                }
+            }
+        }
+    }
+    required.into_iter().collect()
+}
+
+/// Add the intrinsics modules which `inst` may be lowered to a call to, to `required`
+fn add_required_intrinsics(
+    dfg: &hir::DataFlowGraph,
+    inst: &Instruction,
+    required: &mut BTreeSet<String>,
+) {
+    let (op, overflow, ty) = match inst {
+        Instruction::BinaryOp(op) => (op.op, op.overflow, dfg.value_type(op.args[1])),
+        Instruction::BinaryOpImm(op) => (op.op, op.overflow, dfg.value_type(op.arg)),
+        Instruction::UnaryOp(op) => (op.op, op.overflow, dfg.value_type(op.arg)),
+        // Loads and stores through a pointer which isn't known to be aligned are lowered using
+        // the memory intrinsics
+        Instruction::Load(_) => {
+            required.insert("intrinsics::mem".to_string());
+            return;
+        }
+        Instruction::PrimOp(op) if op.op == Opcode::Store => {
+            required.insert("intrinsics::mem".to_string());
+            return;
+        }
+        Instruction::Call(call) => {
+            add_intrinsic_callee(call.callee, required);
+            return;
+        }
+        Instruction::InlineAsm(asm) => {
+            for block in asm.blocks.values() {
+                for op in block.ops.iter() {
+                    if let MasmOp::Exec(callee) | MasmOp::Syscall(callee) = op {
+                        add_intrinsic_callee(*callee, required);
+                    }
+                }
+            }
+            return;
+        }
+        _ => return,
+    };
+    // Signed 32-bit integer operations which have no native equivalent are implemented by the
+    // `i32` intrinsics, see the corresponding methods of `OpEmitter`
+    if ty != &Type::I32 {
+        return;
+    }
+    let uses_i32_intrinsic = match op {
+        Opcode::Add | Opcode::Sub => matches!(
+            overflow.unwrap_or(Overflow::Checked),
+            Overflow::Checked | Overflow::Overflowing
+        ),
+        Opcode::Mul
+        | Opcode::Div
+        | Opcode::Shr
+        | Opcode::Min
+        | Opcode::Max
+        | Opcode::Gt
+        | Opcode::Gte
+        | Opcode::Lt
+        | Opcode::Lte
+        | Opcode::Exp
+        | Opcode::Pow2 => true,
+        _ => false,
+    };
+    if uses_i32_intrinsic {
+        required.insert("intrinsics::i32".to_string());
+    }
+}
+
+/// Add the module of `callee` to `required`, if it is an intrinsics module
+fn add_intrinsic_callee(callee: hir::FunctionIdent, required: &mut BTreeSet<String>) {
+    let module = callee.module.as_str();
+    if module.starts_with("intrinsics::") {
+        required.insert(module.to_string());
+    }
+}
@@ -1,9 +1,11 @@
+mod debug_map;
 mod function;
 pub mod intrinsics;
 mod module;
 mod program;
 mod region;
 
+pub use self::debug_map::{DebugMap, DebugMapEntry, SourceLocation};
 pub use self::function::{FrozenFunctionList, Function, FunctionList};
 pub use self::module::{FrozenModuleTree, LoadModuleError, Module, ModuleTree};
 pub use self::program::Program;
@@ -188,7 +188,11 @@ impl Module {
             imported.insert(import.alias.to_string(), path.clone());
             if let Some(imported_fns) = self.imports.imported(&import.alias) {
                 for import_fn in imported_fns.iter().copied() {
-                    let fname = import_fn.to_string();
+                    let fname = format!(
+                        "{}::{}",
+                        import_fn.module,
+                        miden_hir::mangle_procedure_name(import_fn.function.as_str())
+                    );
                     let name = masm::ProcedureName::try_from(fname.as_str())
                         .expect("invalid function name");
                     let id = masm::ProcedureId::from_name(fname.as_str(), &path);
@@ -15,6 +15,10 @@ pub struct Program {
     modules: Modules,
     /// The data segment table for this program
     pub segments: DataSegmentTable,
+    /// The function invoked by the top-level initialization code, if this is an executable
+    entrypoint: Option<FunctionIdent>,
+    /// The start functions invoked by the top-level initialization code before the entrypoint
+    start_functions: Vec<FunctionIdent>,
     /// The top-level global initialization code for this program, if applicable
     pub body: Option<Begin>,
 }
@@ -50,6 +54,20 @@ impl Program {
         self.modules.insert(module);
     }
 
+    /// Get the entrypoint of this program, if it is an executable
+    ///
+    /// This is the function invoked by the `begin` block emitted for the program
+    pub fn entrypoint(&self) -> Option<FunctionIdent> {
+        self.entrypoint
+    }
+
+    /// Get the start functions of this program, in the order in which they are invoked
+    ///
+    /// If this is an executable, the `begin` block invokes them before the entrypoint
+    pub fn start_functions(&self) -> &[FunctionIdent] {
+        &self.start_functions
+    }
+
     pub fn is_executable(&self) -> bool {
         self.body.is_some()
     }
@@ -119,9 +137,10 @@ impl Program {
                 imported.insert(import.alias.to_string(), path.clone());
                 if let Some(imported_fns) = begin.imports.imported(&import.alias) {
                     for import_fn in imported_fns.iter().copied() {
-                        let name = masm::ProcedureName::try_from(import_fn.function.as_str())
+                        let fname = miden_hir::mangle_procedure_name(import_fn.function.as_str());
+                        let name = masm::ProcedureName::try_from(fname.as_ref())
                             .expect("invalid function name");
-                        let id = masm::ProcedureId::from_name(import_fn.function.as_str(), &path);
+                        let id = masm::ProcedureId::from_name(fname.as_ref(), &path);
                         invoked.insert(id, (name, path.clone()));
                         proc_ids.insert(import_fn, id);
                     }
@@ -137,13 +156,16 @@ impl Program {
             ProgramAst::new(nodes, vec![])
                 .expect("invalid program")
                 .with_import_info(imports)
-        } else if let Some(entry) = self.modules.iter().find_map(|m| m.entrypoint()) {
+        } else if let Some(entry) = self
+            .entrypoint
+            .or_else(|| self.modules.iter().find_map(|m| m.entrypoint()))
+        {
             let entry_import = Import::try_from(entry.module).expect("invalid module name");
             let entry_module_path =
                 masm::LibraryPath::new(entry_import.name.as_str()).expect("invalid module path");
-            let entry_id =
-                masm::ProcedureId::from_name(entry.function.as_str(), &entry_module_path);
-            let entry_name = masm::ProcedureName::try_from(entry.function.as_str())
+            let entry_fname = miden_hir::mangle_procedure_name(entry.function.as_str());
+            let entry_id = masm::ProcedureId::from_name(entry_fname.as_ref(), &entry_module_path);
+            let entry_name = masm::ProcedureName::try_from(entry_fname.as_ref())
                 .expect("invalid entrypoint function name");
             let imported =
                 BTreeMap::from([(entry_import.alias.to_string(), entry_module_path.clone())]);
@@ -244,6 +266,8 @@ impl Program {
         Self {
             modules: Modules::Open(modules),
             segments: DataSegmentTable::default(),
+            entrypoint: None,
+            start_functions: vec![],
             body: None,
         }
     }
@@ -251,18 +275,23 @@ impl Program {
 impl From<&hir::Program> for Program {
     fn from(program: &hir::Program) -> Self {
         let segments = program.segments().clone();
-        let body = if let Some(entry) = program.entrypoint() {
+        let entrypoint = program.entrypoint();
+        let start_functions = program.start_functions().to_vec();
+        let body = if let Some(entry) = entrypoint {
+            // The start functions are run before the entrypoint, in order
             let mut begin = Begin::default();
-            begin.imports.add(entry);
-            let entry_module = begin.imports.alias(&entry.module);
-            begin
-                .body
-                .block_mut(begin.body.body)
-                .ops
-                .push(Op::Exec(FunctionIdent {
-                    module: entry_module.unwrap_or(entry.module),
-                    function: entry.function,
-                }));
+            for callee in start_functions.iter().copied().chain([entry]) {
+                begin.imports.add(callee);
+                let callee_module = begin.imports.alias(&callee.module);
+                begin
+                    .body
+                    .block_mut(begin.body.body)
+                    .ops
+                    .push(Op::Exec(FunctionIdent {
+                        module: callee_module.unwrap_or(callee.module),
+                        function: callee.function,
+                    }));
+            }
             Some(begin)
         } else {
             None
@@ -270,6 +299,8 @@ impl From<&hir::Program> for Program {
         Self {
             modules: Default::default(),
             segments,
+            entrypoint,
+            start_functions,
             body,
         }
     }
@@ -294,3 +325,14 @@ impl fmt::Display for Program {
         Ok(())
     }
 }
+impl midenc_session::Emit for Program {
+    fn name(&self) -> Option<miden_hir::Symbol> {
+        None
+    }
+    fn output_type(&self) -> midenc_session::OutputType {
+        midenc_session::OutputType::MasmAst
+    }
+    fn write_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_fmt(format_args!("{}", self))
+    }
+}
@@ -1,5 +1,5 @@
 use miden_hir::{
-    self,
+    self, assert_matches,
     pass::{AnalysisManager, ConversionPass},
     testing::{self, TestContext},
     AbiParam, Felt, FieldElement, FunctionIdent, Immediate, InstBuilder, OperandStack,
@@ -73,7 +73,8 @@ impl TestByEmulationHarness {
         analyses.insert(ProgramAnalysisKey, global_analysis);
 
         // Apply pre-codegen transformations
-        let mut rewrites = transform::SplitCriticalEdges
+        let mut rewrites = transform::LowerSwitch
+            .chain(transform::SplitCriticalEdges)
             .chain(transform::Treeify)
             .chain(transform::InlineBlocks);
         rewrites.apply(function, &mut analyses, &self.context.session)?;
@@ -228,6 +229,72 @@ fn issue56() {
     compiler.compile(program).expect("compilation failed");
 }
 
+/// Test that the entrypoint of an HIR program is carried over to the MASM program
+#[test]
+fn program_entrypoint() {
+    let harness = TestByEmulationHarness::default();
+
+    let mut builder = ProgramBuilder::new(&harness.context.session.diagnostics);
+    let mut mb = builder.module("test");
+    testing::fib1(mb.as_mut(), &harness.context);
+    mb.build()
+        .expect("unexpected error constructing test module");
+
+    let entrypoint: FunctionIdent = "test::fib".parse().unwrap();
+    let program = builder
+        .with_entrypoint(entrypoint)
+        .link()
+        .expect("failed to link program");
+
+    let mut compiler = MasmCompiler::new(&harness.context.session);
+    let program = compiler.compile(program).expect("compilation failed");
+
+    assert!(program.is_executable());
+    assert_eq!(program.entrypoint(), Some(entrypoint));
+    let begin = program.body.as_ref().unwrap();
+    let body = begin.body.block(begin.body.body);
+    assert_eq!(body.ops.len(), 1);
+    assert_matches!(&body.ops[0], Op::Exec(callee) if callee.function == entrypoint.function);
+}
+
+/// Test that the start functions of an HIR program are invoked before its entrypoint
+#[test]
+fn program_start_functions() {
+    let harness = TestByEmulationHarness::default();
+
+    let mut mb = miden_hir::ModuleBuilder::new("test");
+    let entrypoint = testing::fib1(&mut mb, &harness.context);
+    let mut fb = mb
+        .function("init", Signature::new([], []))
+        .expect("unexpected symbol conflict");
+    fb.ins().ret(None, SourceSpan::UNKNOWN);
+    let init = fb
+        .build(&harness.context.session.diagnostics)
+        .expect("unexpected validation error, see diagnostics output");
+    let mut module = mb.build();
+    module.set_start(init);
+
+    let program = ProgramBuilder::new(&harness.context.session.diagnostics)
+        .with_module(module)
+        .expect("unexpected module conflict")
+        .with_entrypoint(entrypoint)
+        .link()
+        .expect("failed to link program");
+    assert_eq!(program.start_functions(), &[init]);
+
+    let mut compiler = MasmCompiler::new(&harness.context.session);
+    let program = compiler.compile(program).expect("compilation failed");
+
+    assert!(program.is_executable());
+    assert_eq!(program.entrypoint(), Some(entrypoint));
+    assert_eq!(program.start_functions(), &[init]);
+    let begin = program.body.as_ref().unwrap();
+    let body = begin.body.block(begin.body.body);
+    assert_eq!(body.ops.len(), 2);
+    assert_matches!(&body.ops[0], Op::Exec(callee) if callee.function == init.function);
+    assert_matches!(&body.ops[1], Op::Exec(callee) if callee.function == entrypoint.function);
+}
+
 /// Test the emulator on the fibonacci function
 #[test]
 fn fib_emulator() {
@@ -464,6 +531,635 @@ fn codegen_sum_matrix() {
     assert_eq!(stack.pop().map(|e| e.as_int()), Some(6));
 }
 
+/// Build a function which dispatches on its argument to one of `num_arms` blocks, each of which
+/// returns its discriminant plus one, with any other value returning 0.
+///
+/// When `use_switch` is true, the dispatch is a single `switch`, otherwise it is a chain of
+/// equality tests, one per arm, which serves as a baseline for the lowering of `switch`.
+fn dispatch(
+    mb: &mut hir::ModuleBuilder,
+    context: &TestContext,
+    name: &str,
+    num_arms: u32,
+    use_switch: bool,
+) -> FunctionIdent {
+    let mut fb = mb
+        .function(
+            name,
+            Signature::new([AbiParam::new(Type::U32)], [AbiParam::new(Type::U32)]),
+        )
+        .expect("unexpected symbol conflict");
+    let entry = fb.current_block();
+    let arg = fb.block_params(entry)[0];
+
+    let mut arms = Vec::with_capacity(num_arms as usize);
+    for discriminant in 0..num_arms {
+        let arm = fb.create_block();
+        fb.switch_to_block(arm);
+        let result = fb.ins().u32(discriminant + 1, SourceSpan::UNKNOWN);
+        fb.ins().ret(Some(result), SourceSpan::UNKNOWN);
+        arms.push((discriminant, arm));
+    }
+    let default = fb.create_block();
+    fb.switch_to_block(default);
+    let result = fb.ins().u32(0, SourceSpan::UNKNOWN);
+    fb.ins().ret(Some(result), SourceSpan::UNKNOWN);
+
+    fb.switch_to_block(entry);
+    if use_switch {
+        fb.ins().switch(arg, arms, default, SourceSpan::UNKNOWN);
+    } else {
+        for (discriminant, arm) in arms {
+            let next = fb.create_block();
+            let is_match = fb
+                .ins()
+                .eq_imm(arg, Immediate::U32(discriminant), SourceSpan::UNKNOWN);
+            fb.ins()
+                .cond_br(is_match, arm, &[], next, &[], SourceSpan::UNKNOWN);
+            fb.switch_to_block(next);
+        }
+        fb.ins().br(default, &[], SourceSpan::UNKNOWN);
+    }
+
+    fb.build(&context.session.diagnostics)
+        .expect("unexpected error building function")
+}
+
+/// Test that a dense `switch` is lowered to a binary search, rather than a chain of comparisons
+#[test]
+fn codegen_dense_switch() {
+    const NUM_ARMS: u32 = 16;
+
+    /// The number of instructions in `function`
+    fn size(function: &Function) -> usize {
+        function
+            .body
+            .blocks
+            .values()
+            .map(|block| block.ops.len())
+            .sum()
+    }
+
+    /// The maximum nesting depth of conditionals in `block`, i.e. the most comparisons on any
+    /// path through it
+    fn depth(region: &Region, block: BlockId) -> usize {
+        region
+            .block(block)
+            .ops
+            .iter()
+            .map(|op| match op {
+                Op::If(then_blk, else_blk) => {
+                    1 + depth(region, *then_blk).max(depth(region, *else_blk))
+                }
+                Op::While(body) | Op::Repeat(_, body) => depth(region, *body),
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    let harness = TestByEmulationHarness::default();
+
+    let mut builder = ProgramBuilder::new(&harness.context.session.diagnostics);
+    let mut mb = builder.module("test");
+    let switch_id = dispatch(
+        mb.as_mut(),
+        &harness.context,
+        "dispatch_switch",
+        NUM_ARMS,
+        true,
+    );
+    let chain_id = dispatch(
+        mb.as_mut(),
+        &harness.context,
+        "dispatch_chain",
+        NUM_ARMS,
+        false,
+    );
+    mb.build()
+        .expect("unexpected error constructing test module");
+
+    let program = builder
+        .with_entrypoint(switch_id)
+        .link()
+        .expect("failed to link program");
+
+    let mut compiler = MasmCompiler::new(&harness.context.session);
+    let program = compiler.compile(program).expect("compilation failed");
+
+    let module = program.get("test").unwrap();
+    let function = |id: FunctionIdent| {
+        module
+            .functions()
+            .find(|f| f.name == id)
+            .expect("function not found")
+    };
+    let switch_fn = function(switch_id);
+    let chain_fn = function(chain_id);
+
+    // The comparisons executed to reach an arm are bounded by the depth of the conditionals: a
+    // binary search over the arms needs at most ⌈log2 n⌉ of them, plus 1 for the bounds check,
+    // whereas the chain needs one per arm
+    let max_search_depth = NUM_ARMS.next_power_of_two().ilog2() as usize + 1;
+    let switch_depth = depth(&switch_fn.body, switch_fn.body.id());
+    let chain_depth = depth(&chain_fn.body, chain_fn.body.id());
+    assert_eq!(chain_depth, NUM_ARMS as usize);
+    assert!(
+        switch_depth <= max_search_depth,
+        "expected the lowered switch to execute at most {max_search_depth} comparisons, but it \
+         executes up to {switch_depth}, compared to {chain_depth} for the chain"
+    );
+    assert!(switch_depth < chain_depth);
+
+    // The search emits as many comparisons as the chain does, so its code size should be close
+    let switch_size = size(switch_fn);
+    let chain_size = size(chain_fn);
+    assert!(
+        switch_size <= chain_size + chain_size / 4,
+        "expected the size of the lowered switch ({switch_size}) to be comparable to that of an \
+         equivalent chain of comparisons ({chain_size})"
+    );
+
+    // Make sure that every arm, and the default, are still reached correctly
+    let program = program.freeze();
+    for (input, expected) in [(0, 1), (7, 8), (8, 9), (15, 16), (16, 0), (u32::MAX, 0)] {
+        let mut harness = TestByEmulationHarness::default();
+        let mut stack = harness
+            .execute_program(program.clone(), &[Felt::new(input as u64)])
+            .expect("execution failed");
+        assert_eq!(stack.len(), 1);
+        assert_eq!(
+            stack.pop().map(|e| e.as_int()),
+            Some(expected),
+            "unexpected result for input {input}"
+        );
+    }
+}
+
+/// Constant operands of a binary operator should be folded into the operator as an immediate,
+/// and when both operands are constant, into a single push of the result
+#[test]
+fn codegen_constant_operands() {
+    /// All of the instructions in `function`
+    fn ops(function: &Function) -> Vec<Op> {
+        function
+            .body
+            .blocks
+            .values()
+            .flat_map(|block| block.ops.iter().copied())
+            .collect()
+    }
+
+    let harness = TestByEmulationHarness::default();
+
+    let mut builder = ProgramBuilder::new(&harness.context.session.diagnostics);
+    let mut mb = builder.module("test");
+    let fold_id = {
+        let mut fb = mb
+            .function("fold", Signature::new([], [AbiParam::new(Type::I32)]))
+            .expect("unexpected symbol conflict");
+        let a = fb.ins().i32(42, SourceSpan::UNKNOWN);
+        let b = fb.ins().i32(1, SourceSpan::UNKNOWN);
+        let sum = fb.ins().add_wrapping(a, b, SourceSpan::UNKNOWN);
+        fb.ins().ret(Some(sum), SourceSpan::UNKNOWN);
+        fb.build()
+            .expect("unexpected validation error, see diagnostics output")
+    };
+    let add_one_id = {
+        let mut fb = mb
+            .function(
+                "add_one",
+                Signature::new([AbiParam::new(Type::I32)], [AbiParam::new(Type::I32)]),
+            )
+            .expect("unexpected symbol conflict");
+        let entry = fb.current_block();
+        let a = fb.block_params(entry)[0];
+        let one = fb.ins().i32(1, SourceSpan::UNKNOWN);
+        let sum = fb.ins().add_wrapping(a, one, SourceSpan::UNKNOWN);
+        fb.ins().ret(Some(sum), SourceSpan::UNKNOWN);
+        fb.build()
+            .expect("unexpected validation error, see diagnostics output")
+    };
+    mb.build()
+        .expect("unexpected error constructing test module");
+
+    let program = builder
+        .with_entrypoint(fold_id)
+        .link()
+        .expect("failed to link program");
+
+    let mut compiler = MasmCompiler::new(&harness.context.session);
+    let program = compiler.compile(program).expect("compilation failed");
+
+    let module = program.get("test").unwrap();
+    let function = |id: FunctionIdent| {
+        module
+            .functions()
+            .find(|f| f.name == id)
+            .expect("function not found")
+    };
+
+    // `42 + 1` is either folded to a single push of 43, or pushes both operands and adds them
+    let fold_ops = ops(function(fold_id));
+    if fold_ops.contains(&Op::PushU32(43)) {
+        assert!(!fold_ops.contains(&Op::PushU32(42)));
+        assert!(!fold_ops.contains(&Op::U32WrappingAdd));
+    } else {
+        assert!(fold_ops.contains(&Op::PushU32(42)));
+        assert!(fold_ops.contains(&Op::PushU32(1)));
+        assert!(fold_ops.contains(&Op::U32WrappingAdd));
+    }
+
+    // `a + 1` uses the immediate form of the add, rather than pushing the constant
+    let add_one_ops = ops(function(add_one_id));
+    assert!(add_one_ops.contains(&Op::U32WrappingAddImm(1)));
+    assert!(!add_one_ops.contains(&Op::PushU32(1)));
+    assert!(!add_one_ops.contains(&Op::U32WrappingAdd));
+
+    let mut harness = TestByEmulationHarness::default();
+    let mut stack = harness
+        .execute_program(program.freeze(), &[])
+        .expect("execution failed");
+    assert_eq!(stack.len(), 1);
+    assert_eq!(stack.pop().map(|e| e.as_int()), Some(43));
+}
+
+/// Test that converting the modules of a program in parallel produces the same MASM as
+/// converting them serially
+#[test]
+fn parallel_conversion_matches_serial() {
+    const NUM_MODULES: usize = 8;
+
+    let context = TestContext::default();
+    let build = || {
+        let mut builder = ProgramBuilder::new(&context.session.diagnostics);
+        for i in 0..NUM_MODULES {
+            let mut mb = builder.module(format!("test{i}").as_str());
+            testing::fib1(mb.as_mut(), &context);
+            testing::sum_matrix(mb.as_mut(), &context);
+            mb.build()
+                .expect("unexpected error constructing test module");
+        }
+        builder.link().expect("failed to link program")
+    };
+
+    let serial = MasmCompiler::new(&context.session)
+        .compile(build())
+        .expect("compilation failed");
+    let parallel = MasmCompiler::new(&context.session)
+        .with_parallel_conversion(true)
+        .compile(build())
+        .expect("compilation failed");
+    assert_eq!(serial.modules().count(), parallel.modules().count());
+    assert_eq!(serial.to_string(), parallel.to_string());
+}
+
+/// Test that compiling the functions of a module one at a time produces the same procedures as
+/// converting the whole module
+#[test]
+fn compile_function_matches_module_conversion() {
+    use miden_hir::pass::{ModuleRewritePassAdapter, RewritePass, RewriteSet};
+    use miden_hir_transform as transforms;
+
+    let context = TestContext::default();
+    let mut mb = miden_hir::ModuleBuilder::new("test");
+    testing::fib1(&mut mb, &context);
+    testing::sum_matrix(&mut mb, &context);
+    let mut module = mb.build();
+
+    let mut analyses = AnalysisManager::new();
+    let mut rewrites = RewriteSet::default();
+    rewrites.push(ModuleRewritePassAdapter::new(transforms::LowerSwitch));
+    rewrites.push(ModuleRewritePassAdapter::new(
+        transforms::SplitCriticalEdges,
+    ));
+    rewrites.push(ModuleRewritePassAdapter::new(transforms::Treeify));
+    rewrites
+        .apply(&mut module, &mut analyses, &context.session)
+        .expect("rewrites failed");
+
+    let imports = module.imports();
+    let procedures = module
+        .functions()
+        .map(|function| {
+            compile_function(function, &module, &mut analyses, &context.session)
+                .expect("compilation failed")
+                .display(&imports)
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(procedures.len(), 2);
+
+    // A function is rejected as part of a module it does not belong to
+    let other = miden_hir::ModuleBuilder::new("other").build();
+    let function = module.functions().next().unwrap();
+    let err = compile_function(function, &other, &mut analyses, &context.session).unwrap_err();
+    assert!(
+        matches!(err, CompilerError::FunctionNotInModule { .. }),
+        "unexpected error: {err}"
+    );
+
+    let masm_module = ConvertHirToMasm::<hir::Module>::default()
+        .convert(module, &mut AnalysisManager::new(), &context.session)
+        .expect("conversion failed");
+    let expected = masm_module
+        .functions()
+        .map(|function| function.display(&masm_module.imports).to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(procedures, expected);
+}
+
+/// Test that the intrinsics modules a program requires are reported without converting it, and
+/// include every intrinsics module which the conversion links in
+#[test]
+fn required_intrinsics_are_reported() {
+    let context = TestContext::default();
+    let build = |with_signed_and_loads: bool| {
+        let mut builder = ProgramBuilder::new(&context.session.diagnostics);
+        let mut mb = builder.module("test");
+        // `popcnt` is lowered to the native `u32popcnt` instruction, so requires no intrinsics
+        {
+            let mut fb = mb
+                .function(
+                    "count_ones",
+                    Signature::new([AbiParam::new(Type::U32)], [AbiParam::new(Type::U32)]),
+                )
+                .expect("unexpected symbol conflict");
+            let entry = fb.current_block();
+            let a = fb.block_params(entry)[0];
+            let count = fb.ins().popcnt(a, SourceSpan::UNKNOWN);
+            fb.ins().ret(Some(count), SourceSpan::UNKNOWN);
+            fb.build()
+                .expect("unexpected validation error, see diagnostics output");
+        }
+        // Signed comparisons use the `i32` intrinsics, and loads through a pointer use the
+        // `mem` intrinsics
+        if with_signed_and_loads {
+            let mut fb = mb
+                .function(
+                    "is_positive",
+                    Signature::new([AbiParam::new(Type::I32)], [AbiParam::new(Type::I1)]),
+                )
+                .expect("unexpected symbol conflict");
+            let entry = fb.current_block();
+            let a = fb.block_params(entry)[0];
+            let is_positive = fb.ins().gt_imm(a, Immediate::I32(0), SourceSpan::UNKNOWN);
+            fb.ins().ret(Some(is_positive), SourceSpan::UNKNOWN);
+            fb.build()
+                .expect("unexpected validation error, see diagnostics output");
+            testing::sum_matrix(mb.as_mut(), &context);
+        }
+        mb.build()
+            .expect("unexpected error constructing test module");
+        builder.link().expect("failed to link program")
+    };
+    let linked_intrinsics = |program: &Program| {
+        program
+            .modules()
+            .map(|module| module.name.as_str().to_string())
+            .filter(|name| name.starts_with("intrinsics::"))
+            .collect::<Vec<_>>()
+    };
+
+    let program = build(false);
+    assert!(intrinsics::required_intrinsics(&program).is_empty());
+    let program = MasmCompiler::new(&context.session)
+        .compile(program)
+        .expect("compilation failed");
+    assert!(linked_intrinsics(&program).is_empty());
+
+    let program = build(true);
+    let required = intrinsics::required_intrinsics(&program);
+    assert_eq!(required, ["intrinsics::i32", "intrinsics::mem"]);
+    let program = MasmCompiler::new(&context.session)
+        .compile(program)
+        .expect("compilation failed");
+    for name in linked_intrinsics(&program) {
+        assert!(
+            required.contains(&name),
+            "{name} was linked in, but not reported as required"
+        );
+    }
+}
+
+/// Test that a function named after a kebab-case WIT export, e.g. `note-script`, is emitted as a
+/// valid Miden Assembly procedure, which can be parsed back under its original name
+#[test]
+fn kebab_case_function_names_are_mangled() {
+    let mut harness = TestByEmulationHarness::default();
+
+    let mut builder = ProgramBuilder::new(&harness.context.session.diagnostics);
+    let mut mb = builder.module("test");
+    let note_script = {
+        let mut fb = mb
+            .function(
+                "note-script",
+                Signature::new([AbiParam::new(Type::U32)], [AbiParam::new(Type::U32)]),
+            )
+            .expect("unexpected symbol conflict");
+        let entry = fb.current_block();
+        let a = fb.block_params(entry)[0];
+        let one = fb.ins().u32(1, SourceSpan::UNKNOWN);
+        let sum = fb.ins().add_wrapping(a, one, SourceSpan::UNKNOWN);
+        fb.ins().ret(Some(sum), SourceSpan::UNKNOWN);
+        fb.build()
+            .expect("unexpected validation error, see diagnostics output")
+    };
+    let main = {
+        let mut fb = mb
+            .function("main", Signature::new([], [AbiParam::new(Type::U32)]))
+            .expect("unexpected symbol conflict");
+        let note_script = fb
+            .import_function(
+                note_script.module,
+                note_script.function,
+                Signature::new([AbiParam::new(Type::U32)], [AbiParam::new(Type::U32)]),
+            )
+            .expect("unexpected symbol conflict");
+        let arg = fb.ins().u32(41, SourceSpan::UNKNOWN);
+        let call = fb.ins().call(note_script, &[arg], SourceSpan::UNKNOWN);
+        let result = fb.first_result(call);
+        fb.ins().ret(Some(result), SourceSpan::UNKNOWN);
+        fb.build()
+            .expect("unexpected validation error, see diagnostics output")
+    };
+    mb.build()
+        .expect("unexpected error constructing test module");
+    let program = builder
+        .with_entrypoint(main)
+        .link()
+        .expect("failed to link program");
+
+    let mut compiler = MasmCompiler::new(&harness.context.session);
+    let program = compiler.compile(program).expect("compilation failed");
+
+    // Both the definition of the procedure, and the call to it, use the mangled name
+    let module = program.get("test").unwrap();
+    let masm = module.to_string();
+    assert!(masm.contains("export.M_note_2d_script"), "{masm}");
+    assert!(masm.contains("exec.M_note_2d_script"), "{masm}");
+    assert!(!masm.contains("note-script"), "{masm}");
+
+    // The module is accepted by the assembler, and the original name is recovered when parsing it
+    let codemap = &harness.context.session.codemap;
+    module.to_module_ast(codemap);
+    let id = codemap.add(
+        miden_diagnostics::FileName::Virtual("test.masm".into()),
+        masm,
+    );
+    let parsed = Module::parse_source_file(codemap.get(id).unwrap(), "test", codemap)
+        .expect("failed to parse emitted module");
+    assert!(parsed.functions().any(|f| f.name == note_script));
+
+    let mut stack = harness
+        .execute_program(program.freeze(), &[])
+        .expect("execution failed");
+    assert_eq!(stack.pop().map(|e| e.as_int()), Some(42));
+}
+
+/// Test that a user-provided Miden Assembly module is linked in to satisfy an import of the
+/// intrinsics module of that name, which the compiler does not provide itself
+#[test]
+fn user_intrinsic_modules_are_linked() {
+    let path = std::env::temp_dir().join(format!(
+        "user_intrinsic_modules_are_linked_{}.masm",
+        std::process::id()
+    ));
+    std::fs::write(&path, "export.triple\n    push.3 u32wrapping_mul\nend\n")
+        .expect("failed to write intrinsics module");
+    let mut options = midenc_session::Options::default();
+    options
+        .intrinsic_modules
+        .insert("intrinsics::custom".to_string(), path.clone());
+    let mut harness = TestByEmulationHarness {
+        context: TestContext::default_with_opts_and_emitter(options, None),
+        ..Default::default()
+    };
+
+    let triple: FunctionIdent = "intrinsics::custom::triple".parse().unwrap();
+    let mut builder = ProgramBuilder::new(&harness.context.session.diagnostics);
+    let mut mb = builder.module("test");
+    let main = {
+        let mut fb = mb
+            .function("main", Signature::new([], [AbiParam::new(Type::U32)]))
+            .expect("unexpected symbol conflict");
+        let triple = fb
+            .import_function(
+                triple.module,
+                triple.function,
+                Signature::new([AbiParam::new(Type::U32)], [AbiParam::new(Type::U32)]),
+            )
+            .expect("unexpected symbol conflict");
+        let arg = fb.ins().u32(14, SourceSpan::UNKNOWN);
+        let call = fb.ins().call(triple, &[arg], SourceSpan::UNKNOWN);
+        let result = fb.first_result(call);
+        fb.ins().ret(Some(result), SourceSpan::UNKNOWN);
+        fb.build()
+            .expect("unexpected validation error, see diagnostics output")
+    };
+    mb.build()
+        .expect("unexpected error constructing test module");
+    let program = builder
+        .with_entrypoint(main)
+        .link()
+        .expect("failed to link program");
+
+    let mut compiler = MasmCompiler::new(&harness.context.session);
+    let program = compiler.compile(program);
+    std::fs::remove_file(&path).ok();
+    let program = program.expect("compilation failed");
+    assert!(program.get("intrinsics::custom").is_some());
+
+    let mut stack = harness
+        .execute_program(program.freeze(), &[])
+        .expect("execution failed");
+    assert_eq!(stack.pop().map(|e| e.as_int()), Some(42));
+}
+
+/// Test that an import of an intrinsics module which is neither built-in nor provided by the user
+/// is reported as an error, rather than crashing the compiler
+#[test]
+fn unrecognized_intrinsic_modules_are_rejected() {
+    let context = TestContext::default();
+
+    let unknown: FunctionIdent = "intrinsics::unknown::frobnicate".parse().unwrap();
+    let mut builder = ProgramBuilder::new(&context.session.diagnostics);
+    let mut mb = builder.module("test");
+    let main = {
+        let mut fb = mb
+            .function("main", Signature::new([], [AbiParam::new(Type::U32)]))
+            .expect("unexpected symbol conflict");
+        let unknown = fb
+            .import_function(
+                unknown.module,
+                unknown.function,
+                Signature::new([], [AbiParam::new(Type::U32)]),
+            )
+            .expect("unexpected symbol conflict");
+        let call = fb.ins().call(unknown, &[], SourceSpan::UNKNOWN);
+        let result = fb.first_result(call);
+        fb.ins().ret(Some(result), SourceSpan::UNKNOWN);
+        fb.build()
+            .expect("unexpected validation error, see diagnostics output")
+    };
+    mb.build()
+        .expect("unexpected error constructing test module");
+    let program = builder
+        .with_entrypoint(main)
+        .link()
+        .expect("failed to link program");
+
+    let err = MasmCompiler::new(&context.session)
+        .compile(program)
+        .expect_err("expected an unrecognized intrinsic module to be rejected");
+    assert_eq!(
+        err.to_string(),
+        "unrecognized intrinsic module 'intrinsics::unknown', expected one of: \
+         'intrinsics::i32', 'intrinsics::mem'"
+    );
+}
+
+/// Test that, when procedures are ordered by name, the Miden Assembly emitted for a module does
+/// not depend on the order in which its functions were built
+#[test]
+fn procedure_order_by_name_is_stable() {
+    let mut options = midenc_session::Options::default();
+    options.procedure_order = midenc_session::ProcedureOrder::Name;
+    let context = TestContext::default_with_opts_and_emitter(options, None);
+    let compile = |names: &[&str]| {
+        let mut builder = ProgramBuilder::new(&context.session.diagnostics);
+        let mut mb = builder.module("test");
+        for (i, name) in names.iter().enumerate() {
+            let mut fb = mb
+                .function(*name, Signature::new([], [AbiParam::new(Type::U32)]))
+                .expect("unexpected symbol conflict");
+            let value = fb.ins().u32(i as u32, SourceSpan::UNKNOWN);
+            fb.ins().ret(Some(value), SourceSpan::UNKNOWN);
+            fb.build()
+                .expect("unexpected validation error, see diagnostics output");
+        }
+        mb.build()
+            .expect("unexpected error constructing test module");
+        let program = builder.link().expect("failed to link program");
+        let program = MasmCompiler::new(&context.session)
+            .compile(program)
+            .expect("compilation failed");
+        let module = program.get("test").unwrap();
+        let procedures = module
+            .functions()
+            .map(|function| function.name.function.as_str().to_string())
+            .collect::<Vec<_>>();
+        (procedures, module.to_string())
+    };
+
+    let (procedures, masm) = compile(&["zeta", "alpha", "mu"]);
+    assert_eq!(procedures, ["alpha", "mu", "zeta"]);
+    let (reordered_procedures, _) = compile(&["mu", "zeta", "alpha"]);
+    assert_eq!(reordered_procedures, procedures);
+    let (_, recompiled) = compile(&["zeta", "alpha", "mu"]);
+    assert_eq!(recompiled, masm);
+}
+
 #[test]
 #[should_panic(expected = "assertion failed: expected false, got true")]
 fn i32_checked_neg() {
@@ -487,6 +1183,103 @@ fn i32_checked_neg() {
     harness.invoke(neg, &[min]).expect("execution failed");
 }
 
+/// Unchecked i64 addition is performed with field arithmetic, as for i32, but must still carry
+/// out of the low limb into the high limb
+#[test]
+fn i64_unchecked_add_carries_into_high_limb() {
+    let mut harness = TestByEmulationHarness::default();
+
+    let mut builder = ProgramBuilder::new(&harness.context.session.diagnostics);
+    let mut mb = builder.module("test");
+    let id = {
+        let mut fb = mb
+            .function(
+                "add",
+                Signature::new(
+                    [AbiParam::new(Type::I64), AbiParam::new(Type::I64)],
+                    [AbiParam::new(Type::I64)],
+                ),
+            )
+            .expect("unexpected symbol conflict");
+        let entry = fb.current_block();
+        let (a, b) = {
+            let args = fb.block_params(entry);
+            (args[0], args[1])
+        };
+        let c = fb.ins().add_unchecked(a, b, SourceSpan::UNKNOWN);
+        fb.ins().ret(Some(c), SourceSpan::UNKNOWN);
+        fb.build().expect("unexpected error building function")
+    };
+    mb.build()
+        .expect("unexpected error constructing test module");
+
+    let program = builder
+        .with_entrypoint(id)
+        .link()
+        .expect("failed to link program");
+    let mut compiler = MasmCompiler::new(&harness.context.session);
+    let program = compiler.compile(program).expect("compilation failed");
+    let add = program.get("test").unwrap().functions().next().unwrap();
+    assert!(
+        !add.body
+            .block(add.body.id())
+            .ops
+            .iter()
+            .any(|op| matches!(op, Op::Exec(_))),
+        "expected unchecked i64 addition to be inlined"
+    );
+
+    // 0x1_ffff_ffff + 0x2_0000_0001, with each operand given as its high limb, then its low limb
+    let a = [Felt::new(1), Felt::new(u32::MAX as u64)];
+    let b = [Felt::new(2), Felt::new(1)];
+    let mut stack = harness
+        .execute_program(program.freeze(), &[a[0], a[1], b[0], b[1]])
+        .expect("execution failed");
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack.pop().map(|e| e.as_int()), Some(4));
+    assert_eq!(stack.pop().map(|e| e.as_int()), Some(0));
+}
+
+#[test]
+fn mem_memory_grow() {
+    let mut harness = TestByEmulationHarness::default();
+
+    harness
+        .emulator
+        .load_module(
+            Box::new(
+                intrinsics::load("intrinsics::mem", &harness.context.session.codemap)
+                    .expect("undefined intrinsic module"),
+            )
+            .freeze(),
+        )
+        .expect("failed to load intrinsics::mem");
+
+    let memory_grow = "intrinsics::mem::memory_grow".parse().unwrap();
+    let max_pages = Felt::new(16);
+    let size = Felt::new(4);
+    // NOTE: arguments are passed in reverse, i.e. [max_pages, size, pages] not
+    // [pages, size, max_pages]
+    for (pages, expected) in [
+        (0, 4),
+        (1, 4),
+        (12, 4),
+        (13, u32::MAX as u64),
+        (u32::MAX as u64, u32::MAX as u64),
+    ] {
+        let mut stack = harness
+            .invoke(memory_grow, &[max_pages, size, Felt::new(pages)])
+            .expect("execution failed");
+        harness.emulator.stop();
+        assert_eq!(stack.len(), 1);
+        assert_eq!(
+            stack.pop().unwrap().as_int(),
+            expected,
+            "growing by {pages} pages"
+        );
+    }
+}
+
 proptest! {
     #![proptest_config(ProptestConfig { cases: 1000, failure_persistence: None, ..Default::default() })]
 
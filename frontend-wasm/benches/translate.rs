@@ -0,0 +1,56 @@
+//! Measures the translation of Wasm modules with a large number of functions to Miden IR.
+//!
+//! The modules are synthetic: each function is exported and does the same small amount of work,
+//! so the time per function should be constant, and the translation time should grow linearly
+//! with the number of functions, including past 2^16 functions. This can be checked by comparing
+//! the `translate/N` results for the different sizes.
+//!
+//! Run with `cargo bench -p miden-frontend-wasm --bench translate`.
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use miden_diagnostics::{
+    term::termcolor::ColorChoice, CodeMap, DiagnosticsConfig, DiagnosticsHandler, NullEmitter,
+    Verbosity,
+};
+use miden_frontend_wasm::{translate_module, WasmTranslationConfig};
+
+/// Build a Wasm module of `num_funcs` exported functions
+fn synthetic_module(num_funcs: usize) -> Vec<u8> {
+    let mut wat = String::from("(module\n");
+    for i in 0..num_funcs {
+        wat.push_str(&format!(
+            "(func $f{i} (export \"f{i}\") (param i32) (result i32) local.get 0 i32.const {i} \
+             i32.add)\n"
+        ));
+    }
+    wat.push(')');
+    wat::parse_str(wat).expect("invalid benchmark module")
+}
+
+fn translate(c: &mut Criterion) {
+    let diagnostics = DiagnosticsHandler::new(
+        DiagnosticsConfig {
+            verbosity: Verbosity::Silent,
+            warnings_as_errors: false,
+            no_warn: true,
+            display: Default::default(),
+        },
+        Arc::new(CodeMap::new()),
+        Arc::new(NullEmitter::new(ColorChoice::Never)),
+    );
+    let config = WasmTranslationConfig::default();
+    let mut group = c.benchmark_group("translate");
+    group.sample_size(10);
+    for num_funcs in [1_000, 10_000, 50_000, 70_000] {
+        let wasm = synthetic_module(num_funcs);
+        group.throughput(Throughput::Elements(num_funcs as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(num_funcs), &wasm, |b, wasm| {
+            b.iter(|| translate_module(wasm, &config, &diagnostics).expect("translation failed"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, translate);
+criterion_main!(benches);
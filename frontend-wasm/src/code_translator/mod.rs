@@ -16,17 +16,24 @@
 use std::collections::hash_map;
 use std::u64;
 
+use crate::component::SignatureIndex;
 use crate::error::{WasmError, WasmResult};
 use crate::module::func_translation_state::{ControlStackFrame, ElseData, FuncTranslationState};
 use crate::module::function_builder_ext::FunctionBuilderExt;
-use crate::module::types::{ir_type, BlockType, FuncIndex, GlobalIndex, ModuleTypes};
-use crate::module::Module;
+use crate::module::types::{
+    ir_func_sig, ir_func_type, ir_type, BlockType, DataIndex, FuncIndex, GlobalIndex, MemoryIndex,
+    ModuleTypes, TableIndex, TypeIndex,
+};
+use crate::module::{Module, TableInitialValue, TableSegmentElement};
 use crate::ssa::Variable;
-use crate::unsupported_diag;
+use crate::{unsupported_diag, UnsupportedFeature, WasmTranslationConfig};
 use miden_diagnostics::{DiagnosticsHandler, SourceSpan};
 use miden_hir::cranelift_entity::packed_option::ReservedValue;
 use miden_hir::Type::*;
-use miden_hir::{Block, Inst, InstBuilder, Value};
+use miden_hir::{
+    AbiParam, Block, CallConv, FunctionIdent, FunctionType, Ident, Inst, InstBuilder, Instruction,
+    Linkage, Opcode, Signature, Symbol, UnaryOpImm, Value, ValueData,
+};
 use miden_hir::{Immediate, Type};
 use rustc_hash::FxHashMap;
 use wasmparser::{MemArg, Operator};
@@ -38,6 +45,10 @@ mod tests;
 mod tests_unsupported;
 
 /// Translates wasm operators into Miden IR instructions.
+///
+/// `offset` is the offset of `op` in the Wasm binary, which is used to locate the operators which
+/// cannot be translated.
+#[allow(clippy::too_many_arguments)]
 pub fn translate_operator(
     op: &Operator,
     builder: &mut FunctionBuilderExt,
@@ -45,7 +56,9 @@ pub fn translate_operator(
     module: &Module,
     mod_types: &ModuleTypes,
     diagnostics: &DiagnosticsHandler,
+    config: &WasmTranslationConfig,
     span: SourceSpan,
+    offset: usize,
 ) -> WasmResult<()> {
     if !state.reachable {
         translate_unreachable_operator(&op, builder, state, mod_types, span)?;
@@ -55,6 +68,18 @@ pub fn translate_operator(
     // Given that we believe the current block is reachable, the FunctionBuilderExt ought to agree.
     debug_assert!(!builder.is_unreachable());
 
+    // A module may declare several memories, but only accesses to the first can be lowered, as
+    // the Miden VM exposes a single linear memory
+    if let Some(memarg) = memarg_of(op).filter(|memarg| memarg.memory != 0) {
+        unsupported_diag!(
+            diagnostics,
+            UnsupportedFeature::MultiMemory,
+            "Wasm op {:?} accesses memory {}, but only memory 0 is supported",
+            op,
+            memarg.memory
+        );
+    }
+
     match op {
         /********************************** Locals ****************************************
          *  `get_local` and `set_local` are treated as non-SSA variables and will completely
@@ -98,10 +123,7 @@ pub fn translate_operator(
             let cond_i1 = builder.ins().neq_imm(cond, Immediate::I32(0), span);
             state.push1(builder.ins().select(cond_i1, arg1, arg2, span));
         }
-        Operator::Unreachable => {
-            builder.ins().unreachable(span);
-            state.reachable = false;
-        }
+        Operator::Unreachable => translate_trap(state, builder, span),
         Operator::Nop => {}
         /***************************** Control flow blocks *********************************/
         Operator::Block { blockty } => translate_block(blockty, builder, state, mod_types, span)?,
@@ -111,8 +133,10 @@ pub fn translate_operator(
         Operator::End => translate_end(state, builder, span),
 
         /**************************** Branch instructions *********************************/
-        Operator::Br { relative_depth } => translate_br(state, relative_depth, builder, span),
-        Operator::BrIf { relative_depth } => translate_br_if(*relative_depth, builder, state, span),
+        Operator::Br { relative_depth } => translate_br(state, relative_depth, builder, span)?,
+        Operator::BrIf { relative_depth } => {
+            translate_br_if(*relative_depth, builder, state, span)?
+        }
         Operator::BrTable { targets } => translate_br_table(targets, state, builder, span)?,
         Operator::Return => translate_return(state, builder, diagnostics, span)?,
         /************************************ Calls ****************************************/
@@ -127,14 +151,92 @@ pub fn translate_operator(
                 diagnostics,
             )?;
         }
+        Operator::CallIndirect {
+            type_index,
+            table_index,
+            ..
+        } => {
+            translate_call_indirect(
+                state,
+                builder,
+                TypeIndex::from_u32(*type_index),
+                TableIndex::from_u32(*table_index),
+                module,
+                mod_types,
+                span,
+                diagnostics,
+            )?;
+        }
+        Operator::CallRef { type_index } => {
+            translate_call_ref(
+                state,
+                builder,
+                TypeIndex::from_u32(*type_index),
+                module,
+                mod_types,
+                span,
+            )?;
+        }
+        // Miden has no tail calls, so these are lowered to a call, immediately followed by a
+        // return of its results, i.e. the stack of the caller is not reused by the callee
+        Operator::ReturnCall { function_index } => {
+            translate_call(
+                state,
+                builder,
+                FuncIndex::from_u32(*function_index),
+                module,
+                mod_types,
+                span,
+                diagnostics,
+            )?;
+            translate_return(state, builder, diagnostics, span)?;
+        }
+        Operator::ReturnCallIndirect {
+            type_index,
+            table_index,
+        } => {
+            translate_call_indirect(
+                state,
+                builder,
+                TypeIndex::from_u32(*type_index),
+                TableIndex::from_u32(*table_index),
+                module,
+                mod_types,
+                span,
+                diagnostics,
+            )?;
+            translate_return(state, builder, diagnostics, span)?;
+        }
+        Operator::ReturnCallRef { type_index } => {
+            translate_call_ref(
+                state,
+                builder,
+                TypeIndex::from_u32(*type_index),
+                module,
+                mod_types,
+                span,
+            )?;
+            translate_return(state, builder, diagnostics, span)?;
+        }
         /******************************* Memory management *********************************/
+        Operator::MemoryGrow { mem, .. } | Operator::MemorySize { mem, .. } if *mem != 0 => {
+            unsupported_diag!(
+                diagnostics,
+                UnsupportedFeature::MultiMemory,
+                "Wasm op {:?} accesses memory {}, but only memory 0 is supported",
+                op,
+                mem
+            );
+        }
         Operator::MemoryGrow { .. } => {
-            let arg = state.pop1_casted(U32, builder, span);
-            state.push1(builder.ins().mem_grow(arg, span));
+            translate_memory_grow(state, builder, module, config, span, diagnostics)?;
         }
         Operator::MemorySize { .. } => {
-            // Return total Miden memory size
-            state.push1(builder.ins().i32(mem_total_pages(), span));
+            // The current size is tracked in a global, see `translate_memory_grow`
+            let size = builder
+                .ins()
+                .load_symbol(module.memory_size_name(), I32, span);
+            state.push1(size);
         }
         /******************************* Bulk memory operations *********************************/
         Operator::MemoryCopy { dst_mem, src_mem } => {
@@ -147,55 +249,330 @@ pub fn translate_operator(
                 let src = prepare_addr(src_i32, &U8, None, builder, span);
                 builder.ins().memcpy(src, dst, len, span);
             } else {
-                unsupported_diag!(diagnostics, "MemoryCopy: only single memory is supported");
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::MultiMemory,
+                    "MemoryCopy: only single memory is supported"
+                );
+            }
+        }
+        Operator::MemoryInit { data_index, mem } => {
+            // See semantics at https://github.com/WebAssembly/bulk-memory-operations/blob/master/proposals/bulk-memory-operations/Overview.md#memoryinit-instruction
+            if *mem == 0 {
+                translate_memory_init(
+                    DataIndex::from_u32(*data_index),
+                    state,
+                    builder,
+                    module,
+                    span,
+                );
+            } else {
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::MultiMemory,
+                    "MemoryInit: only single memory is supported"
+                );
+            }
+        }
+        Operator::DataDrop { data_index } => {
+            // Dropping a segment is equivalent to truncating it to zero length. Active segments
+            // are implicitly dropped once they are placed in memory, and empty segments have no
+            // contents to drop, so only non-empty passive segments need to be handled.
+            let data_index = DataIndex::from_u32(*data_index);
+            if module
+                .passive_data_map
+                .get(&data_index)
+                .is_some_and(|data| !data.is_empty())
+            {
+                let dropped_ptr = builder.ins().symbol_addr(
+                    module.passive_data_dropped_name(data_index),
+                    Ptr(U32.into()),
+                    span,
+                );
+                let dropped = builder.ins().u32(1, span);
+                builder.ins().store(dropped_ptr, dropped, span);
+            }
+        }
+        /******************************* Atomic operations **********************************/
+        // The Miden VM is single-threaded, so there can never be another thread to wait on, or to
+        // be notified. `notify` therefore wakes no waiters, and `wait` can only report that the
+        // value differs from the expected one, or time out, see [translate_atomic_wait].
+        Operator::MemoryAtomicNotify { .. } if config.single_threaded_atomics => {
+            warn_single_threaded_atomic(op, "a constant result", diagnostics);
+            // Discard the address and waiter count, and report that no waiters were woken
+            state.popn(2);
+            state.push1(builder.ins().i32(0, span));
+        }
+        Operator::MemoryAtomicWait32 { memarg } | Operator::MemoryAtomicWait64 { memarg }
+            if config.single_threaded_atomics =>
+        {
+            let memory = &module.memories[MemoryIndex::from_u32(memarg.memory)];
+            if memory.shared {
+                warn_single_threaded_atomic(op, "a comparison", diagnostics);
+                translate_atomic_wait(op, memarg, state, builder, config, span);
+            } else {
+                // Waiting on a memory which is not shared traps, per the threads proposal
+                state.popn(3);
+                translate_trap(state, builder, span);
+            }
+        }
+        // Without another thread to observe memory in between, atomic accesses are equivalent to
+        // plain ones, and a read-modify-write to a load followed by a store
+        Operator::AtomicFence if config.single_threaded_atomics => {
+            warn_single_threaded_atomic(op, "a no-op", diagnostics);
+        }
+        Operator::I32AtomicLoad { memarg }
+        | Operator::I64AtomicLoad { memarg }
+        | Operator::I32AtomicLoad8U { memarg }
+        | Operator::I32AtomicLoad16U { memarg }
+        | Operator::I64AtomicLoad8U { memarg }
+        | Operator::I64AtomicLoad16U { memarg }
+        | Operator::I64AtomicLoad32U { memarg }
+            if config.single_threaded_atomics =>
+        {
+            warn_single_threaded_atomic(op, "a plain load", diagnostics);
+            let (access_ty, ty) = atomic_access_types(op);
+            if access_ty == ty {
+                translate_load(ty, memarg, state, builder, config, span);
+            } else {
+                translate_load_zext(access_ty, ty, memarg, state, builder, config, span);
             }
         }
+        Operator::I32AtomicStore { memarg }
+        | Operator::I64AtomicStore { memarg }
+        | Operator::I32AtomicStore8 { memarg }
+        | Operator::I32AtomicStore16 { memarg }
+        | Operator::I64AtomicStore8 { memarg }
+        | Operator::I64AtomicStore16 { memarg }
+        | Operator::I64AtomicStore32 { memarg }
+            if config.single_threaded_atomics =>
+        {
+            warn_single_threaded_atomic(op, "a plain store", diagnostics);
+            let (access_ty, _) = atomic_access_types(op);
+            translate_store(access_ty, memarg, state, builder, config, span);
+        }
+        Operator::I32AtomicRmwAdd { memarg }
+        | Operator::I64AtomicRmwAdd { memarg }
+        | Operator::I32AtomicRmw8AddU { memarg }
+        | Operator::I32AtomicRmw16AddU { memarg }
+        | Operator::I64AtomicRmw8AddU { memarg }
+        | Operator::I64AtomicRmw16AddU { memarg }
+        | Operator::I64AtomicRmw32AddU { memarg }
+            if config.single_threaded_atomics =>
+        {
+            warn_single_threaded_atomic(op, "a load and a store", diagnostics);
+            translate_atomic_rmw(
+                op,
+                memarg,
+                |builder, old, val| builder.ins().add_wrapping(old, val, span),
+                state,
+                builder,
+                config,
+                span,
+            );
+        }
+        Operator::I32AtomicRmwSub { memarg }
+        | Operator::I64AtomicRmwSub { memarg }
+        | Operator::I32AtomicRmw8SubU { memarg }
+        | Operator::I32AtomicRmw16SubU { memarg }
+        | Operator::I64AtomicRmw8SubU { memarg }
+        | Operator::I64AtomicRmw16SubU { memarg }
+        | Operator::I64AtomicRmw32SubU { memarg }
+            if config.single_threaded_atomics =>
+        {
+            warn_single_threaded_atomic(op, "a load and a store", diagnostics);
+            translate_atomic_rmw(
+                op,
+                memarg,
+                |builder, old, val| builder.ins().sub_wrapping(old, val, span),
+                state,
+                builder,
+                config,
+                span,
+            );
+        }
+        Operator::I32AtomicRmwAnd { memarg }
+        | Operator::I64AtomicRmwAnd { memarg }
+        | Operator::I32AtomicRmw8AndU { memarg }
+        | Operator::I32AtomicRmw16AndU { memarg }
+        | Operator::I64AtomicRmw8AndU { memarg }
+        | Operator::I64AtomicRmw16AndU { memarg }
+        | Operator::I64AtomicRmw32AndU { memarg }
+            if config.single_threaded_atomics =>
+        {
+            warn_single_threaded_atomic(op, "a load and a store", diagnostics);
+            translate_atomic_rmw(
+                op,
+                memarg,
+                |builder, old, val| builder.ins().band(old, val, span),
+                state,
+                builder,
+                config,
+                span,
+            );
+        }
+        Operator::I32AtomicRmwOr { memarg }
+        | Operator::I64AtomicRmwOr { memarg }
+        | Operator::I32AtomicRmw8OrU { memarg }
+        | Operator::I32AtomicRmw16OrU { memarg }
+        | Operator::I64AtomicRmw8OrU { memarg }
+        | Operator::I64AtomicRmw16OrU { memarg }
+        | Operator::I64AtomicRmw32OrU { memarg }
+            if config.single_threaded_atomics =>
+        {
+            warn_single_threaded_atomic(op, "a load and a store", diagnostics);
+            translate_atomic_rmw(
+                op,
+                memarg,
+                |builder, old, val| builder.ins().bor(old, val, span),
+                state,
+                builder,
+                config,
+                span,
+            );
+        }
+        Operator::I32AtomicRmwXor { memarg }
+        | Operator::I64AtomicRmwXor { memarg }
+        | Operator::I32AtomicRmw8XorU { memarg }
+        | Operator::I32AtomicRmw16XorU { memarg }
+        | Operator::I64AtomicRmw8XorU { memarg }
+        | Operator::I64AtomicRmw16XorU { memarg }
+        | Operator::I64AtomicRmw32XorU { memarg }
+            if config.single_threaded_atomics =>
+        {
+            warn_single_threaded_atomic(op, "a load and a store", diagnostics);
+            translate_atomic_rmw(
+                op,
+                memarg,
+                |builder, old, val| builder.ins().bxor(old, val, span),
+                state,
+                builder,
+                config,
+                span,
+            );
+        }
+        Operator::I32AtomicRmwXchg { memarg }
+        | Operator::I64AtomicRmwXchg { memarg }
+        | Operator::I32AtomicRmw8XchgU { memarg }
+        | Operator::I32AtomicRmw16XchgU { memarg }
+        | Operator::I64AtomicRmw8XchgU { memarg }
+        | Operator::I64AtomicRmw16XchgU { memarg }
+        | Operator::I64AtomicRmw32XchgU { memarg }
+            if config.single_threaded_atomics =>
+        {
+            warn_single_threaded_atomic(op, "a load and a store", diagnostics);
+            translate_atomic_rmw(op, memarg, |_, _, val| val, state, builder, config, span);
+        }
+        Operator::I32AtomicRmwCmpxchg { memarg }
+        | Operator::I64AtomicRmwCmpxchg { memarg }
+        | Operator::I32AtomicRmw8CmpxchgU { memarg }
+        | Operator::I32AtomicRmw16CmpxchgU { memarg }
+        | Operator::I64AtomicRmw8CmpxchgU { memarg }
+        | Operator::I64AtomicRmw16CmpxchgU { memarg }
+        | Operator::I64AtomicRmw32CmpxchgU { memarg }
+            if config.single_threaded_atomics =>
+        {
+            warn_single_threaded_atomic(op, "a load and a store", diagnostics);
+            translate_atomic_cmpxchg(op, memarg, state, builder, config, span);
+        }
+        /******************************* Table operations ***********************************/
+        // The operand types have already been checked against the table's element type by the
+        // validator, but reference types have no representation in Miden IR yet
+        Operator::TableGet { table } | Operator::TableSet { table } => {
+            let table_index = TableIndex::from_u32(*table);
+            let elem_ty = module.tables[table_index].wasm_ty;
+            unsupported_diag!(
+                diagnostics,
+                UnsupportedFeature::Operator,
+                "Wasm op {:?} on a table of {elem_ty} elements is not supported",
+                op
+            );
+        }
+        /***************************** Reference instructions ********************************/
+        // References are represented as 32-bit handles: null is zero, an `i31ref` is tagged by
+        // setting the lowest bit, i.e. `(value << 1) | 1`, and any other reference is a pointer,
+        // which is always word-aligned, so two references are identical iff their handles are.
+        // Function references are the index of the function plus one, see `translate_call_ref`
+        Operator::RefNull { .. } => state.push1(builder.ins().u32(0, span)),
+        Operator::RefFunc { function_index } => {
+            state.push1(builder.ins().u32(*function_index + 1, span));
+        }
+        Operator::RefIsNull => {
+            let arg = state.pop1();
+            let val = builder.ins().eq_imm(arg, Immediate::U32(0), span);
+            state.push1(builder.ins().cast(val, I32, span));
+        }
+        Operator::RefEq => {
+            let (arg0, arg1) = state.pop2();
+            let val = builder.ins().eq(arg0, arg1, span);
+            state.push1(builder.ins().cast(val, I32, span));
+        }
+        Operator::RefI31 => {
+            // The most significant bit of the value is discarded by the shift
+            let arg = state.pop1_casted(U32, builder, span);
+            let shifted = builder.ins().shl_imm_wrapping(arg, Immediate::U32(1), span);
+            state.push1(builder.ins().bor_imm(shifted, Immediate::U32(1), span));
+        }
         /******************************* Load instructions ***********************************/
         Operator::I32Load8U { memarg } => {
-            translate_load_zext(U8, I32, memarg, state, builder, span)
+            translate_load_zext(U8, I32, memarg, state, builder, config, span)
         }
         Operator::I32Load16U { memarg } => {
-            translate_load_zext(U16, I32, memarg, state, builder, span)
+            translate_load_zext(U16, I32, memarg, state, builder, config, span)
         }
         Operator::I32Load8S { memarg } => {
-            translate_load_sext(I8, I32, memarg, state, builder, span);
+            translate_load_sext(I8, I32, memarg, state, builder, config, span);
         }
         Operator::I32Load16S { memarg } => {
-            translate_load_sext(I16, I32, memarg, state, builder, span);
+            translate_load_sext(I16, I32, memarg, state, builder, config, span);
         }
         Operator::I64Load8U { memarg } => {
-            translate_load_zext(U8, I64, memarg, state, builder, span)
+            translate_load_zext(U8, I64, memarg, state, builder, config, span)
         }
         Operator::I64Load16U { memarg } => {
-            translate_load_zext(U16, I64, memarg, state, builder, span)
+            translate_load_zext(U16, I64, memarg, state, builder, config, span)
         }
         Operator::I64Load8S { memarg } => {
-            translate_load_sext(I8, I64, memarg, state, builder, span);
+            translate_load_sext(I8, I64, memarg, state, builder, config, span);
         }
         Operator::I64Load16S { memarg } => {
-            translate_load_sext(I16, I64, memarg, state, builder, span);
+            translate_load_sext(I16, I64, memarg, state, builder, config, span);
         }
         Operator::I64Load32S { memarg } => {
-            translate_load_sext(I32, I64, memarg, state, builder, span)
+            translate_load_sext(I32, I64, memarg, state, builder, config, span)
         }
         Operator::I64Load32U { memarg } => {
-            translate_load_zext(U32, I64, memarg, state, builder, span)
+            translate_load_zext(U32, I64, memarg, state, builder, config, span)
         }
-        Operator::I32Load { memarg } => translate_load(I32, memarg, state, builder, span),
-        Operator::I64Load { memarg } => translate_load(I64, memarg, state, builder, span),
+        Operator::I32Load { memarg } => translate_load(I32, memarg, state, builder, config, span),
+        Operator::I64Load { memarg } => translate_load(I64, memarg, state, builder, config, span),
         /****************************** Store instructions ***********************************/
-        Operator::I32Store { memarg } => translate_store(I32, memarg, state, builder, span),
-        Operator::I64Store { memarg } => translate_store(I64, memarg, state, builder, span),
+        Operator::I32Store { memarg } => translate_store(I32, memarg, state, builder, config, span),
+        Operator::I64Store { memarg } => translate_store(I64, memarg, state, builder, config, span),
         Operator::I32Store8 { memarg } | Operator::I64Store8 { memarg } => {
-            translate_store(U8, memarg, state, builder, span);
+            translate_store(U8, memarg, state, builder, config, span);
         }
         Operator::I32Store16 { memarg } | Operator::I64Store16 { memarg } => {
-            translate_store(U16, memarg, state, builder, span);
+            translate_store(U16, memarg, state, builder, config, span);
+        }
+        Operator::I64Store32 { memarg } => {
+            translate_store(U32, memarg, state, builder, config, span)
         }
-        Operator::I64Store32 { memarg } => translate_store(U32, memarg, state, builder, span),
         /****************************** Nullary Operators **********************************/
-        Operator::I32Const { value } => state.push1(builder.ins().i32(*value, span)),
-        Operator::I64Const { value } => state.push1(builder.ins().i64(*value, span)),
+        Operator::I32Const { value } => {
+            let val = builder.ins().i32(*value, span);
+            if config.felt_bounded_integers && *value >= 0 {
+                state.set_upper_bound(val, *value as u64);
+            }
+            state.push1(val);
+        }
+        Operator::I64Const { value } => {
+            let val = builder.ins().i64(*value, span);
+            if config.felt_bounded_integers && *value >= 0 {
+                state.set_upper_bound(val, *value as u64);
+            }
+            state.push1(val);
+        }
 
         /******************************* Unary Operators *************************************/
         Operator::I32Clz | Operator::I32Ctz => {
@@ -228,12 +605,166 @@ pub fn translate_operator(
             let val = state.pop1();
             state.push1(builder.ins().trunc(val, I32, span));
         }
+        Operator::I32ReinterpretF32 => {
+            let val = state.pop1();
+            state.push1(builder.ins().bitcast(val, I32, span));
+        }
+        Operator::F32ReinterpretI32 => {
+            let val = state.pop1();
+            state.push1(builder.ins().bitcast(val, F32, span));
+        }
+        Operator::I64ReinterpretF64 => {
+            let val = state.pop1();
+            state.push1(builder.ins().bitcast(val, I64, span));
+        }
+        Operator::F64ReinterpretI64 => {
+            let val = state.pop1();
+            state.push1(builder.ins().bitcast(val, F64, span));
+        }
+        Operator::F64PromoteF32 => {
+            let val = state.pop1();
+            state.push1(translate_promote_f32(val, builder, span));
+        }
+        Operator::F32DemoteF64 => {
+            let val = state.pop1();
+            state.push1(translate_demote_f64(val, builder, span));
+        }
+        // Promoting an `f32` value to `f64` is exact, and keeps NaNs as NaNs, so the truncations of
+        // `f32` are those of the promoted value
+        Operator::I32TruncF32S => {
+            let val = translate_promote_f32(state.pop1(), builder, span);
+            state.push1(translate_trunc_f64(val, I32, builder, span));
+        }
+        Operator::I32TruncF32U => {
+            let val = translate_promote_f32(state.pop1(), builder, span);
+            state.push1(translate_trunc_f64(val, U32, builder, span));
+        }
+        Operator::I64TruncF32S => {
+            let val = translate_promote_f32(state.pop1(), builder, span);
+            state.push1(translate_trunc_f64(val, I64, builder, span));
+        }
+        Operator::I64TruncF32U => {
+            let val = translate_promote_f32(state.pop1(), builder, span);
+            state.push1(translate_trunc_f64(val, U64, builder, span));
+        }
+        Operator::I32TruncF64S => {
+            let val = state.pop1();
+            state.push1(translate_trunc_f64(val, I32, builder, span));
+        }
+        Operator::I32TruncF64U => {
+            let val = state.pop1();
+            state.push1(translate_trunc_f64(val, U32, builder, span));
+        }
+        Operator::I64TruncF64S => {
+            let val = state.pop1();
+            state.push1(translate_trunc_f64(val, I64, builder, span));
+        }
+        Operator::I64TruncF64U => {
+            let val = state.pop1();
+            state.push1(translate_trunc_f64(val, U64, builder, span));
+        }
+        /**************************** Floating-point operators *******************************/
+        // There are no floating-point constants or instructions in Miden IR, so constants are
+        // built from their bit patterns, and these operators are performed in software on the
+        // bit patterns of their operands, see [FloatFormat]
+        Operator::F32Const { value } => {
+            let bits = builder.ins().u32(value.bits(), span);
+            state.push1(builder.ins().bitcast(bits, F32, span));
+        }
+        Operator::F64Const { value } => {
+            let bits = builder.ins().u64(value.bits(), span);
+            state.push1(builder.ins().bitcast(bits, F64, span));
+        }
+        Operator::F32Abs | Operator::F64Abs => {
+            let val = state.pop1();
+            let format = FloatFormat::of(op);
+            let bits = format.bits_of(val, builder, span);
+            let bits = builder
+                .ins()
+                .band_imm(bits, format.imm(format.magnitude_mask()), span);
+            state.push1(format.float_of(bits, builder, span));
+        }
+        Operator::F32Neg | Operator::F64Neg => {
+            let val = state.pop1();
+            let format = FloatFormat::of(op);
+            let bits = format.bits_of(val, builder, span);
+            let bits = builder
+                .ins()
+                .bxor_imm(bits, format.imm(format.sign_mask()), span);
+            state.push1(format.float_of(bits, builder, span));
+        }
+        Operator::F32Copysign | Operator::F64Copysign => {
+            let (arg1, arg2) = state.pop2();
+            let format = FloatFormat::of(op);
+            let magnitude = format.bits_of(arg1, builder, span);
+            let magnitude =
+                builder
+                    .ins()
+                    .band_imm(magnitude, format.imm(format.magnitude_mask()), span);
+            let sign = format.bits_of(arg2, builder, span);
+            let sign = builder
+                .ins()
+                .band_imm(sign, format.imm(format.sign_mask()), span);
+            let bits = builder.ins().bor(magnitude, sign, span);
+            state.push1(format.float_of(bits, builder, span));
+        }
+        // The operators which take more than a few instructions are performed by intrinsics, i.e.
+        // functions shared by all of their uses in the module, see [FloatIntrinsic]
+        Operator::F32Min | Operator::F64Min | Operator::F32Max | Operator::F64Max => {
+            let (arg1, arg2) = state.pop2();
+            let float_op = if matches!(op, Operator::F32Max | Operator::F64Max) {
+                FloatOp::Max
+            } else {
+                FloatOp::Min
+            };
+            let intrinsic = FloatIntrinsic {
+                op: float_op,
+                format: FloatFormat::of(op),
+            };
+            let result = emit_float_intrinsic_call(intrinsic, &[arg1, arg2], state, builder, span);
+            state.push1(result);
+        }
+        Operator::F32Trunc
+        | Operator::F64Trunc
+        | Operator::F32Floor
+        | Operator::F64Floor
+        | Operator::F32Ceil
+        | Operator::F64Ceil
+        | Operator::F32Nearest
+        | Operator::F64Nearest => {
+            let val = state.pop1();
+            let rounding = match op {
+                Operator::F32Trunc | Operator::F64Trunc => Rounding::Trunc,
+                Operator::F32Floor | Operator::F64Floor => Rounding::Floor,
+                Operator::F32Ceil | Operator::F64Ceil => Rounding::Ceil,
+                _ => Rounding::Nearest,
+            };
+            let intrinsic = FloatIntrinsic {
+                op: FloatOp::Round(rounding),
+                format: FloatFormat::of(op),
+            };
+            let result = emit_float_intrinsic_call(intrinsic, &[val], state, builder, span);
+            state.push1(result);
+        }
         /****************************** Binary Operators ************************************/
         Operator::I32Add | Operator::I64Add => {
             let (arg1, arg2) = state.pop2();
-            // wrapping because the result is mod 2^N
-            // https://www.w3.org/TR/wasm-core-1/#op-iadd
-            state.push1(builder.ins().add_wrapping(arg1, arg2, span));
+            let max = if let Operator::I32Add = op {
+                i32::MAX as u64
+            } else {
+                i64::MAX as u64
+            };
+            match bounded_sum(config, state, arg1, arg2, max) {
+                Some(bound) => {
+                    // the result provably fits, so no wrapping is required
+                    let val = builder.ins().add_unchecked(arg1, arg2, span);
+                    state.set_upper_bound(val, bound);
+                    state.push1(val);
+                }
+                // wrapping because the result is mod 2^N
+                // https://www.w3.org/TR/wasm-core-1/#op-iadd
+                None => state.push1(builder.ins().add_wrapping(arg1, arg2, span)),
+            }
         }
         Operator::I32And | Operator::I64And => {
             let (arg1, arg2) = state.pop2();
@@ -299,42 +830,90 @@ pub fn translate_operator(
         }
         Operator::I32DivS | Operator::I64DivS => {
             let (arg1, arg2) = state.pop2();
-            state.push1(builder.ins().div_checked(arg1, arg2, span));
+            state.push1(translate_div_or_rem(
+                arg1,
+                arg2,
+                |builder, lhs, rhs| builder.ins().div_checked(lhs, rhs, span),
+                config,
+                builder,
+                span,
+            ));
         }
         Operator::I32DivU => {
             let (arg1, arg2) = state.pop2_casted(U32, builder, span);
-            let val = builder.ins().div_checked(arg1, arg2, span);
+            let val = translate_div_or_rem(
+                arg1,
+                arg2,
+                |builder, lhs, rhs| builder.ins().div_checked(lhs, rhs, span),
+                config,
+                builder,
+                span,
+            );
             state.push1(builder.ins().cast(val, I32, span));
         }
         Operator::I64DivU => {
             let (arg1, arg2) = state.pop2_casted(U64, builder, span);
-            let val = builder.ins().div_checked(arg1, arg2, span);
+            let val = translate_div_or_rem(
+                arg1,
+                arg2,
+                |builder, lhs, rhs| builder.ins().div_checked(lhs, rhs, span),
+                config,
+                builder,
+                span,
+            );
             state.push1(builder.ins().cast(val, I64, span));
         }
         Operator::I32RemU => {
             let (arg1, arg2) = state.pop2_casted(U32, builder, span);
-            let val = builder.ins().r#mod_checked(arg1, arg2, span);
+            let val = translate_div_or_rem(
+                arg1,
+                arg2,
+                |builder, lhs, rhs| builder.ins().r#mod_checked(lhs, rhs, span),
+                config,
+                builder,
+                span,
+            );
             state.push1(builder.ins().cast(val, I32, span));
         }
         Operator::I64RemU => {
             let (arg1, arg2) = state.pop2_casted(U64, builder, span);
-            let val = builder.ins().r#mod_checked(arg1, arg2, span);
+            let val = translate_div_or_rem(
+                arg1,
+                arg2,
+                |builder, lhs, rhs| builder.ins().r#mod_checked(lhs, rhs, span),
+                config,
+                builder,
+                span,
+            );
             state.push1(builder.ins().cast(val, I64, span));
         }
         Operator::I32RemS | Operator::I64RemS => {
             let (arg1, arg2) = state.pop2();
-            state.push1(builder.ins().r#mod_checked(arg1, arg2, span));
+            state.push1(translate_div_or_rem(
+                arg1,
+                arg2,
+                |builder, lhs, rhs| builder.ins().r#mod_checked(lhs, rhs, span),
+                config,
+                builder,
+                span,
+            ));
         }
         /**************************** Comparison Operators **********************************/
         Operator::I32LtU => {
+            let (lhs, rhs) = (state.peekn(2)[0], state.peekn(2)[1]);
             let (arg0, arg1) = state.pop2_casted(U32, builder, span);
             let val = builder.ins().lt(arg0, arg1, span);
-            state.push1(builder.ins().cast(val, I32, span));
+            let cond = builder.ins().cast(val, I32, span);
+            record_bound_condition(config, state, cond, lhs, rhs, false, true);
+            state.push1(cond);
         }
         Operator::I64LtU => {
+            let (lhs, rhs) = (state.peekn(2)[0], state.peekn(2)[1]);
             let (arg0, arg1) = state.pop2_casted(U64, builder, span);
             let val = builder.ins().lt(arg0, arg1, span);
-            state.push1(builder.ins().cast(val, I32, span));
+            let cond = builder.ins().cast(val, I32, span);
+            record_bound_condition(config, state, cond, lhs, rhs, false, true);
+            state.push1(cond);
         }
         Operator::I32LtS => {
             let (arg0, arg1) = state.pop2();
@@ -347,14 +926,20 @@ pub fn translate_operator(
             state.push1(builder.ins().cast(val, I32, span));
         }
         Operator::I32LeU => {
+            let (lhs, rhs) = (state.peekn(2)[0], state.peekn(2)[1]);
             let (arg0, arg1) = state.pop2_casted(U32, builder, span);
             let val = builder.ins().lte(arg0, arg1, span);
-            state.push1(builder.ins().cast(val, I32, span));
+            let cond = builder.ins().cast(val, I32, span);
+            record_bound_condition(config, state, cond, lhs, rhs, true, true);
+            state.push1(cond);
         }
         Operator::I64LeU => {
+            let (lhs, rhs) = (state.peekn(2)[0], state.peekn(2)[1]);
             let (arg0, arg1) = state.pop2_casted(U64, builder, span);
             let val = builder.ins().lte(arg0, arg1, span);
-            state.push1(builder.ins().cast(val, I32, span));
+            let cond = builder.ins().cast(val, I32, span);
+            record_bound_condition(config, state, cond, lhs, rhs, true, true);
+            state.push1(cond);
         }
         Operator::I32LeS => {
             let (arg0, arg1) = state.pop2();
@@ -367,14 +952,20 @@ pub fn translate_operator(
             state.push1(builder.ins().cast(val, I32, span));
         }
         Operator::I32GtU => {
+            let (lhs, rhs) = (state.peekn(2)[0], state.peekn(2)[1]);
             let (arg0, arg1) = state.pop2_casted(U32, builder, span);
             let val = builder.ins().gt(arg0, arg1, span);
-            state.push1(builder.ins().cast(val, I32, span));
+            let cond = builder.ins().cast(val, I32, span);
+            record_bound_condition(config, state, cond, lhs, rhs, true, false);
+            state.push1(cond);
         }
         Operator::I64GtU => {
+            let (lhs, rhs) = (state.peekn(2)[0], state.peekn(2)[1]);
             let (arg0, arg1) = state.pop2_casted(U64, builder, span);
             let val = builder.ins().gt(arg0, arg1, span);
-            state.push1(builder.ins().cast(val, I32, span));
+            let cond = builder.ins().cast(val, I32, span);
+            record_bound_condition(config, state, cond, lhs, rhs, true, false);
+            state.push1(cond);
         }
         Operator::I32GtS | Operator::I64GtS => {
             let (arg0, arg1) = state.pop2();
@@ -382,14 +973,20 @@ pub fn translate_operator(
             state.push1(builder.ins().cast(val, I32, span));
         }
         Operator::I32GeU => {
+            let (lhs, rhs) = (state.peekn(2)[0], state.peekn(2)[1]);
             let (arg0, arg1) = state.pop2_casted(U32, builder, span);
             let val = builder.ins().gte(arg0, arg1, span);
-            state.push1(builder.ins().cast(val, I32, span));
+            let cond = builder.ins().cast(val, I32, span);
+            record_bound_condition(config, state, cond, lhs, rhs, false, false);
+            state.push1(cond);
         }
         Operator::I64GeU => {
+            let (lhs, rhs) = (state.peekn(2)[0], state.peekn(2)[1]);
             let (arg0, arg1) = state.pop2_casted(U64, builder, span);
             let val = builder.ins().gte(arg0, arg1, span);
-            state.push1(builder.ins().cast(val, I32, span));
+            let cond = builder.ins().cast(val, I32, span);
+            record_bound_condition(config, state, cond, lhs, rhs, false, false);
+            state.push1(cond);
         }
         Operator::I32GeS => {
             let (arg0, arg1) = state.pop2();
@@ -431,229 +1028,2330 @@ pub fn translate_operator(
             let val = builder.ins().neq(arg0, arg1, span);
             state.push1(builder.ins().cast(val, I32, span));
         }
+        /********************************* SIMD operators ***********************************/
+        // Miden IR has no vector types, so there is nothing to lower these to yet
+        op if simd_operator_name(op).is_some() => {
+            return reject_simd_operator(op, offset, diagnostics);
+        }
         op => {
-            unsupported_diag!(diagnostics, "Wasm op {:?} is not supported", op);
+            unsupported_diag!(
+                diagnostics,
+                UnsupportedFeature::Operator,
+                "Wasm op {:?} is not supported",
+                op
+            );
         }
     };
     Ok(())
 }
 
-fn translate_br_table(
-    targets: &wasmparser::BrTable<'_>,
+/// Reject the SIMD operator `op`, found at `offset` in the Wasm binary.
+///
+/// This is [unsupported_diag!], with the offset of the operator added to the diagnostic, but not
+/// to the message of the error, so that the uses of an operator are grouped together when listing
+/// the unsupported features of a module.
+fn reject_simd_operator(
+    op: &Operator,
+    offset: usize,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let name = simd_operator_name(op).unwrap_or("v128");
+    let feature = UnsupportedFeature::Operator;
+    let message = format!("SIMD operator {name} is not supported, as Miden IR has no vector types");
+    diagnostics
+        .diagnostic(miden_diagnostics::Severity::Error)
+        .with_message(message.clone())
+        .with_note(format!(
+            "{name} is at offset {offset:#x} of the Wasm binary"
+        ))
+        .with_note(format!(
+            "{feature}: run `midenc explain {feature}` for more information"
+        ))
+        .emit();
+    Err(WasmError::UnsupportedFeature { feature, message })
+}
+
+/// Returns the name of `op` in the text format, if it is one of the common SIMD operators on
+/// `v128` values, or their `i32x4` and `f32x4` interpretations
+fn simd_operator_name(op: &Operator) -> Option<&'static str> {
+    use Operator::*;
+    Some(match op {
+        V128Load { .. } => "v128.load",
+        V128Store { .. } => "v128.store",
+        V128Const { .. } => "v128.const",
+        V128Not => "v128.not",
+        V128And => "v128.and",
+        V128AndNot => "v128.andnot",
+        V128Or => "v128.or",
+        V128Xor => "v128.xor",
+        V128Bitselect => "v128.bitselect",
+        V128AnyTrue => "v128.any_true",
+        I32x4Splat => "i32x4.splat",
+        I32x4ExtractLane { .. } => "i32x4.extract_lane",
+        I32x4ReplaceLane { .. } => "i32x4.replace_lane",
+        I32x4Eq => "i32x4.eq",
+        I32x4Ne => "i32x4.ne",
+        I32x4LtS => "i32x4.lt_s",
+        I32x4LtU => "i32x4.lt_u",
+        I32x4GtS => "i32x4.gt_s",
+        I32x4GtU => "i32x4.gt_u",
+        I32x4LeS => "i32x4.le_s",
+        I32x4LeU => "i32x4.le_u",
+        I32x4GeS => "i32x4.ge_s",
+        I32x4GeU => "i32x4.ge_u",
+        I32x4Abs => "i32x4.abs",
+        I32x4Neg => "i32x4.neg",
+        I32x4AllTrue => "i32x4.all_true",
+        I32x4Bitmask => "i32x4.bitmask",
+        I32x4Shl => "i32x4.shl",
+        I32x4ShrS => "i32x4.shr_s",
+        I32x4ShrU => "i32x4.shr_u",
+        I32x4Add => "i32x4.add",
+        I32x4Sub => "i32x4.sub",
+        I32x4Mul => "i32x4.mul",
+        I32x4MinS => "i32x4.min_s",
+        I32x4MinU => "i32x4.min_u",
+        I32x4MaxS => "i32x4.max_s",
+        I32x4MaxU => "i32x4.max_u",
+        I32x4TruncSatF32x4S => "i32x4.trunc_sat_f32x4_s",
+        I32x4TruncSatF32x4U => "i32x4.trunc_sat_f32x4_u",
+        F32x4Splat => "f32x4.splat",
+        F32x4ExtractLane { .. } => "f32x4.extract_lane",
+        F32x4ReplaceLane { .. } => "f32x4.replace_lane",
+        F32x4Eq => "f32x4.eq",
+        F32x4Ne => "f32x4.ne",
+        F32x4Lt => "f32x4.lt",
+        F32x4Gt => "f32x4.gt",
+        F32x4Le => "f32x4.le",
+        F32x4Ge => "f32x4.ge",
+        F32x4Ceil => "f32x4.ceil",
+        F32x4Floor => "f32x4.floor",
+        F32x4Trunc => "f32x4.trunc",
+        F32x4Nearest => "f32x4.nearest",
+        F32x4Abs => "f32x4.abs",
+        F32x4Neg => "f32x4.neg",
+        F32x4Sqrt => "f32x4.sqrt",
+        F32x4Add => "f32x4.add",
+        F32x4Sub => "f32x4.sub",
+        F32x4Mul => "f32x4.mul",
+        F32x4Div => "f32x4.div",
+        F32x4Min => "f32x4.min",
+        F32x4Max => "f32x4.max",
+        F32x4ConvertI32x4S => "f32x4.convert_i32x4_s",
+        F32x4ConvertI32x4U => "f32x4.convert_i32x4_u",
+        _ => return None,
+    })
+}
+
+/// Warn that the atomic operator `op` is being lowered to `lowering`, e.g. `a plain load`, as if
+/// the program is single-threaded
+fn warn_single_threaded_atomic(op: &Operator, lowering: &str, diagnostics: &DiagnosticsHandler) {
+    diagnostics
+        .diagnostic(miden_diagnostics::Severity::Warning)
+        .with_message(format!(
+            "{op:?} is lowered to {lowering}, as the target is single-threaded"
+        ))
+        .emit();
+}
+
+/// Returns the type of the memory accessed by the atomic load, store or read-modify-write `op`,
+/// along with the type of the value it operates on, e.g. `u8` and `i32` for `i32.atomic.rmw8.add_u`
+fn atomic_access_types(op: &Operator) -> (Type, Type) {
+    use Operator::*;
+    match op {
+        I32AtomicLoad { .. }
+        | I32AtomicStore { .. }
+        | I32AtomicRmwAdd { .. }
+        | I32AtomicRmwSub { .. }
+        | I32AtomicRmwAnd { .. }
+        | I32AtomicRmwOr { .. }
+        | I32AtomicRmwXor { .. }
+        | I32AtomicRmwXchg { .. }
+        | I32AtomicRmwCmpxchg { .. } => (I32, I32),
+        I64AtomicLoad { .. }
+        | I64AtomicStore { .. }
+        | I64AtomicRmwAdd { .. }
+        | I64AtomicRmwSub { .. }
+        | I64AtomicRmwAnd { .. }
+        | I64AtomicRmwOr { .. }
+        | I64AtomicRmwXor { .. }
+        | I64AtomicRmwXchg { .. }
+        | I64AtomicRmwCmpxchg { .. } => (I64, I64),
+        I32AtomicLoad8U { .. }
+        | I32AtomicStore8 { .. }
+        | I32AtomicRmw8AddU { .. }
+        | I32AtomicRmw8SubU { .. }
+        | I32AtomicRmw8AndU { .. }
+        | I32AtomicRmw8OrU { .. }
+        | I32AtomicRmw8XorU { .. }
+        | I32AtomicRmw8XchgU { .. }
+        | I32AtomicRmw8CmpxchgU { .. } => (U8, I32),
+        I32AtomicLoad16U { .. }
+        | I32AtomicStore16 { .. }
+        | I32AtomicRmw16AddU { .. }
+        | I32AtomicRmw16SubU { .. }
+        | I32AtomicRmw16AndU { .. }
+        | I32AtomicRmw16OrU { .. }
+        | I32AtomicRmw16XorU { .. }
+        | I32AtomicRmw16XchgU { .. }
+        | I32AtomicRmw16CmpxchgU { .. } => (U16, I32),
+        I64AtomicLoad8U { .. }
+        | I64AtomicStore8 { .. }
+        | I64AtomicRmw8AddU { .. }
+        | I64AtomicRmw8SubU { .. }
+        | I64AtomicRmw8AndU { .. }
+        | I64AtomicRmw8OrU { .. }
+        | I64AtomicRmw8XorU { .. }
+        | I64AtomicRmw8XchgU { .. }
+        | I64AtomicRmw8CmpxchgU { .. } => (U8, I64),
+        I64AtomicLoad16U { .. }
+        | I64AtomicStore16 { .. }
+        | I64AtomicRmw16AddU { .. }
+        | I64AtomicRmw16SubU { .. }
+        | I64AtomicRmw16AndU { .. }
+        | I64AtomicRmw16OrU { .. }
+        | I64AtomicRmw16XorU { .. }
+        | I64AtomicRmw16XchgU { .. }
+        | I64AtomicRmw16CmpxchgU { .. } => (U16, I64),
+        I64AtomicLoad32U { .. }
+        | I64AtomicStore32 { .. }
+        | I64AtomicRmw32AddU { .. }
+        | I64AtomicRmw32SubU { .. }
+        | I64AtomicRmw32AndU { .. }
+        | I64AtomicRmw32OrU { .. }
+        | I64AtomicRmw32XorU { .. }
+        | I64AtomicRmw32XchgU { .. }
+        | I64AtomicRmw32CmpxchgU { .. } => (U32, I64),
+        _ => unreachable!("{op:?} is not an atomic memory access"),
+    }
+}
+
+/// Load the value accessed by the atomic read-modify-write `op`, zero-extended to the type it
+/// operates on, from the address `addr_int`, offset by `memarg`
+fn load_atomic_operand(
+    op: &Operator,
+    addr_int: Value,
+    memarg: &MemArg,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) -> Value {
+    let (access_ty, ty) = atomic_access_types(op);
+    let val = load(&access_ty, addr_int, memarg, builder, config, span);
+    if access_ty == ty {
+        val
+    } else {
+        builder.ins().zext(val, ty, span)
+    }
+}
+
+/// Translate the atomic read-modify-write `op` as a load of the old value, followed by a store of
+/// the new value computed by `modify` from the old value and the operand. The result is the old
+/// value, zero-extended as necessary.
+fn translate_atomic_rmw(
+    op: &Operator,
+    memarg: &MemArg,
+    modify: impl FnOnce(&mut FunctionBuilderExt, Value, Value) -> Value,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) {
+    let (access_ty, _) = atomic_access_types(op);
+    let (addr_int, val) = state.pop2();
+    let old = load_atomic_operand(op, addr_int, memarg, builder, config, span);
+    let new = modify(builder, old, val);
+    store(&access_ty, addr_int, new, memarg, builder, config, span);
+    state.push1(old);
+}
+
+/// Translate the `memory.atomic.wait32/64` `op` on a shared memory, for a single-threaded target.
+/// The result is "not-equal" (1) if the value at the address differs from the expected one, or
+/// "timed-out" (2) otherwise, as there is no other thread which could notify the waiter.
+fn translate_atomic_wait(
+    op: &Operator,
+    memarg: &MemArg,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) {
+    let ty = match op {
+        Operator::MemoryAtomicWait64 { .. } => I64,
+        _ => I32,
+    };
+    let (addr_int, expected, _timeout) = state.pop3();
+    let current = load(&ty, addr_int, memarg, builder, config, span);
+    let is_expected = builder.ins().eq(current, expected, span);
+    let timed_out = builder.ins().i32(2, span);
+    let not_equal = builder.ins().i32(1, span);
+    state.push1(
+        builder
+            .ins()
+            .select(is_expected, timed_out, not_equal, span),
+    );
+}
+
+/// Translate a trap, which is caught by recording it and returning from the function early if
+/// the function has a trap flag, see [FuncTranslationState::trap_flag]
+fn translate_trap(
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) {
+    if let Some(trap_flag) = state.trap_flag.clone() {
+        let flag_ptr = builder.ins().symbol_addr(trap_flag, Ptr(U32.into()), span);
+        let trapped = builder.ins().u32(1, span);
+        builder.ins().store(flag_ptr, trapped, span);
+        builder.ins().ret(None, span);
+    } else {
+        builder.ins().unreachable(span);
+    }
+    state.reachable = false;
+}
+
+/// Translate the atomic compare-exchange `op` as a load of the old value, followed by a store of
+/// the replacement if the old value equals the expected one, wrapped to the accessed width, or of
+/// the old value otherwise. The result is the old value, zero-extended as necessary.
+fn translate_atomic_cmpxchg(
+    op: &Operator,
+    memarg: &MemArg,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) {
+    let (access_ty, ty) = atomic_access_types(op);
+    let (addr_int, expected, replacement) = state.pop3();
+    let old = load_atomic_operand(op, addr_int, memarg, builder, config, span);
+    let expected = if access_ty == ty {
+        expected
+    } else {
+        let expected = builder.ins().trunc(expected, access_ty.clone(), span);
+        builder.ins().zext(expected, ty, span)
+    };
+    let is_expected = builder.ins().eq(old, expected, span);
+    let new = builder.ins().select(is_expected, replacement, old, span);
+    store(&access_ty, addr_int, new, memarg, builder, config, span);
+    state.push1(old);
+}
+
+/// Returns the upper bound of `lhs + rhs` if felt-bounded integers are enabled, both operands are
+/// bounded, and the sum provably does not exceed `max`.
+fn bounded_sum(
+    config: &WasmTranslationConfig,
+    state: &FuncTranslationState,
+    lhs: Value,
+    rhs: Value,
+    max: u64,
+) -> Option<u64> {
+    if !config.felt_bounded_integers {
+        return None;
+    }
+    let sum = state
+        .upper_bound(lhs)?
+        .checked_add(state.upper_bound(rhs)?)?;
+    (sum <= max).then_some(sum)
+}
+
+/// Record the upper bound on `lhs` implied by the unsigned comparison `cond` of `lhs` against a
+/// bounded `rhs`. If `inclusive` is false, the comparison is strict, i.e. `lhs < rhs`.
+fn record_bound_condition(
+    config: &WasmTranslationConfig,
+    state: &mut FuncTranslationState,
+    cond: Value,
+    lhs: Value,
+    rhs: Value,
+    inclusive: bool,
+    holds_when: bool,
+) {
+    if !config.felt_bounded_integers {
+        return;
+    }
+    let Some(rhs_bound) = state.upper_bound(rhs) else {
+        return;
+    };
+    let bound = if inclusive {
+        rhs_bound
+    } else if rhs_bound > 0 {
+        rhs_bound - 1
+    } else {
+        return;
+    };
+    state.set_bound_condition(cond, lhs, bound, holds_when);
+}
+
+fn translate_br_table(
+    targets: &wasmparser::BrTable<'_>,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> Result<(), WasmError> {
+    let default = targets.default();
+    let mut min_depth = default;
+    let mut max_depth = default;
+    for depth in targets.targets() {
+        let depth = depth?;
+        min_depth = min_depth.min(depth);
+        max_depth = max_depth.max(depth);
+    }
+    // Check the deepest target up front, so that nothing is emitted for an invalid branch
+    state.br_target_frame(max_depth)?;
+    let jump_args_count = {
+        let min_depth_frame = state.br_target_frame(min_depth)?;
+        if min_depth_frame.is_loop() {
+            min_depth_frame.num_param_values()
+        } else {
+            min_depth_frame.num_return_values()
+        }
+    };
+    let val = state.pop1();
+    let val = if builder.data_flow_graph().value_type(val) != &U32 {
+        builder.ins().cast(val, U32, span)
+    } else {
+        val
+    };
+    let mut data = Vec::with_capacity(targets.len() as usize);
+    if jump_args_count == 0 {
+        // No jump arguments
+        for depth in targets.targets() {
+            let depth = depth?;
+            let block = {
+                let frame = state.br_target_frame(depth)?;
+                frame.set_branched_to_exit();
+                frame.br_destination()
+            };
+            data.push((depth, block));
+        }
+        let def_block = {
+            let frame = state.br_target_frame(default)?;
+            frame.set_branched_to_exit();
+            frame.br_destination()
+        };
+        builder.ins().switch(val, data, def_block, span);
+    } else {
+        // Here we have jump arguments, but Midens's switch op doesn't support them
+        // We then proceed to split the edges going out of the br_table
+        let return_count = jump_args_count;
+        let mut dest_block_sequence = vec![];
+        let mut dest_block_map = FxHashMap::default();
+        for depth in targets.targets() {
+            let depth = depth?;
+            let branch_block = match dest_block_map.entry(depth as usize) {
+                hash_map::Entry::Occupied(entry) => *entry.get(),
+                hash_map::Entry::Vacant(entry) => {
+                    let block = builder.create_block();
+                    dest_block_sequence.push((depth as usize, block));
+                    *entry.insert(block)
+                }
+            };
+            data.push((depth, branch_block));
+        }
+        let default_branch_block = match dest_block_map.entry(default as usize) {
+            hash_map::Entry::Occupied(entry) => *entry.get(),
+            hash_map::Entry::Vacant(entry) => {
+                let block = builder.create_block();
+                dest_block_sequence.push((default as usize, block));
+                *entry.insert(block)
+            }
+        };
+        builder.ins().switch(val, data, default_branch_block, span);
+        for (depth, dest_block) in dest_block_sequence {
+            builder.switch_to_block(dest_block);
+            builder.seal_block(dest_block);
+            let real_dest_block = {
+                let frame = state.br_target_frame(depth as u32)?;
+                frame.set_branched_to_exit();
+                frame.br_destination()
+            };
+            let destination_args = state.peekn_mut(return_count);
+            builder.ins().br(real_dest_block, destination_args, span);
+        }
+        state.popn(return_count);
+    }
+    state.reachable = false;
+    Ok(())
+}
+
+/// The module and name of the intrinsic `memory.grow` is lowered to, which is defined by the MASM
+/// backend in `intrinsics/mem.masm`
+const MEMORY_GROW_INTRINSIC: (&str, &str) = ("intrinsics::mem", "memory_grow");
+
+/// The size of a page of linear memory, in bytes
+const WASM_PAGE_SIZE: u32 = 65536;
+
+/// The maximum size, in 64 KiB pages, which the linear memory of `module` is allowed to grow to,
+/// per its declaration and [WasmTranslationConfig::max_memory_pages], and the 4 GiB addressable by
+/// a 32-bit memory
+fn max_memory_pages(module: &Module, config: &WasmTranslationConfig) -> u32 {
+    const MAX_32_BIT_PAGES: u64 = 1 << 16;
+    let declared = module
+        .memories
+        .values()
+        .next()
+        .and_then(|memory| memory.maximum)
+        .unwrap_or(u64::MAX);
+    declared.min(config.max_memory_pages).min(MAX_32_BIT_PAGES) as u32
+}
+
+/// Translate `memory.grow` to a call to the intrinsic which implements it.
+///
+/// Miden memory is all addressable from the start, so growing linear memory only changes its
+/// current size, which is kept in a global, see [Module::memory_size_name], and is what
+/// `memory.size` returns. The intrinsic is given the current size and [max_memory_pages], and
+/// returns the current size if growth by `pages` stays within the maximum, and -1, i.e. failure,
+/// otherwise. The size is only updated if the growth succeeded.
+fn translate_memory_grow(
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    module: &Module,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let pages = state.pop1_casted(U32, builder, span);
+    let size = build_memory_size(builder, module, span);
+    let max_pages = builder.ins().u32(max_memory_pages(module, config), span);
+    let (intrinsic_module, intrinsic_name) = MEMORY_GROW_INTRINSIC;
+    let signature = Signature::new(
+        [AbiParam::new(U32), AbiParam::new(U32), AbiParam::new(U32)],
+        [AbiParam::new(I32)],
+    );
+    let Ok(memory_grow) = builder.data_flow_graph_mut().import_function(
+        Ident::with_empty_span(Symbol::intern(intrinsic_module)),
+        Ident::with_empty_span(Symbol::intern(intrinsic_name)),
+        signature,
+    ) else {
+        let message = format!(
+            "the intrinsic {intrinsic_module}::{intrinsic_name} is already imported with a \
+             different signature"
+        );
+        diagnostics
+            .diagnostic(miden_diagnostics::Severity::Error)
+            .with_message(message.clone())
+            .emit();
+        return Err(WasmError::Unexpected(message));
+    };
+    let call = builder
+        .ins()
+        .call(memory_grow, &[pages, size, max_pages], span);
+    let result = builder.inst_results(call)[0];
+    // The sum may only wrap if the growth failed, in which case the size is left unchanged
+    let failed = builder.ins().eq_imm(result, Immediate::I32(-1), span);
+    let grown = builder.ins().add_wrapping(size, pages, span);
+    let new_size = builder.ins().select(failed, size, grown, span);
+    let size_ptr = builder
+        .ins()
+        .symbol_addr(module.memory_size_name(), Ptr(U32.into()), span);
+    builder.ins().store(size_ptr, new_size, span);
+    state.push1(result);
+    Ok(())
+}
+
+/// Returns the memory immediate of `op`, if it is a load, store or atomic access of linear memory
+fn memarg_of<'a>(op: &'a Operator) -> Option<&'a MemArg> {
+    match op {
+        Operator::I32Load { memarg }
+        | Operator::I64Load { memarg }
+        | Operator::F32Load { memarg }
+        | Operator::F64Load { memarg }
+        | Operator::I32Load8S { memarg }
+        | Operator::I32Load8U { memarg }
+        | Operator::I32Load16S { memarg }
+        | Operator::I32Load16U { memarg }
+        | Operator::I64Load8S { memarg }
+        | Operator::I64Load8U { memarg }
+        | Operator::I64Load16S { memarg }
+        | Operator::I64Load16U { memarg }
+        | Operator::I64Load32S { memarg }
+        | Operator::I64Load32U { memarg }
+        | Operator::I32Store { memarg }
+        | Operator::I64Store { memarg }
+        | Operator::F32Store { memarg }
+        | Operator::F64Store { memarg }
+        | Operator::I32Store8 { memarg }
+        | Operator::I32Store16 { memarg }
+        | Operator::I64Store8 { memarg }
+        | Operator::I64Store16 { memarg }
+        | Operator::I64Store32 { memarg }
+        | Operator::MemoryAtomicNotify { memarg }
+        | Operator::MemoryAtomicWait32 { memarg }
+        | Operator::MemoryAtomicWait64 { memarg }
+        | Operator::I32AtomicLoad { memarg }
+        | Operator::I64AtomicLoad { memarg }
+        | Operator::I32AtomicLoad8U { memarg }
+        | Operator::I32AtomicLoad16U { memarg }
+        | Operator::I64AtomicLoad8U { memarg }
+        | Operator::I64AtomicLoad16U { memarg }
+        | Operator::I64AtomicLoad32U { memarg }
+        | Operator::I32AtomicStore { memarg }
+        | Operator::I64AtomicStore { memarg }
+        | Operator::I32AtomicStore8 { memarg }
+        | Operator::I32AtomicStore16 { memarg }
+        | Operator::I64AtomicStore8 { memarg }
+        | Operator::I64AtomicStore16 { memarg }
+        | Operator::I64AtomicStore32 { memarg }
+        | Operator::I32AtomicRmwAdd { memarg }
+        | Operator::I64AtomicRmwAdd { memarg }
+        | Operator::I32AtomicRmw8AddU { memarg }
+        | Operator::I32AtomicRmw16AddU { memarg }
+        | Operator::I64AtomicRmw8AddU { memarg }
+        | Operator::I64AtomicRmw16AddU { memarg }
+        | Operator::I64AtomicRmw32AddU { memarg }
+        | Operator::I32AtomicRmwSub { memarg }
+        | Operator::I64AtomicRmwSub { memarg }
+        | Operator::I32AtomicRmw8SubU { memarg }
+        | Operator::I32AtomicRmw16SubU { memarg }
+        | Operator::I64AtomicRmw8SubU { memarg }
+        | Operator::I64AtomicRmw16SubU { memarg }
+        | Operator::I64AtomicRmw32SubU { memarg }
+        | Operator::I32AtomicRmwAnd { memarg }
+        | Operator::I64AtomicRmwAnd { memarg }
+        | Operator::I32AtomicRmw8AndU { memarg }
+        | Operator::I32AtomicRmw16AndU { memarg }
+        | Operator::I64AtomicRmw8AndU { memarg }
+        | Operator::I64AtomicRmw16AndU { memarg }
+        | Operator::I64AtomicRmw32AndU { memarg }
+        | Operator::I32AtomicRmwOr { memarg }
+        | Operator::I64AtomicRmwOr { memarg }
+        | Operator::I32AtomicRmw8OrU { memarg }
+        | Operator::I32AtomicRmw16OrU { memarg }
+        | Operator::I64AtomicRmw8OrU { memarg }
+        | Operator::I64AtomicRmw16OrU { memarg }
+        | Operator::I64AtomicRmw32OrU { memarg }
+        | Operator::I32AtomicRmwXor { memarg }
+        | Operator::I64AtomicRmwXor { memarg }
+        | Operator::I32AtomicRmw8XorU { memarg }
+        | Operator::I32AtomicRmw16XorU { memarg }
+        | Operator::I64AtomicRmw8XorU { memarg }
+        | Operator::I64AtomicRmw16XorU { memarg }
+        | Operator::I64AtomicRmw32XorU { memarg }
+        | Operator::I32AtomicRmwXchg { memarg }
+        | Operator::I64AtomicRmwXchg { memarg }
+        | Operator::I32AtomicRmw8XchgU { memarg }
+        | Operator::I32AtomicRmw16XchgU { memarg }
+        | Operator::I64AtomicRmw8XchgU { memarg }
+        | Operator::I64AtomicRmw16XchgU { memarg }
+        | Operator::I64AtomicRmw32XchgU { memarg }
+        | Operator::I32AtomicRmwCmpxchg { memarg }
+        | Operator::I64AtomicRmwCmpxchg { memarg }
+        | Operator::I32AtomicRmw8CmpxchgU { memarg }
+        | Operator::I32AtomicRmw16CmpxchgU { memarg }
+        | Operator::I64AtomicRmw8CmpxchgU { memarg }
+        | Operator::I64AtomicRmw16CmpxchgU { memarg }
+        | Operator::I64AtomicRmw32CmpxchgU { memarg } => Some(memarg),
+        _ => None,
+    }
+}
+
+fn translate_load(
+    ptr_ty: Type,
+    memarg: &MemArg,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) {
+    let addr_int = state.pop1();
+    let val = load(&ptr_ty, addr_int, memarg, builder, config, span);
+    state.push1(val);
+}
+
+fn translate_load_sext(
+    ptr_ty: Type,
+    sext_ty: Type,
+    memarg: &MemArg,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) {
+    let addr_int = state.pop1();
+    let val = load(&ptr_ty, addr_int, memarg, builder, config, span);
+    let sext_val = builder.ins().sext(val, sext_ty, span);
+    state.push1(sext_val);
+}
+
+fn translate_load_zext(
+    ptr_ty: Type,
+    zext_ty: Type,
+    memarg: &MemArg,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) {
+    assert!(ptr_ty.is_unsigned_integer());
+    let addr_int = state.pop1();
+    let val = load(&ptr_ty, addr_int, memarg, builder, config, span);
+    let sext_val = builder.ins().zext(val, zext_ty, span);
+    state.push1(sext_val);
+}
+
+fn translate_store(
+    ptr_ty: Type,
+    memarg: &MemArg,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) {
+    let (addr_int, val) = state.pop2();
+    store(&ptr_ty, addr_int, val, memarg, builder, config, span);
+}
+
+/// Store `val`, truncated to `ptr_ty` if necessary, at the address `addr_int`, offset by `memarg`
+fn store(
+    ptr_ty: &Type,
+    addr_int: Value,
+    val: Value,
+    memarg: &MemArg,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) {
+    let val_ty = builder.data_flow_graph().value_type(val);
+    let arg = if *ptr_ty != *val_ty {
+        builder.ins().trunc(val, ptr_ty.clone(), span)
+    } else {
+        val
+    };
+    if is_known_aligned(addr_int, ptr_ty, memarg, builder) {
+        let addr = prepare_addr(addr_int, ptr_ty, Some(memarg), builder, span);
+        builder.ins().store(addr, arg, span);
+        return;
+    }
+    let addr = effective_addr(addr_int, Some(memarg), builder, span);
+    if config.trap_on_unaligned_access {
+        assert_aligned(addr, ptr_ty, builder, span);
+        let addr = builder
+            .ins()
+            .inttoptr(addr, Type::Ptr(ptr_ty.clone().into()), span);
+        builder.ins().store(addr, arg, span);
+        return;
+    }
+    let (aligned_block, unaligned_block) = branch_on_alignment(addr, ptr_ty, builder, span);
+    let exit_block = builder.create_block();
+
+    builder.switch_to_block(aligned_block);
+    let ptr = builder
+        .ins()
+        .inttoptr(addr, Type::Ptr(ptr_ty.clone().into()), span);
+    builder.ins().store(ptr, arg, span);
+    builder.ins().br(exit_block, &[], span);
+
+    builder.switch_to_block(unaligned_block);
+    match ptr_ty.size_in_bytes() {
+        8 => {
+            let arg = builder.ins().cast(arg, U64, span);
+            let lo = builder.ins().trunc(arg, U32, span);
+            let hi = builder
+                .ins()
+                .shr_imm_wrapping(arg, Immediate::U64(32), span);
+            let hi = builder.ins().trunc(hi, U32, span);
+            store_unaligned_bytes(addr, lo, 4, builder, span);
+            let addr = builder
+                .ins()
+                .add_imm_wrapping(addr, Immediate::U32(4), span);
+            store_unaligned_bytes(addr, hi, 4, builder, span);
+        }
+        4 => {
+            let arg = builder.ins().cast(arg, U32, span);
+            store_unaligned_bytes(addr, arg, 4, builder, span);
+        }
+        size => {
+            let arg = builder.ins().zext(arg, U32, span);
+            store_unaligned_bytes(addr, arg, size as u32, builder, span);
+        }
+    }
+    builder.ins().br(exit_block, &[], span);
+
+    builder.seal_block(exit_block);
+    builder.switch_to_block(exit_block);
+}
+
+/// Returns true if an access of a value of type `ty` at the address `addr_int`, offset by
+/// `memarg`, is known to be naturally aligned at translation time, i.e. if the value is a single
+/// byte, or if the address is a constant which is a multiple of the size of `ty`.
+///
+/// Alignment hints are not trusted, as Wasm requires an access to an unaligned address to succeed
+/// regardless of its hint, so the alignment of any other access is decided at runtime, see
+/// [branch_on_alignment].
+fn is_known_aligned(
+    addr_int: Value,
+    ty: &Type,
+    memarg: &MemArg,
+    builder: &FunctionBuilderExt,
+) -> bool {
+    let size = ty.size_in_bytes() as u64;
+    if size == 1 {
+        return true;
+    }
+    let dfg = builder.data_flow_graph();
+    let ValueData::Inst { inst, .. } = dfg.value_data(addr_int) else {
+        return false;
+    };
+    match dfg.inst(*inst) {
+        Instruction::UnaryOpImm(UnaryOpImm {
+            op: Opcode::ImmI32,
+            imm: Immediate::I32(addr),
+            ..
+        }) => (*addr as u32 as u64 + memarg.offset) % size == 0,
+        _ => false,
+    }
+}
+
+/// Branch on whether `addr` is a multiple of the size of `ty`, to the first of the returned blocks
+/// if it is, or to the second otherwise, both of which are sealed.
+fn branch_on_alignment(
+    addr: Value,
+    ty: &Type,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> (Block, Block) {
+    let aligned_block = builder.create_block();
+    let unaligned_block = builder.create_block();
+    let mask = Immediate::U32(ty.size_in_bytes() as u32 - 1);
+    let misalignment = builder.ins().band_imm(addr, mask, span);
+    let is_aligned = builder.ins().eq_imm(misalignment, Immediate::U32(0), span);
+    builder
+        .ins()
+        .cond_br(is_aligned, aligned_block, &[], unaligned_block, &[], span);
+    builder.seal_block(aligned_block);
+    builder.seal_block(unaligned_block);
+    (aligned_block, unaligned_block)
+}
+
+/// Load a value of type `ty` from the address `addr_int`, offset by `memarg`
+fn load(
+    ty: &Type,
+    addr_int: Value,
+    memarg: &MemArg,
+    builder: &mut FunctionBuilderExt,
+    config: &WasmTranslationConfig,
+    span: SourceSpan,
+) -> Value {
+    if is_known_aligned(addr_int, ty, memarg, builder) {
+        let addr = prepare_addr(addr_int, ty, Some(memarg), builder, span);
+        return builder.ins().load(addr, span);
+    }
+    let addr = effective_addr(addr_int, Some(memarg), builder, span);
+    if config.trap_on_unaligned_access {
+        assert_aligned(addr, ty, builder, span);
+        let addr = builder
+            .ins()
+            .inttoptr(addr, Type::Ptr(ty.clone().into()), span);
+        return builder.ins().load(addr, span);
+    }
+    let (aligned_block, unaligned_block) = branch_on_alignment(addr, ty, builder, span);
+    let exit_block = builder.create_block_with_params([ty.clone()], span);
+
+    builder.switch_to_block(aligned_block);
+    let ptr = builder
+        .ins()
+        .inttoptr(addr, Type::Ptr(ty.clone().into()), span);
+    let val = builder.ins().load(ptr, span);
+    builder.ins().br(exit_block, &[val], span);
+
+    builder.switch_to_block(unaligned_block);
+    let val = match ty.size_in_bytes() {
+        8 => {
+            let lo = load_unaligned_bytes(addr, builder, span);
+            let addr = builder
+                .ins()
+                .add_imm_wrapping(addr, Immediate::U32(4), span);
+            let hi = load_unaligned_bytes(addr, builder, span);
+            let lo = builder.ins().zext(lo, U64, span);
+            let hi = builder.ins().zext(hi, U64, span);
+            let hi = builder.ins().shl_imm_wrapping(hi, Immediate::U64(32), span);
+            let val = builder.ins().bor(hi, lo, span);
+            builder.ins().cast(val, ty.clone(), span)
+        }
+        4 => {
+            let val = load_unaligned_bytes(addr, builder, span);
+            builder.ins().cast(val, ty.clone(), span)
+        }
+        _ => {
+            let val = load_unaligned_bytes(addr, builder, span);
+            builder.ins().trunc(val, ty.clone(), span)
+        }
+    };
+    builder.ins().br(exit_block, &[val], span);
+
+    builder.seal_block(exit_block);
+    builder.switch_to_block(exit_block);
+    builder.block_params(exit_block)[0]
+}
+
+/// Trap unless `addr` is a multiple of the size of `ty`
+fn assert_aligned(addr: Value, ty: &Type, builder: &mut FunctionBuilderExt, span: SourceSpan) {
+    let mask = Immediate::U32(ty.size_in_bytes() as u32 - 1);
+    let misalignment = builder.ins().band_imm(addr, mask, span);
+    let is_aligned = builder.ins().eq_imm(misalignment, Immediate::U32(0), span);
+    builder.ins().assert(is_aligned, span);
+}
+
+/// The address of the 32-bit element containing the byte at `addr`, along with the number of bits
+/// `addr` is offset from it, and 31 minus that number
+///
+/// The latter is used to shift values by `32 - shift` bits in two steps, as a single shift by 32
+/// bits is not valid when `addr` is element-aligned.
+fn unaligned_element_addr(
+    addr: Value,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> (Value, Value, Value) {
+    let base = builder.ins().band_imm(addr, Immediate::U32(!3), span);
+    let offset = builder.ins().band_imm(addr, Immediate::U32(3), span);
+    let shift = builder
+        .ins()
+        .shl_imm_wrapping(offset, Immediate::U32(3), span);
+    // As `shift` is one of 0, 8, 16 or 24, this is `31 - shift`
+    let rem_shift = builder.ins().bxor_imm(shift, Immediate::U32(31), span);
+    (base, shift, rem_shift)
+}
+
+/// Load the four bytes starting at the unaligned address `addr` as a `u32`.
+///
+/// This reads the two 32-bit elements the bytes span, using only element-aligned loads, and
+/// combines them in little-endian order, which is how the code generator lays out the bytes of an
+/// element.
+fn load_unaligned_bytes(addr: Value, builder: &mut FunctionBuilderExt, span: SourceSpan) -> Value {
+    let (base, shift, rem_shift) = unaligned_element_addr(addr, builder, span);
+    let lo_ptr = builder.ins().inttoptr(base, Ptr(U32.into()), span);
+    let lo = builder.ins().load(lo_ptr, span);
+    let hi_addr = builder
+        .ins()
+        .add_imm_wrapping(base, Immediate::U32(4), span);
+    let hi_ptr = builder.ins().inttoptr(hi_addr, Ptr(U32.into()), span);
+    let hi = builder.ins().load(hi_ptr, span);
+    let lo = builder.ins().shr_wrapping(lo, shift, span);
+    let hi = builder.ins().shl_imm_wrapping(hi, Immediate::U32(1), span);
+    let hi = builder.ins().shl_wrapping(hi, rem_shift, span);
+    builder.ins().bor(hi, lo, span)
+}
+
+/// Store the low `size` bytes of the `u32` value `val` starting at the unaligned address `addr`.
+///
+/// The two 32-bit elements the bytes span are read, the bytes are replaced in little-endian order,
+/// and both elements are written back, so that only element-aligned accesses are used.
+fn store_unaligned_bytes(
+    addr: Value,
+    val: Value,
+    size: u32,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) {
+    debug_assert!((1..=4).contains(&size));
+    let (base, shift, rem_shift) = unaligned_element_addr(addr, builder, span);
+    let lo_ptr = builder.ins().inttoptr(base, Ptr(U32.into()), span);
+    let lo = builder.ins().load(lo_ptr, span);
+    let hi_addr = builder
+        .ins()
+        .add_imm_wrapping(base, Immediate::U32(4), span);
+    let hi_ptr = builder.ins().inttoptr(hi_addr, Ptr(U32.into()), span);
+    let hi = builder.ins().load(hi_ptr, span);
+
+    let mask = builder.ins().u32(u32::MAX >> (32 - size * 8), span);
+    let lo_mask = builder.ins().shl_wrapping(mask, shift, span);
+    let hi_mask = builder
+        .ins()
+        .shr_imm_wrapping(mask, Immediate::U32(1), span);
+    let hi_mask = builder.ins().shr_wrapping(hi_mask, rem_shift, span);
+    let lo_bits = builder.ins().shl_wrapping(val, shift, span);
+    let hi_bits = builder.ins().shr_imm_wrapping(val, Immediate::U32(1), span);
+    let hi_bits = builder.ins().shr_wrapping(hi_bits, rem_shift, span);
+
+    let lo_mask = builder.ins().bnot(lo_mask, span);
+    let lo = builder.ins().band(lo, lo_mask, span);
+    let lo = builder.ins().bor(lo, lo_bits, span);
+    let hi_mask = builder.ins().bnot(hi_mask, span);
+    let hi = builder.ins().band(hi, hi_mask, span);
+    let hi = builder.ins().bor(hi, hi_bits, span);
+    builder.ins().store(lo_ptr, lo, span);
+    builder.ins().store(hi_ptr, hi, span);
+}
+
+/// Compute the address accessed by a load or store of `addr_int`, offset by `memarg`, as a `u32`
+fn effective_addr(
+    addr_int: Value,
+    memarg: Option<&MemArg>,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> Value {
+    let addr_int_ty = builder.data_flow_graph().value_type(addr_int);
+    let addr_u32 = if *addr_int_ty == U32 {
+        addr_int
+    } else {
+        builder.ins().cast(addr_int, U32, span)
+    };
+    let mut full_addr_int = addr_u32;
+    if let Some(memarg) = memarg {
+        if memarg.offset != 0 {
+            full_addr_int =
+                builder
+                    .ins()
+                    .add_imm_checked(addr_u32, Immediate::U32(memarg.offset as u32), span);
+        }
+    };
+    full_addr_int
+}
+
+fn prepare_addr(
+    addr_int: Value,
+    ptr_ty: &Type,
+    memarg: Option<&MemArg>,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> Value {
+    let full_addr_int = effective_addr(addr_int, memarg, builder, span);
+    builder
+        .ins()
+        .inttoptr(full_addr_int, Type::Ptr(ptr_ty.clone().into()), span)
+}
+
+/// Translate a division or remainder of `lhs` by `rhs`, emitted by `op`, which traps if `rhs`
+/// is zero, as in Wasm.
+///
+/// If [WasmTranslationConfig::wrapping_division] is set, the result is zero instead: a zero
+/// divisor is replaced with one, so that `op` cannot trap, and its result is then discarded.
+fn translate_div_or_rem(
+    lhs: Value,
+    rhs: Value,
+    op: impl FnOnce(&mut FunctionBuilderExt, Value, Value) -> Value,
+    config: &WasmTranslationConfig,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> Value {
+    if !config.wrapping_division {
+        return op(builder, lhs, rhs);
+    }
+    let (zero, one) = match builder.data_flow_graph().value_type(rhs) {
+        I32 => (Immediate::I32(0), Immediate::I32(1)),
+        U32 => (Immediate::U32(0), Immediate::U32(1)),
+        I64 => (Immediate::I64(0), Immediate::I64(1)),
+        U64 => (Immediate::U64(0), Immediate::U64(1)),
+        ty => unreachable!("unexpected divisor type {ty}"),
+    };
+    let is_zero = builder.ins().eq_imm(rhs, zero, span);
+    // A zero divisor is itself the zero the result is replaced with
+    let one = builder.ins().add_imm_wrapping(rhs, one, span);
+    let divisor = builder.ins().select(is_zero, one, rhs, span);
+    let val = op(builder, lhs, divisor);
+    builder.ins().select(is_zero, rhs, val, span)
+}
+
+/// Translate a trapping truncation of the `f64` value `val` to an integer of type `ty`, i.e. one of
+/// `i32.trunc_f64_s` (`I32`), `i32.trunc_f64_u` (`U32`), `i64.trunc_f64_s` (`I64`) or
+/// `i64.trunc_f64_u` (`U64`). The result is returned as `i32` or `i64`, as in Wasm.
+///
+/// There are no floating-point instructions in Miden IR, so the conversion is performed in software
+/// on the bit pattern of `val`: this traps if `val` is NaN, or if its integral part is not
+/// representable as `ty`, and otherwise shifts the significand of `val` according to its exponent.
+fn translate_trunc_f64(
+    val: Value,
+    ty: Type,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> Value {
+    // The bit patterns of the smallest positive and negative values whose integral part is out of
+    // range, e.g. `2^31` and `-2^31 - 1` for `i32`. As the bit patterns of non-negative values are
+    // ordered like the values themselves, the range check is a comparison of the magnitude bits.
+    let (positive_limit, negative_limit) = match ty {
+        // 2^31 and 2^31 + 1
+        I32 => (0x41e0_0000_0000_0000, 0x41e0_0000_0020_0000),
+        // 2^32 and 1
+        U32 => (0x41f0_0000_0000_0000, 0x3ff0_0000_0000_0000),
+        // 2^63, and the next value after 2^63, as 2^63 + 1 is not representable
+        I64 => (0x43e0_0000_0000_0000, 0x43e0_0000_0000_0001),
+        // 2^64 and 1
+        U64 => (0x43f0_0000_0000_0000, 0x3ff0_0000_0000_0000),
+        ty => unreachable!("unexpected truncation result type {ty}"),
+    };
+    let bits = builder.ins().bitcast(val, U64, span);
+    let magnitude = builder
+        .ins()
+        .band_imm(bits, Immediate::U64(0x7fff_ffff_ffff_ffff), span);
+    let is_negative = builder.ins().neq(bits, magnitude, span);
+
+    // NaNs are those values with an all-ones exponent, and a non-zero significand
+    let is_nan = builder
+        .ins()
+        .gt_imm(magnitude, Immediate::U64(0x7ff0_0000_0000_0000), span);
+    builder.ins().assertz(is_nan, span);
+    let below_positive_limit =
+        builder
+            .ins()
+            .lt_imm(magnitude, Immediate::U64(positive_limit), span);
+    let below_negative_limit =
+        builder
+            .ins()
+            .lt_imm(magnitude, Immediate::U64(negative_limit), span);
+    let in_range = builder.ins().select(
+        is_negative,
+        below_negative_limit,
+        below_positive_limit,
+        span,
+    );
+    builder.ins().assert(in_range, span);
+
+    // The value is `significand * 2^(exponent - 1075)`, where the significand includes the implicit
+    // leading bit. Only one of the two shifts below is non-zero: values with an exponent below 1023
+    // have no integral part, in which case the right shift, clamped to 63, discards every bit.
+    let exponent = builder
+        .ins()
+        .shr_imm_wrapping(magnitude, Immediate::U64(52), span);
+    let exponent = builder.ins().trunc(exponent, U32, span);
+    let significand =
+        builder
+            .ins()
+            .band_imm(magnitude, Immediate::U64(0x000f_ffff_ffff_ffff), span);
+    let significand = builder
+        .ins()
+        .bor_imm(significand, Immediate::U64(1 << 52), span);
+    let clamped_exponent = builder.ins().min_imm(exponent, Immediate::U32(1075), span);
+    let left_shift = builder.ins().sub_wrapping(exponent, clamped_exponent, span);
+    let left_shift = builder.ins().zext(left_shift, U64, span);
+    let bias = builder.ins().u32(1075, span);
+    let right_shift = builder.ins().sub_wrapping(bias, clamped_exponent, span);
+    let right_shift = builder.ins().min_imm(right_shift, Immediate::U32(63), span);
+    let right_shift = builder.ins().zext(right_shift, U64, span);
+    let integral = builder.ins().shl_wrapping(significand, left_shift, span);
+    let integral = builder.ins().shr_wrapping(integral, right_shift, span);
+
+    // Negative values are only in range for unsigned types if their integral part is zero, so
+    // only the signed results need negating
+    match ty {
+        I32 | U32 => {
+            let integral = builder.ins().trunc(integral, U32, span);
+            let result = if ty == I32 {
+                let zero = builder.ins().u32(0, span);
+                let negated = builder.ins().sub_wrapping(zero, integral, span);
+                builder.ins().select(is_negative, negated, integral, span)
+            } else {
+                integral
+            };
+            builder.ins().bitcast(result, I32, span)
+        }
+        _ => {
+            let result = if ty == I64 {
+                let zero = builder.ins().u64(0, span);
+                let negated = builder.ins().sub_wrapping(zero, integral, span);
+                builder.ins().select(is_negative, negated, integral, span)
+            } else {
+                integral
+            };
+            builder.ins().bitcast(result, I64, span)
+        }
+    }
+}
+
+/// Translate `f64.promote_f32`, which is exact, by rebuilding the bit pattern of the `f32` value
+/// `val` as an `f64`.
+///
+/// Subnormal `f32` values are normal as `f64`, so their significand is shifted until its leading
+/// bit is in the position of the implicit bit. In the absence of `clz`, the shift is found with a
+/// binary search. As required by the spec, NaNs are made quiet.
+fn translate_promote_f32(val: Value, builder: &mut FunctionBuilderExt, span: SourceSpan) -> Value {
+    let bits = builder.ins().bitcast(val, U32, span);
+    let sign = builder
+        .ins()
+        .band_imm(bits, Immediate::U32(0x8000_0000), span);
+    let sign = builder.ins().zext(sign, U64, span);
+    let sign = builder
+        .ins()
+        .shl_imm_wrapping(sign, Immediate::U64(32), span);
+    let exponent = builder
+        .ins()
+        .shr_imm_wrapping(bits, Immediate::U32(23), span);
+    let exponent = builder.ins().band_imm(exponent, Immediate::U32(0xff), span);
+    let significand = builder
+        .ins()
+        .band_imm(bits, Immediate::U32(0x7f_ffff), span);
+    let is_zero_significand = builder.ins().eq_imm(significand, Immediate::U32(0), span);
+    let significand = builder.ins().zext(significand, U64, span);
+    let wide_significand = builder
+        .ins()
+        .shl_imm_wrapping(significand, Immediate::U64(29), span);
+
+    // Normal values only need their exponent rebiased, from 127 to 1023
+    let normal_exponent =
+        builder
+            .ins()
+            .add_imm_wrapping(exponent, Immediate::U32(1023 - 127), span);
+    let normal_exponent = builder.ins().zext(normal_exponent, U64, span);
+    let normal_exponent = builder
+        .ins()
+        .shl_imm_wrapping(normal_exponent, Immediate::U64(52), span);
+    let normal = builder.ins().bor(normal_exponent, wide_significand, span);
+
+    // Infinities keep a zero significand, while NaNs keep their payload, with the quiet bit set
+    let zero = builder.ins().u64(0, span);
+    let quiet_bit = builder.ins().u64(1 << 51, span);
+    let quiet_bit = builder
+        .ins()
+        .select(is_zero_significand, zero, quiet_bit, span);
+    let special = builder.ins().bor_imm(
+        wide_significand,
+        Immediate::U64(0x7ff0_0000_0000_0000),
+        span,
+    );
+    let special = builder.ins().bor(special, quiet_bit, span);
+
+    // A subnormal value is `significand * 2^-149`, so once normalized by a shift of `n`, its
+    // biased exponent is `1023 - 126 - n`
+    let mut normalized = significand;
+    let mut shift = builder.ins().u64(0, span);
+    for step in [16, 8, 4, 2, 1] {
+        let shifted = builder
+            .ins()
+            .shl_imm_wrapping(normalized, Immediate::U64(step), span);
+        let fits = builder.ins().lt_imm(shifted, Immediate::U64(1 << 24), span);
+        normalized = builder.ins().select(fits, shifted, normalized, span);
+        let stepped = builder
+            .ins()
+            .add_imm_wrapping(shift, Immediate::U64(step), span);
+        shift = builder.ins().select(fits, stepped, shift, span);
+    }
+    let bias = builder.ins().u64(1023 - 126, span);
+    let subnormal_exponent = builder.ins().sub_wrapping(bias, shift, span);
+    let subnormal_exponent =
+        builder
+            .ins()
+            .shl_imm_wrapping(subnormal_exponent, Immediate::U64(52), span);
+    let subnormal_significand = builder
+        .ins()
+        .band_imm(normalized, Immediate::U64(0x7f_ffff), span);
+    let subnormal_significand =
+        builder
+            .ins()
+            .shl_imm_wrapping(subnormal_significand, Immediate::U64(29), span);
+    let subnormal = builder
+        .ins()
+        .bor(subnormal_exponent, subnormal_significand, span);
+    let subnormal = builder
+        .ins()
+        .select(is_zero_significand, zero, subnormal, span);
+
+    let is_zero_exponent = builder.ins().eq_imm(exponent, Immediate::U32(0), span);
+    let is_max_exponent = builder.ins().eq_imm(exponent, Immediate::U32(0xff), span);
+    let result = builder.ins().select(is_max_exponent, special, normal, span);
+    let result = builder
+        .ins()
+        .select(is_zero_exponent, subnormal, result, span);
+    let result = builder.ins().bor(result, sign, span);
+    builder.ins().bitcast(result, F64, span)
+}
+
+/// Translate `f32.demote_f64`, rounding the `f64` value `val` to the nearest `f32`, with ties to
+/// even, as required by the spec.
+///
+/// Values too large for `f32` become infinities, values too small become zeros, and as with
+/// promotion, NaNs are made quiet.
+fn translate_demote_f64(val: Value, builder: &mut FunctionBuilderExt, span: SourceSpan) -> Value {
+    let bits = builder.ins().bitcast(val, U64, span);
+    let sign = builder
+        .ins()
+        .shr_imm_wrapping(bits, Immediate::U64(32), span);
+    let sign = builder.ins().trunc(sign, U32, span);
+    let sign = builder
+        .ins()
+        .band_imm(sign, Immediate::U32(0x8000_0000), span);
+    let exponent = builder
+        .ins()
+        .shr_imm_wrapping(bits, Immediate::U64(52), span);
+    let exponent = builder.ins().trunc(exponent, U32, span);
+    let exponent = builder
+        .ins()
+        .band_imm(exponent, Immediate::U32(0x7ff), span);
+    let significand = builder
+        .ins()
+        .band_imm(bits, Immediate::U64(0x000f_ffff_ffff_ffff), span);
+
+    // The value is `significand * 2^(exponent - 1075)`, where the significand includes the
+    // implicit leading bit, which is `f32` bits `significand >> (926 - exponent)`, if subnormal
+    // as an `f32`, i.e. the exponent is below 897. Otherwise, the significand is shifted by 29,
+    // and the rebiased exponent, less one for the implicit bit, is added to it. Either way, the
+    // bits shifted out determine the rounding, which may carry into the exponent. Subnormal `f64`
+    // values are shifted out entirely, so it is harmless to set their implicit bit.
+    let implicit_significand = builder
+        .ins()
+        .bor_imm(significand, Immediate::U64(1 << 52), span);
+    let clamped_exponent = builder
+        .ins()
+        .min_imm(exponent, Immediate::U32(1023 - 126), span);
+    let biased_exponent = builder.ins().sub_wrapping(exponent, clamped_exponent, span);
+    let biased_exponent = builder.ins().zext(biased_exponent, U64, span);
+    let biased_exponent = builder
+        .ins()
+        .shl_imm_wrapping(biased_exponent, Immediate::U64(23), span);
+    let shift_base = builder.ins().u32(926, span);
+    let shift = builder
+        .ins()
+        .sub_wrapping(shift_base, clamped_exponent, span);
+    let shift = builder.ins().min_imm(shift, Immediate::U32(63), span);
+    let shift = builder.ins().zext(shift, U64, span);
+    let truncated = builder
+        .ins()
+        .shr_wrapping(implicit_significand, shift, span);
+    let one = builder.ins().u64(1, span);
+    let unit = builder.ins().shl_wrapping(one, shift, span);
+    let mask = builder
+        .ins()
+        .sub_imm_wrapping(unit, Immediate::U64(1), span);
+    let remainder = builder.ins().band(implicit_significand, mask, span);
+    let half = builder
+        .ins()
+        .shr_imm_wrapping(unit, Immediate::U64(1), span);
+    let above_half = builder.ins().gt(remainder, half, span);
+    let at_half = builder.ins().eq(remainder, half, span);
+    let is_odd = builder.ins().is_odd(truncated, span);
+    // Ties round to even, i.e. up only when the truncated significand is odd
+    let round_up_on_tie = builder.ins().and(at_half, is_odd, span);
+    let round_up = builder.ins().or(above_half, round_up_on_tie, span);
+    let zero = builder.ins().u64(0, span);
+    let round_up = builder.ins().select(round_up, one, zero, span);
+    let rounded = builder.ins().add_wrapping(biased_exponent, truncated, span);
+    let rounded = builder.ins().add_wrapping(rounded, round_up, span);
+    let rounded = builder.ins().trunc(rounded, U32, span);
+
+    // Values whose exponent is at least that of 2^128 overflow to infinity
+    let is_overflow = builder
+        .ins()
+        .gte_imm(exponent, Immediate::U32(1023 + 128), span);
+    let infinity = builder.ins().u32(0x7f80_0000, span);
+    let result = builder.ins().select(is_overflow, infinity, rounded, span);
+
+    // Infinities keep a zero significand, while NaNs keep the top bits of their payload, with the
+    // quiet bit set
+    let is_nan = builder.ins().neq_imm(significand, Immediate::U64(0), span);
+    let quiet_bit = builder.ins().u32(0x40_0000, span);
+    let no_quiet_bit = builder.ins().u32(0, span);
+    let quiet_bit = builder.ins().select(is_nan, quiet_bit, no_quiet_bit, span);
+    let payload = builder
+        .ins()
+        .shr_imm_wrapping(significand, Immediate::U64(29), span);
+    let payload = builder.ins().trunc(payload, U32, span);
+    let special = builder.ins().bor(payload, quiet_bit, span);
+    let special = builder.ins().bor(special, infinity, span);
+    let is_max_exponent = builder.ins().eq_imm(exponent, Immediate::U32(0x7ff), span);
+    let result = builder.ins().select(is_max_exponent, special, result, span);
+    let result = builder.ins().bor(result, sign, span);
+    builder.ins().bitcast(result, F32, span)
+}
+
+/// The layout of the bit patterns of `f32` or `f64` values, on which the floating-point operators
+/// are implemented, as unsigned integers of the same width
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct FloatFormat {
+    /// The width of the values, in bits
+    width: u32,
+    /// The number of bits of the significand, excluding the implicit leading bit
+    significand_bits: u32,
+    /// The bias of the exponent
+    bias: u64,
+}
+
+impl FloatFormat {
+    const F32: Self = Self {
+        width: 32,
+        significand_bits: 23,
+        bias: 127,
+    };
+    const F64: Self = Self {
+        width: 64,
+        significand_bits: 52,
+        bias: 1023,
+    };
+
+    /// Returns the format of the operands of the floating-point operator `op`
+    fn of(op: &Operator) -> Self {
+        match op {
+            Operator::F32Abs
+            | Operator::F32Neg
+            | Operator::F32Copysign
+            | Operator::F32Min
+            | Operator::F32Max
+            | Operator::F32Trunc
+            | Operator::F32Floor
+            | Operator::F32Ceil
+            | Operator::F32Nearest => Self::F32,
+            _ => Self::F64,
+        }
+    }
+
+    /// The unsigned integer type of the bit patterns
+    fn bits_ty(&self) -> Type {
+        if self.width == 32 {
+            U32
+        } else {
+            U64
+        }
+    }
+
+    /// Returns `value` as an immediate of the type of the bit patterns
+    fn imm(&self, value: u64) -> Immediate {
+        if self.width == 32 {
+            Immediate::U32(value as u32)
+        } else {
+            Immediate::U64(value)
+        }
+    }
+
+    /// Returns a constant bit pattern
+    fn constant(&self, value: u64, builder: &mut FunctionBuilderExt, span: SourceSpan) -> Value {
+        if self.width == 32 {
+            builder.ins().u32(value as u32, span)
+        } else {
+            builder.ins().u64(value, span)
+        }
+    }
+
+    /// Returns the bit pattern of the floating-point value `val`
+    fn bits_of(&self, val: Value, builder: &mut FunctionBuilderExt, span: SourceSpan) -> Value {
+        builder.ins().bitcast(val, self.bits_ty(), span)
+    }
+
+    /// Returns the floating-point value of the bit pattern `bits`
+    fn float_of(&self, bits: Value, builder: &mut FunctionBuilderExt, span: SourceSpan) -> Value {
+        let ty = if self.width == 32 { F32 } else { F64 };
+        builder.ins().bitcast(bits, ty, span)
+    }
+
+    fn sign_mask(&self) -> u64 {
+        1 << (self.width - 1)
+    }
+
+    fn magnitude_mask(&self) -> u64 {
+        self.sign_mask() - 1
+    }
+
+    /// The bit pattern of positive infinity, above which the magnitudes are those of NaNs
+    fn infinity(&self) -> u64 {
+        self.magnitude_mask() & !((1 << self.significand_bits) - 1)
+    }
+
+    /// The bit which is set in the significand of quiet NaNs
+    fn quiet_bit(&self) -> u64 {
+        1 << (self.significand_bits - 1)
+    }
+
+    /// The bit pattern of `1.0`
+    fn one(&self) -> u64 {
+        self.bias << self.significand_bits
+    }
+
+    /// The bit pattern of `0.5`
+    fn half(&self) -> u64 {
+        (self.bias - 1) << self.significand_bits
+    }
+}
+
+/// A floating-point operator performed in software by an intrinsic, i.e. a function built once
+/// in the module, and called by each of its uses, rather than expanded at each of them.
+///
+/// The function of an intrinsic takes the operands of the operator, and returns its result, see
+/// [float_intrinsic_signature], and is built once the functions of the module have been
+/// translated, see [build_float_intrinsic_body].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct FloatIntrinsic {
+    op: FloatOp,
+    /// The format of the operands and result
+    format: FloatFormat,
+}
+
+/// The floating-point operators performed by a [FloatIntrinsic]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum FloatOp {
+    /// `f32.min`/`f64.min`, see [translate_float_min_max]
+    Min,
+    /// `f32.max`/`f64.max`, see [translate_float_min_max]
+    Max,
+    /// The rounding operators, see [translate_float_round]
+    Round(Rounding),
+}
+
+impl FloatIntrinsic {
+    /// Returns the name of the function of this intrinsic, e.g. `__wasm_f64_min`
+    pub(crate) fn name(self) -> String {
+        let op = match self.op {
+            FloatOp::Min => "min",
+            FloatOp::Max => "max",
+            FloatOp::Round(Rounding::Trunc) => "trunc",
+            FloatOp::Round(Rounding::Floor) => "floor",
+            FloatOp::Round(Rounding::Ceil) => "ceil",
+            FloatOp::Round(Rounding::Nearest) => "nearest",
+        };
+        format!("__wasm_f{}_{op}", self.format.width)
+    }
+
+    /// The number of operands of the operator
+    fn num_operands(self) -> usize {
+        match self.op {
+            FloatOp::Min | FloatOp::Max => 2,
+            FloatOp::Round(_) => 1,
+        }
+    }
+}
+
+/// Returns the signature of the function of `intrinsic`
+pub(crate) fn float_intrinsic_signature(intrinsic: FloatIntrinsic) -> Signature {
+    let ty = if intrinsic.format.width == 32 {
+        F32
+    } else {
+        F64
+    };
+    let params = vec![AbiParam::new(ty.clone()); intrinsic.num_operands()];
+    Signature {
+        linkage: Linkage::Internal,
+        ..Signature::new(params, [AbiParam::new(ty)])
+    }
+}
+
+/// Emits a call of the function of `intrinsic` with `args`, returning its result, and records
+/// that the function must be built, see [FuncTranslationState::float_intrinsics]
+fn emit_float_intrinsic_call(
+    intrinsic: FloatIntrinsic,
+    args: &[Value],
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> Value {
+    let callee = FunctionIdent {
+        module: builder.id().module,
+        function: Ident::with_empty_span(Symbol::intern(intrinsic.name())),
+    };
+    builder
+        .data_flow_graph_mut()
+        .import_function(
+            callee.module,
+            callee.function,
+            float_intrinsic_signature(intrinsic),
+        )
+        .expect("the function of a floating-point intrinsic is imported with its signature");
+    state.float_intrinsics.insert(intrinsic);
+    let call = builder.ins().call(callee, args, span);
+    builder.inst_results(call)[0]
+}
+
+/// Build the body of the function of `intrinsic`, the signature of which is given by
+/// [float_intrinsic_signature]
+pub(crate) fn build_float_intrinsic_body(
+    intrinsic: FloatIntrinsic,
+    builder: &mut FunctionBuilderExt,
+) {
+    let span = SourceSpan::default();
+    let entry_block = builder.current_block();
+    builder.seal_block(entry_block);
+    let params = builder.block_params(entry_block).to_vec();
+    let result = match intrinsic.op {
+        FloatOp::Min | FloatOp::Max => {
+            let is_max = intrinsic.op == FloatOp::Max;
+            translate_float_min_max(
+                params[0],
+                params[1],
+                is_max,
+                intrinsic.format,
+                builder,
+                span,
+            )
+        }
+        FloatOp::Round(rounding) => {
+            translate_float_round(params[0], rounding, intrinsic.format, builder, span)
+        }
+    };
+    builder.ins().ret(Some(result), span);
+}
+
+/// Translate `f32.min`/`f64.min`, or `f32.max`/`f64.max` if `is_max` is set.
+///
+/// Unlike `f64::min` and `f64::max` in Rust, which return the other operand, the result is NaN if
+/// either operand is NaN, as required by the spec: the NaN operand is returned, made quiet. Zeros
+/// are ordered by their sign, i.e. `-0.0` is below `0.0`.
+fn translate_float_min_max(
+    lhs: Value,
+    rhs: Value,
+    is_max: bool,
+    format: FloatFormat,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> Value {
+    let lhs = format.bits_of(lhs, builder, span);
+    let rhs = format.bits_of(rhs, builder, span);
+    let magnitude_mask = format.imm(format.magnitude_mask());
+    let lhs_magnitude = builder.ins().band_imm(lhs, magnitude_mask, span);
+    let rhs_magnitude = builder.ins().band_imm(rhs, magnitude_mask, span);
+    let lhs_is_negative = builder.ins().neq(lhs, lhs_magnitude, span);
+    let rhs_is_negative = builder.ins().neq(rhs, rhs_magnitude, span);
+
+    // The bit patterns of values of the same sign are ordered like their magnitudes, i.e. in the
+    // reverse order of the values if they are negative, and otherwise the negative value is less
+    let lhs_below = builder.ins().lt(lhs, rhs, span);
+    let lhs_above = builder.ins().gt(lhs, rhs, span);
+    let same_sign_lhs_less = builder
+        .ins()
+        .select(lhs_is_negative, lhs_above, lhs_below, span);
+    let signs_differ = builder.ins().xor(lhs_is_negative, rhs_is_negative, span);
+    let lhs_less = builder
+        .ins()
+        .select(signs_differ, lhs_is_negative, same_sign_lhs_less, span);
+    let result = if is_max {
+        builder.ins().select(lhs_less, rhs, lhs, span)
+    } else {
+        builder.ins().select(lhs_less, lhs, rhs, span)
+    };
+
+    let infinity = format.imm(format.infinity());
+    let lhs_is_nan = builder.ins().gt_imm(lhs_magnitude, infinity, span);
+    let rhs_is_nan = builder.ins().gt_imm(rhs_magnitude, infinity, span);
+    let quiet_bit = format.imm(format.quiet_bit());
+    let lhs_nan = builder.ins().bor_imm(lhs, quiet_bit, span);
+    let rhs_nan = builder.ins().bor_imm(rhs, quiet_bit, span);
+    let nan = builder.ins().select(lhs_is_nan, lhs_nan, rhs_nan, span);
+    let is_nan = builder.ins().or(lhs_is_nan, rhs_is_nan, span);
+    let result = builder.ins().select(is_nan, nan, result, span);
+    format.float_of(result, builder, span)
+}
+
+/// The rounding of a floating-point value to an integral value
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Rounding {
+    /// Towards zero, i.e. `f32.trunc`/`f64.trunc`
+    Trunc,
+    /// Towards negative infinity, i.e. `f32.floor`/`f64.floor`
+    Floor,
+    /// Towards positive infinity, i.e. `f32.ceil`/`f64.ceil`
+    Ceil,
+    /// To the nearest integral value, with ties to even, i.e. `f32.nearest`/`f64.nearest`
+    Nearest,
+}
+
+/// Translate the rounding of the floating-point value `val` to an integral value.
+///
+/// Values of magnitude below one have no integral part, so they round to zero or one, keeping
+/// their sign. Otherwise, the bits of the significand below the unit of the integral part are
+/// cleared, and if rounding away from zero, the unit is added to the magnitude, which may carry
+/// into the exponent. Infinities are integral already, and as required by the spec, NaNs are made
+/// quiet.
+fn translate_float_round(
+    val: Value,
+    rounding: Rounding,
+    format: FloatFormat,
+    builder: &mut FunctionBuilderExt,
+    span: SourceSpan,
+) -> Value {
+    let bits = format.bits_of(val, builder, span);
+    let magnitude = builder
+        .ins()
+        .band_imm(bits, format.imm(format.magnitude_mask()), span);
+    let sign = builder
+        .ins()
+        .band_imm(bits, format.imm(format.sign_mask()), span);
+    let is_negative = builder.ins().neq(bits, magnitude, span);
+
+    // The biased exponent of values of magnitude at least one is at least the bias, and those of
+    // magnitude at least `2^significand_bits` are integral, so the number of fractional bits is
+    // `bias + significand_bits - exponent`, clamped to the width of the significand
+    let max_exponent = format.bias + format.significand_bits as u64;
+    let exponent =
+        builder
+            .ins()
+            .shr_imm_wrapping(magnitude, format.imm(format.significand_bits as u64), span);
+    let exponent = builder
+        .ins()
+        .max_imm(exponent, format.imm(format.bias), span);
+    let exponent = builder
+        .ins()
+        .min_imm(exponent, format.imm(max_exponent), span);
+    let max_exponent = format.constant(max_exponent, builder, span);
+    let fraction_bits = builder.ins().sub_wrapping(max_exponent, exponent, span);
+    let one = format.constant(1, builder, span);
+    let unit = builder.ins().shl_wrapping(one, fraction_bits, span);
+    let fraction_mask = builder.ins().sub_imm_wrapping(unit, format.imm(1), span);
+    let fraction = builder.ins().band(bits, fraction_mask, span);
+    let truncated = builder.ins().bxor(bits, fraction, span);
+    let has_fraction = builder.ins().neq_imm(fraction, format.imm(0), span);
+    let round_up = match rounding {
+        Rounding::Trunc => None,
+        Rounding::Floor => Some(builder.ins().and(has_fraction, is_negative, span)),
+        Rounding::Ceil => {
+            let is_positive = builder.ins().not(is_negative, span);
+            Some(builder.ins().and(has_fraction, is_positive, span))
+        }
+        Rounding::Nearest => {
+            // The lowest bit of the integral part is the bit of the unit, which for values of
+            // magnitude below two is the lowest bit of the exponent, and is set, as the bias is odd
+            let half = builder.ins().shr_imm_wrapping(unit, format.imm(1), span);
+            let above_half = builder.ins().gt(fraction, half, span);
+            let at_half = builder.ins().eq(fraction, half, span);
+            let lowest_bit = builder.ins().band(truncated, unit, span);
+            let is_odd = builder.ins().neq_imm(lowest_bit, format.imm(0), span);
+            let tie_to_even = builder.ins().and(at_half, is_odd, span);
+            let round_up = builder.ins().or(above_half, tie_to_even, span);
+            Some(builder.ins().and(has_fraction, round_up, span))
+        }
+    };
+    let integral = match round_up {
+        None => truncated,
+        Some(round_up) => {
+            let rounded_up = builder.ins().add_wrapping(truncated, unit, span);
+            builder.ins().select(round_up, rounded_up, truncated, span)
+        }
+    };
+
+    // The magnitude of values below one rounds to either zero or one
+    let signed_one = builder.ins().bor_imm(sign, format.imm(format.one()), span);
+    let is_nonzero = builder.ins().neq_imm(magnitude, format.imm(0), span);
+    let fractional = match rounding {
+        Rounding::Trunc => sign,
+        Rounding::Floor => {
+            let round_up = builder.ins().and(is_nonzero, is_negative, span);
+            builder.ins().select(round_up, signed_one, sign, span)
+        }
+        Rounding::Ceil => {
+            let is_positive = builder.ins().not(is_negative, span);
+            let round_up = builder.ins().and(is_nonzero, is_positive, span);
+            builder.ins().select(round_up, signed_one, sign, span)
+        }
+        Rounding::Nearest => {
+            let round_up = builder
+                .ins()
+                .gt_imm(magnitude, format.imm(format.half()), span);
+            builder.ins().select(round_up, signed_one, sign, span)
+        }
+    };
+    let is_fractional = builder
+        .ins()
+        .lt_imm(magnitude, format.imm(format.one()), span);
+    let result = builder
+        .ins()
+        .select(is_fractional, fractional, integral, span);
+
+    let is_nan = builder
+        .ins()
+        .gt_imm(magnitude, format.imm(format.infinity()), span);
+    let nan = builder
+        .ins()
+        .bor_imm(bits, format.imm(format.quiet_bit()), span);
+    let result = builder.ins().select(is_nan, nan, result, span);
+    format.float_of(result, builder, span)
+}
+
+/// Translate `memory.init`, which copies `len` bytes starting at offset `src` of a data segment,
+/// to address `dst` in linear memory.
+///
+/// This traps if `src + len` exceeds the current length of the segment, or if `dst + len` exceeds
+/// the current size of linear memory, see [build_memory_size].
+///
+/// The contents of the segment are placed in its global once, by the function built for the
+/// segment, see `build_passive_data_segments`, which is called first. Only the `len` bytes
+/// requested are then copied from there, one at a time.
+fn translate_memory_init(
+    data_index: DataIndex,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    module: &Module,
+    span: SourceSpan,
+) {
+    let len = state.pop1_casted(U32, builder, span);
+    let src = state.pop1_casted(U32, builder, span);
+    let dst = state.pop1_casted(U32, builder, span);
+
+    // The end of the copy is computed with 64 bits, as linear memory may be as large as the
+    // 32-bit address space, in which case a copy may end exactly at the end of it
+    let dst_end = {
+        let dst = builder.ins().zext(dst, U64, span);
+        let len = builder.ins().zext(len, U64, span);
+        builder.ins().add_checked(dst, len, span)
+    };
+    let memory_size = build_memory_size(builder, module, span);
+    let memory_size = builder.ins().zext(memory_size, U64, span);
+    let memory_bytes =
+        builder
+            .ins()
+            .mul_imm_checked(memory_size, Immediate::U64(WASM_PAGE_SIZE as u64), span);
+    let dst_in_bounds = builder.ins().lte(dst_end, memory_bytes, span);
+    builder.ins().assert(dst_in_bounds, span);
+
+    // Segments are never larger than the address space, so `src + len` overflowing is out of
+    // bounds too, and trapping on overflow is correct
+    let src_end = builder.ins().add_checked(src, len, span);
+    let data = match module.passive_data_map.get(&data_index) {
+        Some(data) if !data.is_empty() => data,
+        // Active segments have already been dropped, and empty segments have nothing to copy,
+        // so only a copy of zero bytes from offset zero is in bounds
+        _ => {
+            let src_in_bounds = builder.ins().eq_imm(src_end, Immediate::U32(0), span);
+            builder.ins().assert(src_in_bounds, span);
+            return;
+        }
+    };
+    // The source range must be within the segment, unless it is empty, the segment must also not
+    // have been dropped, as a dropped segment has a length of zero
+    let src_within_segment =
+        builder
+            .ins()
+            .lte_imm(src_end, Immediate::U32(data.len() as u32), span);
+    let src_is_empty = builder.ins().eq_imm(src_end, Immediate::U32(0), span);
+    let dropped =
+        builder
+            .ins()
+            .load_symbol(module.passive_data_dropped_name(data_index), U32, span);
+    let not_dropped = builder.ins().eq_imm(dropped, Immediate::U32(0), span);
+    let src_in_segment = builder.ins().bor(not_dropped, src_is_empty, span);
+    let src_in_bounds = builder.ins().band(src_within_segment, src_in_segment, span);
+    builder.ins().assert(src_in_bounds, span);
+
+    let place = FunctionIdent {
+        module: builder.id().module,
+        function: Ident::with_empty_span(Symbol::intern(
+            module.passive_data_place_name(data_index),
+        )),
+    };
+    builder
+        .data_flow_graph_mut()
+        .import_function(place.module, place.function, passive_data_place_signature())
+        .expect("the placement function of a passive data segment is imported with its signature");
+    builder.ins().call(place, &[], span);
+
+    let segment_addr =
+        builder
+            .ins()
+            .symbol_addr(module.passive_data_name(data_index), Ptr(U8.into()), span);
+    let segment_addr = builder.ins().ptrtoint(segment_addr, U32, span);
+    let src = builder.ins().add_unchecked(segment_addr, src, span);
+
+    // Copy a byte at a time, while the offset `i` into the copied range is less than `len`
+    let header = builder.create_block_with_params([U32], span);
+    let body = builder.create_block();
+    let exit = builder.create_block();
+    let zero = builder.ins().u32(0, span);
+    builder.ins().br(header, &[zero], span);
+
+    builder.switch_to_block(header);
+    let i = builder.block_params(header)[0];
+    let done = builder.ins().gte(i, len, span);
+    builder.ins().cond_br(done, exit, &[], body, &[], span);
+    builder.seal_block(body);
+    builder.seal_block(exit);
+
+    builder.switch_to_block(body);
+    let src_addr = builder.ins().add_unchecked(src, i, span);
+    let src_ptr = prepare_addr(src_addr, &U8, None, builder, span);
+    let byte = builder.ins().load(src_ptr, span);
+    let dst_addr = builder.ins().add_unchecked(dst, i, span);
+    let dst_ptr = prepare_addr(dst_addr, &U8, None, builder, span);
+    builder.ins().store(dst_ptr, byte, span);
+    let next = builder.ins().add_imm_unchecked(i, Immediate::U32(1), span);
+    builder.ins().br(header, &[next], span);
+    builder.seal_block(header);
+
+    builder.switch_to_block(exit);
+}
+
+/// The signature of the function which places the contents of a passive data segment in its
+/// global, see `build_passive_data_segments`
+pub(crate) fn passive_data_place_signature() -> Signature {
+    Signature {
+        linkage: Linkage::Internal,
+        ..Signature::new([], [])
+    }
+}
+
+/// Returns the current size of linear memory in pages, as a `u32`, see `translate_memory_grow`
+fn build_memory_size(builder: &mut FunctionBuilderExt, module: &Module, span: SourceSpan) -> Value {
+    builder
+        .ins()
+        .load_symbol(module.memory_size_name(), U32, span)
+}
+
+fn translate_call(
     state: &mut FuncTranslationState,
     builder: &mut FunctionBuilderExt,
+    function_index: FuncIndex,
+    module: &Module,
+    mod_types: &ModuleTypes,
     span: SourceSpan,
-) -> Result<(), WasmError> {
-    let default = targets.default();
-    let mut min_depth = default;
-    for depth in targets.targets() {
-        let depth = depth?;
-        if depth < min_depth {
-            min_depth = depth;
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let (fident, num_args) = state.get_direct_func(
+        builder.data_flow_graph_mut(),
+        function_index,
+        module,
+        mod_types,
+        diagnostics,
+    )?;
+    let args = state.peekn_mut(num_args);
+    // TODO: For imported functions, use their intended invocation method (e.g. `call` or `exec`)
+    let call = builder.ins().call(fident, &args, span);
+    let inst_results = builder.inst_results(call);
+    state.popn(num_args);
+    state.pushn(inst_results);
+    Ok(())
+}
+
+/// A function shared by all of the indirect calls of a module which are dispatched to the same
+/// functions, so that the code dispatching them is only emitted once, rather than at each call.
+///
+/// The function takes the value dispatched on, followed by the arguments of the call, see
+/// [dispatcher_signature], and is built once the functions of the module have been translated,
+/// see [build_dispatcher_body].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Dispatcher {
+    /// Dispatches `call_indirect` through the given table on the index of an entry, to the
+    /// entries holding functions of the given type
+    Table(TableIndex, SignatureIndex),
+    /// Dispatches `call_ref` on a reference to a function of the given type
+    Ref(SignatureIndex),
+}
+
+impl Dispatcher {
+    /// Returns the type of the functions this dispatches to
+    fn signature(self) -> SignatureIndex {
+        match self {
+            Self::Table(_, signature) | Self::Ref(signature) => signature,
         }
     }
-    let jump_args_count = {
-        let i = state.control_stack.len() - 1 - (min_depth as usize);
-        let min_depth_frame = &state.control_stack[i];
-        if min_depth_frame.is_loop() {
-            min_depth_frame.num_param_values()
-        } else {
-            min_depth_frame.num_return_values()
-        }
-    };
-    let val = state.pop1();
-    let val = if builder.data_flow_graph().value_type(val) != &U32 {
-        builder.ins().cast(val, U32, span)
-    } else {
-        val
-    };
-    let mut data = Vec::with_capacity(targets.len() as usize);
-    if jump_args_count == 0 {
-        // No jump arguments
-        for depth in targets.targets() {
-            let depth = depth?;
-            let block = {
-                let i = state.control_stack.len() - 1 - (depth as usize);
-                let frame = &mut state.control_stack[i];
-                frame.set_branched_to_exit();
-                frame.br_destination()
-            };
-            data.push((depth, block));
-        }
-        let def_block = {
-            let i = state.control_stack.len() - 1 - (default as usize);
-            let frame = &mut state.control_stack[i];
-            frame.set_branched_to_exit();
-            frame.br_destination()
-        };
-        builder.ins().switch(val, data, def_block, span);
-    } else {
-        // Here we have jump arguments, but Midens's switch op doesn't support them
-        // We then proceed to split the edges going out of the br_table
-        let return_count = jump_args_count;
-        let mut dest_block_sequence = vec![];
-        let mut dest_block_map = FxHashMap::default();
-        for depth in targets.targets() {
-            let depth = depth?;
-            let branch_block = match dest_block_map.entry(depth as usize) {
-                hash_map::Entry::Occupied(entry) => *entry.get(),
-                hash_map::Entry::Vacant(entry) => {
-                    let block = builder.create_block();
-                    dest_block_sequence.push((depth as usize, block));
-                    *entry.insert(block)
-                }
-            };
-            data.push((depth, branch_block));
-        }
-        let default_branch_block = match dest_block_map.entry(default as usize) {
-            hash_map::Entry::Occupied(entry) => *entry.get(),
-            hash_map::Entry::Vacant(entry) => {
-                let block = builder.create_block();
-                dest_block_sequence.push((default as usize, block));
-                *entry.insert(block)
+
+    /// Returns the name of the function of this dispatcher in `module`
+    pub(crate) fn name(self, module: &Module) -> String {
+        match self {
+            Self::Table(table_index, signature) => {
+                module.table_dispatcher_name(table_index, signature)
             }
-        };
-        builder.ins().switch(val, data, default_branch_block, span);
-        for (depth, dest_block) in dest_block_sequence {
-            builder.switch_to_block(dest_block);
-            builder.seal_block(dest_block);
-            let real_dest_block = {
-                let i = state.control_stack.len() - 1 - depth;
-                let frame = &mut state.control_stack[i];
-                frame.set_branched_to_exit();
-                frame.br_destination()
-            };
-            let destination_args = state.peekn_mut(return_count);
-            builder.ins().br(real_dest_block, destination_args, span);
+            Self::Ref(signature) => module.ref_dispatcher_name(signature),
         }
-        state.popn(return_count);
     }
-    state.reachable = false;
-    Ok(())
 }
 
-/// Return the total Miden VM memory size (2^32 addresses * word (4 felts) bytes) in 64KB pages
-const fn mem_total_pages() -> i32 {
-    const FELT_BYTES: u64 = 4; // felts are effectively 32 bits
-    const WORD_BYTES: u64 = 4 * FELT_BYTES; // 4 felts per word
-    const PAGE_SIZE: u64 = 64 * 1024;
-    const MEMORY_SIZE: u64 = u32::MAX as u64 * WORD_BYTES;
-    (MEMORY_SIZE / PAGE_SIZE) as i32
+/// Returns the signature of the function of `dispatcher`, which takes the index of the table
+/// entry, or the function reference, dispatched on, followed by the parameters of the functions
+/// it dispatches to, and returns their results
+pub(crate) fn dispatcher_signature(
+    dispatcher: Dispatcher,
+    mod_types: &ModuleTypes,
+) -> WasmResult<Signature> {
+    let func_type = ir_func_type(&mod_types[dispatcher.signature()])?;
+    let selector_ty = match dispatcher {
+        Dispatcher::Table(..) => I32,
+        Dispatcher::Ref(_) => U32,
+    };
+    let params = std::iter::once(selector_ty)
+        .chain(func_type.params)
+        .collect();
+    Ok(ir_func_sig(
+        &FunctionType::new(params, func_type.results),
+        CallConv::SystemV,
+        Linkage::Internal,
+    ))
 }
 
-fn translate_load(
-    ptr_ty: Type,
-    memarg: &MemArg,
+/// Translate a call through a typed function reference, i.e. `call_ref`.
+///
+/// Miden IR has no indirect calls, so the call is dispatched on the value of the reference, which
+/// is the index of the function plus one, to a direct call of each function it may refer to, i.e.
+/// those which escape the module, and have the signature of `type_index`. A null reference traps.
+///
+/// The dispatch is done by a function shared by the calls through references of the same type,
+/// see [Dispatcher].
+fn translate_call_ref(
     state: &mut FuncTranslationState,
     builder: &mut FunctionBuilderExt,
+    type_index: TypeIndex,
+    module: &Module,
+    mod_types: &ModuleTypes,
     span: SourceSpan,
-) {
-    let addr_int = state.pop1();
-    let addr = prepare_addr(addr_int, &ptr_ty, Some(memarg), builder, span);
-    state.push1(builder.ins().load(addr, span));
+) -> WasmResult<()> {
+    let callee = state.pop1();
+    let signature = module.types[type_index].unwrap_function();
+    translate_dispatcher_call(
+        Dispatcher::Ref(signature),
+        callee,
+        state,
+        builder,
+        module,
+        mod_types,
+        span,
+    )
 }
 
-fn translate_load_sext(
-    ptr_ty: Type,
-    sext_ty: Type,
-    memarg: &MemArg,
+/// Translate a call through a table, i.e. `call_indirect`.
+///
+/// Table instructions other than `call_indirect` are not supported, so the contents of a table
+/// never change after instantiation, and are known statically, see [table_entries]. The call is
+/// dispatched on the index into the table to a direct call of the function of each entry which
+/// has the signature of `type_index`. An index which is out of bounds, or refers to a null entry,
+/// or to a function of another type, traps.
+///
+/// An entry initialized from a global is dispatched on the reference held by the global instead,
+/// as for `call_ref`, see [translate_call_ref].
+///
+/// The dispatch is done by a function shared by the calls through the same table with the same
+/// type, see [Dispatcher].
+///
+/// The entries of an imported table may be set by other modules, and so cannot be resolved,
+/// which is why calls through an imported table are not supported.
+#[allow(clippy::too_many_arguments)]
+fn translate_call_indirect(
     state: &mut FuncTranslationState,
     builder: &mut FunctionBuilderExt,
+    type_index: TypeIndex,
+    table_index: TableIndex,
+    module: &Module,
+    mod_types: &ModuleTypes,
     span: SourceSpan,
-) {
-    let addr_int = state.pop1();
-    let addr = prepare_addr(addr_int, &ptr_ty, Some(memarg), builder, span);
-    let val = builder.ins().load(addr, span);
-    let sext_val = builder.ins().sext(val, sext_ty, span);
-    state.push1(sext_val);
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    if module.is_imported_table(table_index) {
+        unsupported_diag!(
+            diagnostics,
+            UnsupportedFeature::Operator,
+            "call_indirect through the imported table {}, the entries of which may be set by \
+             other modules, is not supported",
+            table_index.as_u32()
+        );
+    }
+    if table_entries(module, table_index).is_none() {
+        unsupported_diag!(
+            diagnostics,
+            UnsupportedFeature::Operator,
+            "call_indirect through table {}, which is initialized by a segment whose offset is a \
+             global, is not supported",
+            table_index.as_u32()
+        );
+    }
+    let index = state.pop1();
+    let signature = module.types[type_index].unwrap_function();
+    translate_dispatcher_call(
+        Dispatcher::Table(table_index, signature),
+        index,
+        state,
+        builder,
+        module,
+        mod_types,
+        span,
+    )
 }
 
-fn translate_load_zext(
-    ptr_ty: Type,
-    zext_ty: Type,
-    memarg: &MemArg,
+/// Translate a call of the function of `dispatcher`, dispatching on `selector`, with the
+/// arguments on top of the stack
+fn translate_dispatcher_call(
+    dispatcher: Dispatcher,
+    selector: Value,
     state: &mut FuncTranslationState,
     builder: &mut FunctionBuilderExt,
+    module: &Module,
+    mod_types: &ModuleTypes,
     span: SourceSpan,
-) {
-    assert!(ptr_ty.is_unsigned_integer());
-    let addr_int = state.pop1();
-    let addr = prepare_addr(addr_int, &ptr_ty, Some(memarg), builder, span);
-    let val = builder.ins().load(addr, span);
-    let sext_val = builder.ins().zext(val, zext_ty, span);
-    state.push1(sext_val);
+) -> WasmResult<()> {
+    let num_args = ir_func_type(&mod_types[dispatcher.signature()])?
+        .params
+        .len();
+    let args = state.peekn(num_args).to_vec();
+    let results = emit_dispatcher_call(
+        dispatcher, selector, &args, state, builder, module, mod_types, span,
+    )?;
+    state.popn(num_args);
+    state.pushn(&results);
+    Ok(())
 }
 
-fn translate_store(
-    ptr_ty: Type,
-    memarg: &MemArg,
+/// Emits a call of the function of `dispatcher` with `selector` and `args`, returning its
+/// results, and records that the function must be built, see [FuncTranslationState::dispatchers]
+#[allow(clippy::too_many_arguments)]
+fn emit_dispatcher_call(
+    dispatcher: Dispatcher,
+    selector: Value,
+    args: &[Value],
     state: &mut FuncTranslationState,
     builder: &mut FunctionBuilderExt,
+    module: &Module,
+    mod_types: &ModuleTypes,
     span: SourceSpan,
-) {
-    let (addr_int, val) = state.pop2();
-    let val_ty = builder.data_flow_graph().value_type(val);
-    let arg = if ptr_ty != *val_ty {
-        builder.ins().trunc(val, ptr_ty.clone(), span)
-    } else {
-        val
+) -> WasmResult<Vec<Value>> {
+    let callee = FunctionIdent {
+        module: builder.id().module,
+        function: Ident::with_empty_span(Symbol::intern(dispatcher.name(module))),
     };
-    let addr = prepare_addr(addr_int, &ptr_ty, Some(memarg), builder, span);
-    builder.ins().store(addr, arg, span);
+    builder
+        .data_flow_graph_mut()
+        .import_function(
+            callee.module,
+            callee.function,
+            dispatcher_signature(dispatcher, mod_types)?,
+        )
+        .expect("the function of a dispatcher is imported with its signature");
+    state.dispatchers.insert(dispatcher);
+    let mut call_args = vec![selector];
+    call_args.extend_from_slice(args);
+    let call = builder.ins().call(callee, &call_args, span);
+    Ok(builder.inst_results(call).to_vec())
 }
 
-fn prepare_addr(
-    addr_int: Value,
-    ptr_ty: &Type,
-    memarg: Option<&MemArg>,
+/// Build the body of the function of `dispatcher`, the signature of which is given by
+/// [dispatcher_signature]
+pub(crate) fn build_dispatcher_body(
+    dispatcher: Dispatcher,
+    state: &mut FuncTranslationState,
     builder: &mut FunctionBuilderExt,
-    span: SourceSpan,
-) -> Value {
-    let addr_int_ty = builder.data_flow_graph().value_type(addr_int);
-    let addr_u32 = if *addr_int_ty == U32 {
-        addr_int
-    } else {
-        builder.ins().cast(addr_int, U32, span)
+    module: &Module,
+    mod_types: &ModuleTypes,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let span = SourceSpan::default();
+    let entry_block = builder.current_block();
+    builder.seal_block(entry_block);
+    let params = builder.block_params(entry_block).to_vec();
+    let (&selector, args) = params
+        .split_first()
+        .expect("the function of a dispatcher takes the value dispatched on");
+    let signature = dispatcher.signature();
+    let cases = match dispatcher {
+        Dispatcher::Table(table_index, _) => table_entries(module, table_index)
+            .expect("dispatchers are only built for tables with entries known statically")
+            .into_iter()
+            .enumerate()
+            .filter_map(|(entry, target)| {
+                let target = match target {
+                    TableEntry::Null => return None,
+                    TableEntry::Func(func_index) => {
+                        if module.functions[func_index].signature != signature {
+                            return None;
+                        }
+                        DispatchTarget::Func(func_index)
+                    }
+                    TableEntry::Global(global_index) => DispatchTarget::Global(global_index),
+                };
+                Some((Immediate::I32(entry as i32), target))
+            })
+            .collect::<Vec<_>>(),
+        Dispatcher::Ref(_) => {
+            let not_null = builder.ins().neq_imm(selector, Immediate::U32(0), span);
+            builder.ins().assert(not_null, span);
+            module
+                .functions
+                .iter()
+                .filter(|(_, func)| func.is_escaping() && func.signature == signature)
+                .map(|(func_index, _)| {
+                    let imm = Immediate::U32(func_index.as_u32() + 1);
+                    (imm, DispatchTarget::Func(func_index))
+                })
+                .collect::<Vec<_>>()
+        }
     };
-    let mut full_addr_int = addr_u32;
-    if let Some(memarg) = memarg {
-        if memarg.offset != 0 {
-            full_addr_int =
-                builder
-                    .ins()
-                    .add_imm_checked(addr_u32, Immediate::U32(memarg.offset as u32), span);
+    state.pushn(args);
+    translate_dispatched_call(
+        selector,
+        &cases,
+        signature,
+        state,
+        builder,
+        module,
+        mod_types,
+        span,
+        diagnostics,
+    )?;
+    if !state.reachable {
+        return Ok(());
+    }
+    let results = state.peekn(state.stack.len()).to_vec();
+    state.popn(results.len());
+    match results.as_slice() {
+        [] => builder.ins().ret(None, span),
+        [result] => builder.ins().ret(Some(*result), span),
+        _ => {
+            unsupported_diag!(
+                diagnostics,
+                UnsupportedFeature::MultiValue,
+                "Multiple values are not supported"
+            );
         }
     };
-    builder
-        .ins()
-        .inttoptr(full_addr_int, Type::Ptr(ptr_ty.clone().into()), span)
+    Ok(())
 }
 
-fn translate_call(
+/// An entry of a table after instantiation, see [table_entries]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TableEntry {
+    /// A null reference, calling through which traps
+    Null,
+    /// A reference to the given function
+    Func(FuncIndex),
+    /// The reference held by the given global, which is immutable, as it is read by a constant
+    /// expression, so it may be read when the entry is called through rather than when the table
+    /// is initialized
+    Global(GlobalIndex),
+}
+
+impl From<FuncIndex> for TableEntry {
+    fn from(func_index: FuncIndex) -> Self {
+        if func_index.is_reserved_value() {
+            Self::Null
+        } else {
+            Self::Func(func_index)
+        }
+    }
+}
+
+/// Returns the entries of the table `table_index` after instantiation, i.e. its initial value,
+/// overwritten by the active element segments for it.
+///
+/// Entries set by a segment whose offset is a global are only known at instantiation, in which
+/// case `None` is returned.
+fn table_entries(module: &Module, table_index: TableIndex) -> Option<Vec<TableEntry>> {
+    let table_init = &module.table_initialization;
+    let mut entries = match module
+        .defined_table_index(table_index)
+        .map(|defined_index| &table_init.initial_values[defined_index])
+    {
+        Some(TableInitialValue::Null { precomputed }) => {
+            precomputed.iter().copied().map(TableEntry::from).collect()
+        }
+        Some(TableInitialValue::FuncRef(func_index)) => {
+            vec![TableEntry::from(*func_index); module.tables[table_index].minimum as usize]
+        }
+        // The initial entries of an imported table are set by the module it is imported from
+        None => vec![],
+    };
+    for segment in table_init.segments.iter() {
+        if segment.table_index != table_index {
+            continue;
+        }
+        if segment.base.is_some() {
+            return None;
+        }
+        let offset = segment.offset as usize;
+        let end = offset + segment.elements.len();
+        if entries.len() < end {
+            entries.resize(end, TableEntry::Null);
+        }
+        for (entry, element) in entries[offset..end].iter_mut().zip(segment.elements.iter()) {
+            *entry = match *element {
+                TableSegmentElement::Func(func_index) => TableEntry::from(func_index),
+                TableSegmentElement::Global(global_index) => TableEntry::Global(global_index),
+            };
+        }
+    }
+    Some(entries)
+}
+
+/// The function called by a case of [translate_dispatched_call]
+#[derive(Clone, Copy, Debug)]
+enum DispatchTarget {
+    /// The given function
+    Func(FuncIndex),
+    /// The function referred to by the given global, see [TableEntry::Global]
+    Global(GlobalIndex),
+}
+
+/// Translate a call of a function of type `signature`, with the arguments on top of the stack,
+/// which is dispatched to a direct call of the function of the first of `cases` whose immediate
+/// is equal to `selector`. If there is no such case, the call traps.
+///
+/// A case whose target is a global is dispatched again on the reference it holds, by a call of the
+/// function dispatching `call_ref` with that signature, see [Dispatcher::Ref].
+#[allow(clippy::too_many_arguments)]
+fn translate_dispatched_call(
+    selector: Value,
+    cases: &[(Immediate, DispatchTarget)],
+    signature: SignatureIndex,
     state: &mut FuncTranslationState,
     builder: &mut FunctionBuilderExt,
-    function_index: FuncIndex,
     module: &Module,
     mod_types: &ModuleTypes,
     span: SourceSpan,
     diagnostics: &DiagnosticsHandler,
 ) -> WasmResult<()> {
-    let (fident, num_args) = state.get_direct_func(
+    let func_type = ir_func_type(&mod_types[signature])?;
+    let num_args = func_type.params.len();
+    let args = state.peekn(num_args).to_vec();
+    if cases.is_empty() {
+        // No function can be called, so the call always traps
+        builder.ins().unreachable(span);
+        state.popn(num_args);
+        state.reachable = false;
+        return Ok(());
+    }
+
+    let exit_block = builder.create_block_with_params(func_type.results, span);
+    for &(imm, target) in cases {
+        let call_block = builder.create_block();
+        let next_block = builder.create_block();
+        let is_callee = builder.ins().eq_imm(selector, imm, span);
+        builder
+            .ins()
+            .cond_br(is_callee, call_block, &[], next_block, &[], span);
+        builder.seal_block(call_block);
+        builder.seal_block(next_block);
+
+        builder.switch_to_block(call_block);
+        match target {
+            DispatchTarget::Func(func_index) => emit_dispatched_call(
+                func_index,
+                &args,
+                exit_block,
+                state,
+                builder,
+                module,
+                mod_types,
+                span,
+                diagnostics,
+            )?,
+            DispatchTarget::Global(global_index) => {
+                let callee = builder
+                    .ins()
+                    .load_symbol(module.global_name(global_index), U32, span);
+                let results = emit_dispatcher_call(
+                    Dispatcher::Ref(signature),
+                    callee,
+                    &args,
+                    state,
+                    builder,
+                    module,
+                    mod_types,
+                    span,
+                )?;
+                builder.ins().br(exit_block, &results, span);
+            }
+        }
+        builder.switch_to_block(next_block);
+    }
+    // None of the cases is selected
+    builder.ins().unreachable(span);
+    builder.seal_block(exit_block);
+
+    builder.switch_to_block(exit_block);
+    state.popn(num_args);
+    state.pushn(builder.block_params(exit_block));
+    Ok(())
+}
+
+/// Emits a direct call of `func_index` with `args`, for a case of [translate_dispatched_call],
+/// which branches to `exit_block` with its results
+#[allow(clippy::too_many_arguments)]
+fn emit_dispatched_call(
+    func_index: FuncIndex,
+    args: &[Value],
+    exit_block: Block,
+    state: &mut FuncTranslationState,
+    builder: &mut FunctionBuilderExt,
+    module: &Module,
+    mod_types: &ModuleTypes,
+    span: SourceSpan,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let (fident, _) = state.get_direct_func(
         builder.data_flow_graph_mut(),
-        function_index,
+        func_index,
         module,
         mod_types,
         diagnostics,
     )?;
-    let args = state.peekn_mut(num_args);
-    // TODO: For imported functions, use their intended invocation method (e.g. `call` or `exec`)
-    let call = builder.ins().call(fident, &args, span);
-    let inst_results = builder.inst_results(call);
-    state.popn(num_args);
-    state.pushn(inst_results);
+    let call = builder.ins().call(fident, args, span);
+    let results = builder.inst_results(call).to_vec();
+    builder.ins().br(exit_block, &results, span);
     Ok(())
 }
-
 fn translate_return(
     state: &mut FuncTranslationState,
     builder: &mut FunctionBuilderExt,
@@ -669,7 +3367,11 @@ fn translate_return(
             0 => None,
             1 => Some(state.peekn_mut(return_count).first().unwrap().clone()),
             _ => {
-                unsupported_diag!(diagnostics, "Multiple values are not supported");
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::MultiValue,
+                    "Multiple values are not supported"
+                );
             }
         };
 
@@ -685,10 +3387,9 @@ fn translate_br(
     relative_depth: &u32,
     builder: &mut FunctionBuilderExt,
     span: SourceSpan,
-) {
-    let i = state.control_stack.len() - 1 - (*relative_depth as usize);
+) -> WasmResult<()> {
     let (return_count, br_destination) = {
-        let frame = &mut state.control_stack[i];
+        let frame = state.br_target_frame(*relative_depth)?;
         // We signal that all the code that follows until the next End is unreachable
         frame.set_branched_to_exit();
         let return_count = if frame.is_loop() {
@@ -702,6 +3403,7 @@ fn translate_br(
     builder.ins().br(br_destination, &destination_args, span);
     state.popn(return_count);
     state.reachable = false;
+    Ok(())
 }
 
 fn translate_br_if(
@@ -709,9 +3411,9 @@ fn translate_br_if(
     builder: &mut FunctionBuilderExt,
     state: &mut FuncTranslationState,
     span: SourceSpan,
-) {
+) -> WasmResult<()> {
     let cond = state.pop1();
-    let (br_destination, inputs) = translate_br_if_args(relative_depth, state);
+    let (br_destination, inputs) = translate_br_if_args(relative_depth, state)?;
     let next_block = builder.create_block();
     let then_dest = br_destination;
     let then_args = inputs;
@@ -723,15 +3425,16 @@ fn translate_br_if(
         .cond_br(cond_i1, then_dest, then_args, else_dest, else_args, span);
     builder.seal_block(next_block); // The only predecessor is the current block.
     builder.switch_to_block(next_block);
+    state.assume_condition(cond, false);
+    Ok(())
 }
 
 fn translate_br_if_args(
     relative_depth: u32,
     state: &mut FuncTranslationState,
-) -> (Block, &mut [Value]) {
-    let i = state.control_stack.len() - 1 - (relative_depth as usize);
+) -> WasmResult<(Block, &mut [Value])> {
     let (return_count, br_destination) = {
-        let frame = &mut state.control_stack[i];
+        let frame = state.br_target_frame(relative_depth)?;
         // The values returned by the branch are still available for the reachable
         // code that comes after it
         frame.set_branched_to_exit();
@@ -743,7 +3446,7 @@ fn translate_br_if_args(
         (return_count, frame.br_destination())
     };
     let inputs = state.peekn_mut(return_count);
-    (br_destination, inputs)
+    Ok((br_destination, inputs))
 }
 
 fn translate_block(
@@ -771,6 +3474,7 @@ fn translate_end(
     let frame = state.control_stack.pop().unwrap();
     let next_block = frame.following_code();
     let return_count = frame.num_return_values();
+    state.clear_guard_bounds();
     let return_args = state.peekn_mut(return_count);
 
     builder.ins().br(next_block, return_args, span);
@@ -802,6 +3506,7 @@ fn translate_else(
     span: SourceSpan,
 ) -> WasmResult<()> {
     let i = state.control_stack.len() - 1;
+    state.clear_guard_bounds();
     Ok(match state.control_stack[i] {
         ControlStackFrame::If {
             ref else_data,
@@ -920,6 +3625,7 @@ fn translate_if(
     };
     builder.seal_block(next_block);
     builder.switch_to_block(next_block);
+    state.assume_condition(cond, true);
     state.push_if(
         destination,
         else_data,
@@ -963,6 +3669,9 @@ fn translate_unreachable_operator(
     span: SourceSpan,
 ) -> WasmResult<()> {
     debug_assert!(!state.reachable);
+    if let Operator::Else | Operator::End = op {
+        state.clear_guard_bounds();
+    }
     match *op {
         Operator::If { blockty } => {
             // Push a placeholder control stack entry. The if isn't reachable,
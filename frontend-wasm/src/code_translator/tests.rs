@@ -1,9 +1,32 @@
+use std::sync::Arc;
+
 use expect_test::expect;
+use miden_diagnostics::CodeMap;
+use miden_diagnostics::SourceSpan;
+use miden_hir::symbols;
 use miden_hir::write_instruction;
+use miden_hir::AttributeValue;
+use miden_hir::EntityExport;
+use miden_hir::GlobalValueData;
 use miden_hir::Ident;
-
+use miden_hir::Instruction;
+use miden_hir::Linkage;
+use miden_hir::Opcode;
+use miden_hir::Symbol;
+use miden_hir::Type;
+use wasmparser::WasmFeatures;
+
+use crate::test_utils::callee_names;
+use crate::test_utils::capturing_diagnostics;
+use crate::test_utils::custom_section;
+use crate::test_utils::function_insts;
+use crate::test_utils::function_opcodes;
 use crate::test_utils::test_diagnostics;
+use crate::test_utils::with_unit_length;
 use crate::translate_module;
+use crate::translate_module_unvalidated;
+use crate::UnsupportedFeature;
+use crate::WasmError;
 use crate::WasmTranslationConfig;
 
 /// Compiles the given Wasm code to Miden IR and checks the IR generated.
@@ -17,6 +40,15 @@ fn check_ir(wat: &str, expected_ir: expect_test::Expect) {
 /// Check IR generated for a Wasm op(s).
 /// Wrap Wasm ops in a function and check the IR generated for the entry block of that function.
 fn check_op(wat_op: &str, expected_ir: expect_test::Expect) {
+    check_op_with_config(wat_op, &WasmTranslationConfig::default(), expected_ir)
+}
+
+/// Like [check_op], but translates the Wasm op(s) using `config`.
+fn check_op_with_config(
+    wat_op: &str,
+    config: &WasmTranslationConfig,
+    expected_ir: expect_test::Expect,
+) {
     let wat = format!(
         r#"
         (module
@@ -28,7 +60,7 @@ fn check_op(wat_op: &str, expected_ir: expect_test::Expect) {
     );
     let wasm = wat::parse_str(wat).unwrap();
     let diagnostics = test_diagnostics();
-    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    let module = translate_module(&wasm, config, &diagnostics).unwrap();
     let func = module.function(Ident::from("test_wrapper")).unwrap();
     // let fref = module.get_funcref_by_name("test_wrapper").unwrap();
     // let func = module.get_function(fref).unwrap();
@@ -358,6 +390,117 @@ fn global_var() {
     );
 }
 
+#[test]
+fn global_initializer_chains_are_folded() {
+    let wat = r#"
+        (module
+            (global $a i32 (i32.const 42))
+            (global $b i32 (global.get $a))
+            (global $c i32 (global.get $b))
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .unwrap();
+    for name in ["a", "b", "c"] {
+        let global = module.find_global(Ident::from(name)).unwrap();
+        let init = module.globals().get_constant(global.init.unwrap());
+        assert_eq!(init.as_slice(), 42u32.to_le_bytes().as_slice(), "{name}");
+    }
+}
+
+#[test]
+fn global_initializer_depending_on_imported_global_is_rejected() {
+    // The imported reference is resolved when linking, but the globals initialized from it, even
+    // indirectly, would need its value at compile time
+    let wat = r#"
+        (module
+            (import "env" "callback" (global $callback funcref))
+            (global $direct funcref (global.get $callback))
+            (global $indirect funcref (global.get $direct))
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let err = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap_err();
+    let WasmError::UnsupportedFeature { feature, message } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(feature, UnsupportedFeature::ImportedGlobal);
+    assert_eq!(
+        message,
+        "the initializer of global 'direct' depends on the imported global 'env::callback', the \
+         value of which is not known at compile time"
+    );
+    assert!(diagnostics.has_errors());
+}
+
+#[test]
+fn big_endian_memory_layout_is_rejected() {
+    let wat = r#"
+        (module
+            (memory 1)
+            (global $a i32 (i32.const 42))
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let config = WasmTranslationConfig::builder()
+        .endianness(midenc_session::Endianness::Big)
+        .build();
+    let diagnostics = test_diagnostics();
+    let err = translate_module(&wasm, &config, &diagnostics).unwrap_err();
+    let WasmError::Unsupported(message) = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(
+        message,
+        "big endian memory layout is not supported, only little endian is implemented"
+    );
+    assert!(diagnostics.has_errors());
+}
+
+#[test]
+fn global_var_unnamed() {
+    // Without a name section entry, the declared global and the symbol used to access it must
+    // still agree, otherwise `global.get` would not observe the value written by `global.set`
+    check_ir(
+        r#"
+        (module
+            (global (mut i32) i32.const 42)
+            (func $main
+                i32.const 9
+                global.set 0
+                global.get 0
+                drop
+            )
+        )
+    "#,
+        expect![[r#"
+            module noname
+
+            const $0 = 0x0000002a;
+
+            global external @global0 : i32 = $0 { id = 0 };
+
+            pub fn main() {
+            block0:
+                v0 = const.i32 9 : i32;
+                v1 = global.symbol @global0 : *mut i32;
+                store v1, v0;
+                v2 = global.load (@global0) as *mut i8 : i32;
+                br block1;
+
+            block1:
+                ret;
+            }
+        "#]],
+    );
+}
+
 #[test]
 fn memory_grow() {
     check_op(
@@ -369,11 +512,71 @@ fn memory_grow() {
         expect![[r#"
             v0 = const.i32 1 : i32;
             v1 = cast v0 : u32;
-            v2 = memory.grow v1 : i32;
+            v2 = global.load (@__wasm_memory_size) as *mut i8 : u32;
+            v3 = const.u32 65536 : u32;
+            v4 = call intrinsics::mem::memory_grow(v1, v2, v3) : i32;
+            v5 = eq v4, -1 : i1;
+            v6 = add.wrapping v2, v1 : u32;
+            v7 = select v5, v2, v6 : u32;
+            v8 = global.symbol @__wasm_memory_size : *mut u32;
+            store v8, v7;
         "#]],
     )
 }
 
+#[test]
+fn memory_grow_and_size_are_bounded_by_the_declared_maximum() {
+    let wat = r#"
+        (module
+            (memory (;0;) 1 8)
+            (func $test_wrapper (result i32)
+                memory.size
+                i32.const 1
+                memory.grow
+                i32.add
+            )
+        )"#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let config = WasmTranslationConfig {
+        max_memory_pages: 16,
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &diagnostics).unwrap();
+    let func = module.function(Ident::from("test_wrapper")).unwrap();
+    let mut w = String::new();
+    for inst in func
+        .dfg
+        .block(func.dfg.entry_block())
+        .insts()
+        .take_while(|inst| !func.dfg[*inst].opcode().is_branch())
+    {
+        write_instruction(&mut w, func, inst, 0).unwrap();
+    }
+    expect![[r#"
+        v0 = global.load (@__wasm_memory_size) as *mut i8 : i32;
+        v1 = const.i32 1 : i32;
+        v2 = cast v1 : u32;
+        v3 = global.load (@__wasm_memory_size) as *mut i8 : u32;
+        v4 = const.u32 8 : u32;
+        v5 = call intrinsics::mem::memory_grow(v2, v3, v4) : i32;
+        v6 = eq v5, -1 : i1;
+        v7 = add.wrapping v3, v2 : u32;
+        v8 = select v6, v3, v7 : u32;
+        v9 = global.symbol @__wasm_memory_size : *mut u32;
+        store v9, v8;
+        v10 = add.wrapping v0, v5 : i32;
+    "#]]
+    .assert_eq(&w);
+
+    // The memory starts at its declared initial size
+    let size = module
+        .find_global(Ident::from("__wasm_memory_size"))
+        .unwrap();
+    let init = module.globals().get_constant(size.init.unwrap());
+    assert_eq!(init.as_slice(), 1u32.to_le_bytes().as_slice());
+}
+
 #[test]
 fn memory_size() {
     check_op(
@@ -382,7 +585,7 @@ fn memory_size() {
             drop
         "#,
         expect![[r#"
-            v0 = const.i32 1048575 : i32;
+            v0 = global.load (@__wasm_memory_size) as *mut i8 : i32;
         "#]],
     )
 }
@@ -606,6 +809,55 @@ fn i32_load() {
     )
 }
 
+#[test]
+fn i32_load_unaligned_traps_when_configured() {
+    let config = WasmTranslationConfig {
+        trap_on_unaligned_access: true,
+        ..Default::default()
+    };
+    check_op_with_config(
+        r#"
+            i32.const 1024
+            i32.load offset=1 align=1
+            drop
+        "#,
+        &config,
+        expect![[r#"
+            v0 = const.i32 1024 : i32;
+            v1 = cast v0 : u32;
+            v2 = add.checked v1, 1 : u32;
+            v3 = band v2, 3 : u32;
+            v4 = eq v3, 0 : i1;
+            assert v4;
+            v5 = inttoptr v2 : *mut i32;
+            v6 = load v5 : i32;
+        "#]],
+    )
+}
+
+#[test]
+fn i32_load_of_unknown_alignment_is_checked_at_runtime() {
+    // The address is not a constant, so despite the natural alignment hint, whether the access
+    // is emulated depends on the address
+    check_op(
+        r#"
+            i32.const 1024
+            i32.const 1
+            i32.add
+            i32.load
+            drop
+        "#,
+        expect![[r#"
+            v0 = const.i32 1024 : i32;
+            v1 = const.i32 1 : i32;
+            v2 = add.wrapping v0, v1 : i32;
+            v3 = cast v2 : u32;
+            v4 = band v3, 3 : u32;
+            v5 = eq v4, 0 : i1;
+        "#]],
+    )
+}
+
 #[test]
 fn i64_load() {
     check_op(
@@ -1759,3 +2011,2033 @@ fn select_i32() {
         "#]],
     )
 }
+
+/// A loop counter guarded by `i < 10` can be incremented without wrapping when
+/// `felt_bounded_integers` is enabled.
+#[test]
+fn felt_bounded_loop_counter() {
+    let wat = r#"
+        (module
+            (func $count (result i32) (local i32)
+                block
+                    loop
+                        local.get 0
+                        i32.const 10
+                        i32.ge_u
+                        br_if 1
+                        local.get 0
+                        i32.const 1
+                        i32.add
+                        local.set 0
+                        br 0
+                    end
+                end
+                local.get 0
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+
+    let config = WasmTranslationConfig {
+        felt_bounded_integers: true,
+        ..Default::default()
+    };
+    let ir = translate_module(&wasm, &config, &diagnostics)
+        .unwrap()
+        .to_string();
+    assert!(
+        ir.contains("add.unchecked"),
+        "expected unchecked add in:\n{ir}"
+    );
+    assert!(
+        !ir.contains("add.wrapping"),
+        "unexpected wrapping add in:\n{ir}"
+    );
+
+    let ir = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics)
+        .unwrap()
+        .to_string();
+    assert!(
+        ir.contains("add.wrapping"),
+        "expected wrapping add in:\n{ir}"
+    );
+}
+
+/// Calls to imported functions without results must consume all of their arguments and must not
+/// push anything onto the value stack, regardless of how many parameters the callee takes.
+#[test]
+fn call_void_import_with_many_params() {
+    let wat = r#"
+        (module
+            (type (;0;) (func (param i64 i64 i64 i64 i64 i64 i64 i64 i64 i64)))
+            (import "env" "stub" (func $stub (type 0)))
+            (func $main (result i32)
+                i64.const 1
+                i64.const 2
+                i64.const 3
+                i64.const 4
+                i64.const 5
+                i64.const 6
+                i64.const 7
+                i64.const 8
+                i64.const 9
+                i64.const 10
+                call $stub
+                i32.const 42
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    let func = module.function(Ident::from("main")).unwrap();
+    let calls = function_insts(func)
+        .filter(|inst| func.dfg[*inst].opcode().is_call())
+        .collect::<Vec<_>>();
+    assert_eq!(calls.len(), 1);
+    let call = calls[0];
+    assert_eq!(func.dfg.inst_args(call).len(), 10);
+    assert!(func.dfg.inst_results(call).is_empty());
+    let signature = func.dfg.call_signature(call).unwrap();
+    assert_eq!(signature.arity(), 10);
+    assert!(signature.results.is_empty());
+}
+
+/// Function bodies are translated one at a time, reusing the validator's allocations between
+/// them, so translating a module with many large functions must not leak validation state (e.g.
+/// the operand or control stacks) from one function into the next.
+#[test]
+fn many_large_functions() {
+    const NUM_FUNCS: usize = 64;
+    const NUM_ADDS: usize = 512;
+
+    let mut funcs = String::new();
+    for i in 0..NUM_FUNCS {
+        // Alternate between deeply nested and flat bodies, so that a stale control stack from the
+        // previous function would be detected by the validator
+        let depth = if i % 2 == 0 { 16 } else { 0 };
+        funcs.push_str(&format!("(func $f{i} (param i32) (result i32)\n"));
+        funcs.push_str(&"(block (result i32)\n".repeat(depth));
+        funcs.push_str("local.get 0\n");
+        for j in 0..NUM_ADDS {
+            funcs.push_str(&format!("i32.const {j}\ni32.add\n"));
+        }
+        funcs.push_str(&")\n".repeat(depth));
+        funcs.push_str(")\n");
+    }
+    let wat = format!("(module\n{funcs})");
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    assert_eq!(module.functions().count(), NUM_FUNCS);
+    for i in 0..NUM_FUNCS {
+        let name = format!("f{i}");
+        let func = module.function(Ident::from(name.as_str())).unwrap();
+        let adds = function_insts(func)
+            .filter(|inst| func.dfg[*inst].opcode() == miden_hir::Opcode::Add)
+            .count();
+        assert_eq!(adds, NUM_ADDS, "unexpected number of adds in {name}");
+    }
+}
+
+/// The validator allocations reused from the previous functions must still validate each
+/// function in full, even after a function left values behind in unreachable code.
+#[test]
+fn invalid_function_after_valid_ones_is_rejected() {
+    let valid = r#"
+        (func $nested (param i32) (result i32)
+            (block (result i32)
+                (block (result i32)
+                    local.get 0
+                    local.get 0
+                    unreachable)))
+        (func $flat (param i32) (result i32)
+            local.get 0)
+    "#;
+    let diagnostics = test_diagnostics();
+    let config = WasmTranslationConfig::default();
+    let wasm = wat::parse_str(format!("(module {valid})")).unwrap();
+    assert!(translate_module(&wasm, &config, &diagnostics).is_ok());
+
+    // `$invalid` returns an i64 where an i32 is expected
+    let wasm = wat::parse_str(format!(
+        "(module {valid} (func $invalid (param i32) (result i32) i64.const 0))"
+    ))
+    .unwrap();
+    let err = translate_module(&wasm, &config, &diagnostics).unwrap_err();
+    assert!(
+        matches!(err, WasmError::InvalidWebAssembly { .. }),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn single_threaded_atomic_notify() {
+    let wat = r#"
+        (module
+            (memory (;0;) 1)
+            (func $notify (result i32)
+                i32.const 1024
+                i32.const 1
+                memory.atomic.notify
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+
+    let config = WasmTranslationConfig {
+        single_threaded_atomics: true,
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &diagnostics).unwrap();
+    let func = module.function(Ident::from("notify")).unwrap();
+    let mut w = String::new();
+    for inst in func.dfg.block(func.dfg.entry_block()).insts() {
+        write_instruction(&mut w, func, inst, 0).unwrap();
+    }
+    expect![[r#"
+        v1 = const.i32 1024 : i32;
+        v2 = const.i32 1 : i32;
+        v3 = const.i32 0 : i32;
+        br block1(v3);
+    "#]]
+    .assert_eq(&w);
+
+    let err = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap_err();
+    assert!(
+        err.to_string().contains("MemoryAtomicNotify"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn single_threaded_atomic_wait() {
+    let wat = |memory: &str| {
+        format!(
+            r#"
+            (module
+                (memory (;0;) {memory})
+                (func $wait (result i32)
+                    i32.const 1024
+                    i32.const 0
+                    i64.const -1
+                    memory.atomic.wait32
+                )
+            )
+            "#
+        )
+    };
+    let diagnostics = test_diagnostics();
+    let config = WasmTranslationConfig {
+        single_threaded_atomics: true,
+        ..Default::default()
+    };
+    let opcodes = |memory: &str| {
+        let wasm = wat::parse_str(wat(memory)).unwrap();
+        let module = translate_module(&wasm, &config, &diagnostics).unwrap();
+        function_opcodes(module.function(Ident::from("wait")).unwrap())
+    };
+
+    // On a shared memory, the value at the address is compared with the expected one
+    let shared = opcodes("1 1 shared");
+    assert!(
+        shared.contains(&Opcode::Load),
+        "unexpected opcodes: {shared:?}"
+    );
+    assert!(
+        shared.contains(&Opcode::Eq),
+        "unexpected opcodes: {shared:?}"
+    );
+    assert!(!shared.contains(&Opcode::Unreachable));
+
+    // Waiting on a memory which is not shared traps
+    let unshared = opcodes("1");
+    assert!(
+        unshared.contains(&Opcode::Unreachable),
+        "unexpected opcodes: {unshared:?}"
+    );
+    assert!(!unshared.contains(&Opcode::Load));
+}
+
+#[test]
+fn enumerate_imported_functions() {
+    let wat = r#"
+        (module
+            (import "env" "add" (func $add (param i32 i32) (result i32)))
+            (import "env" "log" (func $log (param i64)))
+            (func $main (result i32)
+                i64.const 1
+                call $log
+                i32.const 1
+                i32.const 2
+                call $add
+                i32.const 3
+                call $add
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+
+    let imports = module
+        .imported_functions()
+        .into_iter()
+        .map(|import| {
+            let sig = &import.signature;
+            (import.id.to_string(), sig.params().len(), sig.results().len())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        imports,
+        vec![
+            ("noname::add".to_string(), 2, 1),
+            ("noname::log".to_string(), 1, 0),
+        ]
+    );
+
+    let exports = module
+        .exported_functions()
+        .into_iter()
+        .map(|export| export.id.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(exports, vec!["noname::main".to_string()]);
+}
+
+#[test]
+fn table_set_element_type_mismatch() {
+    let wat = r#"
+        (module
+            (table 1 externref)
+            (func $f)
+            (elem declare func $f)
+            (func $main
+                i32.const 0
+                ref.func $f
+                table.set 0
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let err = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap_err();
+    assert!(
+        err.to_string().contains("type mismatch"),
+        "unexpected error: {err}"
+    );
+    assert!(
+        diagnostics.has_errors(),
+        "expected a diagnostic to be emitted"
+    );
+}
+
+#[test]
+fn max_function_size_exceeded() {
+    let body = "i32.const 0\ndrop\n".repeat(1000);
+    let wat = format!(
+        r#"
+        (module
+            (func $huge
+                {body}
+            )
+        )
+    "#,
+    );
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let config = WasmTranslationConfig {
+        max_function_size: Some(100),
+        ..Default::default()
+    };
+    let err = translate_module(&wasm, &config, &diagnostics).unwrap_err();
+    assert!(
+        matches!(err, WasmError::InvalidFunctionError),
+        "unexpected error: {err}"
+    );
+    assert!(diagnostics.has_errors());
+
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    assert!(module.function(Ident::from("huge")).is_some());
+}
+
+#[test]
+fn imported_and_defined_function_indices() {
+    // Function imports are interleaved with a memory import, so the function index space is
+    // 3 imported functions, followed by 5 defined functions, while the import index space has 4
+    let wat = r#"
+        (module
+            (import "env" "imp0" (func $imp0 (param i32)))
+            (import "env" "mem" (memory 1))
+            (import "env" "imp1" (func $imp1 (param i64) (result i64)))
+            (import "env" "imp2" (func $imp2 (param i32 i32) (result i32)))
+            (func $d0)
+            (func $d1 (param i32) (result i32)
+                local.get 0
+            )
+            (func $d2 (param i32 i32) (result i64)
+                i64.const 2
+            )
+            (func $d3 (export "d3") (param i64 i32) (result i64)
+                local.get 1
+                call $imp0
+                local.get 0
+                call $imp1
+            )
+            (func $d4 (result i32)
+                i32.const 1
+                i32.const 2
+                call $imp2
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+
+    let defined = module
+        .functions()
+        .map(|function| function.id.function.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(defined, vec!["d0", "d1", "d2", "d3", "d4"]);
+
+    let d3 = module.function(Ident::from("d3")).unwrap();
+    let params = d3
+        .signature
+        .params()
+        .iter()
+        .map(|param| param.ty.clone())
+        .collect::<Vec<_>>();
+    let results = d3
+        .signature
+        .results()
+        .iter()
+        .map(|result| result.ty.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(params, vec![Type::I64, Type::I32]);
+    assert_eq!(results, vec![Type::I64]);
+
+    let imports = module
+        .imported_functions()
+        .into_iter()
+        .map(|import| {
+            let sig = &import.signature;
+            (
+                import.id.to_string(),
+                sig.params().len(),
+                sig.results().len(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        imports,
+        vec![
+            ("noname::imp0".to_string(), 1, 0),
+            ("noname::imp1".to_string(), 1, 1),
+            ("noname::imp2".to_string(), 2, 1),
+        ]
+    );
+}
+
+#[test]
+fn annotate_wasm_indices() {
+    let wat = r#"
+        (module
+            (type $t0 (func (param i32)))
+            (type $t1 (func (result i32)))
+            (import "env" "imp" (func $imp (type $t0)))
+            (func $a (type $t1)
+                i32.const 1
+            )
+            (func $b (type $t0)
+                local.get 0
+                call $imp
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let config = WasmTranslationConfig {
+        annotate_wasm_indices: true,
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &diagnostics).unwrap();
+
+    // Function indices account for the imported function, signature indices are those of the
+    // type section
+    for (name, func_index, sig_index) in [("a", 1, 1), ("b", 2, 0)] {
+        let function = module.function(Ident::from(name)).unwrap();
+        assert_eq!(
+            function.dfg.get_attribute(&symbols::WasmFuncIndex),
+            Some(&AttributeValue::Int(func_index)),
+            "unexpected func index for '{name}'"
+        );
+        assert_eq!(
+            function.dfg.get_attribute(&symbols::WasmSignatureIndex),
+            Some(&AttributeValue::Int(sig_index)),
+            "unexpected signature index for '{name}'"
+        );
+    }
+
+    // Without the option, no attributes are added
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    let function = module.function(Ident::from("a")).unwrap();
+    assert!(!function.has_attribute(&symbols::WasmFuncIndex));
+    assert!(!function.has_attribute(&symbols::WasmSignatureIndex));
+}
+
+#[test]
+fn passive_data_segments_alongside_active_ones() {
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (data $a (i32.const 16) "\01\02\03\04")
+            (data $p "\aa\bb\cc\dd\ee")
+            (data $b (i32.const 32) "\05\06")
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .expect("passive data segments must be accepted");
+
+    // The active segments are placed in linear memory as before, unaffected by the passive one
+    let segments = module.segments().iter().collect::<Vec<_>>();
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].offset(), 16);
+    assert_eq!(segments[0].init().as_slice(), &[0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(segments[1].offset(), 32);
+    assert_eq!(segments[1].init().as_slice(), &[0x05, 0x06]);
+
+    // While the passive segment, the second in the data index space, is kept for `memory.init`
+    let contents = module.find_global(Ident::from("passive_data1")).unwrap();
+    assert_eq!(contents.ty, Type::Array(Box::new(Type::U32), 2));
+    assert!(module
+        .find_global(Ident::from("passive_data1_dropped"))
+        .is_some());
+}
+
+#[test]
+fn memory_init_places_passive_data_segments_once() {
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (data $p "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef")
+            (func $f (param i32 i32)
+                i32.const 1024
+                local.get 0
+                local.get 1
+                memory.init $p
+                i32.const 2048
+                local.get 0
+                local.get 1
+                memory.init $p
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .unwrap();
+    let count_insts = |name: &str, opcode: Opcode| {
+        let function = module.function(Ident::from(name)).unwrap();
+        function_insts(function)
+            .filter(|inst| function.dfg.inst(*inst).opcode() == opcode)
+            .count()
+    };
+
+    // The contents of the 64 byte segment are written a word at a time by a single function,
+    // which then marks them as placed
+    assert_eq!(count_insts("passive_data0_place", Opcode::Store), 17);
+    // Which each `memory.init` calls, before copying the requested bytes in a loop
+    assert_eq!(count_insts("f", Opcode::Call), 2);
+    assert_eq!(count_insts("f", Opcode::Store), 2);
+}
+
+#[test]
+fn unnamed_data_segments() {
+    // Without a name section, e.g. in a stripped module, segments are named after their index
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (data (i32.const 0) "\01\02\03\04")
+            (data (i32.const 2) "\aa")
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let (diagnostics, capture) = capturing_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    let captured = capture.captured();
+    assert!(
+        captured.contains("data segment 'data1' overlaps with data segment 'data0'"),
+        "expected an overlap warning, got: {captured}"
+    );
+    let segments = module.segments().iter().collect::<Vec<_>>();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].init().as_slice(), &[0x01, 0x02, 0xaa, 0x04]);
+    assert!(!segments[0].is_readonly());
+}
+
+#[test]
+fn data_segments_are_readonly_by_linker_section() {
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (data $.rodata (i32.const 0) "\01")
+            (data $.data.rel.ro (i32.const 8) "\02")
+            (data $.data (i32.const 16) "\03")
+            (data $.data.consts (i32.const 24) "\04")
+            (data $.bss.rodata (i32.const 32) "\05")
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let readonly = |config: &WasmTranslationConfig| {
+        let module = translate_module(&wasm, config, &test_diagnostics()).unwrap();
+        module
+            .segments()
+            .iter()
+            .map(|segment| (segment.offset(), segment.is_readonly()))
+            .collect::<Vec<_>>()
+    };
+
+    // Only the sections of read-only data are read-only, whatever else their name contains
+    assert_eq!(
+        readonly(&WasmTranslationConfig::default()),
+        [(0, true), (8, true), (16, false), (24, false), (32, false)]
+    );
+
+    // Unless overridden, the longest matching prefix taking precedence
+    let config = WasmTranslationConfig::builder()
+        .data_segment_readonly(".data", true)
+        .data_segment_readonly(".data.rel", false)
+        .build();
+    assert_eq!(
+        readonly(&config),
+        [(0, true), (8, false), (16, true), (24, true), (32, false)]
+    );
+}
+
+#[test]
+fn overlapping_data_segments() {
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (data $a (i32.const 0) "\01\02\03\04\05\06\07\08")
+            (data $b (i32.const 6) "\aa\bb\cc\dd")
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let (diagnostics, capture) = capturing_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+
+    let captured = capture.captured();
+    assert!(
+        captured
+            .contains("data segment 'b' overlaps with data segment 'a' in the byte range 0x6..0x8"),
+        "expected an overlap warning, got: {captured}"
+    );
+    assert!(!diagnostics.has_errors());
+
+    // As in Wasm, the bytes of the later segment take precedence
+    let segments = module.segments().iter().collect::<Vec<_>>();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].offset(), 0);
+    assert_eq!(
+        segments[0].init().as_slice(),
+        &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0xaa, 0xbb, 0xcc, 0xdd]
+    );
+
+    // The overlap is rejected when overlapping segments are denied
+    let diagnostics = test_diagnostics();
+    let config = WasmTranslationConfig {
+        deny_overlapping_data_segments: true,
+        ..Default::default()
+    };
+    assert!(translate_module(&wasm, &config, &diagnostics).is_err());
+    assert!(diagnostics.has_errors());
+}
+
+#[test]
+fn call_ctors_in_exports() {
+    let wat = r#"
+        (module
+            (global $g (mut i32) i32.const 0)
+            (func $__wasm_call_ctors
+                i32.const 42
+                global.set $g
+            )
+            (func $helper (result i32)
+                global.get $g
+            )
+            (func $entrypoint (export "entrypoint") (result i32)
+                call $helper
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let config = WasmTranslationConfig {
+        call_ctors_in_exports: true,
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &diagnostics).unwrap();
+    // The export checks the guard before anything else, and calls the ctors before its body
+    let entrypoint = module.function(Ident::from("entrypoint")).unwrap();
+    let entry_block = entrypoint.dfg.entry_block();
+    let terminator = entrypoint.dfg.last_inst(entry_block).unwrap();
+    assert!(matches!(
+        entrypoint.dfg.inst(terminator),
+        Instruction::CondBr(_)
+    ));
+    assert_eq!(
+        callee_names(&module, "entrypoint"),
+        ["__wasm_call_ctors", "helper"]
+    );
+    // Functions which are not exported are left as-is
+    assert!(callee_names(&module, "helper").is_empty());
+    assert!(callee_names(&module, "__wasm_call_ctors").is_empty());
+
+    // Without the option, the ctors are not called
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    assert_eq!(callee_names(&module, "entrypoint"), ["helper"]);
+}
+
+#[test]
+fn init_trap_policy() {
+    use midenc_session::InitTrapPolicy;
+
+    let wat = r#"
+        (module
+            (global $g (mut i32) i32.const 0)
+            (func $__wasm_call_ctors
+                i32.const 1
+                global.set $g
+                unreachable
+            )
+            (func $entrypoint (export "entrypoint") (result i32)
+                global.get $g
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let opcodes = |module: &miden_hir::Module| {
+        function_opcodes(module.function(Ident::from("__wasm_call_ctors")).unwrap())
+    };
+
+    // By default, the trap propagates
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    assert!(opcodes(&module).contains(&Opcode::Unreachable));
+    assert!(module
+        .globals()
+        .find(Ident::from("__wasm_init_trapped"))
+        .is_none());
+
+    // Otherwise, it is caught by setting the flag, and returning from the ctors
+    let config = WasmTranslationConfig {
+        init_trap_policy: InitTrapPolicy::Report,
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &diagnostics).unwrap();
+    let opcodes = opcodes(&module);
+    assert!(!opcodes.contains(&Opcode::Unreachable));
+    assert_eq!(opcodes.last(), Some(&Opcode::Ret));
+    assert!(module
+        .globals()
+        .find(Ident::from("__wasm_init_trapped"))
+        .is_some());
+}
+
+#[test]
+fn memory_table_and_global_exports() {
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (table (;0;) 1 funcref)
+            (global $g i32 i32.const 42)
+            (export "memory" (memory 0))
+            (export "table" (table 0))
+            (export "answer" (global $g))
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    // The exported memory and table are recorded under the name of their export, without
+    // declaring a symbol which could collide with those of globals, while the exported global
+    // keeps its own name
+    assert_eq!(
+        module.entity_exports(),
+        [
+            EntityExport::Memory {
+                name: Ident::from("memory")
+            },
+            EntityExport::Table {
+                name: Ident::from("table"),
+                index: 0
+            },
+        ]
+    );
+    assert!(module.find_global(Ident::from("memory")).is_none());
+    assert!(module.find_global(Ident::from("table")).is_none());
+    let global = module.find_global(Ident::from("g")).unwrap();
+    assert_eq!(global.linkage, Linkage::External);
+}
+
+#[test]
+fn float_operators_call_shared_intrinsics() {
+    let wat = r#"
+        (module
+            (func $clamp (param f64 f64 f64) (result f64)
+                local.get 0
+                local.get 1
+                f64.max
+                local.get 2
+                f64.min
+            )
+            (func $lower (param f64 f64) (result f64)
+                local.get 0
+                local.get 1
+                f64.max
+                f64.nearest
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .unwrap();
+
+    // Each use calls the intrinsic of its operator, which is only built once in the module
+    let callees_of = |name: &str| callee_names(&module, name);
+    assert_eq!(callees_of("clamp"), ["__wasm_f64_max", "__wasm_f64_min"]);
+    assert_eq!(
+        callees_of("lower"),
+        ["__wasm_f64_max", "__wasm_f64_nearest"]
+    );
+    let intrinsics = module
+        .functions()
+        .map(|function| function.id.function.as_str())
+        .filter(|name| name.starts_with("__wasm_f64_"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        intrinsics,
+        ["__wasm_f64_min", "__wasm_f64_max", "__wasm_f64_nearest"]
+    );
+}
+
+#[test]
+fn return_call_ref_mutual_recursion() {
+    let wat = r#"
+        (module
+            (type $t (func (param i32) (result i32)))
+            (elem declare func $is_even $is_odd)
+            (func $is_even (type $t) (param $n i32) (result i32)
+                local.get $n
+                i32.eqz
+                if (result i32)
+                    i32.const 1
+                else
+                    local.get $n
+                    i32.const 1
+                    i32.sub
+                    ref.func $is_odd
+                    return_call_ref $t
+                end
+            )
+            (func $is_odd (type $t) (param $n i32) (result i32)
+                local.get $n
+                i32.eqz
+                if (result i32)
+                    i32.const 0
+                else
+                    local.get $n
+                    i32.const 1
+                    i32.sub
+                    ref.func $is_even
+                    return_call_ref $t
+                end
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+
+    // Miden has no tail calls, so the call of the function dispatching on references to functions
+    // of type `$t`, which is shared by both functions, is followed by a return of its result
+    let callees_of = |name: &str| callee_names(&module, name);
+    let dispatcher = callees_of("is_even");
+    assert_eq!(dispatcher.len(), 1, "unexpected callees in 'is_even'");
+    assert!(dispatcher[0].starts_with("ref_dispatch_sig"));
+    assert_eq!(callees_of("is_odd"), dispatcher);
+
+    // The reference is null-checked, and then dispatched to a direct call of each function of
+    // type `$t`
+    let function = module.function(Ident::from(dispatcher[0])).unwrap();
+    let insts = function_insts(function)
+        .map(|inst| function.dfg.inst(inst))
+        .collect::<Vec<_>>();
+    assert_eq!(callees_of(dispatcher[0]), ["is_even", "is_odd"]);
+    assert!(
+        insts.iter().any(|inst| inst.opcode() == Opcode::Assert),
+        "missing null check"
+    );
+    assert!(
+        insts
+            .iter()
+            .any(|inst| inst.opcode() == Opcode::Unreachable),
+        "missing trap for references to functions of another type"
+    );
+}
+
+#[test]
+fn call_indirect_dispatches_on_table_entries() {
+    let wat = r#"
+        (module
+            (type $binop (func (param i32 i32) (result i32)))
+            (type $unop (func (param i32) (result i32)))
+            (table $t 5 funcref)
+            (elem (table $t) (i32.const 0) func $add $sub $neg)
+            (elem (table $t) (i32.const 4) func $add)
+            (func $add (type $binop)
+                local.get 0
+                local.get 1
+                i32.add
+            )
+            (func $sub (type $binop)
+                local.get 0
+                local.get 1
+                i32.sub
+            )
+            (func $neg (type $unop)
+                i32.const 0
+                local.get 0
+                i32.sub
+            )
+            (func $dispatch (param i32 i32 i32) (result i32)
+                local.get 1
+                local.get 2
+                local.get 0
+                call_indirect $t (type $binop)
+            )
+            (func $dispatch_twice (param i32 i32 i32) (result i32)
+                local.get 1
+                local.get 2
+                local.get 0
+                call_indirect $t (type $binop)
+                local.get 2
+                local.get 0
+                call_indirect $t (type $binop)
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+
+    // Every call site calls the same function dispatching through the table on entries of type
+    // `$binop`, so the dispatch is only emitted once
+    let callees_of = |name: &str| callee_names(&module, name);
+    let dispatcher = callees_of("dispatch");
+    assert_eq!(dispatcher.len(), 1, "unexpected callees in 'dispatch'");
+    assert!(dispatcher[0].starts_with("table0_dispatch_sig"));
+    assert_eq!(callees_of("dispatch_twice"), [dispatcher[0], dispatcher[0]]);
+    assert_eq!(
+        module
+            .functions()
+            .filter(|function| function.id.function.as_str().contains("_dispatch_"))
+            .count(),
+        1
+    );
+
+    // Entry 2 is a function of another type, and entry 3 is null, so they are not called
+    let function = module.function(Ident::from(dispatcher[0])).unwrap();
+    assert_eq!(callees_of(dispatcher[0]), ["add", "sub", "add"]);
+    assert!(
+        function_insts(function)
+            .any(|inst| function.dfg.inst(inst).opcode() == Opcode::Unreachable),
+        "missing trap for entries which cannot be called"
+    );
+}
+
+#[test]
+fn return_call_indirect_dispatches_on_table_entries() {
+    let wat = r#"
+        (module
+            (type $binop (func (param i32 i32) (result i32)))
+            (table $t 2 funcref)
+            (elem (table $t) (i32.const 0) func $add $sub)
+            (func $add (type $binop)
+                local.get 0
+                local.get 1
+                i32.add
+            )
+            (func $sub (type $binop)
+                local.get 0
+                local.get 1
+                i32.sub
+            )
+            (func $dispatch (param i32 i32 i32) (result i32)
+                local.get 0
+                if (result i32)
+                    local.get 1
+                    local.get 2
+                    local.get 0
+                    return_call_indirect $t (type $binop)
+                else
+                    i32.const 0
+                end
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+
+    // The tail call is dispatched through the table like `call_indirect`
+    let dispatcher = callee_names(&module, "dispatch");
+    assert_eq!(dispatcher.len(), 1, "unexpected callees in 'dispatch'");
+    assert!(dispatcher[0].starts_with("table0_dispatch_sig"));
+    assert_eq!(callee_names(&module, dispatcher[0]), ["add", "sub"]);
+
+    // Miden has no tail calls, so the call is immediately followed by a return of its result
+    let function = module.function(Ident::from("dispatch")).unwrap();
+    let (block, _) = function
+        .dfg
+        .blocks()
+        .find(|(block, _)| {
+            function
+                .dfg
+                .block_insts(*block)
+                .any(|inst| function.dfg[inst].opcode().is_call())
+        })
+        .unwrap();
+    let last = function.dfg.last_inst(block).unwrap();
+    assert_eq!(function.dfg[last].opcode(), Opcode::Ret);
+}
+
+#[test]
+fn call_indirect_through_imported_table() {
+    // The entries of an imported table may be set by other modules, so they cannot be resolved
+    let wat = r#"
+        (module
+            (type $t (func))
+            (import "env" "table" (table $table 1 funcref))
+            (elem (table $table) (i32.const 0) func $f)
+            (func $f (type $t))
+            (func $main
+                i32.const 0
+                call_indirect $table (type $t)
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let err = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            WasmError::UnsupportedFeature {
+                feature: UnsupportedFeature::Operator,
+                ..
+            }
+        ),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn call_indirect_through_table_initialized_from_global() {
+    let wat = r#"
+        (module
+            (type $t (func))
+            (import "env" "base" (global $base i32))
+            (table $table 1 funcref)
+            (elem (table $table) (global.get $base) func $f)
+            (func $f (type $t))
+            (func $main
+                i32.const 0
+                call_indirect $table (type $t)
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let err = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            WasmError::UnsupportedFeature {
+                feature: UnsupportedFeature::Operator,
+                ..
+            }
+        ),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn list_unsupported_features() {
+    let wat = r#"
+        (module
+            (func $sqrt_a (param f64) (result f64)
+                local.get 0
+                f64.sqrt)
+            (func $sqrt_b (param f64) (result f64)
+                local.get 0
+                f64.sqrt)
+            (func $pair (result i32 i32)
+                i32.const 1
+                i32.const 2
+                return)
+        )"#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let report =
+        crate::list_unsupported_features(&wasm, &WasmTranslationConfig::default()).unwrap();
+
+    let listed = report
+        .entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.first.feature,
+                entry.first.message.as_str(),
+                entry.count,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        listed,
+        vec![
+            (
+                UnsupportedFeature::Operator,
+                "Wasm op F64Sqrt is not supported",
+                2
+            ),
+            (
+                UnsupportedFeature::MultiValue,
+                "Multiple values are not supported",
+                1
+            ),
+        ]
+    );
+    let first_sqrt = &report.entries[0].first;
+    assert!(first_sqrt.function.as_ref().unwrap().ends_with("sqrt_a"));
+    assert!(first_sqrt.offset.is_some());
+
+    let printed = report.to_string();
+    assert!(printed.contains("MIDEN0004: Wasm op F64Sqrt is not supported (2 uses, first in"));
+    assert!(printed.contains("MIDEN0002: Multiple values are not supported (1 use, first in"));
+}
+
+#[test]
+fn list_unsupported_features_of_component() {
+    let wat = r#"
+        (component
+            (core module $a
+                (func $sqrt_a (export "sqrt") (param f64) (result f64)
+                    local.get 0
+                    f64.sqrt)
+            )
+            (core module $b
+                (import "a" "sqrt" (func $sqrt (param f64) (result f64)))
+                (func $sqrt_b (param f64) (result f64)
+                    local.get 0
+                    f64.sqrt)
+                (func $both (param f64) (result f64)
+                    local.get 0
+                    call $sqrt
+                    call $sqrt_b)
+            )
+            (core instance $a (instantiate $a))
+            (core instance $b (instantiate $b (with "a" (instance $a))))
+        )"#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let report =
+        crate::list_unsupported_features(&wasm, &WasmTranslationConfig::default()).unwrap();
+
+    assert_eq!(report.entries.len(), 1);
+    let entry = &report.entries[0];
+    assert_eq!(entry.first.feature, UnsupportedFeature::Operator);
+    assert_eq!(entry.first.message, "Wasm op F64Sqrt is not supported");
+    assert_eq!(entry.count, 2);
+    assert!(entry.first.function.as_ref().unwrap().ends_with("sqrt_a"));
+}
+
+#[test]
+fn stub_unsupported_functions() {
+    let wat = r#"
+        (module
+            (func $sqrt (param f64) (result f64)
+                local.get 0
+                f64.sqrt)
+            (func $main (param i32 f64) (result f64)
+                local.get 0
+                if (result f64)
+                    local.get 1
+                    call $sqrt
+                else
+                    local.get 1
+                end)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+
+    // By default, the unsupported function fails the whole module
+    let diagnostics = test_diagnostics();
+    assert!(translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).is_err());
+
+    let (diagnostics, capture) = capturing_diagnostics();
+    let config = WasmTranslationConfig {
+        stub_unsupported_functions: true,
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &diagnostics).unwrap();
+    assert!(!diagnostics.has_errors());
+    let captured = capture.captured();
+    assert!(
+        captured.contains("uses an unsupported feature, and has been replaced by a stub"),
+        "expected a warning for the stub, got: {captured}"
+    );
+    assert!(captured.contains("MIDEN0004: Wasm op F64Sqrt is not supported"));
+
+    // The stub traps as soon as it is called
+    let sqrt = module.function(Ident::from("sqrt")).unwrap();
+    let insts = sqrt
+        .dfg
+        .block_insts(sqrt.dfg.entry_block())
+        .map(|inst| sqrt.dfg.inst(inst).opcode())
+        .collect::<Vec<_>>();
+    assert_eq!(insts, [Opcode::Unreachable]);
+
+    // While the rest of the program is translated as usual
+    assert_eq!(callee_names(&module, "main"), ["sqrt"]);
+}
+
+#[test]
+fn unvalidated_translation_matches_validated() {
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (global $counter (mut i32) i32.const 0)
+            (func $sum (param i32) (result i32) (local i32)
+                block
+                    loop
+                        local.get 0
+                        i32.eqz
+                        br_if 1
+                        local.get 1
+                        local.get 0
+                        i32.add
+                        local.set 1
+                        local.get 0
+                        i32.const 1
+                        i32.sub
+                        local.set 0
+                        br 0
+                    end
+                end
+                local.get 1)
+            (func $main (export "main") (param i32) (result i32)
+                i32.const 1024
+                local.get 0
+                call $sum
+                i32.store
+                global.get $counter
+                i32.const 1
+                i32.add
+                global.set $counter
+                i32.const 1024
+                i32.load)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let config = WasmTranslationConfig::default();
+    let diagnostics = test_diagnostics();
+    let validated = translate_module(&wasm, &config, &diagnostics).unwrap();
+    let unvalidated = translate_module_unvalidated(&wasm, &config, &diagnostics).unwrap();
+    assert_eq!(validated.to_string(), unvalidated.to_string());
+}
+
+#[test]
+fn invalid_branch_depth_is_reported() {
+    // The branches target one frame past the function body, which the validator rejects, so
+    // they are only seen by the translator when validation is skipped
+    for branch in ["br 2", "i32.const 1 br_if 2", "i32.const 0 br_table 0 2"] {
+        let wat = format!(
+            r#"
+            (module
+                (func $main (export "main")
+                    block
+                        {branch}
+                    end)
+            )
+        "#
+        );
+        let wasm = wat::parse_str(wat).unwrap();
+        let config = WasmTranslationConfig::default();
+        let diagnostics = test_diagnostics();
+        let err = translate_module(&wasm, &config, &diagnostics).unwrap_err();
+        assert!(
+            matches!(err, WasmError::InvalidWebAssembly { .. }),
+            "unexpected error for `{branch}`: {err}"
+        );
+
+        let (diagnostics, capture) = capturing_diagnostics();
+        let err = translate_module_unvalidated(&wasm, &config, &diagnostics).unwrap_err();
+        let WasmError::InvalidWebAssembly { message, .. } = err else {
+            panic!("unexpected error for `{branch}`: {err}");
+        };
+        assert_eq!(
+            message,
+            "Invalid branch depth 2: there are only 2 enclosing control frames"
+        );
+        let captured = capture.captured();
+        assert!(
+            captured.contains("Invalid branch depth 2"),
+            "expected an invalid branch depth error for `{branch}`, got: {captured}"
+        );
+    }
+}
+
+#[test]
+fn deeply_nested_blocks_translate_without_overflow() {
+    const DEPTH: usize = 10_000;
+    let mut body = "block\n".repeat(DEPTH);
+    // Branch from the innermost block out of the outermost one, and to one halfway down
+    body.push_str(&format!(
+        "local.get 0\nbr_if {}\nbr {}\n",
+        DEPTH - 1,
+        DEPTH / 2
+    ));
+    body.push_str(&"end\n".repeat(DEPTH));
+    let wat = format!("(module (func $main (export \"main\") (param i32)\n{body}))");
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    let main = module.function(Ident::from("main")).unwrap();
+    let num_branches = function_insts(main)
+        .filter(|inst| {
+            matches!(
+                main.dfg.inst(*inst),
+                Instruction::Br(_) | Instruction::CondBr(_)
+            )
+        })
+        .count();
+    assert!(num_branches >= 2);
+}
+
+/// Build a Wasm module of `num_funcs` exported functions
+fn many_functions_module(num_funcs: usize) -> Vec<u8> {
+    let mut wat = String::from("(module\n");
+    for i in 0..num_funcs {
+        wat.push_str(&format!(
+            "(func $f{i} (export \"f{i}\") (param i32) (result i32) local.get 0 i32.const {i} \
+             i32.add)\n"
+        ));
+    }
+    wat.push(')');
+    wat::parse_str(wat).unwrap()
+}
+
+#[test]
+#[ignore = "measures wall-clock time, which is flaky alongside other tests"]
+fn translation_time_scales_linearly_with_function_count() {
+    let config = WasmTranslationConfig::default();
+    let diagnostics = test_diagnostics();
+    // The fastest of a few runs is used, to reduce noise from other tests running concurrently
+    let time = |num_funcs: usize| {
+        let wasm = many_functions_module(num_funcs);
+        (0..3)
+            .map(|_| {
+                let start = std::time::Instant::now();
+                let module = translate_module(&wasm, &config, &diagnostics).unwrap();
+                let elapsed = start.elapsed();
+                assert_eq!(module.functions().count(), num_funcs);
+                elapsed
+            })
+            .min()
+            .unwrap()
+    };
+    let small = time(2_000);
+    let large = time(16_000);
+    // With quadratic per-function setup, the large module would take ~64x as long
+    let ratio = large.as_secs_f64() / small.as_secs_f64();
+    assert!(
+        ratio < 16.0,
+        "translating 8x as many functions took {ratio:.1}x as long ({small:?} vs {large:?})"
+    );
+}
+
+#[test]
+fn non_constant_init_expr_is_reported() {
+    for (wat, section) in [
+        ("(global i32 (unreachable))", "global section"),
+        (
+            "(memory 1) (data (offset (unreachable)) \"x\")",
+            "data section",
+        ),
+        (
+            "(table 1 funcref) (elem (offset (unreachable)) func)",
+            "element section",
+        ),
+    ] {
+        let wasm = wat::parse_str(format!("(module {wat})")).unwrap();
+        let (diagnostics, capture) = capturing_diagnostics();
+        let err =
+            translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap_err();
+        let WasmError::InvalidWebAssembly { message, .. } = err else {
+            panic!("unexpected error for `{wat}`: {err}");
+        };
+        let expected = format!("non-constant operator Unreachable in {section} init expr");
+        assert_eq!(message, expected);
+        assert!(capture.captured().contains(&expected));
+    }
+}
+
+#[test]
+fn unsupported_constant_init_expr_is_reported() {
+    let wasm = wat::parse_str("(module (global funcref (ref.null func)))").unwrap();
+    let err = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            WasmError::UnsupportedFeature {
+                feature: UnsupportedFeature::InitExpr,
+                ..
+            }
+        ),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn wasi_imports_are_reported() {
+    let wat = r#"
+        (module
+            (import "wasi_snapshot_preview1" "fd_write"
+                (func $fd_write (param i32 i32 i32 i32) (result i32)))
+            (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+            (import "miden:base/account@1.0.0" "get-id" (func $get_id (result i64)))
+            (func $main (export "main") (param i32)
+                local.get 0
+                call $proc_exit)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let (diagnostics, capture) = capturing_diagnostics();
+    let err = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap_err();
+    let WasmError::UnsupportedFeature { feature, message } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(feature, UnsupportedFeature::WasiImport);
+    assert_eq!(
+        message,
+        "WASI imports are not supported on Miden, but the module imports: \
+         wasi_snapshot_preview1::fd_write, wasi_snapshot_preview1::proc_exit"
+    );
+    let captured = capture.captured();
+    assert!(captured.contains(&message));
+    assert!(captured.contains("MIDEN0008"));
+}
+
+#[test]
+fn simd_is_rejected_by_strict_validation() {
+    let wat = r#"
+        (module
+            (func $main (export "main") (param i32) (result i32)
+                local.get 0
+                i32x4.splat
+                i32x4.extract_lane 0)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+
+    // The validator accepts SIMD by default, leaving it to be reported during translation
+    let diagnostics = test_diagnostics();
+    let err = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap_err();
+    assert!(
+        !matches!(err, WasmError::InvalidWebAssembly { .. }),
+        "unexpected error: {err}"
+    );
+
+    let config = WasmTranslationConfig {
+        strict_validation: true,
+        ..Default::default()
+    };
+    let diagnostics = test_diagnostics();
+    let err = translate_module(&wasm, &config, &diagnostics).unwrap_err();
+    let WasmError::InvalidWebAssembly { message, .. } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert!(message.contains("SIMD"), "unexpected message: {message}");
+}
+
+#[test]
+fn simd_operators_are_rejected_by_name() {
+    let wat = r#"
+        (module
+            (func $first (export "first") (param i32) (result i32)
+                local.get 0
+                i32x4.splat
+                i32x4.extract_lane 0)
+            (func $last (export "last") (param i32) (result i32)
+                local.get 0
+                i32x4.splat
+                i32x4.extract_lane 3)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let (diagnostics, capture) = capturing_diagnostics();
+    let err = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap_err();
+    let WasmError::UnsupportedFeature { feature, message } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(feature, UnsupportedFeature::Operator);
+    assert_eq!(
+        message,
+        "SIMD operator i32x4.splat is not supported, as Miden IR has no vector types"
+    );
+    // The offset of the operator is only given in the diagnostic
+    let captured = capture.captured();
+    assert!(
+        captured.contains("i32x4.splat is at offset 0x"),
+        "expected the offset of the operator, got: {captured}"
+    );
+
+    // Each use of an operator is located, while uses of the same operator are grouped together
+    let report =
+        crate::list_unsupported_features(&wasm, &WasmTranslationConfig::default()).unwrap();
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].count, 2);
+    assert!(report.entries[0].first.offset.is_some());
+}
+
+#[test]
+fn tail_calls_are_validated_only_when_enabled() {
+    let wat = r#"
+        (module
+            (func $inc (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                i32.add)
+            (func $main (export "main") (param i32) (result i32)
+                local.get 0
+                return_call $inc)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+
+    let config = WasmTranslationConfig {
+        features: Some(WasmFeatures {
+            tail_call: true,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &test_diagnostics()).unwrap();
+    assert!(module.function(Ident::from("main")).is_some());
+
+    let config = WasmTranslationConfig {
+        features: Some(WasmFeatures {
+            tail_call: false,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let err = translate_module(&wasm, &config, &test_diagnostics()).unwrap_err();
+    let WasmError::InvalidWebAssembly { message, .. } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert!(
+        message.contains("tail call"),
+        "unexpected message: {message}"
+    );
+}
+
+#[test]
+fn multiple_memories_are_translated_when_only_the_first_is_used() {
+    let wat = r#"
+        (module
+            (memory $main 1)
+            (memory $scratch 1)
+            (data $init (memory $main) (i32.const 16) "\01\02\03\04")
+            (func $main (export "main") (result i32)
+                i32.const 16
+                i32.load $main
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .expect("a module which only uses memory 0 must be translated");
+    assert!(module.function(Ident::from("main")).is_some());
+}
+
+#[test]
+fn uses_of_other_memories_are_reported() {
+    let data_in_scratch = r#"
+        (module
+            (memory $main 1)
+            (memory $scratch 1)
+            (data $init (memory $scratch) (i32.const 16) "\01\02\03\04")
+        )
+    "#;
+    let load_from_scratch = r#"
+        (module
+            (memory $main 1)
+            (memory $scratch 1)
+            (func $main (export "main") (result i32)
+                i32.const 16
+                i32.load $scratch
+            )
+        )
+    "#;
+    for wat in [data_in_scratch, load_from_scratch] {
+        let wasm = wat::parse_str(wat).unwrap();
+        let err = translate_module(
+            &wasm,
+            &WasmTranslationConfig::default(),
+            &test_diagnostics(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                WasmError::UnsupportedFeature {
+                    feature: UnsupportedFeature::MultiMemory,
+                    ..
+                }
+            ),
+            "unexpected error: {err}"
+        );
+    }
+}
+
+#[test]
+fn gc_types_may_be_declared_but_not_referenced() {
+    let declared = r#"
+        (module
+            (type $point (struct (field $x i32) (field $y (mut i64))))
+            (type $bytes (array (mut i8)))
+            (type $list (sub (struct (field (ref null any)))))
+            (func $add (export "add") (param i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.add
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(declared).unwrap();
+    let module = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .expect("a module which only declares GC types must be translated");
+    assert!(module.function(Ident::from("add")).is_some());
+
+    let referenced = r#"
+        (module
+            (type $point (struct (field $x i32) (field $y i32)))
+            (func $is_origin (export "is_origin") (param (ref null $point)) (result i32)
+                i32.const 0
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(referenced).unwrap();
+    let err = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .unwrap_err();
+    let WasmError::UnsupportedFeature { feature, message } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(feature, UnsupportedFeature::GcType);
+    assert_eq!(
+        message,
+        "function 'is_origin' refers to the GC type (ref null 0), which is not supported"
+    );
+}
+
+#[test]
+fn element_segments_mixing_func_refs_and_globals() {
+    let wat = r#"
+        (module
+            (type $unop (func (param i32) (result i32)))
+            (import "env" "callback" (global $callback funcref))
+            (table $t 3 funcref)
+            (elem (table $t) (i32.const 0) funcref
+                (ref.func $double) (global.get $callback) (ref.null func))
+            (elem $p funcref (global.get $callback) (ref.func $double))
+            (func $double (param i32) (result i32)
+                local.get 0
+                i32.const 2
+                i32.mul
+            )
+            (func $apply (export "apply") (param i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                call_indirect $t (type $unop)
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .expect("element segments containing `global.get` expressions must be accepted");
+
+    let callees_of = |name: &str| callee_names(&module, name);
+    let table_dispatcher = callees_of("apply");
+    assert_eq!(table_dispatcher.len(), 1, "unexpected callees in 'apply'");
+    assert!(table_dispatcher[0].starts_with("table0_dispatch_sig"));
+
+    // Entry 0 is called directly, while entry 1 is dispatched on the reference held by the
+    // global, by the function dispatching references, to `double`, the only function escaping the
+    // module, and entry 2 is null
+    let callees = callees_of(table_dispatcher[0]);
+    assert_eq!(
+        callees.len(),
+        2,
+        "unexpected callees in '{}'",
+        table_dispatcher[0]
+    );
+    assert_eq!(callees[0], "double");
+    assert!(callees[1].starts_with("ref_dispatch_sig"));
+    assert_eq!(callees_of(callees[1]), ["double"]);
+
+    let function = module.function(Ident::from(table_dispatcher[0])).unwrap();
+    let globals_read = function_insts(function)
+        .filter_map(|inst| match function.dfg.inst(inst) {
+            Instruction::GlobalValue(global_value) => Some(global_value.global),
+            _ => None,
+        })
+        .filter_map(|global| match function.dfg.global_value(global) {
+            GlobalValueData::Load { base, .. } => Some(*base),
+            _ => None,
+        })
+        .filter_map(|base| match function.dfg.global_value(base) {
+            GlobalValueData::Symbol { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(globals_read, ["env::callback"]);
+}
+
+#[test]
+fn instructions_are_given_spans_from_dwarf_line_programs() {
+    let wat = r#"
+        (module
+            (func $answer (export "answer") (result i32)
+                i32.const 42
+            )
+        )
+    "#;
+    let mut wasm = wat::parse_str(wat).unwrap();
+
+    // A DWARF 4 compile unit, whose line program attributes all of the code to column 5 of the
+    // first line of this crate's root module, which is on disk, so it can be loaded into the
+    // code map
+    let file_name = concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs\0");
+    let debug_abbrev = [1, 0x11, 0, 0x10, 0x17, 0, 0, 0];
+    let debug_info = vec![4, 0, 0, 0, 0, 0, 4, 1, 0, 0, 0, 0];
+    let mut header = vec![
+        1, 1, 1, -5i8 as u8, 14, 13, 0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1, 0,
+    ];
+    header.extend(file_name.as_bytes());
+    header.extend([0, 0, 0, 0]);
+    // DW_LNE_set_address 0, DW_LNS_set_column 5, DW_LNS_copy, DW_LNS_advance_pc 0x40, and
+    // DW_LNE_end_sequence
+    let program = [0, 5, 2, 0, 0, 0, 0, 5, 5, 1, 2, 0x40, 0, 1, 1];
+    let mut debug_line = vec![4, 0];
+    debug_line.extend((header.len() as u32).to_le_bytes());
+    debug_line.extend(header);
+    debug_line.extend(program);
+    wasm.extend(custom_section(".debug_abbrev", &debug_abbrev));
+    wasm.extend(custom_section(".debug_info", &with_unit_length(debug_info)));
+    wasm.extend(custom_section(".debug_line", &with_unit_length(debug_line)));
+
+    let codemap = Arc::new(CodeMap::new());
+    let config = WasmTranslationConfig {
+        generate_native_debuginfo: true,
+        codemap: Some(codemap.clone()),
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &test_diagnostics()).unwrap();
+    let function = module.function(Ident::from("answer")).unwrap();
+    let (entry, _) = function.dfg.blocks().next().unwrap();
+    let constant = function.dfg.block_insts(entry).next().unwrap();
+    let span = function.dfg.inst_span(constant);
+    assert_ne!(span, SourceSpan::default());
+    assert_eq!(
+        span,
+        codemap.line_column_to_span(span.source_id(), 0, 4).unwrap()
+    );
+
+    // Without a code map, there is nowhere to load the source files into
+    let config = WasmTranslationConfig {
+        generate_native_debuginfo: true,
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &test_diagnostics()).unwrap();
+    let function = module.function(Ident::from("answer")).unwrap();
+    let (entry, _) = function.dfg.blocks().next().unwrap();
+    let constant = function.dfg.block_insts(entry).next().unwrap();
+    assert_eq!(function.dfg.inst_span(constant), SourceSpan::default());
+}
+
+#[test]
+fn locals_are_named_when_generating_debug_info() {
+    let wat = r#"
+        (module
+            (func $sum (param $n i32) (result i32) (local $acc i32)
+                block
+                    loop
+                        local.get $n
+                        i32.eqz
+                        br_if 1
+                        local.get $acc
+                        local.get $n
+                        i32.add
+                        local.set $acc
+                        local.get $n
+                        i32.const 1
+                        i32.sub
+                        local.set $n
+                        br 0
+                    end
+                end
+                local.get $acc
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let config = WasmTranslationConfig {
+        generate_native_debuginfo: true,
+        ..Default::default()
+    };
+    let module = translate_module(&wasm, &config, &test_diagnostics()).unwrap();
+    let function = module.function(Ident::from("sum")).unwrap();
+    let dfg = &function.dfg;
+    let param = dfg.block_params(dfg.entry_block())[0];
+    assert_eq!(dfg.value_name(param), Some(Symbol::intern("n")));
+    // The definitions of both locals are merged in the loop header, by parameters named after them
+    let loop_header_names = dfg
+        .blocks()
+        .filter(|(block, _)| *block != dfg.entry_block())
+        .flat_map(|(block, _)| dfg.block_params(block).iter().copied())
+        .filter_map(|param| dfg.value_name(param))
+        .map(|name| name.as_str().to_string())
+        .collect::<std::collections::BTreeSet<_>>();
+    assert!(
+        loop_header_names.contains("n") && loop_header_names.contains("acc"),
+        "unexpected names of block parameters: {loop_header_names:?}"
+    );
+    assert!(
+        function.to_string().contains(&format!("// {param}: n")),
+        "names are missing from the IR:\n{function}"
+    );
+
+    // Names are not kept unless generating debug info
+    let module = translate_module(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        &test_diagnostics(),
+    )
+    .unwrap();
+    let function = module.function(Ident::from("sum")).unwrap();
+    assert!(function.dfg.value_names.is_empty());
+    assert!(!function.to_string().contains("//"));
+}
+
+#[test]
+fn start_function_is_marked_in_module() {
+    let wat = r#"
+        (module
+            (global $g (mut i32) i32.const 0)
+            (func $init
+                i32.const 42
+                global.set $g
+            )
+            (func $entrypoint (export "entrypoint") (result i32)
+                global.get $g
+            )
+            (start $init)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    let start = module
+        .start()
+        .expect("expected the module to have a start function");
+    assert_eq!(start.module, module.name);
+    assert_eq!(start.function.as_str(), "init");
+    // The start function is distinct from the entrypoint
+    assert_eq!(module.entrypoint(), None);
+
+    let wat = r#"
+        (module
+            (func $entrypoint (export "entrypoint") (result i32)
+                i32.const 0
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    assert_eq!(module.start(), None);
+}
+
+#[test]
+fn components_are_rejected_by_translate_module() {
+    let wasm = wat::parse_str("(component)").unwrap();
+    let diagnostics = test_diagnostics();
+    let err = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap_err();
+    assert!(
+        matches!(&err, WasmError::Unsupported(msg) if msg.contains("use `translate_component`")),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn exports_run_the_start_function_once() {
+    let wat = r#"
+        (module
+            (global $g (mut i32) i32.const 0)
+            (func $init
+                i32.const 42
+                global.set $g
+            )
+            (func $get (export "get") (result i32)
+                global.get $g
+            )
+            (func $helper (result i32)
+                i32.const 1
+            )
+            (start $init)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    let guard = module
+        .find_global(Ident::from("__wasm_start_guard"))
+        .unwrap();
+    assert_eq!(guard.ty, Type::U32);
+
+    // The export calls the start function, unless the guard is set, which the start function
+    // sets itself, so that it is not run again when it was run before the entrypoint
+    let callees = |name: &str| callee_names(&module, name);
+    assert_eq!(callees("get"), ["init"]);
+    assert!(callees("helper").is_empty());
+    let init = module.function(Ident::from("init")).unwrap();
+    let entry = init.dfg.entry_block();
+    let first = init.dfg.block_insts(entry).next().unwrap();
+    let Instruction::GlobalValue(global_value) = init.dfg.inst(first) else {
+        panic!("expected the start function to begin by setting the guard");
+    };
+    assert!(matches!(
+        init.dfg.global_value(global_value.global),
+        GlobalValueData::Symbol { name, .. } if name.as_str() == "__wasm_start_guard"
+    ));
+}
+
+#[test]
+fn imported_start_function_is_rejected() {
+    let wat = r#"
+        (module
+            (import "env" "init" (func $init))
+            (start $init)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let err = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap_err();
+    assert!(
+        matches!(&err, WasmError::Unsupported(msg) if msg.contains("start function")),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn non_escaping_functions_are_internalized() {
+    let wat = r#"
+        (module
+            (table 1 funcref)
+            (elem (i32.const 0) $in_table)
+            (func $init)
+            (func $in_table (result i32)
+                i32.const 1
+            )
+            (func $helper (result i32)
+                i32.const 2
+            )
+            (func $unused (result i32)
+                i32.const 3
+            )
+            (func $entrypoint (export "entrypoint") (result i32)
+                call $helper
+            )
+            (start $init)
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let diagnostics = test_diagnostics();
+    let linkage = |module: &miden_hir::Module, name: &str| {
+        module
+            .function(Ident::from(name))
+            .unwrap()
+            .signature
+            .linkage
+    };
+
+    // By default, every function has external linkage, whether it escapes or not
+    let module = translate_module(&wasm, &WasmTranslationConfig::default(), &diagnostics).unwrap();
+    for name in ["init", "in_table", "helper", "unused", "entrypoint"] {
+        assert_eq!(linkage(&module, name), Linkage::External, "{name}");
+    }
+
+    let config = WasmTranslationConfig::builder()
+        .internalize_non_escaping_functions(true)
+        .build();
+    let module = translate_module(&wasm, &config, &diagnostics).unwrap();
+    for name in ["init", "in_table", "entrypoint"] {
+        assert_eq!(linkage(&module, name), Linkage::External, "{name}");
+    }
+    for name in ["helper", "unused"] {
+        assert_eq!(linkage(&module, name), Linkage::Internal, "{name}");
+    }
+}
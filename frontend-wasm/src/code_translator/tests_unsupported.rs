@@ -13,6 +13,7 @@ use crate::module::function_builder_ext::FunctionBuilderContext;
 use crate::module::function_builder_ext::FunctionBuilderExt;
 use crate::module::Module;
 use crate::test_utils::test_diagnostics;
+use crate::WasmTranslationConfig;
 
 use super::translate_operator;
 
@@ -39,7 +40,9 @@ fn check_unsupported(op: &Operator) {
         &module_info,
         &mod_types,
         &diagnostics,
+        &WasmTranslationConfig::default(),
         SourceSpan::default(),
+        0,
     );
     assert!(
         result.is_err(),
@@ -58,11 +61,6 @@ fn check_unsupported(op: &Operator) {
 
 // Wasm Spec v1.0
 const UNSUPPORTED_WASM_V1_OPS: &[Operator] = &[
-    CallIndirect {
-        type_index: 0,
-        table_index: 0,
-        table_byte: 0,
-    },
     /****************************** Memory Operators ************************************/
     F32Load {
         memarg: MemArg {
@@ -113,18 +111,6 @@ const UNSUPPORTED_WASM_V1_OPS: &[Operator] = &[
     I64Clz,
     F32Sqrt,
     F64Sqrt,
-    F32Ceil,
-    F64Ceil,
-    F32Floor,
-    F64Floor,
-    F32Trunc,
-    F64Trunc,
-    F32Nearest,
-    F64Nearest,
-    F32Abs,
-    F64Abs,
-    F32Neg,
-    F64Neg,
     F64ConvertI64U,
     F64ConvertI32U,
     F64ConvertI64S,
@@ -133,16 +119,6 @@ const UNSUPPORTED_WASM_V1_OPS: &[Operator] = &[
     F32ConvertI32S,
     F32ConvertI64U,
     F32ConvertI32U,
-    F64PromoteF32,
-    F32DemoteF64,
-    I64TruncF64S,
-    I64TruncF32S,
-    I32TruncF64S,
-    I32TruncF32S,
-    I64TruncF64U,
-    I64TruncF32U,
-    I32TruncF64U,
-    I32TruncF32U,
     I64TruncSatF64S,
     I64TruncSatF32S,
     I32TruncSatF64S,
@@ -151,24 +127,14 @@ const UNSUPPORTED_WASM_V1_OPS: &[Operator] = &[
     I64TruncSatF32U,
     I32TruncSatF64U,
     I32TruncSatF32U,
-    F32ReinterpretI32,
-    F64ReinterpretI64,
-    I32ReinterpretF32,
-    I64ReinterpretF64,
     /****************************** Binary Operators ************************************/
     F32Add,
     F32Sub,
     F32Mul,
     F32Div,
-    F32Min,
-    F32Max,
-    F32Copysign,
-    F64Copysign,
     F64Add,
     F64Mul,
     F64Div,
-    F64Min,
-    F64Max,
     /**************************** Comparison Operators **********************************/
     F32Eq,
     F32Ne,
@@ -0,0 +1,324 @@
+//! Adapters for functions lifted in one component instance and lowered in another.
+//!
+//! When the arguments or results of such a function are passed through linear memory, e.g. as
+//! strings, they have to be copied from the memory of the caller to the memory of the callee,
+//! and back, which the canonical ABI leaves to a "fused adapter" between the two. Once compiled,
+//! all of the core modules of a component share a single linear memory, so copying a string
+//! amounts to allocating a buffer for it with the `realloc` function of the receiving side, and
+//! copying it there, transcoding it along the way if the two sides disagree on the
+//! `string-encoding` canonical option.
+
+use miden_diagnostics::{DiagnosticsHandler, SourceSpan};
+use miden_hir::{
+    CallConv, FunctionIdent, Immediate, InstBuilder, Linkage, ModuleBuilder, ModuleFunctionBuilder,
+    Type, Value,
+};
+
+use crate::{
+    error::WasmResult,
+    module::types::{ir_func_sig, ir_func_type},
+    WasmError,
+};
+
+use super::{
+    build_ir::{core_def_func_ident, core_def_func_sig},
+    instance::{ComponentInstance, FusedAdapter},
+    transcode::{load_byte, store_byte},
+    CanonicalOptions, InterfaceType, StringEncoding, TrampolineIndex, Transcode, MAX_FLAT_PARAMS,
+};
+
+/// The name of the module holding the adapters synthesized for the functions lifted in one
+/// component instance and lowered in another, see [build_adapter_function]
+pub(super) const ADAPTERS_MODULE_NAME: &str = "adapters";
+
+/// Synthesize a function in the `adapters` module, which calls the function lifted by the
+/// `FusedAdapter` trampoline `trampoline` on behalf of the component instance lowering it.
+///
+/// The adapter has the core signature the function is lowered to. Arguments of primitive types
+/// are passed as they are, while each string argument is copied into a buffer allocated with the
+/// `realloc` function of the callee. A string result is copied back the same way, into a buffer
+/// allocated with the `realloc` function of the caller, and its address and length are written
+/// to the return area given by the caller. The `post-return` function of the callee, if any, is
+/// then called with the results of the callee.
+///
+/// Only parameters of primitive types and strings, and at most one result of either, are
+/// supported.
+pub(super) fn build_adapter_function(
+    component_instance: &ComponentInstance<'_>,
+    trampoline: TrampolineIndex,
+    adapters: &mut ModuleBuilder,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<FunctionIdent> {
+    let adapter = &component_instance.adapters[&trampoline];
+    let component_types = &component_instance.component_types;
+    let callee_id = core_def_func_ident(component_instance, &adapter.callee);
+    let callee_sig = core_def_func_sig(component_instance, &adapter.callee)?;
+    let func_ty = &component_types[adapter.lift_ty];
+    let params = &component_types[func_ty.params].types;
+    let results = &component_types[func_ty.results].types;
+    check_adapted_types(params, results, callee_id)?;
+
+    let sig_idx = component_instance.component.trampolines[trampoline];
+    let func_type = ir_func_type(&component_types.module_types()[sig_idx])?;
+    let sig = ir_func_sig(&func_type, CallConv::SystemV, Linkage::External);
+    let span = SourceSpan::default();
+
+    let name = format!("trampoline{}", trampoline.as_u32());
+    let mut fb = adapters.function(name.as_str(), sig)?;
+    let callee = fb.import_function(callee_id.module, callee_id.function, callee_sig)?;
+    let mut args = fb.block_params(fb.entry_block()).to_vec().into_iter();
+    let mut next_arg = || {
+        args.next().ok_or_else(|| {
+            WasmError::Unexpected(format!(
+                "the core signature {callee_id} is lowered to has fewer parameters than its type"
+            ))
+        })
+    };
+
+    let mut callee_args = Vec::with_capacity(params.len());
+    for ty in params.iter() {
+        if let InterfaceType::String = ty {
+            let (ptr, len) = (next_arg()?, next_arg()?);
+            let realloc = import_realloc(&mut fb, component_instance, &adapter.lift_options)?;
+            let from = adapter.lower_options.string_encoding;
+            let to = adapter.lift_options.string_encoding;
+            let (ptr, len) = build_copy_string(&mut fb, ptr, len, from, to, realloc, span)?;
+            callee_args.extend([ptr, len]);
+        } else {
+            callee_args.push(next_arg()?);
+        }
+    }
+    let call = fb.ins().call(callee, &callee_args, span);
+    let callee_results = fb.inst_results(call).to_vec();
+
+    let result = match results.first() {
+        Some(InterfaceType::String) => {
+            // The callee returns the address of its return area, holding the address and the
+            // length of the string in its memory, while the caller passes the address of its
+            // own return area, to which they are copied
+            let ret_area = next_arg()?;
+            let callee_ret_area = callee_results[0];
+            let ptr = load_i32(&mut fb, callee_ret_area, 0, span);
+            let len = load_i32(&mut fb, callee_ret_area, 4, span);
+            let realloc = import_realloc(&mut fb, component_instance, &adapter.lower_options)?;
+            let from = adapter.lift_options.string_encoding;
+            let to = adapter.lower_options.string_encoding;
+            let (ptr, len) = build_copy_string(&mut fb, ptr, len, from, to, realloc, span)?;
+            store_i32(&mut fb, ret_area, 0, ptr, span);
+            store_i32(&mut fb, ret_area, 4, len, span);
+            None
+        }
+        _ => callee_results.first().copied(),
+    };
+    if let Some(post_return) = build_post_return(&mut fb, component_instance, adapter)? {
+        fb.ins().call(post_return, &callee_results, span);
+    }
+    fb.ins().ret(result, span);
+
+    fb.build(diagnostics)
+        .map_err(|_| WasmError::InvalidFunctionError)
+}
+
+/// Check that the parameters and results of the function called by an adapter are of types the
+/// adapter can pass from the caller to `callee`, and back
+fn check_adapted_types(
+    params: &[InterfaceType],
+    results: &[InterfaceType],
+    callee: FunctionIdent,
+) -> WasmResult<()> {
+    for ty in params.iter().chain(results) {
+        let supported = matches!(
+            ty,
+            InterfaceType::Bool
+                | InterfaceType::S8
+                | InterfaceType::U8
+                | InterfaceType::S16
+                | InterfaceType::U16
+                | InterfaceType::S32
+                | InterfaceType::U32
+                | InterfaceType::S64
+                | InterfaceType::U64
+                | InterfaceType::Float32
+                | InterfaceType::Float64
+                | InterfaceType::Char
+                | InterfaceType::String
+        );
+        if !supported {
+            return Err(WasmError::Unsupported(format!(
+                "calling {callee} from another component instance with a value of type {ty:?} \
+                 is not supported, only primitive types and strings can be passed between \
+                 component instances"
+            )));
+        }
+    }
+    if results.len() > 1 {
+        return Err(WasmError::Unsupported(format!(
+            "calling {callee} from another component instance is not supported, as it returns \
+             {} results, rather than at most one",
+            results.len()
+        )));
+    }
+    // Each string is flattened to its address and length
+    let flat_params = params
+        .iter()
+        .map(|ty| if let InterfaceType::String = ty { 2 } else { 1 })
+        .sum::<usize>();
+    if flat_params > MAX_FLAT_PARAMS {
+        return Err(WasmError::Unsupported(format!(
+            "calling {callee} from another component instance is not supported, as its \
+             parameters are flattened to {flat_params} core values, which are then passed \
+             through linear memory, rather than at most {MAX_FLAT_PARAMS}"
+        )));
+    }
+    Ok(())
+}
+
+/// Import the `realloc` function given in `options`, with which the strings received by that
+/// side of the adapter are allocated
+fn import_realloc(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    component_instance: &ComponentInstance<'_>,
+    options: &CanonicalOptions,
+) -> WasmResult<FunctionIdent> {
+    let Some(realloc) = options.realloc else {
+        return Err(WasmError::Unexpected(
+            "a string is passed to a component instance which gives no `realloc` canonical \
+             option to allocate it with"
+                .to_string(),
+        ));
+    };
+    let def = &component_instance.reallocs[realloc];
+    let id = core_def_func_ident(component_instance, def);
+    let sig = core_def_func_sig(component_instance, def)?;
+    Ok(fb.import_function(id.module, id.function, sig)?)
+}
+
+/// Import the `post-return` function of the callee of `adapter`, if it has one
+fn build_post_return(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    component_instance: &ComponentInstance<'_>,
+    adapter: &FusedAdapter,
+) -> WasmResult<Option<FunctionIdent>> {
+    let Some(post_return) = adapter.lift_options.post_return else {
+        return Ok(None);
+    };
+    let def = &component_instance.post_returns[post_return];
+    let id = core_def_func_ident(component_instance, def);
+    let sig = core_def_func_sig(component_instance, def)?;
+    Ok(Some(fb.import_function(id.module, id.function, sig)?))
+}
+
+/// Emits a copy of the string of `len` code units at the address `ptr`, encoded with `from`, into
+/// a buffer allocated with `realloc`, encoded with `to`, returning the address of the buffer and
+/// the length of the copy in code units, as `i32`s
+fn build_copy_string(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    ptr: Value,
+    len: Value,
+    from: StringEncoding,
+    to: StringEncoding,
+    realloc: FunctionIdent,
+    span: SourceSpan,
+) -> WasmResult<(Value, Value)> {
+    let transcode =
+        Transcode::between(from, to).map_err(|e| WasmError::Unsupported(e.to_string()))?;
+    let src = fb.ins().bitcast(ptr, Type::U32, span);
+    let len = fb.ins().bitcast(len, Type::U32, span);
+    let size = match transcode {
+        Some(transcode) => transcode.build_worst_case_size(fb, len, span),
+        None => fb
+            .ins()
+            .mul_imm_checked(len, Immediate::U32(code_unit_size(to)), span),
+    };
+    let align = fb.ins().i32(code_unit_size(to) as i32, span);
+    let size = fb.ins().bitcast(size, Type::I32, span);
+    let zero = fb.ins().i32(0, span);
+    let call = fb.ins().call(realloc, &[zero, zero, align, size], span);
+    let dst = fb.first_result(call);
+    let dst_u32 = fb.ins().bitcast(dst, Type::U32, span);
+    let len = match transcode {
+        Some(transcode) => transcode.build(fb, src, len, dst_u32, span),
+        None => {
+            let size = fb.ins().bitcast(size, Type::U32, span);
+            build_copy_bytes(fb, src, dst_u32, size, span);
+            len
+        }
+    };
+    let len = fb.ins().bitcast(len, Type::I32, span);
+    Ok((dst, len))
+}
+
+/// Emits a copy of the `size` bytes at the address `src` to the address `dst`, one at a time, all
+/// of them `u32`s
+fn build_copy_bytes(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    src: Value,
+    dst: Value,
+    size: Value,
+    span: SourceSpan,
+) {
+    let loop_header = fb.create_block();
+    let i = fb.append_block_param(loop_header, Type::U32, span);
+    let loop_body = fb.create_block();
+    let exit_block = fb.create_block();
+    let zero = fb.ins().u32(0, span);
+    fb.ins().br(loop_header, &[zero], span);
+
+    fb.switch_to_block(loop_header);
+    let done = fb.ins().gte(i, size, span);
+    fb.ins()
+        .cond_br(done, exit_block, &[], loop_body, &[], span);
+
+    fb.switch_to_block(loop_body);
+    let byte = load_byte(fb, src, i, span);
+    store_byte(fb, dst, i, byte, span);
+    let i = fb.ins().add_imm_checked(i, Immediate::U32(1), span);
+    fb.ins().br(loop_header, &[i], span);
+
+    fb.switch_to_block(exit_block);
+}
+
+/// The size in bytes of a code unit of strings encoded with `encoding`
+fn code_unit_size(encoding: StringEncoding) -> u32 {
+    match encoding {
+        StringEncoding::Utf8 => 1,
+        StringEncoding::Utf16 | StringEncoding::CompactUtf16 => 2,
+    }
+}
+
+/// Emits a load of the `i32` at `offset` bytes from the address `base`, an `i32`
+fn load_i32(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    base: Value,
+    offset: u32,
+    span: SourceSpan,
+) -> Value {
+    let ptr = i32_ptr(fb, base, offset, span);
+    fb.ins().load(ptr, span)
+}
+
+/// Emits a store of `value`, an `i32`, at `offset` bytes from the address `base`, an `i32`
+fn store_i32(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    base: Value,
+    offset: u32,
+    value: Value,
+    span: SourceSpan,
+) {
+    let ptr = i32_ptr(fb, base, offset, span);
+    fb.ins().store(ptr, value, span);
+}
+
+fn i32_ptr(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    base: Value,
+    offset: u32,
+    span: SourceSpan,
+) -> Value {
+    let mut addr = fb.ins().bitcast(base, Type::U32, span);
+    if offset != 0 {
+        addr = fb.ins().add_imm_checked(addr, Immediate::U32(offset), span);
+    }
+    fb.ins()
+        .inttoptr(addr, Type::Ptr(Box::new(Type::I32)), span)
+}
@@ -1,27 +1,43 @@
-use miden_diagnostics::DiagnosticsHandler;
+use miden_diagnostics::{DiagnosticsHandler, SourceSpan};
 use miden_hir::{
-    cranelift_entity::PrimaryMap, FunctionIdent, Ident, InterfaceFunctionIdent, InterfaceIdent,
-    Symbol,
+    cranelift_entity::PrimaryMap, CallConv, FunctionIdent, Ident, InstBuilder,
+    InterfaceFunctionIdent, InterfaceIdent, Linkage, ModuleBuilder, Signature, Symbol,
 };
 use miden_hir_type::LiftedFunctionType;
+use rustc_hash::FxHashMap;
 use wasmparser::WasmFeatures;
 
 use crate::{
     component::{ComponentParser, StringEncoding},
-    error::WasmResult,
-    module::{build_ir::build_ir_module, module_env::ParsedModule, types::EntityIndex},
+    error::{UnsupportedUse, WasmResult},
+    module::{
+        build_ir::{build_ir_module, build_ir_module_using, record_unsupported_uses},
+        module_env::ParsedModule,
+        types::{ir_func_sig, ir_func_type, EntityIndex, FuncIndex},
+        ModuleImport,
+    },
     WasmError, WasmTranslationConfig,
 };
 
 use super::{
+    adapter::{build_adapter_function, ADAPTERS_MODULE_NAME},
     inline,
-    instance::{ComponentImport, ComponentInstance, ComponentInstanceBuilder},
-    interface_type_to_ir, CanonicalOptions, ComponentTypes, ComponentTypesBuilder, CoreDef, Export,
-    ExportItem, LinearComponent, LinearComponentTranslation, ParsedRootComponent,
-    StaticModuleIndex, TypeFuncIndex,
+    instance::{ComponentImport, ComponentInstance, ComponentInstanceBuilder, ModuleArgument},
+    interface_type_to_ir, CanonicalOptions, ComponentTypes, ComponentTypesBuilder, CoreDef,
+    CoreExport, Export, ExportItem, LinearComponent, LinearComponentTranslation,
+    ParsedRootComponent, StaticModuleIndex, TrampolineIndex, TypeFuncIndex,
 };
 
+/// The name of the module holding the functions synthesized to forward each reexported component
+/// import to the imported function, see [build_reexport_function]
+const REEXPORTS_MODULE_NAME: &str = "reexports";
+
 /// Translate a Wasm component binary into Miden IR component
+///
+/// This is the counterpart of [crate::translate_module] for components: the component is parsed
+/// and validated, its nested components and instances are inlined, and each of its core modules
+/// is translated, along with the imports and exports of the component, into a single linked
+/// component, which can then be compiled as a whole.
 pub fn translate_component(
     wasm: &[u8],
     config: &WasmTranslationConfig,
@@ -39,12 +55,53 @@ pub fn translate_component(
     )
 }
 
+/// Translate each core module of the component `wasm`, recording each use of an unsupported
+/// feature rather than failing, see [crate::list_unsupported_features]
+///
+/// The core modules are translated on their own, without resolving their imports to the other
+/// modules of the component, as this does not change which features they use.
+pub(crate) fn find_unsupported_uses(
+    wasm: &[u8],
+    config: &WasmTranslationConfig,
+) -> WasmResult<Vec<UnsupportedUse>> {
+    record_unsupported_uses(|diagnostics, func_translator| {
+        let (component_types_builder, parsed_component) = parse(config, wasm, diagnostics)?;
+        let component_types = component_types_builder.finish();
+        for (idx, mut parsed_module) in parsed_component.static_modules {
+            parsed_module
+                .module
+                .set_name_fallback(format!("module{}", idx.as_u32()));
+            build_ir_module_using(
+                parsed_module,
+                component_types.module_types(),
+                config,
+                diagnostics,
+                func_translator,
+                &[],
+            )?;
+        }
+        Ok(())
+    })
+}
+
 fn parse<'data>(
     config: &WasmTranslationConfig,
     wasm: &'data [u8],
     diagnostics: &DiagnosticsHandler,
 ) -> Result<(ComponentTypesBuilder, ParsedRootComponent<'data>), crate::WasmError> {
-    let wasm_features = WasmFeatures::all();
+    let wasm_features = if let Some(features) = config.features {
+        WasmFeatures {
+            component_model: true,
+            ..features
+        }
+    } else if config.strict_validation {
+        WasmFeatures {
+            component_model: true,
+            ..crate::strict_wasm_features(config)
+        }
+    } else {
+        WasmFeatures::all()
+    };
     let mut validator = wasmparser::Validator::new_with_features(wasm_features);
     let mut component_types_builder = Default::default();
     let component_parser =
@@ -74,6 +131,13 @@ fn inline(
         &parsed_component.static_components,
     )
     .map_err(|e| crate::WasmError::Unsupported(e.to_string()))?;
+    // Dangling indices left behind by a bug in the inliner would otherwise only surface once
+    // they are used, long after the fact
+    if cfg!(debug_assertions) {
+        component_dfg
+            .verify()
+            .map_err(|e| crate::WasmError::Unexpected(format!("invalid component dfg: {e}")))?;
+    }
     Ok(component_dfg.finish())
 }
 
@@ -92,12 +156,41 @@ fn build_ir<'data>(
 
     component_instance.ensure_module_names();
 
+    // Function imports satisfied by an export of another core module are called directly, or
+    // through an adapter if the export is a function lifted in another component instance. These
+    // are resolved up front, as the modules are consumed one at a time below.
+    let mut core_export_imports = FxHashMap::default();
+    let mut adapters = ModuleBuilder::new(ADAPTERS_MODULE_NAME);
+    let mut adapter_functions = FxHashMap::default();
+    for static_module_idx in component_instance.modules.keys() {
+        core_export_imports.insert(
+            static_module_idx,
+            resolve_core_export_imports(
+                &component_instance,
+                static_module_idx,
+                &mut adapters,
+                &mut adapter_functions,
+                diagnostics,
+            )?,
+        );
+    }
+
     // build exports
+    let mut reexports = ModuleBuilder::new(REEXPORTS_MODULE_NAME);
     for (name, export) in &component_instance.component.exports {
-        build_export(export, &component_instance, name, &mut cb, config)?;
+        build_export(
+            export,
+            &component_instance,
+            name,
+            &mut cb,
+            &mut reexports,
+            config,
+            diagnostics,
+        )?;
     }
 
-    for (static_module_idx, parsed_module) in component_instance.modules {
+    let mut start_functions = FxHashMap::default();
+    for (static_module_idx, mut parsed_module) in component_instance.modules {
         let component = &component_instance.component;
         build_import(
             &component_instance.imports[&static_module_idx],
@@ -107,6 +200,10 @@ fn build_ir<'data>(
             &mut cb,
             config,
         )?;
+        parsed_module
+            .module
+            .translated_function_imports
+            .extend(core_export_imports.remove(&static_module_idx).unwrap());
 
         let module = build_ir_module(
             parsed_module,
@@ -114,75 +211,168 @@ fn build_ir<'data>(
             config,
             diagnostics,
         )?;
+        if let Some(start) = module.start() {
+            start_functions.insert(static_module_idx, start);
+        }
         cb.add_module(module.into())
             .expect("module is already added");
     }
 
+    // The start functions run as the modules are instantiated, i.e. in the order of the side
+    // effects of the component, and before any of its exports can be called
+    for static_module_idx in component_instance.module_instances.values() {
+        if let Some(start) = start_functions.get(static_module_idx) {
+            cb.add_start(*start);
+        }
+    }
+
+    let reexports = reexports.build();
+    if reexports.functions().next().is_some() {
+        cb.add_module(reexports).map_err(|_| {
+            WasmError::Unsupported(format!(
+                "the module `{REEXPORTS_MODULE_NAME}` holding the reexported imports of the \
+                 component conflicts with a core module of the same name"
+            ))
+        })?;
+    }
+    let adapters = adapters.build();
+    if adapters.functions().next().is_some() {
+        cb.add_module(adapters).map_err(|_| {
+            WasmError::Unsupported(format!(
+                "the module `{ADAPTERS_MODULE_NAME}` holding the adapters between the component \
+                 instances of the component conflicts with a core module of the same name"
+            ))
+        })?;
+    }
+
     Ok(cb.build())
 }
 
 fn build_import(
-    component_imports: &[ComponentImport],
+    module_args: &[ModuleArgument],
     component_types: &ComponentTypes,
     component: &LinearComponent,
     parsed_module: &ParsedModule<'_>,
     cb: &mut miden_hir::ComponentBuilder<'_>,
     config: &WasmTranslationConfig,
 ) -> WasmResult<()> {
-    for import in component_imports {
-        let (import_idx, import_names) = &component.imports[import.runtime_import_index];
-        if import_names.len() != 1 {
-            return Err(crate::WasmError::Unsupported(
-                "multi-name imports not supported".to_string(),
-            ));
-        }
-        let import_func_name = import_names.first().unwrap();
-        let (full_interface_name, _) = component.import_types[*import_idx].clone();
-        let interface_function = InterfaceFunctionIdent {
-            interface: InterfaceIdent::from_full_ident(full_interface_name.clone()),
-            function: Symbol::intern(import_func_name),
-        };
-        let Some(import_metadata) = config.import_metadata.get(&interface_function) else {
-            return Err(crate::WasmError::MissingImportMetadata(format!(
-                "Import metadata for interface function {:?} not found",
-                &interface_function,
-            )));
-        };
-        let lifted_func_ty = convert_lifted_func_ty(&import.signature, component_types);
-
-        let component_import = miden_hir::ComponentImport {
-            function_ty: lifted_func_ty,
-            interface_function,
-            invoke_method: import_metadata.invoke_method,
-            digest: import_metadata.digest.clone(),
+    // The inliner gives a definition for each import of the module, in the order of its imports
+    for (module_arg, module_import) in module_args.iter().zip(&parsed_module.module.imports) {
+        let ModuleArgument::ComponentImport(import) = module_arg else {
+            continue;
         };
-        let function_id =
-            find_module_import_function(parsed_module, full_interface_name, import_func_name)?;
+        let component_import = build_component_import(import, component_types, component, config)?;
+        let function_id = module_import_function(parsed_module, module_import)?;
         cb.add_import(function_id, component_import);
     }
     Ok(())
 }
 
-fn find_module_import_function(
+/// Describe the component import `import`, along with the metadata given for it in `config`
+fn build_component_import(
+    import: &ComponentImport,
+    component_types: &ComponentTypes,
+    component: &LinearComponent,
+    config: &WasmTranslationConfig,
+) -> WasmResult<miden_hir::ComponentImport> {
+    let (import_idx, import_names) = &component.imports[import.runtime_import_index];
+    if import_names.len() != 1 {
+        return Err(crate::WasmError::Unsupported(
+            "multi-name imports not supported".to_string(),
+        ));
+    }
+    let import_func_name = import_names.first().unwrap();
+    let (full_interface_name, _) = component.import_types[*import_idx].clone();
+    let interface_function = InterfaceFunctionIdent {
+        interface: InterfaceIdent::from_full_ident(full_interface_name.clone()),
+        function: Symbol::intern(import_func_name),
+    };
+    let Some(import_metadata) = config.import_metadata.get(&interface_function) else {
+        return Err(crate::WasmError::MissingImportMetadata(format!(
+            "Import metadata for interface function {:?} not found",
+            &interface_function,
+        )));
+    };
+    let lifted_func_ty = convert_lifted_func_ty(&import.signature, component_types);
+
+    Ok(miden_hir::ComponentImport {
+        function_ty: lifted_func_ty,
+        interface_function,
+        invoke_method: import_metadata.invoke_method,
+        digest: import_metadata.digest.clone(),
+    })
+}
+
+/// Get the identifier used by the module to call the function imported by `module_import`
+fn module_import_function(
     parsed_module: &ParsedModule,
-    full_interface_name: String,
-    import_func_name: &String,
+    module_import: &ModuleImport,
 ) -> WasmResult<FunctionIdent> {
-    for import in &parsed_module.module.imports {
-        if import.module == full_interface_name && &import.field == import_func_name {
-            let func_idx = import.index.unwrap_func();
-            let func_name = parsed_module.module.func_name(func_idx);
-            let module_instance_name = parsed_module.module.name();
-            return Ok(FunctionIdent {
-                module: Ident::with_empty_span(Symbol::intern(module_instance_name)),
-                function: Ident::with_empty_span(Symbol::intern(func_name)),
-            });
-        }
+    let EntityIndex::Function(func_idx) = module_import.index else {
+        return Err(WasmError::Unexpected(format!(
+            "expected module import {}::{} to be a function, as it is given a component import",
+            module_import.module, module_import.field
+        )));
+    };
+    let func_name = parsed_module.module.func_name(func_idx);
+    let module_instance_name = parsed_module.module.name();
+    Ok(FunctionIdent {
+        module: Ident::with_empty_span(Symbol::intern(module_instance_name)),
+        function: Ident::with_empty_span(Symbol::intern(func_name)),
+    })
+}
+
+/// Resolve the function imports of a module which are satisfied by an export of another core
+/// module, to the exported function, along with the signature it is imported with
+///
+/// Imports of a function lifted in another component instance, which need an adapter, are
+/// resolved to the adapter, which is built in `adapters` the first time it is imported, see
+/// [build_adapter_function].
+fn resolve_core_export_imports(
+    component_instance: &ComponentInstance<'_>,
+    static_module_idx: StaticModuleIndex,
+    adapters: &mut ModuleBuilder,
+    adapter_functions: &mut FxHashMap<TrampolineIndex, FunctionIdent>,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<Vec<(FuncIndex, (FunctionIdent, Signature))>> {
+    let parsed_module = &component_instance.modules[static_module_idx];
+    let module_types = component_instance.component_types.module_types();
+    let mut resolved = vec![];
+    for (module_arg, module_import) in component_instance.imports[&static_module_idx]
+        .iter()
+        .zip(&parsed_module.module.imports)
+    {
+        let EntityIndex::Function(func_idx) = module_import.index else {
+            continue;
+        };
+        let function_id = match module_arg {
+            ModuleArgument::CoreExport(export) => {
+                core_def_func_ident(component_instance, &CoreDef::Export(export.clone()))
+            }
+            ModuleArgument::Adapter(trampoline) => match adapter_functions.get(trampoline) {
+                Some(function_id) => *function_id,
+                None => {
+                    let function_id = build_adapter_function(
+                        component_instance,
+                        *trampoline,
+                        adapters,
+                        diagnostics,
+                    )?;
+                    adapter_functions.insert(*trampoline, function_id);
+                    function_id
+                }
+            },
+            ModuleArgument::ComponentImport(_) => continue,
+        };
+        let sig_idx = parsed_module
+            .module
+            .type_of(module_import.index)
+            .unwrap_func();
+        let func_type = ir_func_type(&module_types[sig_idx])?;
+        let sig = ir_func_sig(&func_type, CallConv::SystemV, Linkage::External);
+        resolved.push((func_idx, (function_id, sig)));
     }
-    Err(WasmError::Unexpected(format!(
-        "failed to find module import for interface {} and function {}",
-        full_interface_name, import_func_name
-    )))
+    Ok(resolved)
 }
 
 fn build_export(
@@ -190,16 +380,37 @@ fn build_export(
     component_instance: &ComponentInstance<'_>,
     name: &String,
     cb: &mut miden_hir::ComponentBuilder<'_>,
+    reexports: &mut ModuleBuilder,
     config: &WasmTranslationConfig,
+    diagnostics: &DiagnosticsHandler,
 ) -> WasmResult<()> {
     match export {
-        Export::LiftedFunction { ty, func, options } => {
-            build_export_function(component_instance, name, func, ty, options, cb, config)
-        }
+        Export::LiftedFunction { ty, func, options } => build_export_function(
+            component_instance,
+            name,
+            func,
+            ty,
+            options,
+            cb,
+            reexports,
+            config,
+            diagnostics,
+        ),
         Export::Instance(exports) => {
-            // Flatten any(nested) interface instance exports into the IR `Component` exports
-            for (name, export) in exports {
-                build_export(export, component_instance, name, cb, config)?;
+            // Flatten any (nested) interface instance exports into the IR `Component` exports,
+            // each named after the interface it belongs to, e.g. the `note-script` function of the
+            // `miden:base/note@1.0.0` interface is exported as `miden:base/note@1.0.0#note-script`
+            for (export_name, export) in exports {
+                let name = format!("{name}#{export_name}");
+                build_export(
+                    export,
+                    component_instance,
+                    &name,
+                    cb,
+                    reexports,
+                    config,
+                    diagnostics,
+                )?;
             }
             Ok(())
         }
@@ -216,10 +427,112 @@ fn build_export_function(
     ty: &TypeFuncIndex,
     options: &CanonicalOptions,
     cb: &mut miden_hir::ComponentBuilder<'_>,
+    reexports: &mut ModuleBuilder,
     config: &WasmTranslationConfig,
+    diagnostics: &DiagnosticsHandler,
 ) -> WasmResult<()> {
-    assert_empty_canonical_options(options);
+    check_export_canonical_options(name, options, diagnostics)?;
     let func_ident = match func {
+        CoreDef::Trampoline(trampoline) => build_reexport_function(
+            component_instance,
+            name,
+            *trampoline,
+            cb,
+            reexports,
+            config,
+            diagnostics,
+        )?,
+        _ => core_def_func_ident(component_instance, func),
+    };
+    let post_return = options
+        .post_return
+        .map(|idx| core_def_func_ident(component_instance, &component_instance.post_returns[idx]));
+    let lifted_func_ty = convert_lifted_func_ty(ty, &component_instance.component_types);
+    let export_name = Symbol::intern(name).into();
+    let Some(export_metadata) = config
+        .export_metadata
+        .get(&export_name)
+        .or(config.default_export_metadata.as_ref())
+    else {
+        return Err(WasmError::MissingExportMetadata(format!(
+            "Export metadata for interface function {:?} not found",
+            &export_name,
+        )));
+    };
+    let export = miden_hir::ComponentExport {
+        function: func_ident,
+        function_ty: lifted_func_ty,
+        invoke_method: export_metadata.invoke_method,
+        post_return,
+    };
+    cb.add_export(export_name, export);
+    Ok(())
+}
+
+/// Synthesize a function named `export_name` in the `reexports` module, which forwards its
+/// arguments to the component import lowered by `trampoline`, and returns its result, so that the
+/// import can be exported by the component again.
+///
+/// The function has the flat core signature the import is lowered to with the default canonical
+/// options, i.e. the arguments and result are passed as they are, see
+/// [ComponentTypesBuilder::lowered_func_signature]. The import it calls is named after its
+/// interface function in the same module, e.g. `reexports::miden:base/log@1.0.0#log`.
+///
+/// NOTE: Only imports whose parameters and results all fit in flat core values can be
+/// reexported. Those passing strings, lists, or too many values, would need scratch space in a
+/// linear memory to be copied through, but a reexport has no memory of its own to allocate it
+/// from, so they are rejected by the inliner, see `Inliner::record_export`.
+fn build_reexport_function(
+    component_instance: &ComponentInstance<'_>,
+    export_name: &str,
+    trampoline: TrampolineIndex,
+    cb: &mut miden_hir::ComponentBuilder<'_>,
+    reexports: &mut ModuleBuilder,
+    config: &WasmTranslationConfig,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<FunctionIdent> {
+    let Some(import) = component_instance.lowered_imports.get(&trampoline) else {
+        return Err(WasmError::Unsupported(format!(
+            "component export `{export_name}` is a trampoline which does not lower an import"
+        )));
+    };
+    let component_types = &component_instance.component_types;
+    let component_import = build_component_import(
+        import,
+        component_types,
+        &component_instance.component,
+        config,
+    )?;
+    let sig_idx = component_instance.component.trampolines[trampoline];
+    let func_type = ir_func_type(&component_types.module_types()[sig_idx])?;
+    let sig = ir_func_sig(&func_type, CallConv::SystemV, Linkage::External);
+    let interface_function = &component_import.interface_function;
+    let import_id = FunctionIdent {
+        module: reexports.name(),
+        function: Ident::with_empty_span(Symbol::intern(format!(
+            "{}#{}",
+            interface_function.interface.full_name, interface_function.function
+        ))),
+    };
+
+    let mut fb = reexports.function(export_name, sig.clone())?;
+    let callee = fb.import_function(import_id.module, import_id.function, sig)?;
+    let args = fb.block_params(fb.entry_block()).to_vec();
+    let call = fb.ins().call(callee, &args, SourceSpan::default());
+    let result = fb.inst_results(call).first().copied();
+    fb.ins().ret(result, SourceSpan::default());
+    let function_id = fb
+        .build(diagnostics)
+        .map_err(|_| WasmError::InvalidFunctionError)?;
+    cb.add_import(import_id, component_import);
+    Ok(function_id)
+}
+
+pub(super) fn core_def_func_ident(
+    component_instance: &ComponentInstance<'_>,
+    def: &CoreDef,
+) -> FunctionIdent {
+    match def {
         CoreDef::Export(core_export) => {
             let parsed_module = component_instance.module(core_export.instance);
             let module_name = parsed_module.module.name();
@@ -233,30 +546,41 @@ fn build_export_function(
                 },
                 ExportItem::Name(_) => todo!(),
             };
-            let func_ident = miden_hir::FunctionIdent {
+            miden_hir::FunctionIdent {
                 module: module_ident,
                 function: miden_hir::Ident::with_empty_span(Symbol::intern(func_name)),
-            };
-            func_ident
+            }
         }
         CoreDef::InstanceFlags(_) => todo!(),
         CoreDef::Trampoline(_) => todo!(),
-    };
-    let lifted_func_ty = convert_lifted_func_ty(ty, &component_instance.component_types);
-    let export_name = Symbol::intern(name).into();
-    let Some(export_metadata) = config.export_metadata.get(&export_name) else {
-        return Err(WasmError::MissingExportMetadata(format!(
-            "Export metadata for interface function {:?} not found",
-            &export_name,
+    }
+}
+
+/// Get the signature of the core function `def`, as it is exported by its module
+pub(super) fn core_def_func_sig(
+    component_instance: &ComponentInstance<'_>,
+    def: &CoreDef,
+) -> WasmResult<Signature> {
+    let CoreDef::Export(CoreExport {
+        instance,
+        item: ExportItem::Index(EntityIndex::Function(func_idx)),
+    }) = def
+    else {
+        return Err(WasmError::Unsupported(format!(
+            "expected {def:?} to be a function exported by a core module instance"
         )));
     };
-    let export = miden_hir::ComponentExport {
-        function: func_ident,
-        function_ty: lifted_func_ty,
-        invoke_method: export_metadata.invoke_method,
-    };
-    cb.add_export(export_name, export);
-    Ok(())
+    let parsed_module = component_instance.module(*instance);
+    let sig_idx = parsed_module
+        .module
+        .type_of(EntityIndex::Function(*func_idx))
+        .unwrap_func();
+    let func_type = ir_func_type(&component_instance.component_types.module_types()[sig_idx])?;
+    Ok(ir_func_sig(
+        &func_type,
+        CallConv::SystemV,
+        Linkage::External,
+    ))
 }
 
 fn convert_lifted_func_ty(
@@ -277,24 +601,36 @@ fn convert_lifted_func_ty(
     LiftedFunctionType { params, results }
 }
 
-fn assert_empty_canonical_options(options: &CanonicalOptions) {
-    assert_eq!(
-        options.string_encoding,
-        StringEncoding::Utf8,
-        "UTF-8 is expected in CanonicalOptions, string transcoding is not yet supported"
-    );
-    assert!(
-        options.realloc.is_none(),
-        "realloc in CanonicalOptions is not yet supported"
-    );
-    assert!(
-        options.post_return.is_none(),
-        "post_return in CanonicalOptions is not yet supported"
-    );
-    assert!(
-        options.memory.is_none(),
-        "memory in CanonicalOptions is not yet supported"
-    );
+/// Check that the export `name`, lifted with `options`, can be called by the host, which passes
+/// the arguments and receives the results as they are flattened, and exchanges strings as UTF-8
+fn check_export_canonical_options(
+    name: &str,
+    options: &CanonicalOptions,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let reason = if options.string_encoding != StringEncoding::Utf8 {
+        format!(
+            "it is lifted with the {:?} string encoding, but strings are exchanged with the host \
+             as UTF-8, and transcoding them at the host boundary is not supported",
+            options.string_encoding
+        )
+    } else if options.memory.is_some() || options.realloc.is_some() {
+        "it is lifted with a `memory` or `realloc` canonical option, but passing arguments or \
+         results to and from the host through linear memory is not supported"
+            .to_string()
+    } else {
+        return Ok(());
+    };
+    diagnostics
+        .diagnostic(miden_diagnostics::Severity::Error)
+        .with_message(format!(
+            "component export `{name}` cannot be called by the host"
+        ))
+        .with_note(reason.clone())
+        .emit();
+    Err(WasmError::Unsupported(format!(
+        "component export `{name}` cannot be called by the host: {reason}"
+    )))
 }
 
 #[cfg(test)]
@@ -304,9 +640,11 @@ mod tests {
     use miden_hir_type::Type;
 
     use crate::{
-        component::StaticModuleIndex,
+        component::{
+            GlobalInitializer, InstantiateModule, StaticModuleIndex, Trampoline, Transcode,
+        },
         config::{ExportMetadata, ImportMetadata},
-        test_utils::test_diagnostics,
+        test_utils::{callees, test_diagnostics},
     };
 
     use super::*;
@@ -533,4 +871,577 @@ mod tests {
         };
         assert_eq!(component_import.function_ty, expected_import_func_ty);
     }
+
+    #[test]
+    fn translate_call_to_lowered_tx_kernel_import() {
+        // The core module imports `get-id` under a different module name than the component
+        // interface it is lowered from, so it must be matched to the trampoline by position
+        let wat = r#"
+            (component
+            (type (;0;)
+                (instance
+                (type (;0;) (func (result u64)))
+                (export (;0;) "get-id" (func (type 0)))
+                )
+            )
+            (import "miden:base/tx-kernel@1.0.0" (instance (;0;) (type 0)))
+            (core module (;0;)
+                (type (;0;) (func (result i64)))
+                (import "tx" "get-id" (func $get_id_wit_import (;0;) (type 0)))
+                (func $account_id (;1;) (type 0) (result i64)
+                call $get_id_wit_import
+                )
+                (memory (;0;) 17)
+                (export "memory" (memory 0))
+                (export "account-id" (func $account_id))
+            )
+            (alias export 0 "get-id" (func (;0;)))
+            (core func (;0;) (canon lower (func 0)))
+            (core instance (;0;)
+                (export "get-id" (func 0))
+            )
+            (core instance (;1;) (instantiate 0
+                (with "tx" (instance 0))
+                )
+            )
+            (alias core export 1 "memory" (core memory (;0;)))
+            (type (;1;) (func (result u64)))
+            (alias core export 1 "account-id" (core func (;1;)))
+            (func (;1;) (type 1) (canon lift (core func 1)))
+            (export (;1;) "account-id" (func 1))
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let interface_function_ident = InterfaceFunctionIdent {
+            interface: InterfaceIdent::from_full_ident("miden:base/tx-kernel@1.0.0".to_string()),
+            function: Symbol::intern("get-id"),
+        };
+        let import_metadata = [(
+            interface_function_ident.clone(),
+            ImportMetadata {
+                digest: RpoDigest::default(),
+                invoke_method: miden_hir::FunctionInvocationMethod::Call,
+            },
+        )]
+        .into_iter()
+        .collect();
+        let export_metadata = [(
+            Symbol::intern("account-id").into(),
+            ExportMetadata {
+                invoke_method: miden_hir::FunctionInvocationMethod::Call,
+            },
+        )]
+        .into_iter()
+        .collect();
+        let config = WasmTranslationConfig {
+            import_metadata,
+            export_metadata,
+            ..Default::default()
+        };
+        let ir = translate_component(&wasm, &config, &diagnostics).unwrap();
+
+        let module = ir.modules().front().get().unwrap();
+        let function = module.function(Ident::from("account_id")).unwrap();
+        let callees = callees(function);
+        assert_eq!(callees.len(), 1);
+        let component_import = ir
+            .imports()
+            .get(&callees[0])
+            .expect("expected the call to target the lowered component import");
+        assert_eq!(
+            component_import.interface_function,
+            interface_function_ident
+        );
+        assert_eq!(
+            component_import.function_ty,
+            LiftedFunctionType {
+                params: vec![],
+                results: vec![Type::U64],
+            }
+        );
+    }
+
+    #[test]
+    fn inline_deduplicates_trampolines() {
+        // The same import is lowered twice, with the same options, so both lowerings share one
+        // trampoline
+        let wat = r#"
+            (component
+            (type (;0;)
+                (instance
+                (type (;0;) (func (param "a" u32) (param "b" u32) (result u32)))
+                (export (;0;) "add" (func (type 0)))
+                )
+            )
+            (import "miden:add/add@1.0.0" (instance (;0;) (type 0)))
+            (alias export 0 "add" (func (;0;)))
+            (core func (;0;) (canon lower (func 0)))
+            (core func (;1;) (canon lower (func 0)))
+            (core instance (;0;)
+                (export "add" (func 0))
+                (export "add-again" (func 1))
+            )
+            (core module (;0;)
+                (type (;0;) (func (param i32 i32) (result i32)))
+                (import "add" "add" (func (;0;) (type 0)))
+                (import "add" "add-again" (func (;1;) (type 0)))
+            )
+            (core instance (;1;) (instantiate 0
+                (with "add" (instance 0))
+                )
+            )
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let config = WasmTranslationConfig::default();
+        let (mut component_types_builder, parsed_component) =
+            parse(&config, &wasm, &diagnostics).unwrap();
+        let component_translation =
+            inline(&mut component_types_builder, &parsed_component).unwrap();
+        assert_eq!(component_translation.trampolines.len(), 1);
+        let GlobalInitializer::InstantiateModule(InstantiateModule::Static(_, args)) =
+            component_translation
+                .component
+                .initializers
+                .iter()
+                .find(|init| matches!(init, GlobalInitializer::InstantiateModule(_)))
+                .unwrap()
+        else {
+            panic!("expected the core module to be instantiated statically");
+        };
+        let trampoline = CoreDef::Trampoline(TrampolineIndex::from_u32(0));
+        assert_eq!(&**args, &[trampoline.clone(), trampoline]);
+    }
+
+    #[test]
+    fn translate_export_with_post_return() {
+        let wat = r#"
+            (component
+            (core module (;0;)
+                (type (;0;) (func (param i32 i32) (result i32)))
+                (type (;1;) (func (param i32)))
+                (func $add (;0;) (type 0) (param i32 i32) (result i32)
+                local.get 1
+                local.get 0
+                i32.add
+                )
+                (func $cabi_post_add (;1;) (type 1) (param i32))
+                (memory (;0;) 17)
+                (export "memory" (memory 0))
+                (export "add" (func $add))
+                (export "cabi_post_add" (func $cabi_post_add))
+            )
+            (core instance (;0;) (instantiate 0))
+            (alias core export 0 "memory" (core memory (;0;)))
+            (type (;0;) (func (param "a" u32) (param "b" u32) (result u32)))
+            (alias core export 0 "add" (core func (;0;)))
+            (alias core export 0 "cabi_post_add" (core func (;1;)))
+            (func (;0;) (type 0) (canon lift (core func 0) (post-return 1)))
+            (export (;1;) "add" (func 0))
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let export_metadata = [(
+            Symbol::intern("add").into(),
+            ExportMetadata {
+                invoke_method: miden_hir::FunctionInvocationMethod::Call,
+            },
+        )]
+        .into_iter()
+        .collect();
+        let config = WasmTranslationConfig {
+            export_metadata,
+            ..Default::default()
+        };
+        let ir = translate_component(&wasm, &config, &diagnostics).unwrap();
+        let export = ir.exports().get(&Symbol::intern("add").into()).unwrap();
+        assert_eq!(export.function.function.as_symbol(), Symbol::intern("add"));
+        let post_return = export
+            .post_return
+            .expect("expected the post-return function to be recorded on the export");
+        assert_eq!(post_return.module, export.function.module);
+        assert_eq!(
+            post_return.function.as_symbol(),
+            Symbol::intern("cabi_post_add")
+        );
+        // The post-return function is called with the core results of the export
+        let signature = ir.signature(&post_return).unwrap();
+        assert_eq!(signature.params().len(), 1);
+        assert!(signature.results().is_empty());
+    }
+
+    #[test]
+    fn reexported_import_passing_a_string_is_rejected() {
+        // The string would have to be copied through scratch space in a linear memory, which a
+        // reexport does not have
+        let wat = r#"
+            (component
+            (type (;0;)
+                (instance
+                (type (;0;) (func (param "message" string)))
+                (export (;0;) "log" (func (type 0)))
+                )
+            )
+            (import "miden:base/log@1.0.0" (instance (;0;) (type 0)))
+            (alias export 0 "log" (func (;0;)))
+            (export (;1;) "log" (func 0))
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let err = translate_component(
+            &wasm,
+            &WasmTranslationConfig::default(),
+            &test_diagnostics(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, WasmError::Unsupported(message) if message.contains(
+                "component export `log` is a reexport of an imported function whose parameters \
+                 or results are passed through linear memory"
+            )),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn translate_reexported_import() {
+        let wat = r#"
+            (component
+            (type (;0;)
+                (instance
+                (type (;0;) (func (param "level" u32) (param "code" u64) (result u32)))
+                (export (;0;) "log" (func (type 0)))
+                )
+            )
+            (import "miden:base/log@1.0.0" (instance (;0;) (type 0)))
+            (alias export 0 "log" (func (;0;)))
+            (export (;1;) "log" (func 0))
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let interface_function_ident = InterfaceFunctionIdent {
+            interface: InterfaceIdent::from_full_ident("miden:base/log@1.0.0".to_string()),
+            function: Symbol::intern("log"),
+        };
+        let import_metadata = [(
+            interface_function_ident.clone(),
+            ImportMetadata {
+                digest: RpoDigest::default(),
+                invoke_method: miden_hir::FunctionInvocationMethod::Call,
+            },
+        )]
+        .into_iter()
+        .collect();
+        let config = WasmTranslationConfig {
+            import_metadata,
+            default_export_metadata: Some(ExportMetadata {
+                invoke_method: miden_hir::FunctionInvocationMethod::Call,
+            }),
+            ..Default::default()
+        };
+        let ir = translate_component(&wasm, &config, &diagnostics).unwrap();
+
+        // The export is a function synthesized to forward its arguments to the import
+        let export = ir.exports().get(&Symbol::intern("log").into()).unwrap();
+        assert_eq!(export.function.module.as_str(), REEXPORTS_MODULE_NAME);
+        let lifted_func_ty = LiftedFunctionType {
+            params: vec![Type::U32, Type::U64],
+            results: vec![Type::U32],
+        };
+        assert_eq!(export.function_ty, lifted_func_ty);
+        let module = ir.modules().front().get().unwrap();
+        let function = module.function(export.function.function).unwrap();
+        assert_eq!(function.signature.params().len(), 2);
+        assert_eq!(function.signature.results().len(), 1);
+        let calls = function
+            .dfg
+            .block_insts(function.dfg.entry_block())
+            .filter_map(|inst| match function.dfg.inst(inst) {
+                miden_hir::Instruction::Call(call) => Some(call.callee),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(calls.len(), 1);
+        let component_import = ir
+            .imports()
+            .get(&calls[0])
+            .expect("expected the export to call the reexported import");
+        assert_eq!(
+            component_import.interface_function,
+            interface_function_ident
+        );
+        assert_eq!(component_import.function_ty, lifted_func_ty);
+    }
+
+    #[test]
+    fn reexported_import_passing_strings_is_rejected() {
+        // A string is passed through linear memory, which the component does not give
+        let wat = r#"
+            (component
+            (type (;0;)
+                (instance
+                (type (;0;) (func (param "message" string)))
+                (export (;0;) "log" (func (type 0)))
+                )
+            )
+            (import "miden:base/log@1.0.0" (instance (;0;) (type 0)))
+            (alias export 0 "log" (func (;0;)))
+            (export (;1;) "log" (func 0))
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let config = WasmTranslationConfig::default();
+        let (mut component_types_builder, parsed_component) =
+            parse(&config, &wasm, &diagnostics).unwrap();
+        let Err(err) = inline(&mut component_types_builder, &parsed_component) else {
+            panic!("expected the reexport to be rejected");
+        };
+        assert!(
+            err.to_string().contains("passed through linear memory"),
+            "unexpected error: {err}"
+        );
+    }
+
+    /// A component in which `inc` calls `add`, lifted by a nested component instance, with the
+    /// given canonical ABI options on the lowering of `add`
+    fn cross_instance_lowering_wat(lower_options: &str) -> String {
+        format!(
+            r#"
+            (component
+            (component (;0;)
+                (core module (;0;)
+                (func $add (export "add") (param i32 i32) (result i32)
+                    local.get 0
+                    local.get 1
+                    i32.add
+                )
+                )
+                (core instance (;0;) (instantiate 0))
+                (type (;0;) (func (param "a" u32) (param "b" u32) (result u32)))
+                (alias core export 0 "add" (core func (;0;)))
+                (func (;0;) (type 0) (canon lift (core func 0)))
+                (export (;1;) "add" (func 0))
+            )
+            (instance (;0;) (instantiate 0))
+            (alias export 0 "add" (func (;0;)))
+            (core func (;0;) (canon lower (func 0) {lower_options}))
+            (core instance (;0;)
+                (export "add" (func 0))
+            )
+            (core module (;0;)
+                (import "adder" "add" (func $add (param i32 i32) (result i32)))
+                (func $inc (export "inc") (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                call $add
+                )
+            )
+            (core instance (;1;) (instantiate 0
+                (with "adder" (instance 0))
+                )
+            )
+            (type (;0;) (func (param "a" u32) (result u32)))
+            (alias core export 1 "inc" (core func (;1;)))
+            (func (;1;) (type 0) (canon lift (core func 1)))
+            (export (;1;) "inc" (func 1))
+            )
+        "#
+        )
+    }
+
+    #[test]
+    fn translate_lowering_across_component_instances() {
+        let wasm = wat::parse_str(cross_instance_lowering_wat("")).unwrap();
+        let diagnostics = test_diagnostics();
+        let export_metadata = [(
+            Symbol::intern("inc").into(),
+            ExportMetadata {
+                invoke_method: miden_hir::FunctionInvocationMethod::Call,
+            },
+        )]
+        .into_iter()
+        .collect();
+        let config = WasmTranslationConfig {
+            export_metadata,
+            ..Default::default()
+        };
+        let ir = translate_component(&wasm, &config, &diagnostics).unwrap();
+
+        // The lifted function is called directly, rather than through a component import
+        assert!(ir.imports().is_empty());
+        let (module, function) = ir
+            .modules()
+            .iter()
+            .find_map(|module| Some((module, module.function(Ident::from("inc"))?)))
+            .unwrap();
+        let callees = callees(function);
+        assert_eq!(callees.len(), 1);
+        assert_eq!(callees[0].function.as_str(), "add");
+        assert_ne!(callees[0].module, module.name);
+    }
+
+    #[test]
+    fn lowering_across_component_instances_without_strings_ignores_string_encoding() {
+        let wasm = wat::parse_str(cross_instance_lowering_wat("string-encoding=utf16")).unwrap();
+        let diagnostics = test_diagnostics();
+        let config = WasmTranslationConfig {
+            default_export_metadata: Some(ExportMetadata {
+                invoke_method: miden_hir::FunctionInvocationMethod::Call,
+            }),
+            ..Default::default()
+        };
+        let (mut component_types_builder, parsed_component) =
+            parse(&config, &wasm, &diagnostics).unwrap();
+        let component_translation =
+            inline(&mut component_types_builder, &parsed_component).unwrap();
+        assert!(!component_translation
+            .trampolines
+            .values()
+            .any(|trampoline| matches!(trampoline, Trampoline::Transcoder { .. })));
+        translate_component(&wasm, &config, &diagnostics).unwrap();
+    }
+
+    #[test]
+    fn lowering_across_component_instances_transcodes_strings() {
+        let wat = r#"
+            (component
+            (component (;0;)
+                (core module (;0;)
+                (memory (export "memory") 1)
+                (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                    i32.const 0
+                )
+                (func (export "len") (param i32 i32) (result i32)
+                    local.get 1
+                )
+                )
+                (core instance (;0;) (instantiate 0))
+                (alias core export 0 "memory" (core memory (;0;)))
+                (alias core export 0 "realloc" (core func (;0;)))
+                (alias core export 0 "len" (core func (;1;)))
+                (type (;0;) (func (param "s" string) (result u32)))
+                (func (;0;) (type 0) (canon lift (core func 1) (memory 0) (realloc 0)))
+                (export (;1;) "len" (func 0))
+            )
+            (instance (;0;) (instantiate 0))
+            (alias export 0 "len" (func (;0;)))
+            (core module (;0;)
+                (memory (export "memory") 1)
+            )
+            (core instance (;0;) (instantiate 0))
+            (alias core export 0 "memory" (core memory (;0;)))
+            (core func (;0;) (canon lower (func 0) (memory 0) string-encoding=utf16))
+            (core instance (;1;)
+                (export "len" (func 0))
+            )
+            (core module (;1;)
+                (import "adder" "len" (func (param i32 i32) (result i32)))
+            )
+            (core instance (;2;) (instantiate 1
+                (with "adder" (instance 1))
+                )
+            )
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let config = WasmTranslationConfig::default();
+        let (mut component_types_builder, parsed_component) =
+            parse(&config, &wasm, &diagnostics).unwrap();
+        let component_translation =
+            inline(&mut component_types_builder, &parsed_component).unwrap();
+        // The arguments are transcoded into the memory of the callee, and the results back
+        let transcodes = component_translation
+            .trampolines
+            .values()
+            .filter_map(|trampoline| match trampoline {
+                Trampoline::Transcoder { op, .. } => Some(*op),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            transcodes,
+            vec![Transcode::Utf16ToUtf8, Transcode::Utf8ToUtf16]
+        );
+
+        // The string is copied into the memory of the callee by a synthesized adapter
+        let ir = translate_component(&wasm, &config, &diagnostics).unwrap();
+        let adapters = ir
+            .modules()
+            .iter()
+            .find(|module| module.name.as_str() == ADAPTERS_MODULE_NAME)
+            .unwrap();
+        let adapter = adapters.functions().next().unwrap();
+        let mut callees = adapter
+            .imports()
+            .map(|import| import.id.function.as_str())
+            .collect::<Vec<_>>();
+        callees.sort();
+        assert_eq!(callees, vec!["len", "realloc"]);
+    }
+
+    #[test]
+    fn inline_reports_unresolved_core_module_import() {
+        let wat = r#"
+            (component
+            (core module (;0;)
+                (func $add (export "add") (param i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.add
+                )
+            )
+            (core module (;1;)
+                (import "env" "add" (func (param i32 i32) (result i32)))
+            )
+            (core instance (;0;) (instantiate 0))
+            (core instance (;1;) (instantiate 1
+                (with "env" (instance 0))
+                )
+            )
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let config = WasmTranslationConfig::default();
+        // The validator rejects components which do not provide all of the imports of a core
+        // module, so the import the component does not provide is added after parsing
+        for (module, field, expected) in [
+            (
+                "env",
+                "sub",
+                "core module import `env::sub` is not provided by the component: the core module \
+                 instance has no export named `sub`",
+            ),
+            (
+                "host",
+                "add",
+                "core module import `host::add` is not provided by the component, as no instance \
+                 is given for `host` when instantiating the module",
+            ),
+        ] {
+            let (mut component_types_builder, mut parsed_component) =
+                parse(&config, &wasm, &diagnostics).unwrap();
+            parsed_component.static_modules[StaticModuleIndex::from_u32(1)]
+                .module
+                .imports
+                .push(ModuleImport {
+                    module: module.to_string(),
+                    field: field.to_string(),
+                    index: EntityIndex::Function(FuncIndex::from_u32(1)),
+                });
+            let Err(err) = inline(&mut component_types_builder, &parsed_component) else {
+                panic!("expected `{module}::{field}` to be reported as unresolved");
+            };
+            assert!(
+                err.to_string().contains(expected),
+                "unexpected error: {err}"
+            );
+        }
+    }
 }
@@ -35,6 +35,7 @@
 use crate::component::info;
 use crate::component::*;
 use crate::module::types::{EntityIndex, MemoryIndex, WasmType};
+use anyhow::{bail, Result};
 use indexmap::IndexMap;
 use miden_hir::cranelift_entity::{EntityRef, PrimaryMap};
 use rustc_hash::FxHashMap;
@@ -53,6 +54,9 @@ pub struct ComponentDfg {
     pub exports: IndexMap<String, Export>,
 
     /// All trampolines and their type signature
+    ///
+    /// Trampolines are interned on their contents, so that e.g. lowering the
+    /// same import more than once with the same options yields one trampoline.
     pub trampolines: Intern<TrampolineIndex, (SignatureIndex, Trampoline)>,
 
     /// Know reallocation functions which are used by `lowerings` (e.g. will be
@@ -236,6 +240,19 @@ pub enum Trampoline {
         lower_ty: TypeFuncIndex,
     },
     AlwaysTrap,
+    FusedAdapter {
+        lift_ty: TypeFuncIndex,
+        lift_options: CanonicalOptions,
+        lower_ty: TypeFuncIndex,
+        lower_options: CanonicalOptions,
+        callee: CoreDef,
+        transcoders: Vec<TrampolineIndex>,
+    },
+    Transcoder {
+        op: Transcode,
+        from: MemoryId,
+        to: MemoryId,
+    },
     ResourceNew(TypeResourceTableIndex),
     ResourceRep(TypeResourceTableIndex),
     ResourceDrop(TypeResourceTableIndex),
@@ -298,6 +315,11 @@ where
     pub fn iter(&self) -> impl Iterator<Item = (K, &V)> {
         self.key_map.iter()
     }
+
+    /// Returns the number of values contained within this set.
+    pub fn len(&self) -> usize {
+        self.key_map.len()
+    }
 }
 
 impl<K: EntityRef, V> Index<K> for Intern<K, V> {
@@ -386,6 +408,219 @@ impl ComponentDfg {
     }
 }
 
+impl ComponentDfg {
+    /// Checks that every index referenced by this dataflow graph, e.g. runtime
+    /// imports, trampolines, memories, reallocs and post-returns, refers to an
+    /// entry which actually exists.
+    ///
+    /// This is intended to catch bugs in the inliner as soon as possible,
+    /// rather than when the dangling index is eventually used.
+    pub fn verify(&self) -> Result<()> {
+        for (index, (import, _)) in self.imports.iter() {
+            self.check(
+                || format!("runtime import {}", index.as_u32()),
+                "import",
+                *import,
+                self.import_types.len(),
+            )?;
+        }
+        for (index, (_, trampoline)) in self.trampolines.iter() {
+            let context = || format!("trampoline {}", index.as_u32());
+            if let Trampoline::LowerImport {
+                import, options, ..
+            } = trampoline
+            {
+                self.check(context, "runtime import", *import, self.imports.len())?;
+                self.verify_options(context, options)?;
+            }
+            if let Trampoline::FusedAdapter {
+                lift_options,
+                lower_options,
+                callee,
+                transcoders,
+                ..
+            } = trampoline
+            {
+                self.verify_options(context, lift_options)?;
+                self.verify_options(context, lower_options)?;
+                self.verify_def(context, callee)?;
+                for transcoder in transcoders {
+                    self.check(context, "trampoline", *transcoder, self.trampolines.len())?;
+                }
+            }
+            if let Trampoline::Transcoder { from, to, .. } = trampoline {
+                self.check(context, "memory", *from, self.memories.len())?;
+                self.check(context, "memory", *to, self.memories.len())?;
+            }
+        }
+        for (index, def) in self.reallocs.iter() {
+            self.verify_def(|| format!("realloc {}", index.as_u32()), def)?;
+        }
+        for (index, def) in self.post_returns.iter() {
+            self.verify_def(|| format!("post-return {}", index.as_u32()), def)?;
+        }
+        for (index, export) in self.memories.iter() {
+            self.check(
+                || format!("memory {}", index.as_u32()),
+                "instance",
+                export.instance,
+                self.instances.len(),
+            )?;
+        }
+        for (index, instance) in self.instances.iter() {
+            let context = || format!("instance {}", index.as_u32());
+            match instance {
+                Instance::Static(_, args) => {
+                    for def in args.iter() {
+                        self.verify_def(context, def)?;
+                    }
+                }
+                Instance::Import(import, args) => {
+                    self.check(context, "runtime import", *import, self.imports.len())?;
+                    for def in args.values().flat_map(|args| args.values()) {
+                        self.verify_def(context, def)?;
+                    }
+                }
+            }
+        }
+        for (index, (_, args)) in self.adapter_modules.iter() {
+            for def in args.iter() {
+                self.verify_def(|| format!("adapter module {}", index.as_u32()), def)?;
+            }
+        }
+        for (index, (module, _)) in self.adapter_paritionings.iter() {
+            self.check(
+                || format!("adapter {}", index.as_u32()),
+                "adapter module",
+                *module,
+                self.adapter_modules.len(),
+            )?;
+        }
+        for (index, resource) in self.resources.iter() {
+            let context = || format!("resource {}", index.as_u32());
+            self.check_component_instance(context, resource.instance)?;
+            if let Some(dtor) = &resource.dtor {
+                self.verify_def(context, dtor)?;
+            }
+        }
+        for (index, import) in self.imported_resources.iter() {
+            self.check(
+                || format!("imported resource {}", index.as_u32()),
+                "runtime import",
+                *import,
+                self.imports.len(),
+            )?;
+        }
+        for effect in self.side_effects.iter() {
+            match effect {
+                SideEffect::Instance(instance) => self.check(
+                    || "side effect".to_string(),
+                    "instance",
+                    *instance,
+                    self.instances.len(),
+                )?,
+                SideEffect::Resource(resource) => self.check(
+                    || "side effect".to_string(),
+                    "resource",
+                    *resource,
+                    self.resources.len(),
+                )?,
+            }
+        }
+        for (name, export) in self.exports.iter() {
+            self.verify_export(name, export)?;
+        }
+        Ok(())
+    }
+
+    fn verify_export(&self, name: &str, export: &Export) -> Result<()> {
+        let context = || format!("export `{name}`");
+        match export {
+            Export::LiftedFunction { func, options, .. } => {
+                self.verify_def(context, func)?;
+                self.verify_options(context, options)
+            }
+            Export::ModuleImport(import) => {
+                self.check(context, "runtime import", *import, self.imports.len())
+            }
+            Export::Instance(exports) => {
+                for (nested, export) in exports.iter() {
+                    self.verify_export(&format!("{name}/{nested}"), export)?;
+                }
+                Ok(())
+            }
+            Export::ModuleStatic(_) | Export::Type(_) => Ok(()),
+        }
+    }
+
+    fn verify_options(
+        &self,
+        context: impl Fn() -> String,
+        options: &CanonicalOptions,
+    ) -> Result<()> {
+        self.check_component_instance(&context, options.instance)?;
+        if let Some(memory) = options.memory {
+            self.check(&context, "memory", memory, self.memories.len())?;
+        }
+        if let Some(realloc) = options.realloc {
+            self.check(&context, "realloc", realloc, self.reallocs.len())?;
+        }
+        if let Some(post_return) = options.post_return {
+            self.check(
+                &context,
+                "post-return",
+                post_return,
+                self.post_returns.len(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn verify_def(&self, context: impl Fn() -> String, def: &CoreDef) -> Result<()> {
+        match def {
+            CoreDef::Export(export) => {
+                self.check(context, "instance", export.instance, self.instances.len())
+            }
+            CoreDef::InstanceFlags(instance) => self.check_component_instance(context, *instance),
+            CoreDef::Trampoline(trampoline) => {
+                self.check(context, "trampoline", *trampoline, self.trampolines.len())
+            }
+        }
+    }
+
+    fn check_component_instance(
+        &self,
+        context: impl Fn() -> String,
+        instance: RuntimeComponentInstanceIndex,
+    ) -> Result<()> {
+        self.check(
+            context,
+            "component instance",
+            instance,
+            self.num_runtime_component_instances as usize,
+        )
+    }
+
+    /// Checks that `index` refers to one of the `len` entries of kind `kind`,
+    /// where `context` describes the item the index was found in.
+    fn check<K: EntityRef>(
+        &self,
+        context: impl Fn() -> String,
+        kind: &str,
+        index: K,
+        len: usize,
+    ) -> Result<()> {
+        if index.index() >= len {
+            bail!(
+                "{} refers to {kind} {}, but only {len} are defined",
+                context(),
+                index.index()
+            );
+        }
+        Ok(())
+    }
+}
+
 struct LinearizeDfg<'a> {
     dfg: &'a ComponentDfg,
     initializers: Vec<GlobalInitializer>,
@@ -550,6 +785,29 @@ impl LinearizeDfg<'_> {
                 }
             }
             Trampoline::AlwaysTrap => info::Trampoline::AlwaysTrap,
+            Trampoline::FusedAdapter {
+                lift_ty,
+                lift_options,
+                lower_ty,
+                lower_options,
+                callee,
+                transcoders,
+            } => info::Trampoline::FusedAdapter {
+                lift_ty: *lift_ty,
+                lift_options: self.options(lift_options),
+                lower_ty: *lower_ty,
+                lower_options: self.options(lower_options),
+                callee: self.core_def(callee),
+                transcoders: transcoders
+                    .iter()
+                    .map(|transcoder| self.trampoline(*transcoder))
+                    .collect(),
+            },
+            Trampoline::Transcoder { op, from, to } => info::Trampoline::Transcoder {
+                op: *op,
+                from: self.runtime_memory(*from),
+                to: self.runtime_memory(*to),
+            },
             Trampoline::ResourceNew(ty) => info::Trampoline::ResourceNew(*ty),
             Trampoline::ResourceDrop(ty) => info::Trampoline::ResourceDrop(*ty),
             Trampoline::ResourceRep(ty) => info::Trampoline::ResourceRep(*ty),
@@ -613,3 +871,89 @@ impl LinearizeDfg<'_> {
         index
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lower_import(import: u32, memory: Option<MemoryId>) -> (SignatureIndex, Trampoline) {
+        (
+            SignatureIndex::from_u32(0),
+            Trampoline::LowerImport {
+                import: RuntimeImportIndex::from_u32(import),
+                options: CanonicalOptions {
+                    instance: RuntimeComponentInstanceIndex::from_u32(0),
+                    string_encoding: StringEncoding::Utf8,
+                    memory,
+                    realloc: None,
+                    post_return: None,
+                },
+                lower_ty: TypeFuncIndex::from_u32(0),
+            },
+        )
+    }
+
+    fn valid_dfg() -> ComponentDfg {
+        let mut dfg = ComponentDfg::default();
+        dfg.num_runtime_component_instances = 1;
+        let import = dfg.import_types.push((
+            "f".to_string(),
+            TypeDef::ComponentFunc(TypeFuncIndex::from_u32(0)),
+        ));
+        dfg.imports.push((import, vec![]));
+        dfg.trampolines.push(lower_import(0, None));
+        dfg
+    }
+
+    #[test]
+    fn verify_valid_dfg() {
+        valid_dfg().verify().unwrap();
+    }
+
+    #[test]
+    fn verify_dangling_runtime_import() {
+        let mut dfg = valid_dfg();
+        dfg.trampolines.push(lower_import(3, None));
+        let err = dfg.verify().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "trampoline 1 refers to runtime import 3, but only 1 are defined"
+        );
+    }
+
+    #[test]
+    fn verify_dangling_memory() {
+        let mut dfg = valid_dfg();
+        dfg.trampolines
+            .push(lower_import(0, Some(MemoryId::from_u32(0))));
+        let err = dfg.verify().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "trampoline 1 refers to memory 0, but only 0 are defined"
+        );
+    }
+
+    #[test]
+    fn verify_dangling_trampoline_in_export() {
+        let mut dfg = valid_dfg();
+        dfg.exports.insert(
+            "add".to_string(),
+            Export::LiftedFunction {
+                ty: TypeFuncIndex::from_u32(0),
+                func: CoreDef::Trampoline(TrampolineIndex::from_u32(7)),
+                options: CanonicalOptions {
+                    instance: RuntimeComponentInstanceIndex::from_u32(0),
+                    string_encoding: StringEncoding::Utf8,
+                    memory: None,
+                    realloc: None,
+                    post_return: None,
+                },
+            },
+        );
+        let err = dfg.verify().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "export `add` refers to trampoline 7, but only 1 are defined"
+        );
+    }
+}
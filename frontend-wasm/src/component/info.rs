@@ -447,6 +447,43 @@ pub enum Trampoline {
     /// combinations.
     AlwaysTrap,
 
+    /// A fused adapter for a function lifted in one component instance and
+    /// lowered in another, which calls `callee` on behalf of the lowering
+    /// component.
+    FusedAdapter {
+        /// The type of the function as perceived by the lifting component.
+        lift_ty: TypeFuncIndex,
+
+        /// The canonical ABI options of the lifted function.
+        lift_options: CanonicalOptions,
+
+        /// The type of the function as perceived by the lowering component.
+        lower_ty: TypeFuncIndex,
+
+        /// The canonical ABI options of the lowering.
+        lower_options: CanonicalOptions,
+
+        /// The core wasm function which was lifted.
+        callee: CoreDef,
+
+        /// The `Transcoder` trampolines used to transcode the strings passed
+        /// to `callee` and back, if the two sides disagree on their encoding.
+        transcoders: Vec<TrampolineIndex>,
+    },
+
+    /// An intrinsic used by fused adapters which transcodes a string from
+    /// one encoding to another, while copying it from one memory to another.
+    Transcoder {
+        /// The transcoding to perform.
+        op: Transcode,
+
+        /// The memory the string is read from.
+        from: RuntimeMemoryIndex,
+
+        /// The memory the transcoded string is written to.
+        to: RuntimeMemoryIndex,
+    },
+
     /// A `resource.new` intrinsic which will inject a new resource into the
     /// table specified.
     ResourceNew(TypeResourceTableIndex),
@@ -49,14 +49,14 @@
 use super::resources::ResourcesBuilder;
 use super::{
     types::*, ClosedOverComponent, ClosedOverModule, ExportItem, LocalCanonicalOptions,
-    ParsedComponent, StringEncoding,
+    ParsedComponent, StringEncoding, Transcode,
 };
 use crate::component::dfg;
 use crate::component::LocalInitializer;
 use crate::module::module_env::ParsedModule;
 use crate::module::{types::*, ModuleImport};
 use crate::translation_utils::BuildFxHasher;
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 use indexmap::IndexMap;
 use miden_hir::cranelift_entity::PrimaryMap;
 use rustc_hash::FxHashMap;
@@ -333,7 +333,7 @@ pub struct AdapterOptions {
 #[derive(Clone)]
 enum ComponentFuncDef<'a> {
     /// A host-imported component function.
-    Import(ImportPath<'a>),
+    Import(ImportPath<'a>, TypeFuncIndex),
 
     /// A core wasm function was lifted into a component function.
     Lifted {
@@ -525,7 +525,7 @@ impl<'a> Inliner<'a> {
                     // then this is a lowered host function which needs a
                     // trampoline to enter WebAssembly. That's recorded here
                     // with all relevant information.
-                    ComponentFuncDef::Import(path) => {
+                    ComponentFuncDef::Import(path, _) => {
                         let import = self.runtime_import(path);
                         let options = self.canonical_options(options_lower);
                         let index = self.result.trampolines.push((
@@ -577,8 +577,61 @@ impl<'a> Inliner<'a> {
 
                     // Lowering a lifted function where the destination
                     // component is different than the source component
-                    ComponentFuncDef::Lifted { .. } => {
-                        bail!( "Lowering a lifted function where the destination component is different than the source component is not supported");
+                    // requires a fused adapter which calls the lifted core
+                    // function on behalf of the lowering component.
+                    //
+                    // If the two sides disagree on the encoding of strings,
+                    // the adapter transcodes the strings passed as arguments
+                    // into the memory of the callee, and the strings it
+                    // returns back into the memory of the caller.
+                    ComponentFuncDef::Lifted {
+                        ty: lift_ty,
+                        func,
+                        options: options_lift,
+                    } => {
+                        let func = func.clone();
+                        let lift_options = self.canonical_options(options_lift.clone());
+                        let lower_options = self.canonical_options(options_lower);
+                        let mut transcoders = Vec::new();
+                        // Strings are passed through linear memory, so there
+                        // are none to transcode unless both sides provide one
+                        if let (Some(lower_memory), Some(lift_memory)) =
+                            (lower_options.memory, lift_options.memory)
+                        {
+                            for (from, to, from_memory, to_memory) in [
+                                (&lower_options, &lift_options, lower_memory, lift_memory),
+                                (&lift_options, &lower_options, lift_memory, lower_memory),
+                            ] {
+                                let Some(op) =
+                                    Transcode::between(from.string_encoding, to.string_encoding)?
+                                else {
+                                    continue;
+                                };
+                                let signature = types
+                                    .module_types_builder_mut()
+                                    .intern_func_type(op.signature());
+                                transcoders.push(self.result.trampolines.push((
+                                    signature,
+                                    dfg::Trampoline::Transcoder {
+                                        op,
+                                        from: from_memory,
+                                        to: to_memory,
+                                    },
+                                )));
+                            }
+                        }
+                        let index = self.result.trampolines.push((
+                            *canonical_abi,
+                            dfg::Trampoline::FusedAdapter {
+                                lift_ty: *lift_ty,
+                                lift_options,
+                                lower_ty,
+                                lower_options,
+                                callee: func,
+                                transcoders,
+                            },
+                        ));
+                        dfg::CoreDef::Trampoline(index)
                     }
                 };
                 frame.funcs.push(func);
@@ -683,10 +736,12 @@ impl<'a> Inliner<'a> {
                             index: _,
                         } in &self.nested_modules[*idx].module.imports
                         {
-                            let instance = args[module_name.as_str()];
-                            defs.push(
-                                self.core_def_of_module_instance_export(frame, instance, &field),
-                            );
+                            defs.push(self.core_def_of_module_import(
+                                frame,
+                                args,
+                                module_name,
+                                field,
+                            )?);
                         }
                         instance_module = InstanceModule::Static(*idx);
                         dfg::Instance::Static(*idx, defs.into())
@@ -694,9 +749,7 @@ impl<'a> Inliner<'a> {
                     ModuleDef::Import(path, ty) => {
                         let mut defs = IndexMap::new();
                         for ((module, name), _) in types[*ty].imports.iter() {
-                            let instance = args[module.as_str()];
-                            let def =
-                                self.core_def_of_module_instance_export(frame, instance, name);
+                            let def = self.core_def_of_module_import(frame, args, module, name)?;
                             defs.entry(module.to_string())
                                 .or_insert(IndexMap::new())
                                 .insert(name.to_string(), def);
@@ -791,12 +844,12 @@ impl<'a> Inliner<'a> {
             AliasExportFunc(instance, name) => {
                 frame
                     .funcs
-                    .push(self.core_def_of_module_instance_export(frame, *instance, *name));
+                    .push(self.core_def_of_module_instance_export(frame, *instance, *name)?);
             }
 
             AliasExportTable(instance, name) => {
                 frame.tables.push(
-                    match self.core_def_of_module_instance_export(frame, *instance, *name) {
+                    match self.core_def_of_module_instance_export(frame, *instance, *name)? {
                         dfg::CoreDef::Export(e) => e,
                         _ => unreachable!(),
                     },
@@ -805,7 +858,7 @@ impl<'a> Inliner<'a> {
 
             AliasExportGlobal(instance, name) => {
                 frame.globals.push(
-                    match self.core_def_of_module_instance_export(frame, *instance, *name) {
+                    match self.core_def_of_module_instance_export(frame, *instance, *name)? {
                         dfg::CoreDef::Export(e) => e,
                         _ => unreachable!(),
                     },
@@ -814,7 +867,7 @@ impl<'a> Inliner<'a> {
 
             AliasExportMemory(instance, name) => {
                 frame.memories.push(
-                    match self.core_def_of_module_instance_export(frame, *instance, *name) {
+                    match self.core_def_of_module_instance_export(frame, *instance, *name)? {
                         dfg::CoreDef::Export(e) => e,
                         _ => unreachable!(),
                     },
@@ -898,6 +951,32 @@ impl<'a> Inliner<'a> {
             })
     }
 
+    /// Returns the `CoreDef` provided by the instantiation arguments `args`
+    /// for the import `field` of `module` of a core wasm module.
+    ///
+    /// An error naming the import is returned if the arguments do not provide
+    /// it, rather than panicking on the missing instance or export.
+    fn core_def_of_module_import(
+        &self,
+        frame: &InlinerFrame<'a>,
+        args: &FxHashMap<&str, ModuleInstanceIndex>,
+        module: &str,
+        field: &'a str,
+    ) -> Result<dfg::CoreDef> {
+        let Some(instance) = args.get(module) else {
+            bail!(
+                "core module import `{module}::{field}` is not provided by the component, \
+                 as no instance is given for `{module}` when instantiating the module"
+            )
+        };
+        self.core_def_of_module_instance_export(frame, *instance, field)
+            .map_err(|e| {
+                anyhow!(
+                    "core module import `{module}::{field}` is not provided by the component: {e}"
+                )
+            })
+    }
+
     /// Returns the `CoreDef`, the canonical definition for a core wasm item,
     /// for the export `name` of `instance` within `frame`.
     fn core_def_of_module_instance_export(
@@ -905,7 +984,7 @@ impl<'a> Inliner<'a> {
         frame: &InlinerFrame<'a>,
         instance: ModuleInstanceIndex,
         name: &'a str,
-    ) -> dfg::CoreDef {
+    ) -> Result<dfg::CoreDef> {
         match &frame.module_instances[instance] {
             // Instantiations of a statically known module means that we can
             // refer to the exported item by a precise index, skipping name
@@ -917,26 +996,29 @@ impl<'a> Inliner<'a> {
             ModuleInstanceDef::Instantiated(instance, module) => {
                 let item = match frame.modules[*module] {
                     ModuleDef::Static(idx) => {
-                        let entity = self.nested_modules[idx].module.exports[name];
-                        ExportItem::Index(entity)
+                        let Some(entity) = self.nested_modules[idx].module.exports.get(name) else {
+                            bail!("the core module instance has no export named `{name}`")
+                        };
+                        ExportItem::Index(*entity)
                     }
                     ModuleDef::Import(..) => ExportItem::Name(name.to_string()),
                 };
-                dfg::CoreExport {
+                Ok(dfg::CoreExport {
                     instance: *instance,
                     item,
                 }
-                .into()
+                .into())
             }
 
             // This is a synthetic instance so the canonical definition of the
             // original item is returned.
-            ModuleInstanceDef::Synthetic(instance) => match instance[name] {
-                EntityIndex::Function(i) => frame.funcs[i].clone(),
-                EntityIndex::Table(i) => frame.tables[i].clone().into(),
-                EntityIndex::Global(i) => frame.globals[i].clone().into(),
-                EntityIndex::Memory(i) => frame.memories[i].clone().into(),
-            },
+            ModuleInstanceDef::Synthetic(instance) => Ok(match instance.get(name) {
+                Some(EntityIndex::Function(i)) => frame.funcs[*i].clone(),
+                Some(EntityIndex::Table(i)) => frame.tables[*i].clone().into(),
+                Some(EntityIndex::Global(i)) => frame.globals[*i].clone().into(),
+                Some(EntityIndex::Memory(i)) => frame.memories[*i].clone().into(),
+                None => bail!("the core module instance has no export named `{name}`"),
+            }),
         }
     }
 
@@ -989,7 +1071,7 @@ impl<'a> Inliner<'a> {
         &mut self,
         name: &str,
         def: ComponentItemDef<'a>,
-        types: &'a ComponentTypesBuilder,
+        types: &mut ComponentTypesBuilder,
         map: &mut IndexMap<String, dfg::Export>,
     ) -> Result<()> {
         let export = match def {
@@ -1011,12 +1093,48 @@ impl<'a> Inliner<'a> {
                     dfg::Export::LiftedFunction { ty, func, options }
                 }
 
-                // Currently reexported functions from an import are not
-                // supported. Being able to actually call these functions is
-                // somewhat tricky and needs something like temporary scratch
-                // space that isn't implemented.
-                ComponentFuncDef::Import(_) => {
-                    bail!("component export `{name}` is a reexport of an imported function which is not implemented")
+                // A reexported import is lowered, as if by `canon lower`,
+                // and the resulting trampoline is lifted again as the export,
+                // so that calling the export forwards to the import.
+                //
+                // The forwarding uses the default canonical options of the
+                // root component instance: UTF-8 strings, and neither memory,
+                // realloc, nor post-return. Arguments and results are
+                // therefore passed as flat core values, and left untouched
+                // by the round trip through the core ABI. Strings, lists,
+                // and types which do not fit in the flat representation would
+                // need scratch space in a linear memory to be passed through,
+                // which the component does not give for the reexport, so
+                // those are not supported.
+                ComponentFuncDef::Import(path, ty) => {
+                    let Some(canonical_abi) = types.lowered_func_signature(ty) else {
+                        bail!(
+                            "component export `{name}` is a reexport of an imported function \
+                             whose parameters or results are passed through linear memory, \
+                             which is not supported"
+                        )
+                    };
+                    let import = self.runtime_import(&path);
+                    let options = dfg::CanonicalOptions {
+                        instance: RuntimeComponentInstanceIndex::from_u32(0),
+                        string_encoding: StringEncoding::Utf8,
+                        memory: None,
+                        realloc: None,
+                        post_return: None,
+                    };
+                    let index = self.result.trampolines.push((
+                        canonical_abi,
+                        dfg::Trampoline::LowerImport {
+                            import,
+                            options: options.clone(),
+                            lower_ty: ty,
+                        },
+                    ));
+                    dfg::Export::LiftedFunction {
+                        ty,
+                        func: dfg::CoreDef::Trampoline(index),
+                        options,
+                    }
                 }
             },
 
@@ -1031,10 +1149,10 @@ impl<'a> Inliner<'a> {
                     // Note that for now this would only work with
                     // module-exporting instances.
                     ComponentInstanceDef::Import(path, ty) => {
-                        for (name, ty) in types[ty].exports.iter() {
-                            let path = path.push(name);
-                            let def = ComponentItemDef::from_import(path, *ty)?;
-                            self.record_export(name, def, types, &mut result)?;
+                        for (name, ty) in types[ty].exports.clone() {
+                            let path = path.push(name.clone());
+                            let def = ComponentItemDef::from_import(path, ty)?;
+                            self.record_export(&name, def, types, &mut result)?;
                         }
                     }
 
@@ -1226,7 +1344,9 @@ impl<'a> ComponentItemDef<'a> {
             TypeDef::ComponentInstance(ty) => {
                 ComponentItemDef::Instance(ComponentInstanceDef::Import(path, ty))
             }
-            TypeDef::ComponentFunc(_ty) => ComponentItemDef::Func(ComponentFuncDef::Import(path)),
+            TypeDef::ComponentFunc(ty) => {
+                ComponentItemDef::Func(ComponentFuncDef::Import(path, ty))
+            }
             TypeDef::Component(_ty) => bail!("root-level component imports are not supported"),
             TypeDef::Interface(_) | TypeDef::Resource(_) => ComponentItemDef::Type(ty),
         };
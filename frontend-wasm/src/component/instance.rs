@@ -2,13 +2,20 @@ use miden_hir::cranelift_entity::PrimaryMap;
 use rustc_hash::FxHashMap;
 
 use crate::{
-    component::Trampoline, error::WasmResult, module::module_env::ParsedModule, WasmError,
+    component::Trampoline,
+    error::WasmResult,
+    module::{
+        module_env::ParsedModule,
+        types::{EntityIndex, MemoryIndex},
+    },
+    WasmError,
 };
 
 use super::{
-    ComponentTypes, CoreDef, GlobalInitializer, InstantiateModule, LinearComponent,
-    LinearComponentTranslation, LoweredIndex, RuntimeImportIndex, RuntimeInstanceIndex,
-    StaticModuleIndex, TypeFuncIndex,
+    CanonicalOptions, ComponentTypes, CoreDef, CoreExport, GlobalInitializer, InstantiateModule,
+    LinearComponent, LinearComponentTranslation, LoweredIndex, RuntimeImportIndex,
+    RuntimeInstanceIndex, RuntimeMemoryIndex, RuntimePostReturnIndex, RuntimeReallocIndex,
+    StaticModuleIndex, TrampolineIndex, TypeFuncIndex,
 };
 
 /// A component import
@@ -18,12 +25,52 @@ pub struct ComponentImport {
     pub signature: TypeFuncIndex,
 }
 
+/// The definition the inliner resolved an import of a core module instance to
+#[derive(Debug)]
+pub enum ModuleArgument {
+    /// A lowered component import, e.g. a tx kernel function provided by the host
+    ComponentImport(ComponentImport),
+    /// An item exported by another core module instance of the component
+    CoreExport(CoreExport<EntityIndex>),
+    /// A function lifted in another component instance, which is called through the adapter
+    /// synthesized for the `FusedAdapter` trampoline, see [ComponentInstance::adapters]
+    Adapter(TrampolineIndex),
+}
+
+/// A function lifted in one component instance and lowered in another, which is called through
+/// an adapter between the two, e.g. to copy, and possibly transcode, the strings it is passed
+#[derive(Debug)]
+pub struct FusedAdapter {
+    /// The type of the function as perceived by the lifting component
+    pub lift_ty: TypeFuncIndex,
+    /// The canonical ABI options of the lifted function
+    pub lift_options: CanonicalOptions,
+    /// The type of the function as perceived by the lowering component
+    pub lower_ty: TypeFuncIndex,
+    /// The canonical ABI options of the lowering
+    pub lower_options: CanonicalOptions,
+    /// The core function which was lifted
+    pub callee: CoreDef,
+}
+
 pub struct ComponentInstance<'data> {
     pub modules: PrimaryMap<StaticModuleIndex, ParsedModule<'data>>,
     pub module_instances: PrimaryMap<RuntimeInstanceIndex, StaticModuleIndex>,
     pub component: LinearComponent,
     pub component_types: ComponentTypes,
-    pub imports: FxHashMap<StaticModuleIndex, Vec<ComponentImport>>,
+    /// The definitions given for the imports of each core module, in the order of its imports
+    pub imports: FxHashMap<StaticModuleIndex, Vec<ModuleArgument>>,
+    /// The memories referenced by the `memory` canonical option
+    pub memories: PrimaryMap<RuntimeMemoryIndex, CoreExport<MemoryIndex>>,
+    /// The functions referenced by the `realloc` canonical option
+    pub reallocs: PrimaryMap<RuntimeReallocIndex, CoreDef>,
+    /// The functions referenced by the `post-return` canonical option of lifted exports
+    pub post_returns: PrimaryMap<RuntimePostReturnIndex, CoreDef>,
+    /// The component import lowered by each `LowerImport` trampoline, e.g. to be exported again
+    pub lowered_imports: FxHashMap<TrampolineIndex, ComponentImport>,
+    /// The lifted function called by each `FusedAdapter` trampoline which is resolved to an
+    /// adapter, see [ModuleArgument::Adapter]
+    pub adapters: FxHashMap<TrampolineIndex, FusedAdapter>,
 }
 
 impl<'data> ComponentInstance<'data> {
@@ -63,7 +110,11 @@ impl<'data> ComponentInstanceBuilder<'data> {
         let mut module_instances: PrimaryMap<RuntimeInstanceIndex, StaticModuleIndex> =
             PrimaryMap::new();
         let mut lower_imports: FxHashMap<LoweredIndex, RuntimeImportIndex> = FxHashMap::default();
-        let mut imports: FxHashMap<StaticModuleIndex, Vec<ComponentImport>> = FxHashMap::default();
+        let mut imports: FxHashMap<StaticModuleIndex, Vec<ModuleArgument>> = FxHashMap::default();
+        let mut memories: PrimaryMap<RuntimeMemoryIndex, CoreExport<MemoryIndex>> =
+            PrimaryMap::new();
+        let mut reallocs: PrimaryMap<RuntimeReallocIndex, CoreDef> = PrimaryMap::new();
+        let mut post_returns: PrimaryMap<RuntimePostReturnIndex, CoreDef> = PrimaryMap::new();
         let component = &self.linear_component_translation.component;
         for initializer in &component.initializers {
             match initializer {
@@ -82,33 +133,10 @@ impl<'data> ComponentInstanceBuilder<'data> {
                             }
 
                             module_instances.push(*static_module_idx);
-                            let mut module_args: Vec<ComponentImport> = Vec::new();
-                            for arg in args.iter() {
-                                match arg {
-                                    CoreDef::Export(_) => todo!(),
-                                    CoreDef::InstanceFlags(_) => todo!(),
-                                    CoreDef::Trampoline(trampoline_idx) => {
-                                        let trampoline = &self
-                                            .linear_component_translation
-                                            .trampolines[*trampoline_idx];
-                                        match trampoline {
-                                            Trampoline::LowerImport {
-                                                index,
-                                                lower_ty,
-                                                options: _,
-                                            } => {
-                                                let import = lower_imports[index];
-                                                let import = ComponentImport {
-                                                    runtime_import_index: import,
-                                                    signature: *lower_ty,
-                                                };
-                                                module_args.push(import);
-                                            }
-                                            _ => unreachable!(),
-                                        }
-                                    }
-                                }
-                            }
+                            let module_args = args
+                                .iter()
+                                .map(|arg| self.module_argument(arg, &lower_imports))
+                                .collect::<WasmResult<Vec<_>>>()?;
                             imports.insert(*static_module_idx, module_args);
                         }
                         InstantiateModule::Import(_, _) => todo!(),
@@ -120,18 +148,126 @@ impl<'data> ComponentInstanceBuilder<'data> {
                 } => {
                     lower_imports.insert(*init_lowered_idx, *import);
                 }
-                GlobalInitializer::ExtractMemory(_) => todo!(),
-                GlobalInitializer::ExtractRealloc(_) => todo!(),
-                GlobalInitializer::ExtractPostReturn(_) => todo!(),
+                GlobalInitializer::ExtractMemory(memory) => {
+                    let index = memories.push(memory.export.clone());
+                    debug_assert_eq!(index, memory.index);
+                }
+                GlobalInitializer::ExtractRealloc(realloc) => {
+                    let index = reallocs.push(realloc.def.clone());
+                    debug_assert_eq!(index, realloc.index);
+                }
+                GlobalInitializer::ExtractPostReturn(post_return) => {
+                    let index = post_returns.push(post_return.def.clone());
+                    debug_assert_eq!(index, post_return.index);
+                }
                 GlobalInitializer::Resource(_) => todo!(),
             }
         }
+        let lowered_imports = self
+            .linear_component_translation
+            .trampolines
+            .iter()
+            .filter_map(|(trampoline_idx, trampoline)| match trampoline {
+                Trampoline::LowerImport {
+                    index, lower_ty, ..
+                } => Some((
+                    trampoline_idx,
+                    ComponentImport {
+                        runtime_import_index: lower_imports[index],
+                        signature: *lower_ty,
+                    },
+                )),
+                _ => None,
+            })
+            .collect();
+        let adapters = self
+            .linear_component_translation
+            .trampolines
+            .iter()
+            .filter_map(|(trampoline_idx, trampoline)| match trampoline {
+                Trampoline::FusedAdapter {
+                    lift_ty,
+                    lift_options,
+                    lower_ty,
+                    lower_options,
+                    callee,
+                    ..
+                } if needs_adapter(lift_options, lower_options) => Some((
+                    trampoline_idx,
+                    FusedAdapter {
+                        lift_ty: *lift_ty,
+                        lift_options: lift_options.clone(),
+                        lower_ty: *lower_ty,
+                        lower_options: lower_options.clone(),
+                        callee: callee.clone(),
+                    },
+                )),
+                _ => None,
+            })
+            .collect();
         Ok(ComponentInstance {
             modules: self.modules,
             module_instances,
             component: self.linear_component_translation.component,
             component_types: self.component_types,
             imports,
+            memories,
+            reallocs,
+            post_returns,
+            lowered_imports,
+            adapters,
         })
     }
+
+    /// Resolves the definition given for an import of a core module instance
+    fn module_argument(
+        &self,
+        def: &CoreDef,
+        lower_imports: &FxHashMap<LoweredIndex, RuntimeImportIndex>,
+    ) -> WasmResult<ModuleArgument> {
+        match def {
+            CoreDef::Export(export) => Ok(ModuleArgument::CoreExport(export.clone())),
+            CoreDef::InstanceFlags(_) => todo!(),
+            CoreDef::Trampoline(trampoline_idx) => {
+                match &self.linear_component_translation.trampolines[*trampoline_idx] {
+                    Trampoline::LowerImport {
+                        index,
+                        lower_ty,
+                        options: _,
+                    } => Ok(ModuleArgument::ComponentImport(ComponentImport {
+                        runtime_import_index: lower_imports[index],
+                        signature: *lower_ty,
+                    })),
+                    // Strings, and anything else passed through linear memory, are copied into
+                    // the memory of the callee, and transcoded if need be, by an adapter, which
+                    // also calls the `post-return` function of the callee
+                    Trampoline::FusedAdapter {
+                        lift_options,
+                        lower_options,
+                        ..
+                    } if needs_adapter(lift_options, lower_options) => {
+                        Ok(ModuleArgument::Adapter(*trampoline_idx))
+                    }
+                    // Without a memory the flattened arguments and results are passed through
+                    // unchanged, so the lifted function is called directly
+                    Trampoline::FusedAdapter { callee, .. } => {
+                        self.module_argument(callee, lower_imports)
+                    }
+                    other => Err(WasmError::Unsupported(format!(
+                        "core module imports resolved to the {other:?} trampoline are not supported"
+                    ))),
+                }
+            }
+        }
+    }
+}
+
+/// Returns whether a function lifted with `lift_options` and lowered with `lower_options` has to
+/// be called through an adapter, i.e. if it may pass its arguments or results through linear
+/// memory, which are then copied by the adapter, or if it has a `post-return` function, which the
+/// adapter calls once the results are copied
+fn needs_adapter(lift_options: &CanonicalOptions, lower_options: &CanonicalOptions) -> bool {
+    lift_options.memory.is_some()
+        || lower_options.memory.is_some()
+        || lift_options.post_return.is_some()
 }
@@ -3,14 +3,17 @@
 //! This module contains all of the internal type definitions to parse and
 //! translate the component model.
 
+mod adapter;
 pub mod build_ir;
 mod dfg;
 pub mod info;
 mod inline;
 mod instance;
 mod parser;
+mod transcode;
 mod types;
 
 pub use self::info::*;
 pub use self::parser::*;
+pub use self::transcode::*;
 pub use self::types::*;
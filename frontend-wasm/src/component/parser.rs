@@ -10,7 +10,9 @@ use crate::module::types::{
     TableIndex, WasmType,
 };
 use crate::translation_utils::BuildFxHasher;
-use crate::{component::*, unsupported_diag, WasmError, WasmTranslationConfig};
+use crate::{
+    component::*, unsupported_diag, UnsupportedFeature, WasmError, WasmTranslationConfig,
+};
 use indexmap::IndexMap;
 use miden_diagnostics::DiagnosticsHandler;
 use miden_hir::cranelift_entity::PrimaryMap;
@@ -381,7 +383,11 @@ impl<'a, 'data> ComponentParser<'a, 'data> {
             Payload::ComponentExportSection(s) => self.component_export_section(s)?,
             Payload::ComponentStartSection { start, range } => {
                 self.validator.component_start_section(&start, &range)?;
-                unsupported_diag!(diagnostics, "component start section is not supported");
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::ComponentStart,
+                    "component start section is not supported"
+                );
             }
             Payload::ComponentAliasSection(s) => self.component_alias_section(s)?,
             // All custom sections are ignored at this time.
@@ -394,7 +400,11 @@ impl<'a, 'data> ComponentParser<'a, 'data> {
             // debug.
             other => {
                 self.validator.payload(&other)?;
-                unsupported_diag!(diagnostics, "unsupported section {other:?}");
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::Section,
+                    "unsupported section {other:?}"
+                );
             }
         }
 
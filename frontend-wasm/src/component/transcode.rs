@@ -0,0 +1,411 @@
+//! Transcoding of strings between the encodings of the canonical ABI.
+//!
+//! When a string is passed between two component instances which disagree on
+//! the `string-encoding` canonical option, the adapter between them has to
+//! transcode it while copying it from the memory of one instance to the memory
+//! of the other. The transcoding itself is described by a [Transcode], which
+//! is referenced by a `Trampoline::Transcoder`, and is emitted inline in the
+//! code of the adapter, see [Transcode::build].
+
+use anyhow::{bail, Result};
+use miden_diagnostics::SourceSpan;
+use miden_hir::{Block, Immediate, InstBuilder, ModuleFunctionBuilder, Type, Value};
+
+use super::StringEncoding;
+use crate::module::types::{WasmFuncType, WasmType};
+
+/// A transcoding of strings from one encoding to another.
+///
+/// UTF-16 strings are sequences of little-endian code units, as laid out in
+/// linear memory by the canonical ABI.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Transcode {
+    /// From UTF-8 to UTF-16
+    Utf8ToUtf16,
+    /// From UTF-16 to UTF-8
+    Utf16ToUtf8,
+}
+
+impl Transcode {
+    /// Returns the transcoding required to pass a string encoded with `from`
+    /// to a component expecting strings encoded with `to`, or `None` if the
+    /// string can be copied as is.
+    pub fn between(from: StringEncoding, to: StringEncoding) -> Result<Option<Self>> {
+        match (from, to) {
+            _ if from == to => Ok(None),
+            (StringEncoding::Utf8, StringEncoding::Utf16) => Ok(Some(Self::Utf8ToUtf16)),
+            (StringEncoding::Utf16, StringEncoding::Utf8) => Ok(Some(Self::Utf16ToUtf8)),
+            _ => bail!("transcoding strings from {from:?} to {to:?} is not supported"),
+        }
+    }
+
+    /// The core wasm signature of the transcoder trampoline.
+    ///
+    /// It takes the address and the length in bytes of the string in the
+    /// source memory, and the address and the capacity in bytes of the buffer
+    /// in the destination memory, and returns the number of bytes written.
+    pub fn signature(&self) -> WasmFuncType {
+        WasmFuncType::new([WasmType::I32; 4].into(), [WasmType::I32].into())
+    }
+
+    /// Emits code computing the size in bytes of the buffer needed to hold the transcoding of a
+    /// string of `len` code units, in the worst case, i.e. three bytes for each UTF-16 code unit,
+    /// or two bytes for each byte of UTF-8.
+    ///
+    /// Traps if the size does not fit in 32 bits.
+    pub fn build_worst_case_size(
+        &self,
+        fb: &mut ModuleFunctionBuilder<'_>,
+        len: Value,
+        span: SourceSpan,
+    ) -> Value {
+        let factor = match self {
+            Self::Utf8ToUtf16 => 2,
+            Self::Utf16ToUtf8 => 3,
+        };
+        fb.ins().mul_imm_checked(len, Immediate::U32(factor), span)
+    }
+
+    /// Emits code transcoding the string of `len` code units at the address `src`, into the
+    /// buffer at the address `dst`, which must be at least [Self::build_worst_case_size] bytes,
+    /// returning the length of the transcoded string in code units.
+    ///
+    /// All of the operands, and the result, are `u32`. The emitted code traps if the string is
+    /// not valid in its encoding, i.e. on an unpaired surrogate in UTF-16, or on a truncated
+    /// sequence, or a sequence with an invalid leading or continuation byte in UTF-8.
+    ///
+    /// NOTE: Overlong encodings, and encodings of surrogates, are not rejected in UTF-8 strings.
+    pub fn build(
+        &self,
+        fb: &mut ModuleFunctionBuilder<'_>,
+        src: Value,
+        len: Value,
+        dst: Value,
+        span: SourceSpan,
+    ) -> Value {
+        // The string is transcoded one code point at a time, by a loop over the index of the next
+        // code unit to read, and the index of the next code unit to write
+        let loop_header = fb.create_block();
+        let i = fb.append_block_param(loop_header, Type::U32, span);
+        let j = fb.append_block_param(loop_header, Type::U32, span);
+        let body = fb.create_block();
+        let exit = fb.create_block();
+        let transcoded_len = fb.append_block_param(exit, Type::U32, span);
+        let zero = fb.ins().u32(0, span);
+        fb.ins().br(loop_header, &[zero, zero], span);
+
+        fb.switch_to_block(loop_header);
+        let done = fb.ins().gte(i, len, span);
+        fb.ins().cond_br(done, exit, &[j], body, &[], span);
+
+        fb.switch_to_block(body);
+        match self {
+            Self::Utf8ToUtf16 => {
+                build_utf8_to_utf16_step(fb, src, len, dst, i, j, loop_header, span)
+            }
+            Self::Utf16ToUtf8 => {
+                build_utf16_to_utf8_step(fb, src, len, dst, i, j, loop_header, span)
+            }
+        }
+
+        fb.switch_to_block(exit);
+        transcoded_len
+    }
+}
+
+/// Emits the body of the loop transcoding a UTF-8 string to UTF-16, which decodes the code point
+/// starting at byte `i` of `src`, writes it at code unit `j` of `dst`, and continues the loop.
+#[allow(clippy::too_many_arguments)]
+fn build_utf8_to_utf16_step(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    src: Value,
+    len: Value,
+    dst: Value,
+    i: Value,
+    j: Value,
+    loop_header: Block,
+    span: SourceSpan,
+) {
+    let b0 = load_byte(fb, src, i, span);
+    // The leading byte determines the length of the sequence, which is 1 to 4 bytes
+    let mut next = fb.create_block();
+    let one_byte = fb.ins().lt_imm(b0, Immediate::U32(0x80), span);
+    let single = fb.create_block();
+    fb.ins().cond_br(one_byte, single, &[], next, &[], span);
+    fb.switch_to_block(single);
+    store_utf16_unit(fb, dst, j, b0, span);
+    continue_loop(fb, loop_header, i, 1, j, 1, span);
+
+    for (seq_len, lead_mask, lead_tag) in [(2, 0xe0, 0xc0), (3, 0xf0, 0xe0), (4, 0xf8, 0xf0)] {
+        fb.switch_to_block(next);
+        next = fb.create_block();
+        let lead = fb.ins().band_imm(b0, Immediate::U32(lead_mask), span);
+        let is_lead = fb.ins().eq_imm(lead, Immediate::U32(lead_tag), span);
+        let sequence = fb.create_block();
+        fb.ins().cond_br(is_lead, sequence, &[], next, &[], span);
+
+        fb.switch_to_block(sequence);
+        let end = fb.ins().add_imm_checked(i, Immediate::U32(seq_len), span);
+        let in_bounds = fb.ins().lte(end, len, span);
+        fb.ins().assert(in_bounds, span);
+        let mut code_point = fb
+            .ins()
+            .band_imm(b0, Immediate::U32(!lead_mask & 0xff), span);
+        for k in 1..seq_len {
+            let index = fb.ins().add_imm_checked(i, Immediate::U32(k), span);
+            let byte = load_byte(fb, src, index, span);
+            let tag = fb.ins().band_imm(byte, Immediate::U32(0xc0), span);
+            let is_continuation = fb.ins().eq_imm(tag, Immediate::U32(0x80), span);
+            fb.ins().assert(is_continuation, span);
+            let bits = fb.ins().band_imm(byte, Immediate::U32(0x3f), span);
+            let shifted = fb
+                .ins()
+                .shl_imm_wrapping(code_point, Immediate::U32(6), span);
+            code_point = fb.ins().bor(shifted, bits, span);
+        }
+        if seq_len < 4 {
+            store_utf16_unit(fb, dst, j, code_point, span);
+            continue_loop(fb, loop_header, i, seq_len, j, 1, span);
+        } else {
+            // Code points outside of the basic multilingual plane take a surrogate pair
+            let offset = fb
+                .ins()
+                .sub_imm_checked(code_point, Immediate::U32(0x10000), span);
+            let high = fb.ins().shr_imm_wrapping(offset, Immediate::U32(10), span);
+            let high = fb.ins().bor_imm(high, Immediate::U32(0xd800), span);
+            let low = fb.ins().band_imm(offset, Immediate::U32(0x3ff), span);
+            let low = fb.ins().bor_imm(low, Immediate::U32(0xdc00), span);
+            store_utf16_unit(fb, dst, j, high, span);
+            let j_low = fb.ins().add_imm_checked(j, Immediate::U32(1), span);
+            store_utf16_unit(fb, dst, j_low, low, span);
+            continue_loop(fb, loop_header, i, seq_len, j, 2, span);
+        }
+    }
+
+    // Anything else is a continuation byte, or a byte which never appears in UTF-8
+    fb.switch_to_block(next);
+    fb.ins().unreachable(span);
+}
+
+/// Emits the body of the loop transcoding a UTF-16 string to UTF-8, which decodes the code point
+/// starting at code unit `i` of `src`, writes it at byte `j` of `dst`, and continues the loop.
+#[allow(clippy::too_many_arguments)]
+fn build_utf16_to_utf8_step(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    src: Value,
+    len: Value,
+    dst: Value,
+    i: Value,
+    j: Value,
+    loop_header: Block,
+    span: SourceSpan,
+) {
+    let unit = load_utf16_unit(fb, src, i, span);
+
+    let single = fb.create_block();
+    let not_single = fb.create_block();
+    let is_single = fb.ins().lt_imm(unit, Immediate::U32(0x80), span);
+    fb.ins()
+        .cond_br(is_single, single, &[], not_single, &[], span);
+    fb.switch_to_block(single);
+    store_utf8_sequence(fb, dst, j, unit, 1, span);
+    continue_loop(fb, loop_header, i, 1, j, 1, span);
+
+    fb.switch_to_block(not_single);
+    let double = fb.create_block();
+    let not_double = fb.create_block();
+    let is_double = fb.ins().lt_imm(unit, Immediate::U32(0x800), span);
+    fb.ins()
+        .cond_br(is_double, double, &[], not_double, &[], span);
+    fb.switch_to_block(double);
+    store_utf8_sequence(fb, dst, j, unit, 2, span);
+    continue_loop(fb, loop_header, i, 1, j, 2, span);
+
+    fb.switch_to_block(not_double);
+    let surrogate = fb.ins().band_imm(unit, Immediate::U32(0xfc00), span);
+    let pair = fb.create_block();
+    let not_pair = fb.create_block();
+    let is_high_surrogate = fb.ins().eq_imm(surrogate, Immediate::U32(0xd800), span);
+    fb.ins()
+        .cond_br(is_high_surrogate, pair, &[], not_pair, &[], span);
+
+    // A high surrogate must be followed by a low surrogate
+    fb.switch_to_block(pair);
+    let i_low = fb.ins().add_imm_checked(i, Immediate::U32(1), span);
+    let in_bounds = fb.ins().lt(i_low, len, span);
+    fb.ins().assert(in_bounds, span);
+    let low = load_utf16_unit(fb, src, i_low, span);
+    let low_surrogate = fb.ins().band_imm(low, Immediate::U32(0xfc00), span);
+    let is_low_surrogate = fb.ins().eq_imm(low_surrogate, Immediate::U32(0xdc00), span);
+    fb.ins().assert(is_low_surrogate, span);
+    let high_bits = fb.ins().band_imm(unit, Immediate::U32(0x3ff), span);
+    let high_bits = fb
+        .ins()
+        .shl_imm_wrapping(high_bits, Immediate::U32(10), span);
+    let low_bits = fb.ins().band_imm(low, Immediate::U32(0x3ff), span);
+    let offset = fb.ins().bor(high_bits, low_bits, span);
+    let code_point = fb
+        .ins()
+        .add_imm_checked(offset, Immediate::U32(0x10000), span);
+    store_utf8_sequence(fb, dst, j, code_point, 4, span);
+    continue_loop(fb, loop_header, i, 2, j, 4, span);
+
+    // Any other code unit is a code point of its own, unless it is an unpaired low surrogate
+    fb.switch_to_block(not_pair);
+    let triple = fb.create_block();
+    let unpaired = fb.create_block();
+    let is_low_surrogate = fb.ins().eq_imm(surrogate, Immediate::U32(0xdc00), span);
+    fb.ins()
+        .cond_br(is_low_surrogate, unpaired, &[], triple, &[], span);
+    fb.switch_to_block(unpaired);
+    fb.ins().unreachable(span);
+    fb.switch_to_block(triple);
+    store_utf8_sequence(fb, dst, j, unit, 3, span);
+    continue_loop(fb, loop_header, i, 1, j, 3, span);
+}
+
+/// Emits a branch back to the loop header, having read `di` code units and written `dj`
+fn continue_loop(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    loop_header: Block,
+    i: Value,
+    di: u32,
+    j: Value,
+    dj: u32,
+    span: SourceSpan,
+) {
+    let i = fb.ins().add_imm_checked(i, Immediate::U32(di), span);
+    let j = fb.ins().add_imm_checked(j, Immediate::U32(dj), span);
+    fb.ins().br(loop_header, &[i, j], span);
+}
+
+/// Emits the encoding of `code_point` as a UTF-8 sequence of `seq_len` bytes, at byte `j` of `dst`
+fn store_utf8_sequence(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    dst: Value,
+    j: Value,
+    code_point: Value,
+    seq_len: u32,
+    span: SourceSpan,
+) {
+    // The tag of the leading byte, and the mask of the bits of the code point it holds
+    let (lead_tag, lead_mask) = match seq_len {
+        1 => (0x00, 0x7f),
+        2 => (0xc0, 0x1f),
+        3 => (0xe0, 0x0f),
+        4 => (0xf0, 0x07),
+        _ => unreachable!("invalid UTF-8 sequence length {seq_len}"),
+    };
+    for k in 0..seq_len {
+        let shift = 6 * (seq_len - 1 - k);
+        let bits = if shift == 0 {
+            code_point
+        } else {
+            fb.ins()
+                .shr_imm_wrapping(code_point, Immediate::U32(shift), span)
+        };
+        let (tag, mask) = if k == 0 {
+            (lead_tag, lead_mask)
+        } else {
+            (0x80, 0x3f)
+        };
+        let bits = fb.ins().band_imm(bits, Immediate::U32(mask), span);
+        let byte = if tag == 0 {
+            bits
+        } else {
+            fb.ins().bor_imm(bits, Immediate::U32(tag), span)
+        };
+        let index = if k == 0 {
+            j
+        } else {
+            fb.ins().add_imm_checked(j, Immediate::U32(k), span)
+        };
+        store_byte(fb, dst, index, byte, span);
+    }
+}
+
+/// Emits a load of the UTF-16 code unit at index `i` of the string at `base`, as a `u32`
+///
+/// The code unit is read one byte at a time, as the canonical ABI lays it out in little-endian
+/// order, whatever the alignment of the string.
+fn load_utf16_unit(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    base: Value,
+    i: Value,
+    span: SourceSpan,
+) -> Value {
+    let offset = fb.ins().mul_imm_checked(i, Immediate::U32(2), span);
+    let lo = load_byte(fb, base, offset, span);
+    let offset = fb.ins().add_imm_checked(offset, Immediate::U32(1), span);
+    let hi = load_byte(fb, base, offset, span);
+    let hi = fb.ins().shl_imm_wrapping(hi, Immediate::U32(8), span);
+    fb.ins().bor(hi, lo, span)
+}
+
+/// Emits a store of the UTF-16 code unit `unit`, a `u32`, at index `j` of the string at `base`
+fn store_utf16_unit(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    base: Value,
+    j: Value,
+    unit: Value,
+    span: SourceSpan,
+) {
+    let offset = fb.ins().mul_imm_checked(j, Immediate::U32(2), span);
+    let lo = fb.ins().band_imm(unit, Immediate::U32(0xff), span);
+    store_byte(fb, base, offset, lo, span);
+    let offset = fb.ins().add_imm_checked(offset, Immediate::U32(1), span);
+    let hi = fb.ins().shr_imm_wrapping(unit, Immediate::U32(8), span);
+    store_byte(fb, base, offset, hi, span);
+}
+
+/// Emits a load of the byte at `base + offset`, zero-extended to a `u32`
+pub(super) fn load_byte(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    base: Value,
+    offset: Value,
+    span: SourceSpan,
+) -> Value {
+    let addr = fb.ins().add_checked(base, offset, span);
+    let ptr = fb.ins().inttoptr(addr, Type::Ptr(Box::new(Type::U8)), span);
+    let byte = fb.ins().load(ptr, span);
+    fb.ins().zext(byte, Type::U32, span)
+}
+
+/// Emits a store of the low byte of `value`, a `u32`, at `base + offset`
+pub(super) fn store_byte(
+    fb: &mut ModuleFunctionBuilder<'_>,
+    base: Value,
+    offset: Value,
+    value: Value,
+    span: SourceSpan,
+) {
+    let addr = fb.ins().add_checked(base, offset, span);
+    let ptr = fb.ins().inttoptr(addr, Type::Ptr(Box::new(Type::U8)), span);
+    let byte = fb.ins().trunc(value, Type::U8, span);
+    fb.ins().store(ptr, byte, span);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcode_between_encodings() {
+        use StringEncoding::*;
+        assert_eq!(Transcode::between(Utf8, Utf8).unwrap(), None);
+        assert_eq!(Transcode::between(Utf16, Utf16).unwrap(), None);
+        assert_eq!(
+            Transcode::between(Utf8, Utf16).unwrap(),
+            Some(Transcode::Utf8ToUtf16)
+        );
+        assert_eq!(
+            Transcode::between(Utf16, Utf8).unwrap(),
+            Some(Transcode::Utf16ToUtf8)
+        );
+        let err = Transcode::between(CompactUtf16, Utf8).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "transcoding strings from CompactUtf16 to Utf8 is not supported"
+        );
+    }
+}
@@ -18,7 +18,7 @@ use crate::{
     indices,
     module::types::{
         convert_func_type, convert_global_type, convert_table_type, EntityType, ModuleTypes,
-        ModuleTypesBuilder,
+        ModuleTypesBuilder, WasmFuncType, WasmType,
     },
     translation_utils::{DiscriminantSize, FlagsSize},
 };
@@ -849,6 +849,70 @@ impl ComponentTypesBuilder {
         self.type_information(ty).flat.as_flat_types()
     }
 
+    /// Returns the signature of the core function which a component function
+    /// of type `ty` is lowered to by the canonical ABI.
+    ///
+    /// Returns `None` if lowering such a function requires a linear memory,
+    /// i.e. if its parameters or results contain strings or lists, or exceed
+    /// `MAX_FLAT_PARAMS` or `MAX_FLAT_RESULTS` flat types respectively, in
+    /// which case the canonical ABI passes them through linear memory.
+    pub fn lowered_func_signature(&mut self, ty: TypeFuncIndex) -> Option<SignatureIndex> {
+        let func = &self.component_types[ty];
+        let params = self.flat_tuple(func.params, MAX_FLAT_PARAMS)?;
+        let results = self.flat_tuple(func.results, MAX_FLAT_RESULTS)?;
+        let sig = WasmFuncType::new(params.into(), results.into());
+        Some(self.module_types.intern_func_type(sig))
+    }
+
+    /// Returns the core types the elements of `tuple` are flattened to, if
+    /// there are at most `max` of them, and none refers to linear memory.
+    fn flat_tuple(&self, tuple: TypeTupleIndex, max: usize) -> Option<Vec<WasmType>> {
+        let mut flat = Vec::new();
+        for ty in self.component_types[tuple].types.iter() {
+            if self.refers_to_memory(ty) {
+                return None;
+            }
+            flat.extend(self.flat_types(ty)?.memory32.iter().map(|ty| match ty {
+                FlatType::I32 => WasmType::I32,
+                FlatType::I64 => WasmType::I64,
+                FlatType::F32 => WasmType::F32,
+                FlatType::F64 => WasmType::F64,
+            }));
+        }
+        (flat.len() <= max).then_some(flat)
+    }
+
+    /// Returns whether values of type `ty` refer to data in linear memory,
+    /// i.e. whether it contains strings or lists.
+    fn refers_to_memory(&self, ty: &InterfaceType) -> bool {
+        let types = &self.component_types;
+        match ty {
+            InterfaceType::String | InterfaceType::List(_) => true,
+            InterfaceType::Record(idx) => types[*idx]
+                .fields
+                .iter()
+                .any(|field| self.refers_to_memory(&field.ty)),
+            InterfaceType::Variant(idx) => types[*idx]
+                .cases
+                .iter()
+                .filter_map(|case| case.ty.as_ref())
+                .any(|ty| self.refers_to_memory(ty)),
+            InterfaceType::Tuple(idx) => {
+                types[*idx].types.iter().any(|ty| self.refers_to_memory(ty))
+            }
+            InterfaceType::Option(idx) => self.refers_to_memory(&types[*idx].ty),
+            InterfaceType::Result(idx) => {
+                let result = &types[*idx];
+                result
+                    .ok
+                    .iter()
+                    .chain(result.err.iter())
+                    .any(|ty| self.refers_to_memory(ty))
+            }
+            _ => false,
+        }
+    }
+
     /// Returns whether the type specified contains any borrowed resources
     /// within it.
     pub fn ty_contains_borrow_resource(&self, ty: &InterfaceType) -> bool {
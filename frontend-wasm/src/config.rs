@@ -1,6 +1,11 @@
+use std::sync::Arc;
+
 use miden_core::crypto::hash::RpoDigest;
+use miden_diagnostics::CodeMap;
 use miden_hir::{FunctionExportName, FunctionInvocationMethod, InterfaceFunctionIdent};
+use midenc_session::{Endianness, InitTrapPolicy};
 use rustc_hash::FxHashMap;
+use wasmparser::WasmFeatures;
 
 /// Represents Miden VM codegen metadata for a function import.
 /// This struct will have more fields in the future e.g. where the function
@@ -21,25 +26,142 @@ pub struct ExportMetadata {
 }
 
 /// Configuration for the WASM translation.
+///
+/// Use [WasmTranslationConfig::builder] to construct a configuration other than the default one.
 #[derive(Debug)]
 pub struct WasmTranslationConfig {
     /// The source file name.
     /// This is used as a fallback for module/component name if it's not parsed from the Wasm binary.
-    pub source_name: String,
+    pub(crate) source_name: String,
 
     /// Whether or not to generate native DWARF debug information.
-    pub generate_native_debuginfo: bool,
+    ///
+    /// When set, along with [WasmTranslationConfig::codemap], the instructions translated from
+    /// each Wasm operator are given the span of the source location the line programs of the
+    /// module give for it.
+    pub(crate) generate_native_debuginfo: bool,
+
+    /// The code map into which the source files described by the DWARF line programs of the
+    /// module are loaded, so that translated instructions can be given spans in them. Without it,
+    /// no spans are given, even if [WasmTranslationConfig::generate_native_debuginfo] is set.
+    pub(crate) codemap: Option<Arc<CodeMap>>,
 
     /// Whether or not to retain DWARF sections in compiled modules.
-    pub parse_wasm_debuginfo: bool,
+    ///
+    /// When set, each translated function is also annotated with its Wasm function index, the
+    /// offset of its body in the code section, and, if the line programs of the module describe
+    /// it, the source file and line it was compiled from.
+    pub(crate) parse_wasm_debuginfo: bool,
 
     /// Import metadata for MAST hashes, calling convention, of
     /// each imported function. Having it here might be a temporary solution,
     /// later we might want to move it to Wasm custom section.
-    pub import_metadata: FxHashMap<InterfaceFunctionIdent, ImportMetadata>,
+    pub(crate) import_metadata: FxHashMap<InterfaceFunctionIdent, ImportMetadata>,
 
     /// Export metadata for calling convention, etc.
-    pub export_metadata: FxHashMap<FunctionExportName, ExportMetadata>,
+    pub(crate) export_metadata: FxHashMap<FunctionExportName, ExportMetadata>,
+
+    /// The metadata of the exports of a component which are not given any in
+    /// [WasmTranslationConfig::export_metadata], or `None` if every export must be given some.
+    pub(crate) default_export_metadata: Option<ExportMetadata>,
+
+    /// Whether or not to lower `i32`/`i64` arithmetic as field element arithmetic when the
+    /// operands are provably small enough that the result cannot wrap, e.g. a loop counter
+    /// guarded by a comparison against a constant. Falls back to faithful 32/64-bit emulation
+    /// whenever the range of an operand is unknown.
+    pub(crate) felt_bounded_integers: bool,
+
+    /// Whether or not to lower atomic operators as if the program is single-threaded, rather than
+    /// rejecting them as unsupported, i.e. `memory.atomic.notify` wakes no waiters,
+    /// `memory.atomic.wait32/64` returns immediately on a shared memory, and traps on any other,
+    /// atomic loads and stores are plain ones, and read-modify-writes are a plain load followed by
+    /// a plain store. A warning is emitted for each such operator.
+    pub(crate) single_threaded_atomics: bool,
+
+    /// The maximum number of Wasm instructions a single function may contain, or `None` for no
+    /// limit. Functions exceeding this limit are rejected with a diagnostic, rather than risking
+    /// unbounded translation and codegen time on pathological (e.g. adversarial or generated)
+    /// inputs.
+    pub(crate) max_function_size: Option<usize>,
+
+    /// The maximum initial or maximum size, in 64 KiB pages, of a memory declared by the module.
+    /// Larger memories are rejected with a diagnostic where they are declared. Defaults to the
+    /// 65536 pages, i.e. 4 GiB, addressable by a 32-bit memory.
+    pub(crate) max_memory_pages: u64,
+
+    /// The maximum initial or maximum size, in elements, of a table declared by the module.
+    /// Larger tables are rejected with a diagnostic where they are declared. Defaults to the
+    /// largest initial size accepted by the validator.
+    pub(crate) max_table_elements: u32,
+
+    /// Whether or not to annotate each translated function with the index of the Wasm function
+    /// it was translated from, and the index of its Wasm signature, as the `wasm_func_index` and
+    /// `wasm_signature_index` attributes respectively. This is useful for mapping HIR functions
+    /// back to the original Wasm module when debugging.
+    pub(crate) annotate_wasm_indices: bool,
+
+    /// The byte order in which multi-byte values are laid out in memory. Defaults to
+    /// little-endian, which is the byte order of WebAssembly, and the only one the code generator
+    /// implements, so translating a module with any other byte order is an error.
+    pub(crate) endianness: Endianness,
+
+    /// Whether or not active data segments which overlap in linear memory are rejected with an
+    /// error. By default, a warning is emitted for each overlap, and, as in Wasm, the contents of
+    /// the segment declared last take precedence over those of the segments it overlaps.
+    pub(crate) deny_overlapping_data_segments: bool,
+
+    /// Overrides of whether the active data segments whose names start with a given prefix are
+    /// read-only, e.g. `(".data.consts", true)`. The override with the longest matching prefix
+    /// wins. Segments matched by none are read-only if they are placed in one of the sections of
+    /// read-only data by the linker, i.e. `.rodata*` and `.data.rel.ro*`, and writable otherwise.
+    pub(crate) data_segment_readonly: Vec<(String, bool)>,
+
+    /// Whether or not to call the module's static constructors, i.e. `__wasm_call_ctors`, on
+    /// entry to each exported function, rather than relying on the runtime to call them before
+    /// any export. A guard global ensures the constructors run at most once.
+    pub(crate) call_ctors_in_exports: bool,
+
+    /// Whether or not to replace functions which use an unsupported feature with a stub which
+    /// traps when called, rather than failing translation. A warning is emitted for each stub, so
+    /// that the problem only manifests if such a function is actually called at runtime.
+    pub(crate) stub_unsupported_functions: bool,
+
+    /// How a trap raised by the static constructors of the module, i.e. `__wasm_call_ctors`, is
+    /// surfaced. By default, the trap propagates, aborting the program. Otherwise, an explicit
+    /// trap in the body of the constructors returns from them early, after setting the
+    /// `__wasm_init_trapped` global, which the embedder can inspect to report the failure.
+    pub(crate) init_trap_policy: InitTrapPolicy,
+
+    /// Whether or not to trap on a load or store of an address which is not a multiple of the size
+    /// of the accessed type, regardless of its alignment hint. By default, such accesses are
+    /// emulated using naturally-aligned accesses instead.
+    pub(crate) trap_on_unaligned_access: bool,
+
+    /// Whether or not integer division and remainder by zero produce zero, rather than trapping
+    /// as in Wasm. This is meant for code which checks its divisors before dividing, and would
+    /// rather not pay for, or risk, a trap.
+    pub(crate) wrapping_division: bool,
+
+    /// Whether or not to validate the input against only the Wasm proposals which the backend
+    /// implements, see [crate::strict_wasm_features], so that a module using e.g. SIMD is rejected
+    /// by the validator up front, rather than accepted, only to fail, or be mishandled, later on.
+    pub(crate) strict_validation: bool,
+
+    /// The Wasm proposals accepted by the validator, overriding the default set, as well as
+    /// [WasmTranslationConfig::strict_validation], e.g. to reject a proposal which is accepted
+    /// by default, or to accept one which is not.
+    ///
+    /// Accepting a proposal the translator does not implement only defers the rejection of a
+    /// module using it to the translation of the first operator it does not support.
+    pub(crate) features: Option<WasmFeatures>,
+
+    /// Whether or not to give internal linkage to the functions which do not escape the module,
+    /// i.e. which are not exported, referenced by `ref.func`, placed in a table, or the start
+    /// function of the module. By default, every function has external linkage.
+    ///
+    /// As internal functions can only be called from within the module, this allows those which
+    /// are never called to be removed, see `EliminateDeadFunctions` in `miden-hir-transform`.
+    pub(crate) internalize_non_escaping_functions: bool,
 }
 
 impl Default for WasmTranslationConfig {
@@ -47,9 +169,227 @@ impl Default for WasmTranslationConfig {
         Self {
             source_name: "noname".to_string(),
             generate_native_debuginfo: false,
+            codemap: None,
             parse_wasm_debuginfo: false,
             import_metadata: Default::default(),
             export_metadata: Default::default(),
+            default_export_metadata: None,
+            felt_bounded_integers: false,
+            single_threaded_atomics: false,
+            max_function_size: None,
+            max_memory_pages: 1 << 16,
+            max_table_elements: 10_000_000,
+            annotate_wasm_indices: false,
+            endianness: Endianness::default(),
+            deny_overlapping_data_segments: false,
+            data_segment_readonly: Vec::new(),
+            call_ctors_in_exports: false,
+            stub_unsupported_functions: false,
+            init_trap_policy: InitTrapPolicy::default(),
+            trap_on_unaligned_access: false,
+            wrapping_division: false,
+            strict_validation: false,
+            features: None,
+            internalize_non_escaping_functions: false,
         }
     }
 }
+
+impl WasmTranslationConfig {
+    /// Returns a builder for a configuration, starting from the default one
+    pub fn builder() -> WasmTranslationConfigBuilder {
+        WasmTranslationConfigBuilder::default()
+    }
+
+    /// The source file name, which is used as a fallback for the module/component name
+    pub fn source_name(&self) -> &str {
+        &self.source_name
+    }
+}
+
+/// A builder for a [WasmTranslationConfig], see [WasmTranslationConfig::builder]
+#[derive(Debug, Default)]
+pub struct WasmTranslationConfigBuilder {
+    config: WasmTranslationConfig,
+}
+
+impl WasmTranslationConfigBuilder {
+    /// Sets the source file name, which is used as a fallback for the module/component name if it
+    /// is not parsed from the Wasm binary. Defaults to `noname`.
+    pub fn source_name(mut self, source_name: impl Into<String>) -> Self {
+        self.config.source_name = source_name.into();
+        self
+    }
+
+    /// Sets whether or not to give each translated instruction the span of the source location
+    /// the DWARF line programs of the module give for it, see [Self::codemap].
+    pub fn generate_native_debuginfo(mut self, yes: bool) -> Self {
+        self.config.generate_native_debuginfo = yes;
+        self
+    }
+
+    /// Sets the code map into which the source files described by the DWARF line programs of
+    /// the module are loaded.
+    pub fn codemap(mut self, codemap: Arc<CodeMap>) -> Self {
+        self.config.codemap = Some(codemap);
+        self
+    }
+
+    /// Sets whether or not to annotate each translated function with the location of its body in
+    /// the module, and the source location it was compiled from, if known.
+    pub fn parse_wasm_debuginfo(mut self, yes: bool) -> Self {
+        self.config.parse_wasm_debuginfo = yes;
+        self
+    }
+
+    /// Adds the metadata of the given imported interface functions
+    pub fn import_metadata(
+        mut self,
+        metadata: impl IntoIterator<Item = (InterfaceFunctionIdent, ImportMetadata)>,
+    ) -> Self {
+        self.config.import_metadata.extend(metadata);
+        self
+    }
+
+    /// Adds the metadata of the given exported functions
+    pub fn export_metadata(
+        mut self,
+        metadata: impl IntoIterator<Item = (FunctionExportName, ExportMetadata)>,
+    ) -> Self {
+        self.config.export_metadata.extend(metadata);
+        self
+    }
+
+    /// Sets the metadata of the exports of a component which are not given any with
+    /// [Self::export_metadata]. By default, every export must be given some.
+    pub fn default_export_metadata(mut self, metadata: ExportMetadata) -> Self {
+        self.config.default_export_metadata = Some(metadata);
+        self
+    }
+
+    /// Sets whether or not to lower integer arithmetic as field element arithmetic when the
+    /// operands are provably small enough that the result cannot wrap.
+    pub fn felt_bounded_integers(mut self, yes: bool) -> Self {
+        self.config.felt_bounded_integers = yes;
+        self
+    }
+
+    /// Sets whether or not to lower atomic operators as if the program is single-threaded,
+    /// rather than rejecting them as unsupported.
+    pub fn single_threaded_atomics(mut self, yes: bool) -> Self {
+        self.config.single_threaded_atomics = yes;
+        self
+    }
+
+    /// Sets the maximum number of Wasm instructions a single function may contain. By default,
+    /// there is no limit.
+    pub fn max_function_size(mut self, max: usize) -> Self {
+        self.config.max_function_size = Some(max);
+        self
+    }
+
+    /// Sets the maximum initial or maximum size, in 64 KiB pages, of a memory declared by the
+    /// module. Defaults to 65536 pages.
+    pub fn max_memory_pages(mut self, max: u64) -> Self {
+        self.config.max_memory_pages = max;
+        self
+    }
+
+    /// Sets the maximum initial or maximum size, in elements, of a table declared by the module.
+    /// Defaults to 10,000,000 elements.
+    pub fn max_table_elements(mut self, max: u32) -> Self {
+        self.config.max_table_elements = max;
+        self
+    }
+
+    /// Sets whether or not to annotate each translated function with the indices of the Wasm
+    /// function and signature it was translated from.
+    pub fn annotate_wasm_indices(mut self, yes: bool) -> Self {
+        self.config.annotate_wasm_indices = yes;
+        self
+    }
+
+    /// Sets the byte order in which multi-byte values are laid out in memory. Defaults to
+    /// little-endian, which is currently the only byte order that can be translated.
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.config.endianness = endianness;
+        self
+    }
+
+    /// Sets whether or not active data segments which overlap in linear memory are rejected,
+    /// rather than only warned about.
+    pub fn deny_overlapping_data_segments(mut self, yes: bool) -> Self {
+        self.config.deny_overlapping_data_segments = yes;
+        self
+    }
+
+    /// Sets whether or not the active data segments whose names start with `prefix` are
+    /// read-only, overriding the classification of their linker section. The override with the
+    /// longest matching prefix wins.
+    pub fn data_segment_readonly(mut self, prefix: impl Into<String>, readonly: bool) -> Self {
+        self.config
+            .data_segment_readonly
+            .push((prefix.into(), readonly));
+        self
+    }
+
+    /// Sets whether or not to call the static constructors of the module on entry to each
+    /// exported function.
+    pub fn call_ctors_in_exports(mut self, yes: bool) -> Self {
+        self.config.call_ctors_in_exports = yes;
+        self
+    }
+
+    /// Sets whether or not to replace functions which use an unsupported feature with a stub
+    /// which traps when called, rather than failing translation.
+    pub fn stub_unsupported_functions(mut self, yes: bool) -> Self {
+        self.config.stub_unsupported_functions = yes;
+        self
+    }
+
+    /// Sets how a trap raised by the static constructors of the module is surfaced
+    pub fn init_trap_policy(mut self, policy: InitTrapPolicy) -> Self {
+        self.config.init_trap_policy = policy;
+        self
+    }
+
+    /// Sets whether or not to trap on an under-aligned load or store of an unaligned address,
+    /// rather than emulating it.
+    pub fn trap_on_unaligned_access(mut self, yes: bool) -> Self {
+        self.config.trap_on_unaligned_access = yes;
+        self
+    }
+
+    /// Sets whether or not integer division and remainder by zero produce zero, rather than
+    /// trapping.
+    pub fn wrapping_division(mut self, yes: bool) -> Self {
+        self.config.wrapping_division = yes;
+        self
+    }
+
+    /// Sets whether or not to validate the input against only the Wasm proposals which the
+    /// backend implements, see [crate::strict_wasm_features].
+    pub fn strict_validation(mut self, yes: bool) -> Self {
+        self.config.strict_validation = yes;
+        self
+    }
+
+    /// Sets the Wasm proposals accepted by the validator, overriding the default set, as well as
+    /// [Self::strict_validation].
+    pub fn features(mut self, features: WasmFeatures) -> Self {
+        self.config.features = Some(features);
+        self
+    }
+
+    /// Sets whether or not to give internal linkage to the functions which do not escape the
+    /// module.
+    pub fn internalize_non_escaping_functions(mut self, yes: bool) -> Self {
+        self.config.internalize_non_escaping_functions = yes;
+        self
+    }
+
+    /// Returns the configuration built
+    pub fn build(self) -> WasmTranslationConfig {
+        self.config
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use miden_diagnostics::Diagnostic;
 use miden_diagnostics::ToDiagnostic;
 use miden_hir::SymbolConflictError;
@@ -26,6 +28,30 @@ pub enum WasmError {
     #[error("Unsupported Wasm: {0}")]
     Unsupported(String),
 
+    /// A feature used by the WebAssembly code is not supported, and has a diagnostic code which
+    /// can be passed to `midenc explain`
+    #[error("Unsupported Wasm: {message}")]
+    UnsupportedFeature {
+        /// The unsupported feature
+        feature: UnsupportedFeature,
+        /// A string describing the particular use of the feature
+        message: String,
+    },
+
+    /// A branch targets a control frame outside of the function, i.e. its relative depth is not
+    /// less than the number of enclosing blocks. This should never happen for validated
+    /// WebAssembly code.
+    #[error(
+        "Invalid branch depth {relative_depth}: there are only {num_frames} enclosing control \
+         frames"
+    )]
+    InvalidBranchDepth {
+        /// The relative depth of the branch target
+        relative_depth: u32,
+        /// The number of control frames enclosing the branch, including the function body
+        num_frames: usize,
+    },
+
     /// Too many functions were declared in a module
     #[error("Too many declared functions in the module")]
     FuncNumLimitExceeded,
@@ -71,16 +97,259 @@ impl ToDiagnostic for WasmError {
 /// A convenient alias for a `Result` that uses `WasmError` as the error type.
 pub type WasmResult<T> = Result<T, WasmError>;
 
-/// Emit diagnostics and return an `Err(WasmError::Unsupported(msg))` where `msg` the string built by calling `format!`
-/// on the arguments to this macro.
+/// A WebAssembly feature which is not yet supported by the compiler.
+///
+/// Each feature has a stable diagnostic code, which is attached to the diagnostics emitted for
+/// it, and which can be passed to `midenc explain` to get a longer description of the limitation
+/// along with any known workarounds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnsupportedFeature {
+    /// Accesses to a linear memory other than the first
+    MultiMemory,
+    /// Functions or blocks returning more than one value
+    MultiValue,
+    /// Globals imported from another module
+    ImportedGlobal,
+    /// An operator which has no lowering to Miden IR
+    Operator,
+    /// A section which is not handled by the translator
+    Section,
+    /// The component model start section
+    ComponentStart,
+    /// A constant initializer expression of an unsupported form
+    InitExpr,
+    /// Functions imported from WASI, e.g. by the preview1 adapter embedded by `cargo component`
+    WasiImport,
+    /// References to the struct and array types of the GC proposal
+    GcType,
+    /// Memories or tables declared larger than the configured limits
+    MemoryLimits,
+}
+impl UnsupportedFeature {
+    /// All known unsupported features, in diagnostic code order
+    pub const ALL: [Self; 10] = [
+        Self::MultiMemory,
+        Self::MultiValue,
+        Self::ImportedGlobal,
+        Self::Operator,
+        Self::Section,
+        Self::ComponentStart,
+        Self::InitExpr,
+        Self::WasiImport,
+        Self::GcType,
+        Self::MemoryLimits,
+    ];
+
+    /// The diagnostic code for this feature, e.g. `MIDEN0001`
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::MultiMemory => "MIDEN0001",
+            Self::MultiValue => "MIDEN0002",
+            Self::ImportedGlobal => "MIDEN0003",
+            Self::Operator => "MIDEN0004",
+            Self::Section => "MIDEN0005",
+            Self::ComponentStart => "MIDEN0006",
+            Self::InitExpr => "MIDEN0007",
+            Self::WasiImport => "MIDEN0008",
+            Self::GcType => "MIDEN0009",
+            Self::MemoryLimits => "MIDEN0010",
+        }
+    }
+
+    /// Look up the feature corresponding to the given diagnostic code
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|feature| feature.code().eq_ignore_ascii_case(code))
+    }
+
+    /// A longer description of this limitation, and how to work around it
+    pub const fn explain(self) -> &'static str {
+        match self {
+            Self::MultiMemory => {
+                "The Miden VM exposes a single linear memory to compiled programs, so Wasm modules \
+                 may declare more than one memory, but only the first, memory 0, may be accessed \
+                 or initialized by a data segment.\n\n\
+                 Workaround: build without the `multi-memory` target feature, and merge any \
+                 additional memories into the default memory."
+            }
+            Self::MultiValue => {
+                "Functions and blocks may currently produce at most one result.\n\n\
+                 Workaround: build without the `multivalue` target feature, or return aggregates \
+                 through memory (e.g. via an out-pointer parameter) instead of as multiple values."
+            }
+            Self::ImportedGlobal => {
+                "Only globals of reference types may be imported, e.g. a function pointer of the \
+                 host, which is resolved against the definition of the external symbol when \
+                 linking. The value of any other imported global is not known at compile time, \
+                 and neither is that of a global initialized from an imported global.\n\n\
+                 Workaround: define the global in the module itself, or pass the value to the \
+                 functions which need it explicitly."
+            }
+            Self::Operator => {
+                "The module uses a Wasm instruction which has no lowering to Miden IR, e.g. \
+                 floating-point arithmetic such as `f64.add` or `f32.sqrt`, SIMD instructions, or \
+                 `table.get` and `table.set`. Floating-point loads, stores, conversions between \
+                 formats, sign operations, `min`, `max` and rounding are supported, as are \
+                 function references and indirect calls.\n\n\
+                 Workaround: avoid the offending operations in the source program, for example by \
+                 using integer or fixed-point arithmetic in place of floating-point."
+            }
+            Self::Section => {
+                "The module or component contains a section which the translator does not \
+                 handle, e.g. a tag or a core type section.\n\n\
+                 Workaround: build without the proposal which introduces the section, or strip \
+                 it from the binary before compiling."
+            }
+            Self::ComponentStart => {
+                "Components with a start function are not supported, as there is no way to run \
+                 component initialization code before the entrypoint.\n\n\
+                 Workaround: perform initialization explicitly at the start of the entrypoint."
+            }
+            Self::InitExpr => {
+                "Global initializers and data segment offsets must be simple constants, or a \
+                 reference to another global.\n\n\
+                 Workaround: compute the value at runtime, or use a constant of the type of the \
+                 global, or of the index type of the memory for a data segment offset, i.e. `i32`, \
+                 or `i64` for a 64-bit memory."
+            }
+            Self::WasiImport => {
+                "The module imports functions from WASI, which is not available on Miden, whose \
+                 programs instead interact with their environment through Miden imports, such as \
+                 `get_id` or `add_asset`. These imports typically come from the WASI preview1 \
+                 adapter embedded by `cargo component`, or from the Rust standard library.\n\n\
+                 Workaround: build for a target without WASI, e.g. `wasm32-unknown-unknown`, \
+                 use `#![no_std]`, and avoid APIs which depend on the host operating system, such \
+                 as I/O, clocks, or environment variables."
+            }
+            Self::GcType => {
+                "The struct and array types of the GC proposal have no representation in Miden IR \
+                 yet. Modules may declare them, but functions whose parameters or results refer \
+                 to them, or which use the GC instructions, cannot be translated.\n\n\
+                 Workaround: build without the `gc` target feature, or keep GC types out of the \
+                 functions which are compiled for Miden."
+            }
+            Self::MemoryLimits => {
+                "The module declares a memory or table whose initial or maximum size exceeds the \
+                 limits of the translator, by default the 65536 pages addressable by a 32-bit \
+                 memory, and the 10,000,000 elements accepted by the validator for a table. Larger \
+                 memories cannot be laid out in the address space of the Miden VM.\n\n\
+                 Workaround: lower the limits declared by the module, e.g. with the \
+                 `-C link-arg=--max-memory=<bytes>` linker flag, or raise those of the translator \
+                 if the module is known not to use that much memory."
+            }
+        }
+    }
+}
+impl fmt::Display for UnsupportedFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// A single use of an [UnsupportedFeature] in a Wasm module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedUse {
+    /// The feature which was used
+    pub feature: UnsupportedFeature,
+    /// A description of the particular use of the feature, e.g. the operator used
+    pub message: String,
+    /// The function in which the feature was used, if it was used in a function body
+    pub function: Option<String>,
+    /// The offset in the Wasm binary of the use, if known
+    pub offset: Option<usize>,
+}
+impl UnsupportedUse {
+    fn location(&self) -> String {
+        match (&self.function, self.offset) {
+            (Some(function), Some(offset)) => {
+                format!("in function {function} at offset {offset:#x}")
+            }
+            (Some(function), None) => format!("in function {function}"),
+            (None, Some(offset)) => format!("at offset {offset:#x}"),
+            (None, None) => "in module".to_string(),
+        }
+    }
+}
+
+/// The unsupported features used by a Wasm module, as listed by
+/// [crate::list_unsupported_features]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UnsupportedReport {
+    /// Each distinct use of an unsupported feature, in the order first encountered
+    pub entries: Vec<UnsupportedReportEntry>,
+}
+
+/// The uses of an [UnsupportedFeature] which share the same description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedReportEntry {
+    /// The first use of the feature
+    pub first: UnsupportedUse,
+    /// The total number of uses
+    pub count: usize,
+}
+
+impl UnsupportedReport {
+    /// Build a report from the given uses, grouping uses with the same feature and description
+    pub fn new(uses: impl IntoIterator<Item = UnsupportedUse>) -> Self {
+        let mut entries = Vec::<UnsupportedReportEntry>::new();
+        for use_ in uses {
+            let existing = entries.iter_mut().find(|entry| {
+                entry.first.feature == use_.feature && entry.first.message == use_.message
+            });
+            match existing {
+                Some(entry) => entry.count += 1,
+                None => entries.push(UnsupportedReportEntry {
+                    first: use_,
+                    count: 1,
+                }),
+            }
+        }
+        Self { entries }
+    }
+
+    /// Returns true if no unsupported features were found
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for UnsupportedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no unsupported features found");
+        }
+        for entry in self.entries.iter() {
+            let uses = if entry.count == 1 { "use" } else { "uses" };
+            writeln!(
+                f,
+                "{}: {} ({} {uses}, first {})",
+                entry.first.feature,
+                entry.first.message,
+                entry.count,
+                entry.first.location()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Emit diagnostics and return an `Err(WasmError::UnsupportedFeature { .. })` whose message is the
+/// string built by calling `format!` on the arguments to this macro.
+///
+/// The emitted diagnostic refers the user to `midenc explain` for the given [UnsupportedFeature].
 #[macro_export]
 macro_rules! unsupported_diag {
-    ($diagnostics:expr, $($arg:tt)*) => {
+    ($diagnostics:expr, $feature:expr, $($arg:tt)*) => {
         let message = format!($($arg)*);
+        let feature: $crate::UnsupportedFeature = $feature;
         $diagnostics
             .diagnostic(miden_diagnostics::Severity::Error)
             .with_message(message.clone())
+            .with_note(format!(
+                "{feature}: run `midenc explain {feature}` for more information"
+            ))
             .emit();
-        return Err($crate::error::WasmError::Unsupported(message));
+        return Err($crate::error::WasmError::UnsupportedFeature { feature, message });
     }
 }
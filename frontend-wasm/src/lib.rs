@@ -18,5 +18,13 @@ mod test_utils;
 
 pub use self::component::build_ir::translate_component;
 pub use self::config::*;
-pub use self::error::WasmError;
-pub use self::module::build_ir::translate_module;
+pub use self::error::{
+    UnsupportedFeature, UnsupportedReport, UnsupportedReportEntry, UnsupportedUse, WasmError,
+};
+pub use self::module::build_ir::{
+    list_unsupported_features, parse_module, strict_wasm_features, translate_module,
+    translate_module_unvalidated,
+};
+pub use self::module::call_graph::{call_graph, CallEdge, CallGraph};
+pub use self::module::line_table::SourceLocation;
+pub use self::module::module_env::ParsedModule;
@@ -1,16 +1,33 @@
-use miden_diagnostics::{DiagnosticsHandler, SourceSpan};
-use miden_hir::{CallConv, ConstantData, FunctionIdent, Ident, Linkage, ModuleBuilder, Symbol};
-use wasmparser::{Validator, WasmFeatures};
+use std::collections::BTreeSet;
+use std::mem;
+use std::sync::Arc;
+
+use miden_diagnostics::term::termcolor::ColorChoice;
+use miden_diagnostics::{CodeMap, DiagnosticsHandler, NullEmitter, SourceSpan};
+use miden_hir::{
+    symbols, CallConv, ConstantData, EntityExport, FunctionIdent, Ident, Immediate, InstBuilder,
+    Linkage, ModuleBuilder, ModuleFunctionBuilder, Symbol, Type,
+};
+use midenc_session::{Endianness, InitTrapPolicy};
+use rustc_hash::FxHashMap;
+use wasmparser::{FuncValidatorAllocations, Validator, WasmFeatures};
 
 use crate::{
-    error::WasmResult,
+    code_translator::{
+        dispatcher_signature, float_intrinsic_signature, passive_data_place_signature,
+    },
+    error::{UnsupportedReport, UnsupportedUse, WasmResult},
     module::func_translator::FuncTranslator,
+    module::line_table::{FunctionSpans, LineTable, SourceFiles},
     module::module_env::{FunctionBodyData, ModuleEnvironment, ParsedModule},
-    module::types::{ir_func_sig, ir_func_type, ir_type, ModuleTypes},
-    WasmError, WasmTranslationConfig,
+    module::types::{
+        ir_func_sig, ir_func_type, ir_type, DataIndex, EntityIndex, ModuleTypes, WasmFuncType,
+        WasmHeapType, WasmRefType, WasmType,
+    },
+    unsupported_diag, UnsupportedFeature, WasmError, WasmTranslationConfig,
 };
 
-use super::Module;
+use super::{Module, ModuleType};
 
 /// Translate a valid Wasm core module binary into Miden IR module
 pub fn translate_module(
@@ -18,7 +35,150 @@ pub fn translate_module(
     config: &WasmTranslationConfig,
     diagnostics: &DiagnosticsHandler,
 ) -> WasmResult<miden_hir::Module> {
-    let wasm_features = WasmFeatures::default();
+    translate_module_using(wasm, config, diagnostics, FuncTranslator::new())
+}
+
+/// Translate a Wasm core module binary, which has already been validated, into Miden IR module
+///
+/// This is the same as [translate_module], except that function bodies, the validation of which
+/// dominates the cost of validating a module, are not validated again. The other sections of the
+/// module are still processed by the validator, as the types of the module are derived from it.
+///
+/// NOTE: Passing a module which is not valid is undefined behavior: translation may panic, or
+/// produce IR which does not reflect the input, rather than report an error. This is only meant
+/// for trusted inputs, e.g. modules validated by an earlier stage of the compiler.
+pub fn translate_module_unvalidated(
+    wasm: &[u8],
+    config: &WasmTranslationConfig,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<miden_hir::Module> {
+    translate_module_using(
+        wasm,
+        config,
+        diagnostics,
+        FuncTranslator::without_validation(),
+    )
+}
+
+/// Translate a Wasm core module binary, translating function bodies with `func_translator`
+fn translate_module_using(
+    wasm: &[u8],
+    config: &WasmTranslationConfig,
+    diagnostics: &DiagnosticsHandler,
+    mut func_translator: FuncTranslator,
+) -> WasmResult<miden_hir::Module> {
+    // Functions using unsupported features are found up front, without reporting them, as they
+    // are reported as errors when translated normally. Any other problem with the module is left
+    // to be reported by the translation below.
+    let stubs = if config.stub_unsupported_functions {
+        find_unsupported_uses(wasm, config)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|unsupported| unsupported.function.is_some())
+            .collect()
+    } else {
+        vec![]
+    };
+    let (parsed_module, module_types) = parse_module(wasm, config, diagnostics)?;
+    build_ir_module_using(
+        parsed_module,
+        &module_types,
+        config,
+        diagnostics,
+        &mut func_translator,
+        &stubs,
+    )
+}
+
+/// List the unsupported features used by a Wasm core module or component binary, without
+/// compiling it.
+///
+/// Unlike [translate_module], this does not stop at the first unsupported operator, but records
+/// it, and carries on translating the rest of the module, so that every use of an unsupported
+/// operator is reported. Each core module of a component is translated in turn. Unsupported
+/// features outside of function bodies, e.g. multiple memories, still stop translation, so at
+/// most one of those is reported.
+pub fn list_unsupported_features(
+    wasm: &[u8],
+    config: &WasmTranslationConfig,
+) -> WasmResult<UnsupportedReport> {
+    let uses = if wasmparser::Parser::is_component(wasm) {
+        crate::component::build_ir::find_unsupported_uses(wasm, config)
+    } else {
+        find_unsupported_uses(wasm, config)
+    };
+    uses.map(UnsupportedReport::new)
+}
+
+/// Translate `wasm`, recording each use of an unsupported feature rather than failing
+fn find_unsupported_uses(
+    wasm: &[u8],
+    config: &WasmTranslationConfig,
+) -> WasmResult<Vec<UnsupportedUse>> {
+    record_unsupported_uses(|diagnostics, func_translator| {
+        let (parsed_module, module_types) = parse_module(wasm, config, diagnostics)?;
+        build_ir_module_using(
+            parsed_module,
+            &module_types,
+            config,
+            diagnostics,
+            func_translator,
+            &[],
+        )?;
+        Ok(())
+    })
+}
+
+/// Run `translate` with a translator which records each use of an unsupported feature in a
+/// function body, and return those uses, along with the unsupported feature `translate` stopped
+/// on, if any
+pub(crate) fn record_unsupported_uses(
+    translate: impl FnOnce(&DiagnosticsHandler, &mut FuncTranslator) -> WasmResult<()>,
+) -> WasmResult<Vec<UnsupportedUse>> {
+    // Every use is returned to the caller, so the diagnostics emitted along the way are dropped
+    let diagnostics = DiagnosticsHandler::new(
+        Default::default(),
+        Arc::new(CodeMap::new()),
+        Arc::new(NullEmitter::new(ColorChoice::Never)),
+    );
+    let mut func_translator = FuncTranslator::recording_unsupported();
+    let result = translate(&diagnostics, &mut func_translator);
+    let mut uses = func_translator.take_unsupported();
+    match result {
+        Ok(_) => (),
+        Err(WasmError::UnsupportedFeature { feature, message }) => uses.push(UnsupportedUse {
+            feature,
+            message,
+            function: None,
+            offset: None,
+        }),
+        Err(err) => return Err(err),
+    }
+    Ok(uses)
+}
+
+/// Parse and validate a Wasm core module binary, without translating its function bodies.
+///
+/// The parsed module can be queried for the source locations of its code with
+/// [ParsedModule::source_location].
+pub fn parse_module<'data>(
+    wasm: &'data [u8],
+    config: &WasmTranslationConfig,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<(ParsedModule<'data>, ModuleTypes)> {
+    let wasm_features = if let Some(features) = config.features {
+        features
+    } else if config.strict_validation {
+        strict_wasm_features(config)
+    } else {
+        WasmFeatures {
+            function_references: true,
+            gc: true,
+            tail_call: true,
+            multi_memory: true,
+            ..Default::default()
+        }
+    };
     let mut validator = Validator::new_with_features(wasm_features);
     let parser = wasmparser::Parser::new(0);
     let mut module_types_builder = Default::default();
@@ -32,19 +192,98 @@ pub fn translate_module(
         .module
         .set_name_fallback(config.source_name.clone());
     let module_types = module_types_builder.finish();
-    build_ir_module(parsed_module, &module_types, config, diagnostics)
+    Ok((parsed_module, module_types))
+}
+
+/// Returns the Wasm features which are accepted when validating a module under
+/// [WasmTranslationConfig::strict_validation], i.e. the MVP, along with the proposals which the
+/// backend implements.
+///
+/// Individual operators of an accepted proposal may still be unsupported, e.g. `memory.fill` from
+/// the bulk memory proposal, or the floating-point operators of the MVP, but those are reported
+/// as unsupported when the function using them is translated. Atomics are only accepted with
+/// [WasmTranslationConfig::single_threaded_atomics].
+pub fn strict_wasm_features(config: &WasmTranslationConfig) -> WasmFeatures {
+    WasmFeatures {
+        mutable_global: true,
+        sign_extension: true,
+        multi_value: true,
+        bulk_memory: true,
+        reference_types: true,
+        tail_call: true,
+        function_references: true,
+        floats: true,
+        threads: config.single_threaded_atomics,
+        saturating_float_to_int: false,
+        simd: false,
+        relaxed_simd: false,
+        multi_memory: false,
+        exceptions: false,
+        memory64: false,
+        extended_const: false,
+        memory_control: false,
+        gc: false,
+        component_model: false,
+        ..Default::default()
+    }
 }
 
 pub fn build_ir_module(
+    parsed_module: ParsedModule,
+    module_types: &ModuleTypes,
+    config: &WasmTranslationConfig,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<miden_hir::Module> {
+    build_ir_module_using(
+        parsed_module,
+        module_types,
+        config,
+        diagnostics,
+        &mut FuncTranslator::new(),
+        &[],
+    )
+}
+
+/// Build the IR module for `parsed_module`, translating function bodies with `func_translator`,
+/// except for the functions in `stubs`, which are replaced by a stub which traps when called
+pub(crate) fn build_ir_module_using(
     mut parsed_module: ParsedModule,
     module_types: &ModuleTypes,
-    _config: &WasmTranslationConfig,
+    config: &WasmTranslationConfig,
     diagnostics: &DiagnosticsHandler,
+    func_translator: &mut FuncTranslator,
+    stubs: &[UnsupportedUse],
 ) -> WasmResult<miden_hir::Module> {
+    // Loads, stores and data segments are all laid out in little-endian order by the code
+    // generator, so any other byte order would miscompile every multi-byte memory access
+    if config.endianness != Endianness::Little {
+        let message = format!(
+            "{} endian memory layout is not supported, only little endian is implemented",
+            config.endianness
+        );
+        diagnostics
+            .diagnostic(miden_diagnostics::Severity::Error)
+            .with_message(message.clone())
+            .emit();
+        return Err(WasmError::Unsupported(message));
+    }
     let name = parsed_module.module.name();
     let mut module_builder = ModuleBuilder::new(name.clone().as_str());
     for import in parsed_module.module.imports.clone() {
-        let func_idx = import.index.unwrap_func();
+        // Imports of other kinds of entities may be interleaved with function imports, and are
+        // handled elsewhere
+        let EntityIndex::Function(func_idx) = import.index else {
+            continue;
+        };
+        // Imports which have already been resolved to a function defined elsewhere, e.g. by the
+        // component translation, are called directly
+        if parsed_module
+            .module
+            .translated_function_imports
+            .contains_key(&func_idx)
+        {
+            continue;
+        }
         let func_name = parsed_module.module.func_name(func_idx);
         let sig_idx = parsed_module.module.type_of(import.index).unwrap_func();
         let func = &module_types[sig_idx];
@@ -62,55 +301,280 @@ pub fn build_ir_module(
             .insert(func_idx, (function_id, sig));
     }
     build_globals(&parsed_module.module, &mut module_builder, diagnostics)?;
-    build_data_segments(&parsed_module, &mut module_builder, diagnostics)?;
-    let mut func_translator = FuncTranslator::new();
-    for (defined_func_idx, body_data) in parsed_module.function_body_inputs {
+    build_memory_size_global(&parsed_module.module, &mut module_builder, diagnostics)?;
+    if parsed_module.module.defined_start_func().is_some() {
+        let guard_name = parsed_module.module.start_guard_name();
+        build_flag_global(&guard_name, &mut module_builder, diagnostics)?;
+    }
+    if parsed_module.module.ctors_func().is_some() {
+        if config.call_ctors_in_exports {
+            let guard_name = parsed_module.module.ctors_guard_name();
+            build_flag_global(&guard_name, &mut module_builder, diagnostics)?;
+        }
+        if config.init_trap_policy == InitTrapPolicy::Report {
+            let trapped_name = parsed_module.module.init_trapped_name();
+            build_flag_global(&trapped_name, &mut module_builder, diagnostics)?;
+        }
+    }
+    build_data_segments(&parsed_module, &mut module_builder, config, diagnostics)?;
+    build_passive_data_segments(&parsed_module, &mut module_builder, diagnostics)?;
+    // Function bodies are validated and translated one at a time, and the allocations of the
+    // validator of each function, i.e. its operand and control stacks, are reused to validate the
+    // next one, rather than allocating them anew for every function.
+    let function_body_inputs = mem::take(&mut parsed_module.function_body_inputs);
+    let line_table = if config.parse_wasm_debuginfo || config.generate_native_debuginfo {
+        parse_line_table(&parsed_module, diagnostics)
+    } else {
+        LineTable::default()
+    };
+    let code_section_offset = parsed_module.debuginfo.wasm_file.code_section_offset;
+    let mut source_files = SourceFiles::default();
+    let mut validator_allocs = FuncValidatorAllocations::default();
+    // Stubs are looked up by function name, so that the per-function work below doesn't grow
+    // with the size of the module
+    let mut stubs_by_function = FxHashMap::<&str, &UnsupportedUse>::default();
+    for unsupported in stubs {
+        if let Some(function) = unsupported.function.as_deref() {
+            stubs_by_function.entry(function).or_insert(unsupported);
+        }
+    }
+    for (defined_func_idx, body_data) in function_body_inputs {
         let func_index = parsed_module.module.func_index(defined_func_idx);
         let func_type = parsed_module.module.functions[func_index];
         let func_name = parsed_module.module.func_name(func_index);
         let wasm_func_type = module_types[func_type.signature].clone();
+        if let Some(gc_type) = find_gc_type_reference(&wasm_func_type, &parsed_module.module) {
+            unsupported_diag!(
+                diagnostics,
+                UnsupportedFeature::GcType,
+                "function '{func_name}' refers to the GC type {gc_type}, which is not supported"
+            );
+        }
         let ir_func_type = ir_func_type(&wasm_func_type)?;
-        let sig = ir_func_sig(&ir_func_type, CallConv::SystemV, Linkage::External);
+        let linkage = if config.internalize_non_escaping_functions && !func_type.is_escaping() {
+            Linkage::Internal
+        } else {
+            Linkage::External
+        };
+        let sig = ir_func_sig(&ir_func_type, CallConv::SystemV, linkage);
         let mut module_func_builder = module_builder.function(func_name.as_str(), sig.clone())?;
-        let FunctionBodyData { validator, body } = body_data;
-        let mut func_validator = validator.into_validator(Default::default());
-        func_translator.translate_body(
-            &body,
-            &mut module_func_builder,
-            &parsed_module.module,
-            &module_types,
-            diagnostics,
-            &mut func_validator,
-        )?;
+        let body_range = body_data.body.range();
+        // DWARF addresses are relative to the code section
+        let code_range = body_range.start as u64 - code_section_offset
+            ..body_range.end as u64 - code_section_offset;
+        let stub = if stubs_by_function.is_empty() {
+            None
+        } else {
+            let function_id = module_func_builder.id().to_string();
+            stubs_by_function.get(function_id.as_str()).copied()
+        };
+        if let Some(unsupported) = stub {
+            build_unsupported_stub(&mut module_func_builder, unsupported, diagnostics);
+        } else {
+            let FunctionBodyData { validator, body } = body_data;
+            let mut func_validator = validator.into_validator(validator_allocs);
+            let spans = match config.codemap.as_deref() {
+                Some(codemap) if config.generate_native_debuginfo => FunctionSpans::new(
+                    &line_table,
+                    code_range.clone(),
+                    code_section_offset,
+                    codemap,
+                    &mut source_files,
+                ),
+                _ => FunctionSpans::default(),
+            };
+            func_translator.translate_body(
+                func_index,
+                &body,
+                &mut module_func_builder,
+                &parsed_module.module,
+                &module_types,
+                diagnostics,
+                &mut func_validator,
+                config,
+                &spans,
+            )?;
+            validator_allocs = func_validator.into_allocations();
+        }
+        if config.annotate_wasm_indices {
+            module_func_builder.set_attribute(symbols::WasmFuncIndex, func_index.as_u32());
+            module_func_builder
+                .set_attribute(symbols::WasmSignatureIndex, func_type.signature.as_u32());
+        }
+        if config.parse_wasm_debuginfo {
+            module_func_builder.set_attribute(symbols::WasmFuncIndex, func_index.as_u32());
+            module_func_builder.set_attribute(symbols::WasmOffset, code_range.start as u32);
+            if let Some(location) = line_table.location(code_range) {
+                module_func_builder.set_attribute(symbols::SourceFile, location.file.as_str());
+                module_func_builder.set_attribute(symbols::SourceLine, location.line);
+            }
+        }
         module_func_builder
             .build(diagnostics)
             .map_err(|_| WasmError::InvalidFunctionError)?;
     }
-    let module = module_builder.build();
+    // The dispatchers of indirect calls are shared by all of the calls through them, so they are
+    // built once every function has been translated. A dispatcher may itself call another, which
+    // is then built on the next iteration.
+    let mut dispatchers = BTreeSet::new();
+    loop {
+        let pending = func_translator
+            .take_dispatchers()
+            .into_iter()
+            .filter(|dispatcher| dispatchers.insert(*dispatcher))
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            break;
+        }
+        for dispatcher in pending {
+            let name = dispatcher.name(&parsed_module.module);
+            let sig = dispatcher_signature(dispatcher, module_types)?;
+            let mut module_func_builder = module_builder.function(name.as_str(), sig)?;
+            func_translator.build_dispatcher(
+                dispatcher,
+                &mut module_func_builder,
+                &parsed_module.module,
+                module_types,
+                diagnostics,
+            )?;
+            module_func_builder
+                .build(diagnostics)
+                .map_err(|_| WasmError::InvalidFunctionError)?;
+        }
+    }
+    for intrinsic in func_translator.take_float_intrinsics() {
+        let name = intrinsic.name();
+        let sig = float_intrinsic_signature(intrinsic);
+        let mut module_func_builder = module_builder.function(name.as_str(), sig)?;
+        func_translator.build_float_intrinsic(intrinsic, &mut module_func_builder);
+        module_func_builder
+            .build(diagnostics)
+            .map_err(|_| WasmError::InvalidFunctionError)?;
+    }
+    let mut module = module_builder.build();
+    // Exported memories and tables have no symbol of their own, so the host finds them through
+    // these records, while exported globals need none, as every global is declared with external
+    // linkage by `build_globals`
+    for (export_name, entity) in &parsed_module.module.exports {
+        let name = Ident::with_empty_span(Symbol::intern(export_name));
+        let export = match entity {
+            EntityIndex::Memory(_) => EntityExport::Memory { name },
+            EntityIndex::Table(index) => EntityExport::Table {
+                name,
+                index: index.as_u32(),
+            },
+            EntityIndex::Function(_) | EntityIndex::Global(_) => continue,
+        };
+        module.export_entity(export);
+    }
+    if let Some(start_func) = parsed_module.module.start_func {
+        // The start function is run on its own before any export, so it is called by the
+        // program directly, which is not possible for a function provided by another module
+        if parsed_module.module.is_imported_function(start_func) {
+            return Err(WasmError::Unsupported(format!(
+                "the start function of module '{name}' is imported, which is not supported"
+            )));
+        }
+        let function = parsed_module.module.func_name(start_func);
+        module.set_start(FunctionIdent {
+            module: module.name,
+            function: Ident::with_empty_span(Symbol::intern(function)),
+        });
+    }
     Ok(*module)
 }
 
+/// Returns the first reference to a GC struct or array type among the parameters and results of
+/// `func_type`, as those types can be declared, but not yet lowered
+fn find_gc_type_reference(func_type: &WasmFuncType, module: &Module) -> Option<WasmRefType> {
+    func_type
+        .params()
+        .iter()
+        .chain(func_type.returns())
+        .find_map(|ty| match ty {
+            WasmType::Ref(ref_ty) => match ref_ty.heap_type {
+                WasmHeapType::Struct | WasmHeapType::Array => Some(*ref_ty),
+                WasmHeapType::Concrete(index) => match module.types[index] {
+                    ModuleType::Struct(_) | ModuleType::Array(_) => Some(*ref_ty),
+                    ModuleType::Function(_) => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+}
+
+/// Parse the line programs of the DWARF sections of `parsed_module`.
+///
+/// Malformed debug information does not prevent compilation, so it is reported as a warning, and
+/// an empty table is returned instead.
+fn parse_line_table(parsed_module: &ParsedModule, diagnostics: &DiagnosticsHandler) -> LineTable {
+    match LineTable::parse(&parsed_module.debuginfo.dwarf) {
+        Ok(line_table) => line_table,
+        Err(err) => {
+            diagnostics
+                .diagnostic(miden_diagnostics::Severity::Warning)
+                .with_message(format!(
+                    "ignoring the DWARF line programs of module {}, as they are malformed: {err}",
+                    parsed_module.module.name()
+                ))
+                .emit();
+            LineTable::default()
+        }
+    }
+}
+
+/// Give the function being built a body which traps, in place of its original body, which uses
+/// the unsupported feature described by `unsupported`
+fn build_unsupported_stub(
+    module_func_builder: &mut ModuleFunctionBuilder,
+    unsupported: &UnsupportedUse,
+    diagnostics: &DiagnosticsHandler,
+) {
+    let feature = unsupported.feature;
+    diagnostics
+        .diagnostic(miden_diagnostics::Severity::Warning)
+        .with_message(format!(
+            "function {} uses an unsupported feature, and has been replaced by a stub which \
+             traps when called",
+            module_func_builder.id()
+        ))
+        .with_note(format!(
+            "{feature}: {}, run `midenc explain {feature}` for more information",
+            unsupported.message
+        ))
+        .emit();
+    module_func_builder.ins().unreachable(SourceSpan::default());
+}
+
 fn build_globals(
     wasm_module: &Module,
     module_builder: &mut ModuleBuilder,
     diagnostics: &DiagnosticsHandler,
 ) -> Result<(), WasmError> {
     Ok(for (global_idx, global) in &wasm_module.globals {
-        let global_name = wasm_module
-            .name_section
-            .globals_names
-            .get(&global_idx)
-            .cloned()
-            .unwrap_or(format!("gv{}", global_idx.as_u32()));
-        let global_init = wasm_module.try_global_initializer(global_idx, diagnostics)?;
-        let init = ConstantData::from(global_init.to_le_bytes(&wasm_module, diagnostics)?);
+        // This must agree with the name used when translating `global.get`/`global.set`,
+        // otherwise reads and writes of unnamed globals would refer to different storage
+        let global_name = wasm_module.global_name(global_idx);
+        // An imported reference is declared without an initializer, to be resolved against the
+        // definition of the external symbol when linking, e.g. a function pointer of the host
+        let init = match global.ty {
+            WasmType::Ref(_) if wasm_module.is_imported_global(global_idx) => None,
+            _ => {
+                let global_init = wasm_module.const_global_initializer(global_idx, diagnostics)?;
+                Some(ConstantData::from(
+                    global_init.to_le_bytes(&wasm_module, diagnostics)?,
+                ))
+            }
+        };
         if let Err(e) = module_builder.declare_global_variable(
             &global_name,
             ir_type(global.ty.clone())?,
             Linkage::External,
-            Some(init.clone()),
+            init.clone(),
             SourceSpan::default(),
         ) {
+            let init = init.map(|init| init.to_string()).unwrap_or_default();
             let message = format!("Failed to declare global variable '{global_name}' with initializer '{init}' with error: {:?}", e);
             diagnostics
                 .diagnostic(miden_diagnostics::Severity::Error)
@@ -121,19 +585,210 @@ fn build_globals(
     })
 }
 
+/// Declare a `u32` global variable, initially zero, which records an event in the lifecycle of the
+/// module, i.e. whether its start function has been run, whether its static constructors have been
+/// run, see `WasmTranslationConfig::call_ctors_in_exports`, or whether they trapped, see
+/// `WasmTranslationConfig::init_trap_policy`
+fn build_flag_global(
+    name: &str,
+    module_builder: &mut ModuleBuilder,
+    diagnostics: &DiagnosticsHandler,
+) -> Result<(), WasmError> {
+    if let Err(e) = module_builder.declare_global_variable(
+        name,
+        Type::U32,
+        Linkage::External,
+        Some(ConstantData::from(0u32.to_le_bytes().as_slice())),
+        SourceSpan::default(),
+    ) {
+        let message = format!(
+            "Failed to declare global variable '{name}' with error: {:?}",
+            e
+        );
+        diagnostics
+            .diagnostic(miden_diagnostics::Severity::Error)
+            .with_message(message.clone())
+            .emit();
+        return Err(WasmError::Unexpected(message));
+    }
+    Ok(())
+}
+
+/// Declare the `u32` global variable holding the current size of the linear memory of
+/// `wasm_module`, in 64 KiB pages, initially its declared initial size, see
+/// [Module::memory_size_name]. Nothing is declared for a module without a memory.
+fn build_memory_size_global(
+    wasm_module: &Module,
+    module_builder: &mut ModuleBuilder,
+    diagnostics: &DiagnosticsHandler,
+) -> Result<(), WasmError> {
+    let Some(memory) = wasm_module.memories.values().next() else {
+        return Ok(());
+    };
+    let name = wasm_module.memory_size_name();
+    // The initial size is at most `WasmTranslationConfig::max_memory_pages`, which is checked
+    // where the memory is declared
+    let init = (memory.minimum as u32).to_le_bytes();
+    if let Err(e) = module_builder.declare_global_variable(
+        &name,
+        Type::U32,
+        Linkage::External,
+        Some(ConstantData::from(init.as_slice())),
+        SourceSpan::default(),
+    ) {
+        let message = format!(
+            "Failed to declare global variable '{name}' with error: {:?}",
+            e
+        );
+        diagnostics
+            .diagnostic(miden_diagnostics::Severity::Error)
+            .with_message(message.clone())
+            .emit();
+        return Err(WasmError::Unexpected(message));
+    }
+    Ok(())
+}
+
+/// An active data segment, or a group of overlapping active data segments merged into one
+struct ActiveDataSegment {
+    name: String,
+    offset: u32,
+    data: Vec<u8>,
+    readonly: bool,
+}
+impl ActiveDataSegment {
+    fn end(&self) -> u32 {
+        self.offset + self.data.len() as u32
+    }
+
+    fn overlaps(&self, offset: u32, end: u32) -> bool {
+        self.offset < end && offset < self.end()
+    }
+}
+
+/// The prefixes of the names of the sections which the linker places read-only data in, i.e.
+/// constants (`.rodata`), and data which is only written when relocated (`.data.rel.ro`)
+const READONLY_DATA_SEGMENT_PREFIXES: &[&str] = &[".rodata", ".data.rel.ro"];
+
+/// Returns whether the active data segment `name` is read-only.
+///
+/// This is decided by the override with the longest prefix of `name` among those given by
+/// [WasmTranslationConfig::data_segment_readonly], if any, and otherwise by whether the linker
+/// section `name` is one of read-only data, see [READONLY_DATA_SEGMENT_PREFIXES].
+fn is_readonly_data_segment(name: &str, config: &WasmTranslationConfig) -> bool {
+    let overridden = config
+        .data_segment_readonly
+        .iter()
+        .filter(|(prefix, _)| name.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len());
+    match overridden {
+        Some((_, readonly)) => *readonly,
+        None => READONLY_DATA_SEGMENT_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix)),
+    }
+}
+
 fn build_data_segments(
     translation: &ParsedModule,
     module_builder: &mut ModuleBuilder,
+    config: &WasmTranslationConfig,
     diagnostics: &DiagnosticsHandler,
 ) -> Result<(), WasmError> {
+    let overlap_severity = if config.deny_overlapping_data_segments {
+        miden_diagnostics::Severity::Error
+    } else {
+        miden_diagnostics::Severity::Warning
+    };
+    let mut segments: Vec<ActiveDataSegment> = vec![];
     for (data_segment_idx, data_segment) in &translation.data_segments {
-        let data_segment_name =
-            translation.module.name_section.data_segment_names[&data_segment_idx].clone();
-        let readonly = data_segment_name.contains(".rodata");
-        let init = ConstantData::from(data_segment.data);
-        let offset = data_segment
-            .offset
-            .as_i32(&translation.module, diagnostics)? as u32;
+        // Passive segments are not placed in linear memory, see `build_passive_data_segments`
+        let Some(offset) = data_segment.offset else {
+            continue;
+        };
+        let data_segment_name = translation.module.data_segment_name(data_segment_idx);
+        if data_segment.memory_index.as_u32() != 0 {
+            unsupported_diag!(
+                diagnostics,
+                UnsupportedFeature::MultiMemory,
+                "data segment '{data_segment_name}' is placed in memory {}, but only memory 0 is \
+                 supported",
+                data_segment.memory_index.as_u32()
+            );
+        }
+        let readonly = is_readonly_data_segment(&data_segment_name, config);
+        let offset = offset.as_i32(&translation.module, diagnostics)? as u32;
+        let size = data_segment.data.len() as u32;
+        let Some(end) = offset.checked_add(size) else {
+            let message = format!("Failed to declare data segment '{data_segment_name}' with size '{size}' at '{offset}': segment is out of bounds of linear memory");
+            diagnostics
+                .diagnostic(miden_diagnostics::Severity::Error)
+                .with_message(message.clone())
+                .emit();
+            return Err(WasmError::Unexpected(message));
+        };
+
+        // Segments are initialized in order, so where this segment overlaps any of those before
+        // it, its contents win. As the HIR does not permit overlapping segments, we merge all
+        // of the segments it overlaps with it, into a single segment covering all of them.
+        let mut merged = ActiveDataSegment {
+            name: data_segment_name.clone(),
+            offset,
+            data: vec![],
+            readonly,
+        };
+        let mut overlapped = vec![];
+        let mut i = 0;
+        while i < segments.len() {
+            if !segments[i].overlaps(offset, end) {
+                i += 1;
+                continue;
+            }
+            let segment = segments.swap_remove(i);
+            diagnostics
+                .diagnostic(overlap_severity)
+                .with_message(format!(
+                    "data segment '{data_segment_name}' overlaps with data segment '{}' in the \
+                     byte range {:#x}..{:#x}",
+                    segment.name,
+                    offset.max(segment.offset),
+                    end.min(segment.end()),
+                ))
+                .emit();
+            merged.offset = merged.offset.min(segment.offset);
+            merged.readonly &= segment.readonly;
+            merged.name = format!("{}, {}", segment.name, merged.name);
+            overlapped.push(segment);
+        }
+        if config.deny_overlapping_data_segments && !overlapped.is_empty() {
+            return Err(WasmError::Unexpected(format!(
+                "data segment '{data_segment_name}' overlaps with other data segments"
+            )));
+        }
+        let merged_end = overlapped
+            .iter()
+            .map(ActiveDataSegment::end)
+            .fold(end, u32::max);
+        // The segments merged here are disjoint, as any overlap between them would have been
+        // merged when the later of them was declared, so only the new segment may clobber them
+        merged.data = vec![0; (merged_end - merged.offset) as usize];
+        for segment in overlapped {
+            let start = (segment.offset - merged.offset) as usize;
+            merged.data[start..][..segment.data.len()].copy_from_slice(&segment.data);
+        }
+        let start = (offset - merged.offset) as usize;
+        merged.data[start..][..data_segment.data.len()].copy_from_slice(data_segment.data);
+        segments.push(merged);
+    }
+
+    for segment in segments {
+        let ActiveDataSegment {
+            name: data_segment_name,
+            offset,
+            data,
+            readonly,
+        } = segment;
+        let init = ConstantData::from(data);
         let size = init.len() as u32;
         if let Err(e) = module_builder.declare_data_segment(offset, size, init, readonly) {
             let message = format!("Failed to declare data segment '{data_segment_name}' with size '{size}' at '{offset}' with error: {:?}", e);
@@ -146,3 +801,127 @@ fn build_data_segments(
     }
     Ok(())
 }
+
+/// Declare the global variables of each non-empty passive data segment: one holding the contents
+/// of the segment, from which `memory.init` copies, one which `data.drop` sets to a non-zero
+/// value, to mark the segment as dropped, and one marking the contents as placed.
+///
+/// Global initializers are not yet written to memory when a program starts, so a function is
+/// also built for each segment, which writes its contents to its global the first time it is
+/// called, see `translate_memory_init`.
+fn build_passive_data_segments(
+    translation: &ParsedModule,
+    module_builder: &mut ModuleBuilder,
+    diagnostics: &DiagnosticsHandler,
+) -> Result<(), WasmError> {
+    for (&data_index, data) in translation.module.passive_data_map.iter() {
+        // Empty segments have nothing to copy, so `memory.init` only checks bounds against zero
+        if data.is_empty() {
+            continue;
+        }
+        // The contents are stored as 32-bit elements, so that they can be placed a word at a
+        // time, see `build_passive_data_place_function`
+        let num_elements = data.len().div_ceil(4);
+        let globals = [
+            (
+                translation.module.passive_data_name(data_index),
+                Type::Array(Box::new(Type::U32), num_elements),
+                ConstantData::from(data.as_ref()),
+            ),
+            (
+                translation.module.passive_data_dropped_name(data_index),
+                Type::U32,
+                ConstantData::from(0u32.to_le_bytes()),
+            ),
+            (
+                translation.module.passive_data_placed_name(data_index),
+                Type::U32,
+                ConstantData::from(0u32.to_le_bytes()),
+            ),
+        ];
+        for (name, ty, init) in globals {
+            if let Err(e) = module_builder.declare_global_variable(
+                &name,
+                ty,
+                Linkage::External,
+                Some(init),
+                SourceSpan::default(),
+            ) {
+                let message = format!(
+                    "Failed to declare global variable '{name}' for passive data segment with error: {:?}",
+                    e
+                );
+                diagnostics
+                    .diagnostic(miden_diagnostics::Severity::Error)
+                    .with_message(message.clone())
+                    .emit();
+                return Err(WasmError::Unexpected(message));
+            }
+        }
+        build_passive_data_place_function(
+            &translation.module,
+            data_index,
+            data,
+            module_builder,
+            diagnostics,
+        )?;
+    }
+    Ok(())
+}
+
+/// Build the function which writes `data`, the contents of the passive data segment
+/// `data_index`, to its global a word at a time, unless they have already been placed there.
+///
+/// The function is shared by every `memory.init` of the segment, so the code writing the
+/// contents is only emitted once.
+fn build_passive_data_place_function(
+    module: &Module,
+    data_index: DataIndex,
+    data: &[u8],
+    module_builder: &mut ModuleBuilder,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let span = SourceSpan::default();
+    let name = module.passive_data_place_name(data_index);
+    let mut fb = module_builder.function(name.as_str(), passive_data_place_signature())?;
+    let place_block = fb.create_block();
+    let exit_block = fb.create_block();
+    let placed = fb
+        .ins()
+        .load_symbol(module.passive_data_placed_name(data_index), Type::U32, span);
+    let not_placed = fb.ins().eq_imm(placed, Immediate::U32(0), span);
+    fb.ins()
+        .cond_br(not_placed, place_block, &[], exit_block, &[], span);
+
+    fb.switch_to_block(place_block);
+    let segment_addr = fb.ins().symbol_addr(
+        module.passive_data_name(data_index),
+        Type::Ptr(Type::U32.into()),
+        span,
+    );
+    let segment_addr = fb.ins().ptrtoint(segment_addr, Type::U32, span);
+    for (i, chunk) in data.chunks(4).enumerate() {
+        let mut bytes = [0u8; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        let addr = fb
+            .ins()
+            .add_imm_unchecked(segment_addr, Immediate::U32(i as u32 * 4), span);
+        let ptr = fb.ins().inttoptr(addr, Type::Ptr(Type::U32.into()), span);
+        let word = fb.ins().u32(u32::from_le_bytes(bytes), span);
+        fb.ins().store(ptr, word, span);
+    }
+    let placed_ptr = fb.ins().symbol_addr(
+        module.passive_data_placed_name(data_index),
+        Type::Ptr(Type::U32.into()),
+        span,
+    );
+    let one = fb.ins().u32(1, span);
+    fb.ins().store(placed_ptr, one, span);
+    fb.ins().br(exit_block, &[], span);
+
+    fb.switch_to_block(exit_block);
+    fb.ins().ret(None, span);
+    fb.build(diagnostics)
+        .map_err(|_| WasmError::InvalidFunctionError)?;
+    Ok(())
+}
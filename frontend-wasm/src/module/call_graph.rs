@@ -0,0 +1,276 @@
+//! The call graph of a Wasm core module, emitted with `--emit=callgraph`.
+
+use std::fmt::{self, Write};
+
+use miden_diagnostics::DiagnosticsHandler;
+use miden_hir::{cranelift_entity::packed_option::ReservedValue, Symbol};
+use rustc_hash::FxHashSet;
+use wasmparser::Operator;
+
+use crate::{
+    error::WasmResult,
+    module::{
+        build_ir::parse_module,
+        types::{FuncIndex, TableIndex, TypeIndex},
+        Module, TableInitialValue, TableSegmentElement,
+    },
+    WasmTranslationConfig,
+};
+
+/// The call graph of a Wasm core module, relating each function to the functions it calls.
+///
+/// Functions are named as they are in the IR the module is translated to, i.e. `module::function`.
+///
+/// The callees of an indirect call are the functions of the expected type which may be placed
+/// in the table the call goes through, i.e. those in an element segment for that table, or in a
+/// passive element segment. This is an over-approximation, as the actual callee depends on the
+/// index of the table entry used at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallGraph {
+    /// The name of the module
+    pub name: Symbol,
+    /// The functions of the module, imported functions first, in the order of their indices
+    pub functions: Vec<String>,
+    /// The edges of the graph, without duplicates, in the order they first appear in the module
+    pub edges: Vec<CallEdge>,
+}
+
+/// An edge of a [CallGraph], from a caller to one of its callees
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CallEdge {
+    /// The name of the calling function
+    pub caller: String,
+    /// The name of the called function
+    pub callee: String,
+    /// Whether the call goes through a table
+    pub indirect: bool,
+}
+
+/// Build the call graph of the Wasm core module `wasm`.
+///
+/// The call graph is derived from the Wasm module rather than the IR it is translated to, as
+/// indirect calls are resolved using the tables of the module, which have no counterpart in the
+/// IR. Like [crate::translate_module], this fails if the module is not valid.
+pub fn call_graph(
+    wasm: &[u8],
+    config: &WasmTranslationConfig,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<CallGraph> {
+    let (parsed_module, _) = parse_module(wasm, config, diagnostics)?;
+    let module = &parsed_module.module;
+    let module_name = module.name();
+    let qualified_name = |index: FuncIndex| format!("{module_name}::{}", module.func_name(index));
+
+    let functions = module
+        .functions
+        .keys()
+        .map(&qualified_name)
+        .collect::<Vec<_>>();
+    let mut edges = vec![];
+    let mut seen = FxHashSet::default();
+    for (defined_index, body) in parsed_module.function_body_inputs.iter() {
+        let caller = module.func_index(defined_index);
+        let mut reader = body.body.get_operators_reader()?;
+        while !reader.eof() {
+            let (callees, indirect) = match reader.read()? {
+                Operator::Call { function_index } | Operator::ReturnCall { function_index } => {
+                    (vec![FuncIndex::from_u32(function_index)], false)
+                }
+                Operator::CallIndirect {
+                    type_index,
+                    table_index,
+                    ..
+                }
+                | Operator::ReturnCallIndirect {
+                    type_index,
+                    table_index,
+                } => (
+                    indirect_callees(
+                        module,
+                        TypeIndex::from_u32(type_index),
+                        TableIndex::from_u32(table_index),
+                    ),
+                    true,
+                ),
+                _ => continue,
+            };
+            for callee in callees {
+                let edge = CallEdge {
+                    caller: qualified_name(caller),
+                    callee: qualified_name(callee),
+                    indirect,
+                };
+                if seen.insert(edge.clone()) {
+                    edges.push(edge);
+                }
+            }
+        }
+    }
+
+    Ok(CallGraph {
+        name: Symbol::intern(&module_name),
+        functions,
+        edges,
+    })
+}
+
+/// Returns the functions of type `type_index` which may be placed in the table `table_index`
+fn indirect_callees(
+    module: &Module,
+    type_index: TypeIndex,
+    table_index: TableIndex,
+) -> Vec<FuncIndex> {
+    let signature = module.types[type_index].unwrap_function();
+    let table_init = &module.table_initialization;
+    let mut candidates = vec![];
+    if let Some(defined_index) = module.defined_table_index(table_index) {
+        match table_init.initial_values.get(defined_index) {
+            Some(TableInitialValue::Null { precomputed }) => {
+                candidates.extend(precomputed.iter().copied())
+            }
+            Some(TableInitialValue::FuncRef(index)) => candidates.push(*index),
+            None => (),
+        }
+    }
+    let mut elements = vec![];
+    for segment in table_init.segments.iter() {
+        if segment.table_index == table_index {
+            elements.extend(segment.elements.iter().copied());
+        }
+    }
+    // Passive element segments may be copied into any table with `table.init`
+    for segment in module.passive_elements.iter() {
+        elements.extend(segment.iter().copied());
+    }
+    for element in elements {
+        match element {
+            TableSegmentElement::Func(index) => candidates.push(index),
+            // The global's value is only known at instantiation, so it may be
+            // any function which escapes the module
+            TableSegmentElement::Global(_) => candidates.extend(
+                module
+                    .functions
+                    .iter()
+                    .filter(|(_, func)| func.is_escaping())
+                    .map(|(index, _)| index),
+            ),
+        }
+    }
+
+    let mut seen = FxHashSet::default();
+    candidates
+        .into_iter()
+        .filter(|index| !index.is_reserved_value())
+        .filter(|index| module.functions[*index].signature == signature)
+        .filter(|index| seen.insert(*index))
+        .collect()
+}
+
+impl fmt::Display for CallGraph {
+    /// Writes the call graph in the DOT format, with indirect calls as dashed edges
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("digraph ")?;
+        write_dot_id(f, self.name.as_str())?;
+        f.write_str(" {\n")?;
+        for function in self.functions.iter() {
+            f.write_str("  ")?;
+            write_dot_id(f, function)?;
+            f.write_str(";\n")?;
+        }
+        for edge in self.edges.iter() {
+            f.write_str("  ")?;
+            write_dot_id(f, &edge.caller)?;
+            f.write_str(" -> ")?;
+            write_dot_id(f, &edge.callee)?;
+            if edge.indirect {
+                f.write_str(" [style=dashed]")?;
+            }
+            f.write_str(";\n")?;
+        }
+        f.write_str("}\n")
+    }
+}
+
+impl midenc_session::Emit for CallGraph {
+    fn name(&self) -> Option<Symbol> {
+        None
+    }
+    fn output_type(&self) -> midenc_session::OutputType {
+        midenc_session::OutputType::CallGraph
+    }
+    fn write_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_fmt(format_args!("{}", self))
+    }
+}
+
+/// Write `s` as a quoted DOT identifier, escaping it as necessary
+fn write_dot_id(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_diagnostics;
+
+    #[test]
+    fn call_graph_has_direct_and_indirect_edges() {
+        let wat = r#"
+            (module $m
+                (type $binop (func (param i32 i32) (result i32)))
+                (table 2 funcref)
+                (elem (i32.const 0) $add $neg)
+                (func $add (type $binop)
+                    local.get 0
+                    local.get 1
+                    i32.add
+                )
+                (func $neg (param i32) (result i32)
+                    i32.const 0
+                    local.get 0
+                    i32.sub
+                )
+                (func $main (export "main") (param i32) (result i32)
+                    local.get 0
+                    call $neg
+                    i32.const 1
+                    i32.const 0
+                    call_indirect (type $binop)
+                )
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let call_graph = call_graph(
+            &wasm,
+            &WasmTranslationConfig::default(),
+            &test_diagnostics(),
+        )
+        .unwrap();
+        let edge = |callee: &str, indirect| CallEdge {
+            caller: "m::main".to_string(),
+            callee: callee.to_string(),
+            indirect,
+        };
+        // `neg` is in the table, but is not a candidate for the indirect call, as its type differs
+        assert_eq!(
+            call_graph.edges,
+            [edge("m::neg", false), edge("m::add", true)]
+        );
+        assert_eq!(call_graph.functions, ["m::add", "m::neg", "m::main"]);
+        let dot = call_graph.to_string();
+        assert!(dot.contains("\"m::main\" -> \"m::neg\";"), "{dot}");
+        assert!(
+            dot.contains("\"m::main\" -> \"m::add\" [style=dashed];"),
+            "{dot}"
+        );
+    }
+}
@@ -6,6 +6,7 @@
 //! Based on Cranelift's Wasm -> CLIF translator v11.0.0
 
 use crate::{
+    code_translator::{Dispatcher, FloatIntrinsic},
     error::{WasmError, WasmResult},
     module::types::{ir_func_type, BlockType, FuncIndex, ModuleTypes},
     translation_utils::sig_from_funct_type,
@@ -17,7 +18,7 @@ use miden_hir::{
 use miden_hir_type::Type;
 use rustc_hash::FxHashMap;
 use std::{
-    collections::{hash_map::Entry::Occupied, hash_map::Entry::Vacant},
+    collections::{hash_map::Entry::Occupied, hash_map::Entry::Vacant, BTreeSet},
     vec::Vec,
 };
 
@@ -236,11 +237,34 @@ pub struct FuncTranslationState {
     /// Is the current translation state still reachable? This is false when translating operators
     /// like End, Return, or Unreachable.
     pub(crate) reachable: bool,
+    /// When set, an explicit trap, i.e. `unreachable`, sets the global variable of this name and
+    /// returns from the function, rather than trapping, see
+    /// `WasmTranslationConfig::init_trap_policy`
+    pub(crate) trap_flag: Option<String>,
 
     // Imported and local functions that have been created by
     // `FuncEnvironment::make_direct_func()`.
     // Stores both the function reference and the number of WebAssembly arguments
     functions: FxHashMap<FuncIndex, (FunctionIdent, usize)>,
+
+    /// Known inclusive upper bounds of values which are provably non-negative, e.g. constants and
+    /// the results of arithmetic on bounded values. These hold wherever the value is defined.
+    value_bounds: FxHashMap<Value, u64>,
+    /// Upper bounds which are only known to hold on the current path, e.g. after a `br_if` on a
+    /// comparison against a constant. These are discarded whenever control flow merges.
+    guard_bounds: FxHashMap<Value, u64>,
+    /// Comparison results which imply an upper bound on one of their operands. Maps the
+    /// comparison result to the compared value, its bound, and whether the bound holds when the
+    /// comparison is true (`true`) or false (`false`).
+    bound_conditions: FxHashMap<Value, (Value, u64, bool)>,
+
+    /// The dispatchers called by the functions translated so far, which are shared by all of
+    /// the functions of the module, so these are kept between functions, and built once the
+    /// module has been translated, see `FuncTranslator::take_dispatchers`
+    pub(crate) dispatchers: BTreeSet<Dispatcher>,
+    /// The floating-point intrinsics called by the functions translated so far, which are kept
+    /// between functions, like [FuncTranslationState::dispatchers]
+    pub(crate) float_intrinsics: BTreeSet<FloatIntrinsic>,
 }
 
 impl FuncTranslationState {
@@ -250,15 +274,25 @@ impl FuncTranslationState {
             stack: Vec::new(),
             control_stack: Vec::new(),
             reachable: true,
+            trap_flag: None,
             functions: FxHashMap::default(),
+            value_bounds: FxHashMap::default(),
+            guard_bounds: FxHashMap::default(),
+            bound_conditions: FxHashMap::default(),
+            dispatchers: BTreeSet::new(),
+            float_intrinsics: BTreeSet::new(),
         }
     }
 
-    fn clear(&mut self) {
+    pub(crate) fn clear(&mut self) {
         debug_assert!(self.stack.is_empty());
         debug_assert!(self.control_stack.is_empty());
         self.reachable = true;
+        self.trap_flag = None;
         self.functions.clear();
+        self.value_bounds.clear();
+        self.guard_bounds.clear();
+        self.bound_conditions.clear();
     }
 
     /// Initialize the state for compiling a function with the given signature.
@@ -441,6 +475,67 @@ impl FuncTranslationState {
             blocktype,
         });
     }
+
+    /// Get the control stack frame targeted by a branch to `relative_depth`.
+    ///
+    /// Returns an error rather than panicking if there is no such frame, which can only happen
+    /// if the function body was not validated.
+    pub(crate) fn br_target_frame(
+        &mut self,
+        relative_depth: u32,
+    ) -> WasmResult<&mut ControlStackFrame> {
+        let num_frames = self.control_stack.len();
+        match num_frames.checked_sub(1 + relative_depth as usize) {
+            Some(i) => Ok(&mut self.control_stack[i]),
+            None => Err(WasmError::InvalidBranchDepth {
+                relative_depth,
+                num_frames,
+            }),
+        }
+    }
+}
+
+/// Methods for tracking value ranges, used to lower provably bounded integers as field elements.
+impl FuncTranslationState {
+    /// Record that `val` is non-negative and at most `bound` wherever it is defined.
+    pub(crate) fn set_upper_bound(&mut self, val: Value, bound: u64) {
+        self.value_bounds.insert(val, bound);
+    }
+
+    /// Get the inclusive upper bound of `val` on the current path, if known.
+    pub(crate) fn upper_bound(&self, val: Value) -> Option<u64> {
+        match (self.value_bounds.get(&val), self.guard_bounds.get(&val)) {
+            (Some(a), Some(b)) => Some(*a.min(b)),
+            (Some(bound), None) | (None, Some(bound)) => Some(*bound),
+            (None, None) => None,
+        }
+    }
+
+    /// Record that if `cond` is `holds_when` then `val` is at most `bound`.
+    pub(crate) fn set_bound_condition(
+        &mut self,
+        cond: Value,
+        val: Value,
+        bound: u64,
+        holds_when: bool,
+    ) {
+        self.bound_conditions.insert(cond, (val, bound, holds_when));
+    }
+
+    /// Record the outcome of branching on `cond` for the rest of the current path.
+    pub(crate) fn assume_condition(&mut self, cond: Value, outcome: bool) {
+        if let Some((val, bound, holds_when)) = self.bound_conditions.get(&cond).copied() {
+            if holds_when == outcome {
+                let bound = self.upper_bound(val).map_or(bound, |b| b.min(bound));
+                self.guard_bounds.insert(val, bound);
+            }
+        }
+    }
+
+    /// Forget all path-sensitive bounds, e.g. when control flow merges.
+    pub(crate) fn clear_guard_bounds(&mut self) {
+        self.guard_bounds.clear();
+    }
 }
 
 /// Methods for handling entity references.
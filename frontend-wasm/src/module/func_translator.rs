@@ -6,16 +6,25 @@
 //!
 //! Based on Cranelift's Wasm -> CLIF translator v11.0.0
 
-use crate::code_translator::translate_operator;
-use crate::error::WasmResult;
+use crate::code_translator::{
+    build_dispatcher_body, build_float_intrinsic_body, translate_operator, Dispatcher,
+    FloatIntrinsic,
+};
+use crate::error::{UnsupportedUse, WasmError, WasmResult};
 use crate::module::func_translation_state::FuncTranslationState;
 use crate::module::function_builder_ext::{FunctionBuilderContext, FunctionBuilderExt};
-use crate::module::types::{convert_valtype, ir_type, ModuleTypes};
+use crate::module::line_table::FunctionSpans;
+use crate::module::types::{convert_valtype, ir_type, FuncIndex, ModuleTypes};
 use crate::ssa::Variable;
 use crate::translation_utils::emit_zero;
+use crate::WasmTranslationConfig;
 use miden_diagnostics::{DiagnosticsHandler, SourceSpan};
 use miden_hir::cranelift_entity::EntityRef;
-use miden_hir::{Block, InstBuilder, ModuleFunctionBuilder};
+use miden_hir::Type::*;
+use miden_hir::{Block, Immediate, InstBuilder, ModuleFunctionBuilder, Symbol};
+use midenc_session::InitTrapPolicy;
+use rustc_hash::FxHashMap;
+use std::collections::BTreeSet;
 use wasmparser::{BinaryReader, FuncValidator, FunctionBody, WasmModuleResources};
 
 use super::Module;
@@ -28,6 +37,10 @@ use super::Module;
 pub struct FuncTranslator {
     func_ctx: FunctionBuilderContext,
     state: FuncTranslationState,
+    /// When set, uses of unsupported features are recorded here instead of failing translation
+    unsupported: Option<Vec<UnsupportedUse>>,
+    /// Whether or not function bodies are validated as they are translated
+    validate: bool,
 }
 
 impl FuncTranslator {
@@ -36,18 +49,102 @@ impl FuncTranslator {
         Self {
             func_ctx: FunctionBuilderContext::new(),
             state: FuncTranslationState::new(),
+            unsupported: None,
+            validate: true,
         }
     }
 
+    /// Create a new translator which trusts that the function bodies it translates are valid,
+    /// and so does not validate them.
+    ///
+    /// Translating an invalid function body with such a translator is undefined behavior: it may
+    /// panic, or produce IR which does not reflect the input, rather than report an error.
+    pub fn without_validation() -> Self {
+        Self {
+            validate: false,
+            ..Self::new()
+        }
+    }
+
+    /// Create a new translator which records any unsupported operator it encounters and keeps
+    /// going, rather than failing.
+    ///
+    /// The remainder of the block containing an unsupported operator is treated as unreachable, so
+    /// the resulting IR is only useful for finding the unsupported features used by a module, see
+    /// [FuncTranslator::take_unsupported].
+    pub fn recording_unsupported() -> Self {
+        Self {
+            unsupported: Some(vec![]),
+            ..Self::new()
+        }
+    }
+
+    /// Take the uses of unsupported features recorded so far
+    pub fn take_unsupported(&mut self) -> Vec<UnsupportedUse> {
+        self.unsupported
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Take the dispatchers called by the functions translated, or dispatchers built, so far,
+    /// each of which must be built once, see [FuncTranslator::build_dispatcher]
+    pub(crate) fn take_dispatchers(&mut self) -> BTreeSet<Dispatcher> {
+        std::mem::take(&mut self.state.dispatchers)
+    }
+
+    /// Build the function shared by the indirect calls dispatched by `dispatcher`
+    pub(crate) fn build_dispatcher(
+        &mut self,
+        dispatcher: Dispatcher,
+        mod_func_builder: &mut ModuleFunctionBuilder,
+        module: &Module,
+        mod_types: &ModuleTypes,
+        diagnostics: &DiagnosticsHandler,
+    ) -> WasmResult<()> {
+        let mut builder = FunctionBuilderExt::new(mod_func_builder, &mut self.func_ctx);
+        self.state.clear();
+        build_dispatcher_body(
+            dispatcher,
+            &mut self.state,
+            &mut builder,
+            module,
+            mod_types,
+            diagnostics,
+        )?;
+        builder.finalize();
+        Ok(())
+    }
+
+    /// Take the floating-point intrinsics called by the functions translated so far, each of
+    /// which must be built once, see [FuncTranslator::build_float_intrinsic]
+    pub(crate) fn take_float_intrinsics(&mut self) -> BTreeSet<FloatIntrinsic> {
+        std::mem::take(&mut self.state.float_intrinsics)
+    }
+
+    /// Build the function of the floating-point intrinsic `intrinsic`
+    pub(crate) fn build_float_intrinsic(
+        &mut self,
+        intrinsic: FloatIntrinsic,
+        mod_func_builder: &mut ModuleFunctionBuilder,
+    ) {
+        let mut builder = FunctionBuilderExt::new(mod_func_builder, &mut self.func_ctx);
+        build_float_intrinsic_body(intrinsic, &mut builder);
+        builder.finalize();
+    }
+
     /// Translate a binary WebAssembly function from a `FunctionBody`.
     pub fn translate_body(
         &mut self,
+        func_index: FuncIndex,
         body: &FunctionBody<'_>,
         mod_func_builder: &mut ModuleFunctionBuilder,
         module: &Module,
         mod_types: &ModuleTypes,
         diagnostics: &DiagnosticsHandler,
         func_validator: &mut FuncValidator<impl WasmModuleResources>,
+        config: &WasmTranslationConfig,
+        spans: &FunctionSpans,
     ) -> WasmResult<()> {
         let mut reader = body.get_binary_reader();
 
@@ -62,8 +159,55 @@ impl FuncTranslator {
         let exit_block = builder.create_block();
         builder.append_block_params_for_function_returns(exit_block);
         self.state.initialize(&builder.signature(), exit_block);
+        if config.init_trap_policy == InitTrapPolicy::Report
+            && module.ctors_func() == Some(func_index)
+            && builder.signature().results().is_empty()
+        {
+            self.state.trap_flag = Some(module.init_trapped_name());
+        }
 
-        parse_local_decls(&mut reader, &mut builder, num_params, func_validator)?;
+        let mut func_validator = self.validate.then_some(func_validator);
+        let num_locals = parse_local_decls(
+            &mut reader,
+            &mut builder,
+            num_params,
+            func_validator.as_deref_mut(),
+        )?;
+        // The names of locals are only recorded when generating debug info
+        if let Some(names) = module.name_section.locals_names.get(&func_index) {
+            name_locals(&mut builder, entry_block, num_params, num_locals, names);
+        }
+        // The start function is only called by the program before the entrypoint, if there is
+        // one, so every export runs it once, unless it has already been run, which it records
+        // itself, so that it is not run again after being called by the program
+        if let Some(start) = module.defined_start_func() {
+            if start == func_index {
+                emit_set_guard(&mut builder, &module.start_guard_name());
+            } else if module.is_exported_function(func_index) {
+                emit_guarded_call(
+                    &mut builder,
+                    &mut self.state,
+                    start,
+                    &module.start_guard_name(),
+                    module,
+                    mod_types,
+                    diagnostics,
+                )?;
+            }
+        }
+        if config.call_ctors_in_exports && module.is_exported_function(func_index) {
+            if let Some(ctors) = module.ctors_func().filter(|ctors| *ctors != func_index) {
+                emit_guarded_call(
+                    &mut builder,
+                    &mut self.state,
+                    ctors,
+                    &module.ctors_guard_name(),
+                    module,
+                    mod_types,
+                    diagnostics,
+                )?;
+            }
+        }
         parse_function_body(
             reader,
             &mut builder,
@@ -72,6 +216,9 @@ impl FuncTranslator {
             mod_types,
             diagnostics,
             func_validator,
+            config,
+            spans,
+            self.unsupported.as_mut(),
         )?;
 
         builder.finalize();
@@ -97,15 +244,43 @@ fn declare_parameters(builder: &mut FunctionBuilderExt, entry_block: Block) -> u
     next_local
 }
 
+/// Give the local variables, and the parameters among them, the names given to them in the name
+/// section of the module, ignoring the names of locals which are not declared.
+///
+/// The values later assigned to the locals are named after them, but their initial values are
+/// not, as they are shared by all the locals of the same type.
+fn name_locals(
+    builder: &mut FunctionBuilderExt,
+    entry_block: Block,
+    num_params: usize,
+    num_locals: usize,
+    names: &FxHashMap<u32, String>,
+) {
+    for (&index, name) in names.iter() {
+        let index = index as usize;
+        if index >= num_locals {
+            continue;
+        }
+        let name = Symbol::intern(name);
+        builder.set_var_name(Variable::new(index), name);
+        if index < num_params {
+            let param = builder.block_params(entry_block)[index];
+            builder.data_flow_graph_mut().set_value_name(param, name);
+        }
+    }
+}
+
 /// Parse the local variable declarations that precede the function body.
 ///
-/// Declare local variables, starting from `num_params`.
+/// Declare local variables, starting from `num_params`, validating them with `validator`, if given.
+///
+/// Return the number of local variables declared, including the parameters.
 fn parse_local_decls(
     reader: &mut BinaryReader,
     builder: &mut FunctionBuilderExt,
     num_params: usize,
-    validator: &mut FuncValidator<impl WasmModuleResources>,
-) -> WasmResult<()> {
+    mut validator: Option<&mut FuncValidator<impl WasmModuleResources>>,
+) -> WasmResult<usize> {
     let mut next_local = num_params;
     let local_count = reader.read_var_u32()?;
 
@@ -113,11 +288,13 @@ fn parse_local_decls(
         let pos = reader.original_position();
         let count = reader.read_var_u32()?;
         let ty = reader.read()?;
-        validator.define_locals(pos, count, ty)?;
+        if let Some(validator) = validator.as_deref_mut() {
+            validator.define_locals(pos, count, ty)?;
+        }
         declare_locals(builder, count, ty, &mut next_local)?;
     }
 
-    Ok(())
+    Ok(next_local)
 }
 
 /// Declare `count` local variables of the same type, starting from `next_local`.
@@ -141,10 +318,66 @@ fn declare_locals(
     Ok(())
 }
 
+/// Emit a call to `callee`, i.e. the start function or the static constructors, unless it has
+/// already been run, as recorded by the global `guard_name`, leaving the builder in a new block in
+/// which the body of the function can be translated.
+fn emit_guarded_call(
+    builder: &mut FunctionBuilderExt,
+    state: &mut FuncTranslationState,
+    callee: FuncIndex,
+    guard_name: &str,
+    module: &Module,
+    mod_types: &ModuleTypes,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let span = SourceSpan::default();
+    let (callee, _) = state.get_direct_func(
+        builder.data_flow_graph_mut(),
+        callee,
+        module,
+        mod_types,
+        diagnostics,
+    )?;
+    let call_block = builder.create_block();
+    let body_block = builder.create_block();
+
+    let guard = builder.ins().load_symbol(guard_name, U32, span);
+    let not_run = builder.ins().eq_imm(guard, Immediate::U32(0), span);
+    builder
+        .ins()
+        .cond_br(not_run, call_block, &[], body_block, &[], span);
+    builder.seal_block(call_block);
+
+    // The guard is set before the callee is called, so that an export called by it does not run
+    // it again
+    builder.switch_to_block(call_block);
+    emit_set_guard(builder, guard_name);
+    builder.ins().call(callee, &[], span);
+    builder.ins().br(body_block, &[], span);
+    builder.seal_block(body_block);
+
+    builder.switch_to_block(body_block);
+    Ok(())
+}
+
+/// Emit a store of a non-zero value to the guard global `guard_name`, see [emit_guarded_call]
+fn emit_set_guard(builder: &mut FunctionBuilderExt, guard_name: &str) {
+    let span = SourceSpan::default();
+    let guard_ptr = builder.ins().symbol_addr(guard_name, Ptr(U32.into()), span);
+    let run = builder.ins().u32(1, span);
+    builder.ins().store(guard_ptr, run, span);
+}
+
 /// Parse the function body in `reader`.
 ///
 /// This assumes that the local variable declarations have already been parsed and function
 /// arguments and locals are declared in the builder.
+///
+/// If `unsupported` is given, operators using unsupported features are recorded there, and the
+/// rest of the enclosing block is treated as unreachable, rather than failing translation.
+///
+/// Operators are validated with `func_validator`, if given, before they are translated, and the
+/// instructions emitted for each are given its span in `spans`.
 fn parse_function_body(
     mut reader: BinaryReader,
     builder: &mut FunctionBuilderExt,
@@ -152,27 +385,98 @@ fn parse_function_body(
     module: &Module,
     mod_types: &ModuleTypes,
     diagnostics: &DiagnosticsHandler,
-    func_validator: &mut FuncValidator<impl WasmModuleResources>,
+    mut func_validator: Option<&mut FuncValidator<impl WasmModuleResources>>,
+    config: &WasmTranslationConfig,
+    spans: &FunctionSpans,
+    mut unsupported: Option<&mut Vec<UnsupportedUse>>,
 ) -> WasmResult<()> {
     // The control stack is initialized with a single block representing the whole function.
     debug_assert_eq!(state.control_stack.len(), 1, "State not initialized");
 
+    let mut num_ops = 0usize;
     while !reader.eof() {
         let pos = reader.original_position();
         let op = reader.read_operator()?;
-        func_validator.op(pos, &op)?;
-        translate_operator(
+        num_ops += 1;
+        if let Some(max_function_size) = config.max_function_size {
+            if num_ops > max_function_size {
+                diagnostics
+                    .diagnostic(miden_diagnostics::Severity::Error)
+                    .with_message(format!(
+                        "function {} exceeds the maximum function size of {max_function_size} \
+                         instructions",
+                        builder.id()
+                    ))
+                    .with_note("the limit is set by `WasmTranslationConfig::max_function_size`")
+                    .emit();
+                return Err(WasmError::InvalidFunctionError);
+            }
+        }
+        let validated = match func_validator.as_deref_mut() {
+            Some(func_validator) => func_validator.op(pos, &op),
+            None => Ok(()),
+        };
+        if let Err(err) = validated {
+            // Type errors (e.g. storing a `funcref` into an `externref` table) are only caught by
+            // the validator, so make sure they are reported rather than silently propagated
+            diagnostics
+                .diagnostic(miden_diagnostics::Severity::Error)
+                .with_message(format!(
+                    "invalid Wasm operator {op:?} at offset {pos}: {}",
+                    err.message()
+                ))
+                .emit();
+            return Err(err.into());
+        }
+        let result = translate_operator(
             &op,
             builder,
             state,
             module,
             mod_types,
             diagnostics,
-            SourceSpan::default(),
-        )?;
+            config,
+            spans.span_at(pos as u64),
+            pos,
+        );
+        match (result, unsupported.as_deref_mut()) {
+            (Ok(()), _) => (),
+            (Err(WasmError::UnsupportedFeature { feature, message }), Some(unsupported)) => {
+                unsupported.push(UnsupportedUse {
+                    feature,
+                    message,
+                    function: Some(builder.id().to_string()),
+                    offset: Some(pos),
+                });
+                // Unsupported operators are rejected before anything is emitted for them, so we
+                // can carry on from here as if the operator had trapped
+                if state.reachable {
+                    builder.ins().unreachable(SourceSpan::default());
+                    state.reachable = false;
+                }
+            }
+            (Err(err @ WasmError::InvalidBranchDepth { .. }), _) => {
+                // Only unvalidated function bodies can get here, so this is reported in the same
+                // way as the errors caught by the validator
+                let message = err.to_string();
+                diagnostics
+                    .diagnostic(miden_diagnostics::Severity::Error)
+                    .with_message(format!(
+                        "invalid Wasm operator {op:?} at offset {pos}: {message}"
+                    ))
+                    .emit();
+                return Err(WasmError::InvalidWebAssembly {
+                    message,
+                    offset: pos,
+                });
+            }
+            (Err(err), _) => return Err(err),
+        }
+    }
+    if let Some(func_validator) = func_validator {
+        let pos = reader.original_position();
+        func_validator.finish(pos)?;
     }
-    let pos = reader.original_position();
-    func_validator.finish(pos)?;
 
     // The final `End` operator left us in the exit block where we need to manually add a return
     // instruction.
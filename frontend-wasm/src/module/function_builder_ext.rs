@@ -5,6 +5,7 @@ use miden_hir::Block;
 use miden_hir::Br;
 use miden_hir::CondBr;
 use miden_hir::DataFlowGraph;
+use miden_hir::FunctionIdent;
 use miden_hir::InsertionPoint;
 use miden_hir::Inst;
 use miden_hir::InstBuilderBase;
@@ -12,6 +13,7 @@ use miden_hir::Instruction;
 use miden_hir::ModuleFunctionBuilder;
 use miden_hir::ProgramPoint;
 use miden_hir::Switch;
+use miden_hir::Symbol;
 use miden_hir::Value;
 use miden_hir_type::Type;
 
@@ -81,6 +83,10 @@ impl<'a, 'b, 'c> FunctionBuilderExt<'a, 'b, 'c> {
         self.inner.data_flow_graph_mut()
     }
 
+    pub fn id(&self) -> FunctionIdent {
+        self.inner.id()
+    }
+
     pub fn signature(&self) -> &miden_hir::Signature {
         self.inner.signature()
     }
@@ -309,9 +315,22 @@ impl<'a, 'b, 'c> FunctionBuilderExt<'a, 'b, 'c> {
         self.func_ctx
             .ssa
             .def_var(var, val, self.inner.current_block());
+        // A value assigned to several variables keeps the name of the first of them
+        if let Some(name) = self.func_ctx.ssa.var_name(var) {
+            let dfg = self.inner.data_flow_graph_mut();
+            if dfg.value_name(val).is_none() {
+                dfg.set_value_name(val, name);
+            }
+        }
         Ok(())
     }
 
+    /// Gives a name to a user variable, which is given to the values subsequently assigned to it,
+    /// and to the block parameters created to merge its definitions, to make the IR easier to read.
+    pub fn set_var_name(&mut self, var: Variable, name: Symbol) {
+        self.func_ctx.ssa.set_var_name(var, name);
+    }
+
     /// Register a new definition of a user variable. The type of the value must be
     /// the same as the type registered for the variable.
     pub fn def_var(&mut self, var: Variable, val: Value) {
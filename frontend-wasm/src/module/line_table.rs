@@ -0,0 +1,182 @@
+//! Source locations of Wasm code, as described by the DWARF line programs of a module.
+
+use std::ops::Range;
+use std::path::PathBuf;
+
+use gimli::ColumnType;
+use miden_diagnostics::{CodeMap, SourceId, SourceSpan};
+use rustc_hash::FxHashMap;
+
+use super::module_env::Dwarf;
+
+/// A location in the source code a Wasm module was compiled from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// The path of the source file, joined to its directory, if one is given
+    pub file: String,
+    /// The 1-based line number in the source file
+    pub line: u32,
+    /// The 1-based column number in the line, or `None` if the location is the whole line
+    pub column: Option<u32>,
+}
+
+/// Maps offsets in the code section of a Wasm module to source locations.
+///
+/// Addresses in the DWARF sections of a Wasm module are offsets from the start of the contents
+/// of the code section, so the offsets given to this table must be relative to it as well.
+#[derive(Debug, Default)]
+pub struct LineTable {
+    /// The rows of all line programs, sorted by address.
+    ///
+    /// A row without a location marks the end of a sequence, i.e. the address following the
+    /// last instruction the preceding rows describe.
+    rows: Vec<(u64, Option<SourceLocation>)>,
+}
+
+impl LineTable {
+    /// Build the line table from the line programs of all units in `dwarf`
+    pub fn parse(dwarf: &Dwarf<'_>) -> gimli::Result<Self> {
+        let mut rows = vec![];
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            let Some(program) = unit.line_program.clone() else {
+                continue;
+            };
+            let mut program_rows = program.rows();
+            while let Some((header, row)) = program_rows.next_row()? {
+                if row.end_sequence() {
+                    rows.push((row.address(), None));
+                    continue;
+                }
+                let (Some(file), Some(line)) = (row.file(header), row.line()) else {
+                    continue;
+                };
+                let mut path = PathBuf::new();
+                if let Some(dir) = file.directory(header) {
+                    path.push(&*dwarf.attr_string(&unit, dir)?.to_string_lossy());
+                }
+                path.push(
+                    &*dwarf
+                        .attr_string(&unit, file.path_name())?
+                        .to_string_lossy(),
+                );
+                let location = SourceLocation {
+                    file: path.to_string_lossy().into_owned(),
+                    line: u32::try_from(line.get()).unwrap_or(u32::MAX),
+                    column: match row.column() {
+                        ColumnType::LeftEdge => None,
+                        ColumnType::Column(column) => {
+                            Some(u32::try_from(column.get()).unwrap_or(u32::MAX))
+                        }
+                    },
+                };
+                rows.push((row.address(), Some(location)));
+            }
+        }
+        // The sort is stable, so rows of a sequence at the same address keep their order
+        rows.sort_by_key(|(address, _)| *address);
+        Ok(Self { rows })
+    }
+
+    /// Get the source location of the code in `range`.
+    ///
+    /// This is the location of the first row within `range`, if there is one, otherwise it is
+    /// the location of the row in effect at the start of `range`.
+    pub fn location(&self, range: Range<u64>) -> Option<&SourceLocation> {
+        let start = self
+            .rows
+            .partition_point(|(address, _)| *address < range.start);
+        let within = self.rows[start..]
+            .iter()
+            .take_while(|(address, _)| *address < range.end)
+            .find_map(|(_, location)| location.as_ref());
+        within.or_else(|| {
+            // The last row before the start of the range, which ends its sequence if it has no
+            // location, in which case the start of the range is not described by any row
+            self.rows[..start]
+                .last()
+                .and_then(|(_, location)| location.as_ref())
+        })
+    }
+    /// Get the source location of the instruction at `address`.
+    ///
+    /// This is the location of the last row at or before `address`, unless that row ends its
+    /// sequence, in which case `address` is not described by any row.
+    pub fn location_at(&self, address: u64) -> Option<&SourceLocation> {
+        let end = self.rows.partition_point(|(row, _)| *row <= address);
+        self.rows[..end]
+            .last()
+            .and_then(|(_, location)| location.as_ref())
+    }
+    /// Get the rows describing the code in `range`, starting with the row in effect at its start
+    fn rows_in(&self, range: Range<u64>) -> &[(u64, Option<SourceLocation>)] {
+        let start = self
+            .rows
+            .partition_point(|(address, _)| *address <= range.start)
+            .saturating_sub(1);
+        let end = self
+            .rows
+            .partition_point(|(address, _)| *address < range.end);
+        &self.rows[start..end.max(start)]
+    }
+}
+
+/// The source files of a module which have been loaded into the code map, by path, or `None` for
+/// those which cannot be read. These are shared by the functions of the module, so that each file
+/// is only loaded once, however many functions it contains.
+pub type SourceFiles<'a> = FxHashMap<&'a str, Option<SourceId>>;
+
+/// The source spans of the code of a single function.
+///
+/// These are resolved from the rows of the line table describing the function when it is
+/// translated, so that finding the span of each of its operators only searches these rows, and
+/// each source file is loaded into the code map once.
+#[derive(Debug, Default)]
+pub struct FunctionSpans {
+    /// The offset in the module binary at which each row starts, with the span of its location
+    rows: Vec<(u64, SourceSpan)>,
+}
+
+impl FunctionSpans {
+    /// Resolve the spans of the code in `range`, relative to the code section, which starts at
+    /// `code_section_offset` in the module binary, loading the source files which are not yet
+    /// in `files` into `codemap`.
+    ///
+    /// Locations in source files which cannot be read, e.g. as the module was compiled on
+    /// another machine, are given the default span.
+    pub fn new<'a>(
+        line_table: &'a LineTable,
+        range: Range<u64>,
+        code_section_offset: u64,
+        codemap: &CodeMap,
+        files: &mut SourceFiles<'a>,
+    ) -> Self {
+        let rows = line_table
+            .rows_in(range)
+            .iter()
+            .map(|(address, location)| {
+                let span = location.as_ref().and_then(|location| {
+                    let source_id = *files
+                        .entry(location.file.as_str())
+                        .or_insert_with(|| codemap.add_file(&location.file).ok());
+                    // Lines and columns are 1-based in DWARF, but 0-based in the code map
+                    let line = location.line.saturating_sub(1);
+                    let column = location.column.unwrap_or(1).saturating_sub(1);
+                    codemap.line_column_to_span(source_id?, line, column).ok()
+                });
+                (code_section_offset + address, span.unwrap_or_default())
+            })
+            .collect();
+        Self { rows }
+    }
+
+    /// Get the span of the operator at `offset` in the module binary
+    pub fn span_at(&self, offset: u64) -> SourceSpan {
+        let end = self.rows.partition_point(|(row, _)| *row <= offset);
+        self.rows[..end]
+            .last()
+            .map(|(_, span)| *span)
+            .unwrap_or_default()
+    }
+}
@@ -5,7 +5,7 @@
 
 use crate::component::SignatureIndex;
 use crate::error::WasmResult;
-use crate::unsupported_diag;
+use crate::{unsupported_diag, UnsupportedFeature};
 
 use self::types::*;
 
@@ -14,15 +14,15 @@ use miden_diagnostics::DiagnosticsHandler;
 use miden_hir::cranelift_entity::packed_option::ReservedValue;
 use miden_hir::cranelift_entity::{EntityRef, PrimaryMap};
 use miden_hir::{FunctionIdent, Signature};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::BTreeMap;
 
-use std::ops::Range;
-
 pub mod build_ir;
+pub mod call_graph;
 pub mod func_translation_state;
 pub mod func_translator;
 pub mod function_builder_ext;
+pub mod line_table;
 pub mod module_env;
 pub mod types;
 
@@ -73,7 +73,24 @@ pub struct TableSegment {
     /// The offset to add to the base.
     pub offset: u32,
     /// The values to write into the table elements.
-    pub elements: Box<[FuncIndex]>,
+    pub elements: Box<[TableSegmentElement]>,
+}
+
+/// A single entry of a table initializer segment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableSegmentElement {
+    /// A reference to the function given by the `FuncIndex`, or a null
+    /// reference if the index is the reserved value.
+    Func(FuncIndex),
+    /// The reference held by the given global, as produced by a `global.get`
+    /// element expression.
+    ///
+    /// The global is resolved when the table is initialized. For an imported
+    /// global this means its value is whatever the host provides at
+    /// instantiation, which may be any function escaping the module or a null
+    /// reference. Calling through a slot initialized from a null reference
+    /// traps, exactly as for a `ref.null` element.
+    Global(GlobalIndex),
 }
 
 /// Different types that can appear in a module.
@@ -83,6 +100,10 @@ pub struct TableSegment {
 #[derive(Debug, Copy, Clone)]
 pub enum ModuleType {
     Function(SignatureIndex),
+    /// A GC struct type, which may be declared, but not yet used, see [UnsupportedFeature::GcType]
+    Struct(StructTypeIndex),
+    /// A GC array type, which may be declared, but not yet used, see [UnsupportedFeature::GcType]
+    Array(ArrayTypeIndex),
 }
 
 impl ModuleType {
@@ -91,6 +112,9 @@ impl ModuleType {
     pub fn unwrap_function(&self) -> SignatureIndex {
         match self {
             ModuleType::Function(f) => *f,
+            ModuleType::Struct(_) | ModuleType::Array(_) => {
+                panic!("attempted to unwrap a non-function type {self:?} as a function type")
+            }
         }
     }
 }
@@ -107,6 +131,9 @@ pub struct Module {
     /// Exported entities.
     pub exports: IndexMap<String, EntityIndex>,
 
+    /// The set of functions exported from this module, see [Module::is_exported_function]
+    pub exported_funcs: FxHashSet<FuncIndex>,
+
     /// The module "start" function, if present.
     pub start_func: Option<FuncIndex>,
 
@@ -114,13 +141,13 @@ pub struct Module {
     pub table_initialization: TableInitialization,
 
     /// WebAssembly passive elements.
-    pub passive_elements: Vec<Box<[FuncIndex]>>,
+    pub passive_elements: Vec<Box<[TableSegmentElement]>>,
 
     /// The map from passive element index (element segment index space) to index in `passive_elements`.
     pub passive_elements_map: BTreeMap<ElemIndex, usize>,
 
-    /// The map from passive data index (data segment index space) to index in `passive_data`.
-    pub passive_data_map: BTreeMap<DataIndex, Range<u32>>,
+    /// The map from passive data index (data segment index space) to the contents of the segment.
+    pub passive_data_map: BTreeMap<DataIndex, Box<[u8]>>,
 
     /// Types declared in the wasm module.
     pub types: PrimaryMap<TypeIndex, ModuleType>,
@@ -283,7 +310,21 @@ impl Module {
         index.index() < self.num_imported_globals
     }
 
+    /// Returns the import of the given global, if it is an imported global
+    pub fn global_import(&self, index: GlobalIndex) -> Option<&ModuleImport> {
+        if !self.is_imported_global(index) {
+            return None;
+        }
+        self.imports
+            .iter()
+            .find(|import| import.index == EntityIndex::Global(index))
+    }
+
     pub fn global_name(&self, index: GlobalIndex) -> String {
+        // The storage of an imported global is provided under the name of the import
+        if let Some(import) = self.global_import(index) {
+            return format!("{}::{}", import.module, import.field);
+        }
         self.name_section
             .globals_names
             .get(&index)
@@ -291,6 +332,95 @@ impl Module {
             .unwrap_or(format!("global{}", index.as_u32()))
     }
 
+    /// Returns the name of the data segment `index`, as given by the name section, or `data{index}`
+    /// if it is not named there, e.g. because the module was stripped
+    pub fn data_segment_name(&self, index: DataSegmentIndex) -> String {
+        self.name_section
+            .data_segment_names
+            .get(&index)
+            .cloned()
+            .unwrap_or_else(|| format!("data{}", index.as_u32()))
+    }
+
+    /// Returns the name of the global variable holding the contents of the passive data segment
+    /// `index`
+    pub fn passive_data_name(&self, index: DataIndex) -> String {
+        format!("passive_data{}", index.as_u32())
+    }
+
+    /// Returns the name of the global variable which is set to a non-zero value once the passive
+    /// data segment `index` has been dropped by `data.drop`
+    pub fn passive_data_dropped_name(&self, index: DataIndex) -> String {
+        format!("passive_data{}_dropped", index.as_u32())
+    }
+
+    /// Returns the name of the global variable which is set to a non-zero value once the contents
+    /// of the passive data segment `index` have been placed in its global
+    pub fn passive_data_placed_name(&self, index: DataIndex) -> String {
+        format!("passive_data{}_placed", index.as_u32())
+    }
+
+    /// Returns the name of the function which places the contents of the passive data segment
+    /// `index` in its global, the first time it is called
+    pub fn passive_data_place_name(&self, index: DataIndex) -> String {
+        format!("passive_data{}_place", index.as_u32())
+    }
+
+    /// Returns the name of the function which dispatches `call_indirect` through the table
+    /// `table` to its entries of type `signature`
+    pub fn table_dispatcher_name(&self, table: TableIndex, signature: SignatureIndex) -> String {
+        format!("table{}_dispatch_sig{}", table.as_u32(), signature.as_u32())
+    }
+
+    /// Returns the name of the function which dispatches `call_ref` on references to the
+    /// functions of type `signature`
+    pub fn ref_dispatcher_name(&self, signature: SignatureIndex) -> String {
+        format!("ref_dispatch_sig{}", signature.as_u32())
+    }
+
+    /// Returns the index of `__wasm_call_ctors`, the function which runs the static constructors
+    /// of this module, if it defines one
+    pub fn ctors_func(&self) -> Option<FuncIndex> {
+        self.name_section.ctors_func
+    }
+
+    /// Returns the index of the start function of this module, if it defines one, rather than
+    /// importing it
+    pub fn defined_start_func(&self) -> Option<FuncIndex> {
+        self.start_func
+            .filter(|start| !self.is_imported_function(*start))
+    }
+
+    /// Returns the name of the global variable which is set to a non-zero value once the start
+    /// function of this module has been run, see [Module::defined_start_func]
+    pub fn start_guard_name(&self) -> String {
+        "__wasm_start_guard".to_string()
+    }
+
+    /// Returns the name of the global variable which is set to a non-zero value once the static
+    /// constructors of this module have been run, see [Module::ctors_func]
+    pub fn ctors_guard_name(&self) -> String {
+        "__wasm_call_ctors_guard".to_string()
+    }
+
+    /// Returns the name of the global variable which is set to a non-zero value if a trap in the
+    /// static constructors of this module was caught, see [Module::ctors_func]
+    pub fn init_trapped_name(&self) -> String {
+        "__wasm_init_trapped".to_string()
+    }
+
+    /// Returns the name of the global variable holding the current size, in 64 KiB pages, of the
+    /// linear memory of this module, which starts at its declared initial size, and is increased
+    /// by `memory.grow`
+    pub fn memory_size_name(&self) -> String {
+        "__wasm_memory_size".to_string()
+    }
+
+    /// Returns true if the given function is exported from this module
+    pub fn is_exported_function(&self, index: FuncIndex) -> bool {
+        self.exported_funcs.contains(&index)
+    }
+
     /// Returns the type of an item based on its index
     pub fn type_of(&self, index: EntityIndex) -> EntityType {
         match index {
@@ -332,8 +462,40 @@ impl Module {
         if let Some(defined_index) = self.defined_global_index(index) {
             Ok(&self.global_initializers[defined_index])
         } else {
-            unsupported_diag!(diagnostics, "Imported globals are not supported yet");
+            unsupported_diag!(
+                diagnostics,
+                UnsupportedFeature::ImportedGlobal,
+                "Imported globals are not supported yet"
+            );
+        }
+    }
+
+    /// Returns the constant the global `index` is initialized with, following the `global.get`s
+    /// its initializer may be made of, e.g. `42` for a global initialized with the value of
+    /// another global, itself initialized with `42`.
+    ///
+    /// Fails with an [UnsupportedFeature::ImportedGlobal] error naming the global if its value
+    /// depends on that of an imported global, which is not known at compile time.
+    pub fn const_global_initializer(
+        &self,
+        index: GlobalIndex,
+        diagnostics: &DiagnosticsHandler,
+    ) -> WasmResult<GlobalInit> {
+        let mut init = *self.try_global_initializer(index, diagnostics)?;
+        while let GlobalInit::GetGlobal(referenced) = init {
+            let Some(defined) = self.defined_global_index(referenced) else {
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::ImportedGlobal,
+                    "the initializer of global '{}' depends on the imported global '{}', the \
+                     value of which is not known at compile time",
+                    self.global_name(index),
+                    self.global_name(referenced)
+                );
+            };
+            init = self.global_initializers[defined];
         }
+        Ok(init)
     }
 
     /// Returns the name of this module
@@ -389,6 +551,8 @@ miden_hir::cranelift_entity::entity_impl!(FuncRefIndex);
 pub struct NameSection {
     pub module_name: Option<String>,
     pub func_names: FxHashMap<FuncIndex, String>,
+    /// The defined function named `__wasm_call_ctors`, if any, see [Module::ctors_func]
+    pub ctors_func: Option<FuncIndex>,
     pub locals_names: FxHashMap<FuncIndex, FxHashMap<u32, String>>,
     pub globals_names: FxHashMap<GlobalIndex, String>,
     pub data_segment_names: FxHashMap<DataSegmentIndex, String>,
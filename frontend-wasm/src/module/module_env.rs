@@ -1,31 +1,39 @@
 use crate::component::SignatureIndex;
 use crate::error::WasmResult;
 use crate::module::types::{
-    convert_func_type, convert_global_type, convert_table_type, convert_valtype, DataSegmentOffset,
-    DefinedFuncIndex, ElemIndex, EntityIndex, EntityType, FuncIndex, GlobalIndex, GlobalInit,
-    MemoryIndex, ModuleTypesBuilder, TableIndex, TypeIndex, WasmType,
+    convert_array_type, convert_func_type, convert_global_type, convert_struct_type,
+    convert_table_type, convert_valtype, DataSegmentOffset, DefinedFuncIndex, ElemIndex,
+    EntityIndex, EntityType, FuncIndex, GlobalIndex, GlobalInit, Memory, MemoryIndex,
+    ModuleTypesBuilder, Table, TableIndex, TypeIndex, WasmType,
 };
-use crate::module::{FuncRefIndex, Module, ModuleType, TableSegment};
-use crate::{unsupported_diag, WasmError, WasmTranslationConfig};
+use crate::module::{FuncRefIndex, Module, ModuleType, TableSegment, TableSegmentElement};
+use crate::{unsupported_diag, UnsupportedFeature, WasmError, WasmTranslationConfig};
 
-use miden_diagnostics::DiagnosticsHandler;
+use miden_diagnostics::{DiagnosticsHandler, Severity};
 use miden_hir::cranelift_entity::packed_option::ReservedValue;
 use miden_hir::cranelift_entity::PrimaryMap;
 use rustc_hash::FxHashMap;
+use std::cell::OnceCell;
 use std::convert::TryFrom;
 use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::Arc;
 use wasmparser::types::CoreTypeId;
 use wasmparser::{
-    CompositeType, CustomSectionReader, DataKind, ElementItems, ElementKind, Encoding,
-    ExternalKind, FuncToValidate, FunctionBody, NameSectionReader, Naming, Operator, Parser,
-    Payload, TypeRef, Validator, ValidatorResources,
+    CompositeType, ConstExpr, CustomSectionReader, DataKind, Dylink0SectionReader,
+    Dylink0Subsection, ElementItems, ElementKind, Encoding, ExternalKind, FuncToValidate,
+    FunctionBody, NameSectionReader, Naming, Operator, Parser, Payload, SubType, TableInit,
+    TypeRef, Validator, ValidatorResources,
 };
 
-use super::types::{DataSegment, DataSegmentIndex};
+use super::line_table::{LineTable, SourceLocation};
+use super::types::{DataIndex, DataSegment, DataSegmentIndex};
 use super::{ModuleImport, TableInitialValue};
 
+/// The modules of the WASI preview1 API, including its earlier `wasi_unstable` snapshot, which
+/// may be imported by the adapter module embedded by `cargo component`
+const WASI_MODULES: [&str; 2] = ["wasi_snapshot_preview1", "wasi_unstable"];
+
 /// Object containing the standalone environment information.
 pub struct ModuleEnvironment<'a, 'data> {
     /// The current module being translated
@@ -66,9 +74,50 @@ pub struct ParsedModule<'data> {
     /// List of data segments found in this module
     pub data_segments: PrimaryMap<DataSegmentIndex, DataSegment<'data>>,
 
+    /// Dynamic linking metadata, parsed from the `dylink.0` custom section, if present.
+    ///
+    /// This is purely informational when compiling for the default, statically-linked, model.
+    pub dylink: Option<DylinkInfo>,
+
     /// When we're parsing the code section this will be incremented so we know
     /// which function is currently being defined.
     code_index: u32,
+
+    /// The line table of `debuginfo`, built on the first call to [ParsedModule::source_location]
+    line_table: OnceCell<LineTable>,
+}
+
+impl<'data> ParsedModule<'data> {
+    /// Get the source location of the Wasm code at `code_offset`, an offset from the start of
+    /// the module binary, as described by the DWARF line programs of the module.
+    ///
+    /// Returns `None` if the offset is not in the code section, or no line program describes
+    /// it. DWARF sections are only recorded when parsing Wasm debug info is enabled, see
+    /// [WasmTranslationConfig::parse_wasm_debuginfo], and malformed ones describe nothing.
+    pub fn source_location(&self, code_offset: u64) -> Option<SourceLocation> {
+        // DWARF addresses are relative to the start of the code section
+        let address = code_offset.checked_sub(self.debuginfo.wasm_file.code_section_offset)?;
+        let line_table = self
+            .line_table
+            .get_or_init(|| LineTable::parse(&self.debuginfo.dwarf).unwrap_or_default());
+        line_table.location_at(address).cloned()
+    }
+}
+
+/// Dynamic linking metadata of a module, as described by its `dylink.0` custom section, see
+/// <https://github.com/WebAssembly/tool-conventions/blob/main/DynamicLinking.md>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DylinkInfo {
+    /// The size, in bytes, of the memory the module requires for its static data
+    pub memory_size: u32,
+    /// The alignment of the module's static data, as a power of 2
+    pub memory_alignment: u32,
+    /// The number of table elements the module requires
+    pub table_size: u32,
+    /// The alignment of the module's table elements, as a power of 2
+    pub table_alignment: u32,
+    /// The names of the dynamic libraries the module depends on
+    pub needed: Vec<String>,
 }
 
 /// Contains function data: byte code and its offset in the module.
@@ -153,30 +202,33 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                 encoding,
                 range,
             } => {
-                self.validator.version(num, encoding, &range)?;
-                match encoding {
-                    Encoding::Module => {}
-                    Encoding::Component => {
-                        return Err(WasmError::Unsupported(format!("component model")));
-                    }
+                // Components are rejected before they are validated, as the component model may
+                // not be among the features accepted by the validator
+                if encoding == Encoding::Component {
+                    return Err(WasmError::Unsupported(
+                        "the binary is a Wasm component, rather than a core module, use \
+                         `translate_component` to translate it"
+                            .to_string(),
+                    ));
                 }
+                self.validator.version(num, encoding, &range)?;
             }
             Payload::End(offset) => self.payload_end(offset)?,
             Payload::TypeSection(types) => self.type_section(types)?,
-            Payload::ImportSection(imports) => self.import_section(imports)?,
+            Payload::ImportSection(imports) => self.import_section(imports, diagnostics)?,
             Payload::FunctionSection(functions) => self.function_section(functions)?,
-            Payload::TableSection(tables) => self.table_section(tables)?,
-            Payload::MemorySection(memories) => self.memory_section(memories)?,
+            Payload::TableSection(tables) => self.table_section(tables, diagnostics)?,
+            Payload::MemorySection(memories) => self.memory_section(memories, diagnostics)?,
             Payload::TagSection(tags) => {
                 self.validator.tag_section(&tags)?;
                 // This feature isn't enabled at this time, so we should
                 // never get here.
                 unreachable!();
             }
-            Payload::GlobalSection(globals) => self.global_section(globals)?,
+            Payload::GlobalSection(globals) => self.global_section(globals, diagnostics)?,
             Payload::ExportSection(exports) => self.export_section(exports)?,
             Payload::StartSection { func, range } => self.start_section(func, range)?,
-            Payload::ElementSection(elements) => self.element_section(elements)?,
+            Payload::ElementSection(elements) => self.element_section(elements, diagnostics)?,
             Payload::CodeSectionStart { count, range, .. } => {
                 self.code_section_start(count, range)?
             }
@@ -196,13 +248,25 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     log::warn!("failed to parse name section {:?}", e);
                 }
             }
+            Payload::CustomSection(s) if s.name() == "dylink.0" => {
+                let result =
+                    self.dylink_section(Dylink0SectionReader::new(s.data(), s.data_offset()));
+                if let Err(e) = result {
+                    log::warn!("failed to parse dylink.0 section {:?}", e);
+                }
+            }
             Payload::CustomSection(s) => self.dwarf_section(&s),
             // It's expected that validation will probably reject other
             // payloads such as `UnknownSection` or those related to the
             // component model.
             other => {
                 self.validator.payload(&other)?;
-                unsupported_diag!(diagnostics, "unsupported section in wasm file {:?}", other);
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::Section,
+                    "unsupported section in wasm file {:?}",
+                    other
+                );
             }
         }
         Ok(())
@@ -236,22 +300,38 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
         let num = usize::try_from(types.count()).unwrap();
         self.result.module.types.reserve(num);
         self.types.reserve_wasm_signatures(num);
-        Ok(for i in 0..types.count() {
-            let types = self.validator.types(0).unwrap();
-            let ty = types.core_type_at(i);
-            self.declare_type(ty.unwrap_sub())?;
-        })
+        // The types are converted as they are read from the section, rather than as recorded by
+        // the validator, so that concrete types are still referred to by their module type index
+        let mut index = self.result.module.types.len() as u32;
+        for rec_group in types {
+            for ty in rec_group?.into_types() {
+                let id = self
+                    .validator
+                    .types(0)
+                    .unwrap()
+                    .core_type_at(index)
+                    .unwrap_sub();
+                self.declare_type(id, ty)?;
+                index += 1;
+            }
+        }
+        Ok(())
     }
 
     fn import_section(
         &mut self,
         imports: wasmparser::ImportSectionReader<'data>,
+        diagnostics: &DiagnosticsHandler,
     ) -> Result<(), WasmError> {
         self.validator.import_section(&imports)?;
         let cnt = usize::try_from(imports.count()).unwrap();
         self.result.module.imports.reserve(cnt);
-        Ok(for entry in imports {
+        let mut wasi_imports = Vec::new();
+        for entry in imports {
             let import = entry?;
+            if WASI_MODULES.contains(&import.module) {
+                wasi_imports.push(format!("{}::{}", import.module, import.name));
+            }
             let ty = match import.ty {
                 TypeRef::Func(index) => {
                     let index = TypeIndex::from_u32(index);
@@ -261,23 +341,39 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     EntityType::Function(sig_index)
                 }
                 TypeRef::Memory(ty) => {
+                    let memory: Memory = ty.into();
+                    let index =
+                        MemoryIndex::from_u32(self.result.module.num_imported_memories as u32);
+                    self.check_memory_limits(index, &memory, diagnostics)?;
                     self.result.module.num_imported_memories += 1;
-                    EntityType::Memory(ty.into())
+                    EntityType::Memory(memory)
                 }
                 TypeRef::Global(ty) => {
                     self.result.module.num_imported_globals += 1;
                     EntityType::Global(convert_global_type(&ty))
                 }
                 TypeRef::Table(ty) => {
+                    let table = convert_table_type(&ty);
+                    let index = TableIndex::from_u32(self.result.module.num_imported_tables as u32);
+                    self.check_table_limits(index, &table, diagnostics)?;
                     self.result.module.num_imported_tables += 1;
-                    EntityType::Table(convert_table_type(&ty))
+                    EntityType::Table(table)
                 }
 
                 // doesn't get past validation
                 TypeRef::Tag(_) => unreachable!(),
             };
             self.declare_import(import.module, import.name, ty);
-        })
+        }
+        if !wasi_imports.is_empty() {
+            unsupported_diag!(
+                diagnostics,
+                UnsupportedFeature::WasiImport,
+                "WASI imports are not supported on Miden, but the module imports: {}",
+                wasi_imports.join(", ")
+            );
+        }
+        Ok(())
     }
 
     fn function_section(
@@ -298,19 +394,33 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
     fn table_section(
         &mut self,
         tables: wasmparser::TableSectionReader<'data>,
+        diagnostics: &DiagnosticsHandler,
     ) -> Result<(), WasmError> {
-        self.validator.table_section(&tables)?;
+        if let Err(err) = self.validator.table_section(&tables) {
+            let init_exprs =
+                tables
+                    .into_iter()
+                    .map_while(Result::ok)
+                    .filter_map(|table| match table.init {
+                        TableInit::Expr(init_expr) => Some(init_expr),
+                        TableInit::RefNull => None,
+                    });
+            check_const_exprs(init_exprs, "table section", diagnostics)?;
+            return Err(err.into());
+        }
         let cnt = usize::try_from(tables.count()).unwrap();
         self.result.module.tables.reserve_exact(cnt);
         Ok(for entry in tables {
             let wasmparser::Table { ty, init } = entry?;
             let table = convert_table_type(&ty);
+            let index = TableIndex::from_u32(self.result.module.tables.len() as u32);
+            self.check_table_limits(index, &table, diagnostics)?;
             self.result.module.tables.push(table);
             let init = match init {
-                wasmparser::TableInit::RefNull => TableInitialValue::Null {
+                TableInit::RefNull => TableInitialValue::Null {
                     precomputed: Vec::new(),
                 },
-                wasmparser::TableInit::Expr(cexpr) => {
+                TableInit::Expr(cexpr) => {
                     let mut init_expr_reader = cexpr.get_binary_reader();
                     match init_expr_reader.read_operator()? {
                         Operator::RefNull { hty: _ } => TableInitialValue::Null {
@@ -322,10 +432,12 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                             TableInitialValue::FuncRef(index)
                         }
                         s => {
-                            return Err(WasmError::Unsupported(format!(
-                                "unsupported init expr in table section: {:?}",
+                            unsupported_diag!(
+                                diagnostics,
+                                UnsupportedFeature::InitExpr,
+                                "unsupported constant init expr in table section: {:?}",
                                 s
-                            )));
+                            );
                         }
                     }
                 }
@@ -341,18 +453,85 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
     fn memory_section(
         &mut self,
         memories: wasmparser::MemorySectionReader<'data>,
+        diagnostics: &DiagnosticsHandler,
     ) -> Result<(), WasmError> {
         self.validator.memory_section(&memories)?;
         let cnt = usize::try_from(memories.count()).unwrap();
-        assert_eq!(cnt, 1, "only one memory per module is supported");
+        self.result.module.memories.reserve_exact(cnt);
+        // Every memory is recorded, though only accesses to the first can be lowered, which is
+        // checked where the other memories are used, i.e. by data segments and memory operators
+        Ok(for entry in memories {
+            let memory: Memory = entry?.into();
+            let index = MemoryIndex::from_u32(self.result.module.memories.len() as u32);
+            self.check_memory_limits(index, &memory, diagnostics)?;
+            self.result.module.memories.push(memory);
+        })
+    }
+
+    /// Reject the declaration of memory `index` if its initial or maximum size exceeds
+    /// [WasmTranslationConfig::max_memory_pages].
+    fn check_memory_limits(
+        &self,
+        index: MemoryIndex,
+        memory: &Memory,
+        diagnostics: &DiagnosticsHandler,
+    ) -> WasmResult<()> {
+        let limit = self.config.max_memory_pages;
+        let sizes = [
+            ("initial", Some(memory.minimum)),
+            ("maximum", memory.maximum),
+        ];
+        for (kind, size) in sizes {
+            if let Some(size) = size.filter(|size| *size > limit) {
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::MemoryLimits,
+                    "the {kind} size of memory {} is {size} pages, but at most {limit} are \
+                     supported",
+                    index.as_u32()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject the declaration of table `index` if its initial or maximum size exceeds
+    /// [WasmTranslationConfig::max_table_elements].
+    fn check_table_limits(
+        &self,
+        index: TableIndex,
+        table: &Table,
+        diagnostics: &DiagnosticsHandler,
+    ) -> WasmResult<()> {
+        let limit = self.config.max_table_elements;
+        let sizes = [("initial", Some(table.minimum)), ("maximum", table.maximum)];
+        for (kind, size) in sizes {
+            if let Some(size) = size.filter(|size| *size > limit) {
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::MemoryLimits,
+                    "the {kind} size of table {} is {size} elements, but at most {limit} are \
+                     supported",
+                    index.as_u32()
+                );
+            }
+        }
         Ok(())
     }
 
     fn global_section(
         &mut self,
         globals: wasmparser::GlobalSectionReader<'data>,
+        diagnostics: &DiagnosticsHandler,
     ) -> Result<(), WasmError> {
-        self.validator.global_section(&globals)?;
+        if let Err(err) = self.validator.global_section(&globals) {
+            let init_exprs = globals
+                .into_iter()
+                .map_while(Result::ok)
+                .map(|global| global.init_expr);
+            check_const_exprs(init_exprs, "global section", diagnostics)?;
+            return Err(err.into());
+        }
         let cnt = usize::try_from(globals.count()).unwrap();
         self.result.module.globals.reserve_exact(cnt);
         Ok(for entry in globals {
@@ -370,10 +549,12 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     GlobalInit::GetGlobal(GlobalIndex::from_u32(global_index))
                 }
                 s => {
-                    return Err(WasmError::Unsupported(format!(
-                        "unsupported init expr in global section: {:?}",
+                    unsupported_diag!(
+                        diagnostics,
+                        UnsupportedFeature::InitExpr,
+                        "unsupported constant init expr in global section: {:?}",
                         s
-                    )));
+                    );
                 }
             };
             let ty = convert_global_type(&ty);
@@ -395,6 +576,7 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                 ExternalKind::Func => {
                     let index = FuncIndex::from_u32(index);
                     self.flag_func_escaped(index);
+                    self.result.module.exported_funcs.insert(index);
                     EntityIndex::Function(index)
                 }
                 ExternalKind::Table => EntityIndex::Table(TableIndex::from_u32(index)),
@@ -423,8 +605,21 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
     fn element_section(
         &mut self,
         elements: wasmparser::ElementSectionReader<'data>,
+        diagnostics: &DiagnosticsHandler,
     ) -> Result<(), WasmError> {
-        self.validator.element_section(&elements)?;
+        if let Err(err) = self.validator.element_section(&elements) {
+            let mut init_exprs = Vec::new();
+            for element in elements.into_iter().map_while(Result::ok) {
+                if let ElementKind::Active { offset_expr, .. } = element.kind {
+                    init_exprs.push(offset_expr);
+                }
+                if let ElementItems::Expressions(_ty, items) = element.items {
+                    init_exprs.extend(items.into_iter().map_while(Result::ok));
+                }
+            }
+            check_const_exprs(init_exprs, "element section", diagnostics)?;
+            return Err(err.into());
+        }
         Ok(for (index, entry) in elements.into_iter().enumerate() {
             let wasmparser::Element {
                 kind,
@@ -432,8 +627,8 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                 range: _,
             } = entry?;
 
-            // Build up a list of `FuncIndex` corresponding to all the
-            // entries listed in this segment. Note that it's not
+            // Build up a list of `TableSegmentElement` corresponding to all
+            // the entries listed in this segment. Note that it's not
             // possible to create anything other than a `ref.null
             // extern` for externref segments, so those just get
             // translated to the reserved value of `FuncIndex`.
@@ -444,24 +639,31 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     for func in funcs {
                         let func = FuncIndex::from_u32(func?);
                         self.flag_func_escaped(func);
-                        elements.push(func);
+                        elements.push(TableSegmentElement::Func(func));
                     }
                 }
                 ElementItems::Expressions(_ty, funcs) => {
                     elements.reserve(usize::try_from(funcs.count()).unwrap());
                     for func in funcs {
                         let func = match func?.get_binary_reader().read_operator()? {
-                            Operator::RefNull { .. } => FuncIndex::reserved_value(),
+                            Operator::RefNull { .. } => {
+                                TableSegmentElement::Func(FuncIndex::reserved_value())
+                            }
                             Operator::RefFunc { function_index } => {
                                 let func = FuncIndex::from_u32(function_index);
                                 self.flag_func_escaped(func);
-                                func
+                                TableSegmentElement::Func(func)
+                            }
+                            Operator::GlobalGet { global_index } => {
+                                TableSegmentElement::Global(GlobalIndex::from_u32(global_index))
                             }
                             s => {
-                                return Err(WasmError::Unsupported(format!(
-                                    "unsupported init expr in element section: {:?}",
+                                unsupported_diag!(
+                                    diagnostics,
+                                    UnsupportedFeature::InitExpr,
+                                    "unsupported constant init expr in element section: {:?}",
                                     s
-                                )));
+                                );
                             }
                         };
                         elements.push(func);
@@ -475,17 +677,29 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     offset_expr,
                 } => {
                     let table_index = TableIndex::from_u32(table_index.unwrap_or(0));
+                    let segment = format!("element segment {index}");
+                    // Tables are always indexed by i32, as the table64 proposal is not supported
                     let mut offset_expr_reader = offset_expr.get_binary_reader();
                     let (base, offset) = match offset_expr_reader.read_operator()? {
                         Operator::I32Const { value } => (None, value as u32),
+                        Operator::I64Const { .. } => {
+                            return Err(WasmError::Unsupported(format!(
+                                "{segment} has an i64 offset, but table {} is indexed by i32",
+                                table_index.as_u32()
+                            )));
+                        }
                         Operator::GlobalGet { global_index } => {
-                            (Some(GlobalIndex::from_u32(global_index)), 0)
+                            let global_index = GlobalIndex::from_u32(global_index);
+                            self.check_segment_base(&segment, global_index, WasmType::I32)?;
+                            (Some(global_index), 0)
                         }
                         ref s => {
-                            return Err(WasmError::Unsupported(format!(
-                                "unsupported init expr in element section: {:?}",
+                            unsupported_diag!(
+                                diagnostics,
+                                UnsupportedFeature::InitExpr,
+                                "unsupported constant init expr in element section: {:?}",
                                 s
-                            )));
+                            );
                         }
                     };
 
@@ -526,8 +740,10 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
 
     fn code_section_entry(&mut self, mut body: FunctionBody<'data>) -> Result<(), WasmError> {
         let validator = self.validator.code_section_entry(&body)?;
-        let func_index = self.result.code_index + self.result.module.num_imported_funcs as u32;
-        let func_index = FuncIndex::from_u32(func_index);
+        // Code section entries are only given for defined functions, which are indexed after all
+        // of the imported functions, so the function index space must be offset by the imports
+        let defined_func_index = DefinedFuncIndex::from_u32(self.result.code_index);
+        let func_index = self.result.module.func_index(defined_func_index);
         if self.config.generate_native_debuginfo {
             let sig_index = self.result.module.functions[func_index].signature;
             let sig = &self.types[sig_index];
@@ -547,9 +763,11 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                 });
         }
         body.allow_memarg64(false);
-        self.result
+        let pushed_index = self
+            .result
             .function_body_inputs
             .push(FunctionBodyData { validator, body });
+        debug_assert_eq!(pushed_index, defined_func_index);
         self.result.code_index += 1;
         Ok(())
     }
@@ -559,7 +777,17 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
         data_section: wasmparser::DataSectionReader<'data>,
         diagnostics: &DiagnosticsHandler,
     ) -> WasmResult<()> {
-        self.validator.data_section(&data_section)?;
+        if let Err(err) = self.validator.data_section(&data_section) {
+            let init_exprs = data_section
+                .into_iter()
+                .map_while(Result::ok)
+                .filter_map(|data| match data.kind {
+                    DataKind::Active { offset_expr, .. } => Some(offset_expr),
+                    DataKind::Passive => None,
+                });
+            check_const_exprs(init_exprs, "data section", diagnostics)?;
+            return Err(err.into());
+        }
         let cnt = usize::try_from(data_section.count()).unwrap();
         self.result.data_segments.reserve_exact(cnt);
         for entry in data_section.into_iter() {
@@ -573,31 +801,55 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     memory_index,
                     offset_expr,
                 } => {
-                    assert_eq!(
-                        memory_index, 0,
-                        "data section memory index must be 0 (only one memory per module is supported)"
-                    );
+                    let segment = format!("data segment {}", self.result.data_segments.len());
+                    let index_ty = self.result.module.memories[MemoryIndex::from_u32(memory_index)]
+                        .index_type();
                     let mut offset_expr_reader = offset_expr.get_binary_reader();
                     let offset = match offset_expr_reader.read_operator()? {
-                        Operator::I32Const { value } => DataSegmentOffset::I32Const(value),
+                        Operator::I32Const { value } if index_ty == WasmType::I32 => {
+                            DataSegmentOffset::I32Const(value)
+                        }
+                        Operator::I64Const { value } if index_ty == WasmType::I64 => {
+                            DataSegmentOffset::I64Const(value)
+                        }
+                        Operator::I32Const { .. } | Operator::I64Const { .. } => {
+                            return Err(WasmError::Unsupported(format!(
+                                "{segment} has an offset which does not match the index type \
+                                 {index_ty} of memory {memory_index}"
+                            )));
+                        }
                         Operator::GlobalGet { global_index } => {
-                            DataSegmentOffset::GetGlobal(GlobalIndex::from_u32(global_index))
+                            let global_index = GlobalIndex::from_u32(global_index);
+                            self.check_segment_base(&segment, global_index, index_ty)?;
+                            DataSegmentOffset::GetGlobal(global_index)
                         }
                         ref s => {
                             unsupported_diag!(
                                 diagnostics,
-                                "unsupported init expr in data section offset: {:?}",
+                                UnsupportedFeature::InitExpr,
+                                "unsupported constant init expr in data section offset: {:?}",
                                 s
                             );
                         }
                     };
-                    let segment = DataSegment { offset, data };
+                    let segment = DataSegment {
+                        memory_index: MemoryIndex::from_u32(memory_index),
+                        offset: Some(offset),
+                        data,
+                    };
                     self.result.data_segments.push(segment);
                 }
                 DataKind::Passive => {
-                    return Err(WasmError::Unsupported(
-                        "unsupported passive data segment in data section".to_string(),
-                    ));
+                    let segment = DataSegment {
+                        memory_index: MemoryIndex::from_u32(0),
+                        offset: None,
+                        data,
+                    };
+                    let index = self.result.data_segments.push(segment);
+                    self.result
+                        .module
+                        .passive_data_map
+                        .insert(DataIndex::from_u32(index.as_u32()), data.into());
                 }
             }
         }
@@ -622,8 +874,11 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                         // names are almost always present in the
                         // final compilation artifact.
                         let index = FuncIndex::from_u32(index);
-                        self.result
-                            .module
+                        let module = &mut self.result.module;
+                        if name == "__wasm_call_ctors" && !module.is_imported_function(index) {
+                            module.name_section.ctors_func = Some(index);
+                        }
+                        module
                             .name_section
                             .func_names
                             .insert(index, name.to_string());
@@ -691,6 +946,29 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
         Ok(())
     }
 
+    fn dylink_section(&mut self, subsections: Dylink0SectionReader<'data>) -> WasmResult<()> {
+        let mut info = DylinkInfo::default();
+        for subsection in subsections {
+            match subsection? {
+                Dylink0Subsection::MemInfo(mem_info) => {
+                    info.memory_size = mem_info.memory_size;
+                    info.memory_alignment = mem_info.memory_alignment;
+                    info.table_size = mem_info.table_size;
+                    info.table_alignment = mem_info.table_alignment;
+                }
+                Dylink0Subsection::Needed(needed) => {
+                    info.needed
+                        .extend(needed.into_iter().map(|name| name.to_string()));
+                }
+                Dylink0Subsection::ExportInfo(_)
+                | Dylink0Subsection::ImportInfo(_)
+                | Dylink0Subsection::Unknown { .. } => {}
+            }
+        }
+        self.result.dylink = Some(info);
+        Ok(())
+    }
+
     fn dwarf_section(&mut self, section: &CustomSectionReader<'data>) {
         let name = section.name();
         if !name.starts_with(".debug_") {
@@ -702,6 +980,8 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
         }
         let info = &mut self.result.debuginfo;
         let dwarf = &mut info.dwarf;
+        // This is the byte order of the DWARF sections in the Wasm binary, which is always
+        // little-endian, regardless of how memory is laid out by the compiled program
         let endian = gimli::LittleEndian;
         let data = section.data();
         let slice = gimli::EndianSlice::new(data, endian);
@@ -761,6 +1041,24 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
         }
     }
 
+    /// Returns an error if the global `base`, giving the offset of `segment`, is not of
+    /// `index_ty`, the index type of the table or memory which the segment initializes.
+    fn check_segment_base(
+        &self,
+        segment: &str,
+        base: GlobalIndex,
+        index_ty: WasmType,
+    ) -> WasmResult<()> {
+        let ty = self.result.module.globals[base].ty;
+        if ty != index_ty {
+            return Err(WasmError::Unsupported(format!(
+                "{segment} is offset by global {} of type {ty}, but is indexed by {index_ty}",
+                base.as_u32()
+            )));
+        }
+        Ok(())
+    }
+
     fn flag_func_escaped(&mut self, func: FuncIndex) {
         let ty = &mut self.result.module.functions[func];
         // If this was already assigned a funcref index no need to re-assign it.
@@ -772,22 +1070,296 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
         self.result.module.num_escaped_funcs += 1;
     }
 
-    fn declare_type(&mut self, id: CoreTypeId) -> WasmResult<()> {
-        let types = self.validator.types(0).unwrap();
-        let ty = &types[id];
-        assert!(ty.is_final);
-        assert!(ty.supertype_idx.is_none());
-        match &ty.composite_type {
+    /// Declare the type `ty`. Subtyping only matters to the validator, so whether a type is final
+    /// and what its supertype is are disregarded.
+    fn declare_type(&mut self, id: CoreTypeId, ty: SubType) -> WasmResult<()> {
+        match ty.composite_type {
             CompositeType::Func(ty) => {
-                let wasm = convert_func_type(ty);
+                let wasm = convert_func_type(&ty);
                 let sig_index = self.types.wasm_func_type(id, wasm);
                 self.result
                     .module
                     .types
                     .push(ModuleType::Function(sig_index));
             }
-            CompositeType::Array(_) | CompositeType::Struct(_) => unimplemented!(),
+            // GC types can't be lowered yet, but are recorded, so that a module which merely
+            // declares them can still be translated, see `build_ir_module_using`
+            CompositeType::Struct(ty) => {
+                let index = self.types.wasm_struct_type(convert_struct_type(&ty));
+                self.result.module.types.push(ModuleType::Struct(index));
+            }
+            CompositeType::Array(ty) => {
+                let index = self.types.wasm_array_type(convert_array_type(&ty));
+                self.result.module.types.push(ModuleType::Array(index));
+            }
         }
         Ok(())
     }
 }
+
+/// Report the first non-constant operator found in `init_exprs`, the initializer expressions of
+/// a section which failed validation.
+///
+/// The validator rejects such operators with a rather opaque error, so this is used to tell
+/// initializers which can never be constant apart from constant ones we don't support yet, which
+/// are reported as [UnsupportedFeature::InitExpr] once the section is known to be valid.
+fn check_const_exprs<'data>(
+    init_exprs: impl IntoIterator<Item = ConstExpr<'data>>,
+    section: &str,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    for init_expr in init_exprs {
+        let mut reader = init_expr.get_operators_reader();
+        while let Ok((op, offset)) = reader.read_with_offset() {
+            if is_const_operator(&op) {
+                continue;
+            }
+            let message = format!("non-constant operator {op:?} in {section} init expr");
+            diagnostics
+                .diagnostic(Severity::Error)
+                .with_message(message.clone())
+                .with_note(
+                    "init exprs may only contain constant instructions, e.g. `i32.const` or \
+                     `global.get`",
+                )
+                .emit();
+            return Err(WasmError::InvalidWebAssembly { message, offset });
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if `op` may appear in a constant expression, including the arithmetic allowed by
+/// the extended-const proposal
+fn is_const_operator(op: &Operator) -> bool {
+    matches!(
+        op,
+        Operator::I32Const { .. }
+            | Operator::I64Const { .. }
+            | Operator::F32Const { .. }
+            | Operator::F64Const { .. }
+            | Operator::V128Const { .. }
+            | Operator::GlobalGet { .. }
+            | Operator::RefNull { .. }
+            | Operator::RefFunc { .. }
+            | Operator::I32Add
+            | Operator::I32Sub
+            | Operator::I32Mul
+            | Operator::I64Add
+            | Operator::I64Sub
+            | Operator::I64Mul
+            | Operator::End
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use wasmparser::{Parser, Validator, WasmFeatures};
+
+    use super::{DylinkInfo, ModuleEnvironment};
+    use crate::{
+        module::{
+            build_ir::parse_module,
+            line_table::SourceLocation,
+            types::{DataSegmentOffset, MemoryIndex},
+        },
+        test_utils::{custom_section, test_diagnostics, with_unit_length},
+        UnsupportedFeature, WasmError, WasmTranslationConfig,
+    };
+
+    #[test]
+    fn parse_dylink_section() {
+        // The `mem-info` subsection (1) declares 16 bytes of memory aligned to 2^2, and 1 table
+        // element aligned to 2^0, and the `needed` subsection (2) lists two libraries
+        let wat = r#"
+            (module
+                (@custom "dylink.0" (before first) "\01\04\10\02\01\00\02\0a\02\04libc\03foo")
+                (memory (;0;) 1)
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let config = WasmTranslationConfig::default();
+        let mut validator = Validator::new();
+        let mut types = Default::default();
+        let parsed_module = ModuleEnvironment::new(&config, &mut validator, &mut types)
+            .parse(Parser::new(0), &wasm, &diagnostics)
+            .unwrap();
+        assert_eq!(
+            parsed_module.dylink,
+            Some(DylinkInfo {
+                memory_size: 16,
+                memory_alignment: 2,
+                table_size: 1,
+                table_alignment: 0,
+                needed: vec!["libc".to_string(), "foo".to_string()],
+            })
+        );
+    }
+    #[test]
+    fn parse_memory64_data_segment_offsets() {
+        let wat = r#"
+            (module
+                (memory (;0;) i64 1)
+                (data (i64.const 4096) "\01\02")
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let config = WasmTranslationConfig::default();
+        let mut validator = Validator::new_with_features(WasmFeatures {
+            memory64: true,
+            ..Default::default()
+        });
+        let mut types = Default::default();
+        let parsed_module = ModuleEnvironment::new(&config, &mut validator, &mut types)
+            .parse(Parser::new(0), &wasm, &diagnostics)
+            .unwrap();
+        assert!(parsed_module.module.memories[MemoryIndex::from_u32(0)].memory64);
+        let segment = parsed_module.data_segments.values().next().unwrap();
+        let offset = segment.offset.unwrap();
+        assert_eq!(offset, DataSegmentOffset::I64Const(4096));
+        assert_eq!(
+            offset.as_i32(&parsed_module.module, &diagnostics).unwrap(),
+            4096
+        );
+
+        // Offsets beyond the 32-bit address space of Miden are rejected rather than truncated
+        assert!(DataSegmentOffset::I64Const(1 << 32)
+            .as_i32(&parsed_module.module, &diagnostics)
+            .is_err());
+    }
+    #[test]
+    fn parse_memory64_data_segment_global_offsets() {
+        let wat = r#"
+            (module
+                (memory (;0;) i64 1)
+                (global $base i64 (i64.const 8192))
+                (global $too_far i64 (i64.const 0x100000000))
+                (data (global.get $base) "\01\02")
+                (data (global.get $too_far) "\03")
+            )
+        "#;
+        let wasm = wat::parse_str(wat).unwrap();
+        let diagnostics = test_diagnostics();
+        let config = WasmTranslationConfig::default();
+        let mut validator = Validator::new_with_features(WasmFeatures {
+            memory64: true,
+            // Locally defined globals are only permitted in constant expressions with GC
+            function_references: true,
+            gc: true,
+            ..Default::default()
+        });
+        let mut types = Default::default();
+        let parsed_module = ModuleEnvironment::new(&config, &mut validator, &mut types)
+            .parse(Parser::new(0), &wasm, &diagnostics)
+            .unwrap();
+        let mut offsets = parsed_module.data_segments.values().map(|segment| {
+            segment
+                .offset
+                .unwrap()
+                .as_i32(&parsed_module.module, &diagnostics)
+        });
+        assert_eq!(offsets.next().unwrap().unwrap(), 8192);
+        let err = offsets.next().unwrap().unwrap_err();
+        assert!(
+            matches!(err, WasmError::Unsupported(ref message) if message.contains("out of bounds")),
+            "unexpected error: {err:?}"
+        );
+    }
+    #[test]
+    fn oversized_memories_and_tables_are_rejected() {
+        let parse = |wat: &str, config: &WasmTranslationConfig| {
+            let wasm = wat::parse_str(wat).unwrap();
+            let mut validator = Validator::new_with_features(WasmFeatures {
+                memory64: true,
+                ..Default::default()
+            });
+            let mut types = Default::default();
+            ModuleEnvironment::new(config, &mut validator, &mut types)
+                .parse(Parser::new(0), &wasm, &test_diagnostics())
+                .map(|_| ())
+        };
+        let config = WasmTranslationConfig::default();
+        assert!(parse("(module (memory i64 1 65536))", &config).is_ok());
+        let err = parse("(module (memory i64 1 65537))", &config).unwrap_err();
+        let WasmError::UnsupportedFeature { feature, message } = err else {
+            panic!("expected an unsupported feature error, got {err:?}");
+        };
+        assert_eq!(feature, UnsupportedFeature::MemoryLimits);
+        assert!(message.contains("memory 0"), "{message}");
+
+        // The limits are configurable, and apply to imported memories and tables as well
+        let config = WasmTranslationConfig {
+            max_memory_pages: 16,
+            max_table_elements: 8,
+            ..Default::default()
+        };
+        assert!(parse("(module (memory 17))", &config).is_err());
+        assert!(parse(r#"(module (import "env" "memory" (memory 1 17)))"#, &config).is_err());
+        assert!(parse("(module (table 1 funcref) (table 9 funcref))", &config).is_err());
+        assert!(parse(
+            r#"(module (import "env" "t" (table 1 9 funcref)))"#,
+            &config
+        )
+        .is_err());
+        assert!(parse("(module (memory 1 16) (table 8 8 funcref))", &config).is_ok());
+    }
+    #[test]
+    fn source_location_from_dwarf5_line_program() {
+        let wat = r#"
+            (module
+                (func $answer (export "answer") (result i32)
+                    i32.const 42
+                )
+            )
+        "#;
+        let mut wasm = wat::parse_str(wat).unwrap();
+
+        // A DWARF 5 compile unit, with only a line program
+        let debug_abbrev = [1, 0x11, 0, 0x10, 0x17, 0, 0, 0];
+        let debug_info = vec![5, 0, 1, 4, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+
+        // The directory is a `DW_FORM_line_strp` into `.debug_line_str`, and the file name a
+        // `DW_FORM_strp_sup` into the `.debug_str` of the supplementary object file
+        let mut header = vec![
+            1, 1, 1, -5i8 as u8, 14, 13, 0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1,
+        ];
+        header.extend([1, 0x01, 0x1f, 1, 0, 0, 0, 0]);
+        header.extend([2, 0x01, 0x1d, 0x02, 0x0f, 1, 0, 0, 0, 0, 0]);
+        // DW_LNE_set_address 0, DW_LNS_set_file 0, DW_LNS_advance_line 6, DW_LNS_set_column 3,
+        // DW_LNS_copy, DW_LNS_advance_pc 0x40, and DW_LNE_end_sequence
+        let program = [0, 5, 2, 0, 0, 0, 0, 4, 0, 3, 6, 5, 3, 1, 2, 0x40, 0, 1, 1];
+        let mut debug_line = vec![5, 0, 4, 0];
+        debug_line.extend((header.len() as u32).to_le_bytes());
+        debug_line.extend(header);
+        debug_line.extend(program);
+
+        wasm.extend(custom_section(".debug_abbrev", &debug_abbrev));
+        wasm.extend(custom_section(".debug_info", &with_unit_length(debug_info)));
+        wasm.extend(custom_section(".debug_line", &with_unit_length(debug_line)));
+        wasm.extend(custom_section(".debug_line_str", b"/src\0"));
+        wasm.extend(custom_section(".debug_str_sup", b"lib.rs\0"));
+
+        let config = WasmTranslationConfig {
+            parse_wasm_debuginfo: true,
+            ..Default::default()
+        };
+        let (parsed_module, _) = parse_module(&wasm, &config, &test_diagnostics()).unwrap();
+        let code_section_offset = parsed_module.debuginfo.wasm_file.code_section_offset;
+        assert_eq!(
+            parsed_module.source_location(code_section_offset + 4),
+            Some(SourceLocation {
+                file: "/src/lib.rs".to_string(),
+                line: 7,
+                column: Some(3),
+            })
+        );
+        // Offsets before the code section, and past the end of the sequence, have no location
+        assert_eq!(parsed_module.source_location(0), None);
+        assert_eq!(
+            parsed_module.source_location(code_section_offset + 0x40),
+            None
+        );
+    }
+}
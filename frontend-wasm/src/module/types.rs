@@ -13,7 +13,7 @@ use miden_hir_type as hir;
 use crate::component::SignatureIndex;
 use crate::error::WasmResult;
 use crate::module::Module;
-use crate::{unsupported_diag, WasmError};
+use crate::{unsupported_diag, UnsupportedFeature, WasmError};
 
 /// Generates a new index type for each entity.
 #[macro_export]
@@ -72,6 +72,12 @@ pub struct TypeIndex(u32);
 /// Index type of a data segment inside the WebAssembly module.
 pub struct DataSegmentIndex(u32);
 
+/// Index type of a GC struct type inside the [ModuleTypes] of a WebAssembly module.
+pub struct StructTypeIndex(u32);
+
+/// Index type of a GC array type inside the [ModuleTypes] of a WebAssembly module.
+pub struct ArrayTypeIndex(u32);
+
 }
 
 /// WebAssembly value type -- equivalent of `wasmparser`'s Type.
@@ -149,6 +155,29 @@ pub enum WasmHeapType {
     ///
     /// Introduced in the references-types proposal.
     Extern,
+    /// A concrete type defined by the module, e.g. the function type of a typed function
+    /// reference.
+    ///
+    /// Introduced in the function-references proposal.
+    Concrete(TypeIndex),
+    /// The abstract type of all internal references, i.e. all but external references.
+    ///
+    /// Introduced in the GC proposal, as are the remaining heap types.
+    Any,
+    /// The bottom type of the internal references, of which only `null` is a value.
+    None,
+    /// The bottom type of the external references, of which only `null` is a value.
+    NoExtern,
+    /// The bottom type of the function references, of which only `null` is a value.
+    NoFunc,
+    /// The abstract type of the references which can be compared with `ref.eq`.
+    Eq,
+    /// The abstract type of all struct references.
+    Struct,
+    /// The abstract type of all array references.
+    Array,
+    /// The type of unboxed scalars, i.e. 31-bit integers.
+    I31,
 }
 
 impl fmt::Display for WasmHeapType {
@@ -156,6 +185,15 @@ impl fmt::Display for WasmHeapType {
         match self {
             Self::Func => write!(f, "func"),
             Self::Extern => write!(f, "extern"),
+            Self::Concrete(index) => write!(f, "{}", index.as_u32()),
+            Self::Any => write!(f, "any"),
+            Self::None => write!(f, "none"),
+            Self::NoExtern => write!(f, "noextern"),
+            Self::NoFunc => write!(f, "nofunc"),
+            Self::Eq => write!(f, "eq"),
+            Self::Struct => write!(f, "struct"),
+            Self::Array => write!(f, "array"),
+            Self::I31 => write!(f, "i31"),
         }
     }
 }
@@ -344,7 +382,30 @@ impl GlobalInit {
                 global_init.as_i32(module, diagnostics)?
             }
             g => {
-                unsupported_diag!(diagnostics, "Expected global init to be i32, got: {:?}", g);
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::InitExpr,
+                    "Expected global init to be i32, got: {:?}",
+                    g
+                );
+            }
+        })
+    }
+
+    pub fn as_i64(&self, module: &Module, diagnostics: &DiagnosticsHandler) -> WasmResult<i64> {
+        Ok(match self {
+            GlobalInit::I64Const(x) => *x,
+            GlobalInit::GetGlobal(global_idx) => {
+                let global_init = module.try_global_initializer(*global_idx, diagnostics)?;
+                global_init.as_i64(module, diagnostics)?
+            }
+            g => {
+                unsupported_diag!(
+                    diagnostics,
+                    UnsupportedFeature::InitExpr,
+                    "Expected global init to be i64, got: {:?}",
+                    g
+                );
             }
         })
     }
@@ -368,6 +429,21 @@ pub struct Memory {
     pub minimum: u64,
     /// The maximum number of pages in the memory.
     pub maximum: Option<u64>,
+    /// Whether the memory is indexed by i64, per the memory64 proposal, rather than i32.
+    pub memory64: bool,
+    /// Whether the memory may be shared between threads, per the threads proposal.
+    pub shared: bool,
+}
+
+impl Memory {
+    /// The type of the addresses of this memory, and so of the offsets of its data segments.
+    pub fn index_type(&self) -> WasmType {
+        if self.memory64 {
+            WasmType::I64
+        } else {
+            WasmType::I32
+        }
+    }
 }
 
 impl From<wasmparser::MemoryType> for Memory {
@@ -375,6 +451,8 @@ impl From<wasmparser::MemoryType> for Memory {
         Memory {
             minimum: ty.initial,
             maximum: ty.maximum,
+            memory64: ty.memory64,
+            shared: ty.shared,
         }
     }
 }
@@ -400,6 +478,8 @@ impl From<wasmparser::TagType> for Tag {
 pub enum DataSegmentOffset {
     /// An `i32.const` offset.
     I32Const(i32),
+    /// An `i64.const` offset, into a memory64 memory.
+    I64Const(i64),
     /// An offset as a `global.get` of another global.
     GetGlobal(GlobalIndex),
 }
@@ -409,9 +489,19 @@ impl DataSegmentOffset {
     pub fn as_i32(&self, module: &Module, diagnostics: &DiagnosticsHandler) -> WasmResult<i32> {
         Ok(match self {
             DataSegmentOffset::I32Const(x) => *x,
+            DataSegmentOffset::I64Const(x) => offset_from_i64(*x)?,
             DataSegmentOffset::GetGlobal(global_idx) => {
-                let global_init = &module.try_global_initializer(*global_idx, diagnostics)?;
-                match global_init.as_i32(module, diagnostics) {
+                let global_init = &module.const_global_initializer(*global_idx, diagnostics)?;
+                // The offset of a segment of a memory64 memory is an i64 global, as checked
+                // when the segment was parsed
+                let offset = if module.globals[*global_idx].ty == WasmType::I64 {
+                    global_init
+                        .as_i64(module, diagnostics)
+                        .and_then(offset_from_i64)
+                } else {
+                    global_init.as_i32(module, diagnostics)
+                };
+                match offset {
                     Err(e) => {
                         diagnostics
                             .diagnostic(miden_diagnostics::Severity::Error)
@@ -429,11 +519,25 @@ impl DataSegmentOffset {
     }
 }
 
+/// Linear memory is addressed by u32 in Miden, so a 64-bit offset must fit in one
+fn offset_from_i64(offset: i64) -> WasmResult<i32> {
+    match u32::try_from(offset) {
+        Ok(offset) => Ok(offset as i32),
+        Err(_) => Err(WasmError::Unsupported(format!(
+            "data segment offset {offset} is out of bounds of the 32-bit linear memory"
+        ))),
+    }
+}
+
 /// A WebAssembly data segment.
 /// https://www.w3.org/TR/wasm-core-1/#data-segments%E2%91%A0
 pub struct DataSegment<'a> {
-    /// The offset of the data segment inside the linear memory.
-    pub offset: DataSegmentOffset,
+    /// The linear memory into which an active segment is placed. A passive segment is not bound
+    /// to any memory, and is recorded as belonging to the default memory, 0.
+    pub memory_index: MemoryIndex,
+    /// The offset of the data segment inside the linear memory, or `None` for a passive segment,
+    /// which is only copied into linear memory by `memory.init`.
+    pub offset: Option<DataSegmentOffset>,
     /// The initialization data.
     pub data: &'a [u8],
 }
@@ -473,11 +577,43 @@ impl BlockType {
     }
 }
 
+/// The storage type of a field of a GC struct, or of the elements of a GC array -- equivalent of
+/// `wasmparser`'s StorageType.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WasmStorageType {
+    /// A packed 8-bit integer
+    I8,
+    /// A packed 16-bit integer
+    I16,
+    /// Any value type
+    Val(WasmType),
+}
+
+/// A field of a GC struct, or the elements of a GC array -- equivalent of `wasmparser`'s
+/// FieldType.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WasmFieldType {
+    pub element_type: WasmStorageType,
+    pub mutable: bool,
+}
+
+/// WebAssembly GC struct type -- equivalent of `wasmparser`'s StructType.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WasmStructType {
+    pub fields: Box<[WasmFieldType]>,
+}
+
+/// WebAssembly GC array type -- equivalent of `wasmparser`'s ArrayType.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WasmArrayType(pub WasmFieldType);
+
 /// Note that accesing this type is primarily done through the `Index`
 /// implementations for this type.
 #[derive(Default)]
 pub struct ModuleTypes {
     wasm_signatures: PrimaryMap<SignatureIndex, WasmFuncType>,
+    wasm_struct_types: PrimaryMap<StructTypeIndex, WasmStructType>,
+    wasm_array_types: PrimaryMap<ArrayTypeIndex, WasmArrayType>,
 }
 
 impl ModuleTypes {
@@ -496,6 +632,22 @@ impl Index<SignatureIndex> for ModuleTypes {
     }
 }
 
+impl Index<StructTypeIndex> for ModuleTypes {
+    type Output = WasmStructType;
+
+    fn index(&self, index: StructTypeIndex) -> &WasmStructType {
+        &self.wasm_struct_types[index]
+    }
+}
+
+impl Index<ArrayTypeIndex> for ModuleTypes {
+    type Output = WasmArrayType;
+
+    fn index(&self, index: ArrayTypeIndex) -> &WasmArrayType {
+        &self.wasm_array_types[index]
+    }
+}
+
 /// A builder for [`ModuleTypes`].
 #[derive(Default)]
 pub struct ModuleTypesBuilder {
@@ -519,7 +671,21 @@ impl ModuleTypesBuilder {
         sig
     }
 
-    fn intern_func_type(&mut self, sig: WasmFuncType) -> SignatureIndex {
+    /// Records the GC struct type `ty`, returning the index under which it can be looked up
+    /// within [`ModuleTypes`]
+    pub fn wasm_struct_type(&mut self, ty: WasmStructType) -> StructTypeIndex {
+        self.types.wasm_struct_types.push(ty)
+    }
+
+    /// Records the GC array type `ty`, returning the index under which it can be looked up
+    /// within [`ModuleTypes`]
+    pub fn wasm_array_type(&mut self, ty: WasmArrayType) -> ArrayTypeIndex {
+        self.types.wasm_array_types.push(ty)
+    }
+
+    /// Interns the `sig` specified, which does not correspond to any type of a module, e.g. the
+    /// signature a component function is lowered to, and returns its unique `SignatureIndex`.
+    pub fn intern_func_type(&mut self, sig: WasmFuncType) -> SignatureIndex {
         if let Some(idx) = self.interned_func_types.get(&sig) {
             return *idx;
         }
@@ -573,22 +739,15 @@ pub fn ir_type(ty: WasmType) -> WasmResult<hir::Type> {
     Ok(match ty {
         WasmType::I32 => hir::Type::I32,
         WasmType::I64 => hir::Type::I64,
-        WasmType::F32 => {
-            return Err(WasmError::Unsupported(
-                "no f32 type in Miden IR".to_string(),
-            ))
-        }
+        WasmType::F32 => hir::Type::F32,
         WasmType::F64 => hir::Type::F64,
         WasmType::V128 => {
             return Err(WasmError::Unsupported(
                 "V128 type is not supported".to_string(),
             ));
         }
-        WasmType::Ref(_) => {
-            return Err(WasmError::Unsupported(
-                "Ref type is not supported".to_string(),
-            ));
-        }
+        // References are represented as 32-bit handles, see the translation of `ref.eq`
+        WasmType::Ref(_) => hir::Type::U32,
     })
 }
 
@@ -663,16 +822,46 @@ pub fn convert_heap_type(ty: wasmparser::HeapType) -> WasmHeapType {
     match ty {
         wasmparser::HeapType::Func => WasmHeapType::Func,
         wasmparser::HeapType::Extern => WasmHeapType::Extern,
-        wasmparser::HeapType::Concrete(_)
-        | wasmparser::HeapType::Any
-        | wasmparser::HeapType::None
-        | wasmparser::HeapType::NoExtern
-        | wasmparser::HeapType::NoFunc
-        | wasmparser::HeapType::Eq
-        | wasmparser::HeapType::Struct
-        | wasmparser::HeapType::Array
-        | wasmparser::HeapType::I31 => {
-            unimplemented!("unsupported heap type {ty:?}");
-        }
+        // Types are converted as they are read from the binary, where a concrete type is referred
+        // to by its index in the type index space of the module, before validation canonicalizes
+        // it to a type identifier
+        wasmparser::HeapType::Concrete(index) => WasmHeapType::Concrete(TypeIndex::from_u32(
+            index
+                .as_module_index()
+                .expect("expected a concrete type to be referred to by its module type index"),
+        )),
+        wasmparser::HeapType::Any => WasmHeapType::Any,
+        wasmparser::HeapType::None => WasmHeapType::None,
+        wasmparser::HeapType::NoExtern => WasmHeapType::NoExtern,
+        wasmparser::HeapType::NoFunc => WasmHeapType::NoFunc,
+        wasmparser::HeapType::Eq => WasmHeapType::Eq,
+        wasmparser::HeapType::Struct => WasmHeapType::Struct,
+        wasmparser::HeapType::Array => WasmHeapType::Array,
+        wasmparser::HeapType::I31 => WasmHeapType::I31,
+    }
+}
+
+/// Converts a wasmparser struct type
+pub fn convert_struct_type(ty: &wasmparser::StructType) -> WasmStructType {
+    WasmStructType {
+        fields: ty.fields.iter().map(convert_field_type).collect(),
+    }
+}
+
+/// Converts a wasmparser array type
+pub fn convert_array_type(ty: &wasmparser::ArrayType) -> WasmArrayType {
+    WasmArrayType(convert_field_type(&ty.0))
+}
+
+/// Converts a wasmparser field type
+pub fn convert_field_type(ty: &wasmparser::FieldType) -> WasmFieldType {
+    let element_type = match ty.element_type {
+        wasmparser::StorageType::I8 => WasmStorageType::I8,
+        wasmparser::StorageType::I16 => WasmStorageType::I16,
+        wasmparser::StorageType::Val(ty) => WasmStorageType::Val(convert_valtype(ty)),
+    };
+    WasmFieldType {
+        element_type,
+        mutable: ty.mutable,
     }
 }
@@ -13,7 +13,7 @@ use core::mem;
 use miden_diagnostics::SourceSpan;
 use miden_hir::cranelift_entity::packed_option::PackedOption;
 use miden_hir::cranelift_entity::{entity_impl, EntityList, EntitySet, ListPool, SecondaryMap};
-use miden_hir::{Block, DataFlowGraph, Inst, Value};
+use miden_hir::{Block, DataFlowGraph, Inst, Symbol, Value};
 use miden_hir_type::Type;
 
 /// Structure containing the data relevant the construction of SSA for a given function.
@@ -35,6 +35,9 @@ pub struct SSABuilder {
     /// the variable in the block.
     variables: SecondaryMap<Variable, SecondaryMap<Block, PackedOption<Value>>>,
 
+    /// The names of variables, which are given to the block parameters created for them.
+    variable_names: SecondaryMap<Variable, Option<Symbol>>,
+
     /// Records the position of the basic blocks and the list of values used but not defined in the
     /// block.
     ssa_blocks: SecondaryMap<Block, SSABlockData>,
@@ -110,6 +113,7 @@ impl SSABuilder {
     /// deallocating memory.
     pub fn clear(&mut self) {
         self.variables.clear();
+        self.variable_names.clear();
         self.ssa_blocks.clear();
         self.variable_pool.clear();
         self.inst_pool.clear();
@@ -121,6 +125,7 @@ impl SSABuilder {
     /// Tests whether an `SSABuilder` is in a cleared state.
     pub fn is_empty(&self) -> bool {
         self.variables.is_empty()
+            && self.variable_names.is_empty()
             && self.ssa_blocks.is_empty()
             && self.calls.is_empty()
             && self.results.is_empty()
@@ -159,6 +164,17 @@ impl SSABuilder {
         self.variables[var][block] = PackedOption::from(val);
     }
 
+    /// Gives a name to a variable, see [SSABuilder::var_name].
+    pub fn set_var_name(&mut self, var: Variable, name: Symbol) {
+        self.variable_names[var] = Some(name);
+    }
+
+    /// Returns the name of a variable, if it was given one. The block parameters created to
+    /// merge the definitions of a named variable are given its name.
+    pub fn var_name(&self, var: Variable) -> Option<Symbol> {
+        self.variable_names[var]
+    }
+
     /// Declares a use of a variable in a given basic block. Returns the SSA value corresponding
     /// to the current SSA definition of this variable and a list of newly created Blocks
     ///
@@ -280,6 +296,9 @@ impl SSABuilder {
         // find a usable definition. So create one.
         let val = dfg.append_block_param(block, ty, SourceSpan::default());
         var_defs[block] = PackedOption::from(val);
+        if let Some(name) = self.variable_names[var] {
+            dfg.set_value_name(val, name);
+        }
 
         // Now every predecessor needs to pass its definition of this variable to the newly added
         // block parameter. To do that we have to "recursively" call `use_var`, but there are two
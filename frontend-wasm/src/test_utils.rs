@@ -1,12 +1,20 @@
 use std::sync::Arc;
 
 use miden_diagnostics::term::termcolor::ColorChoice;
+use miden_diagnostics::CaptureEmitter;
 use miden_diagnostics::CodeMap;
 use miden_diagnostics::DiagnosticsConfig;
 use miden_diagnostics::DiagnosticsHandler;
 use miden_diagnostics::Emitter;
 use miden_diagnostics::NullEmitter;
 use miden_diagnostics::Verbosity;
+use miden_hir::Function;
+use miden_hir::FunctionIdent;
+use miden_hir::Ident;
+use miden_hir::Inst;
+use miden_hir::Instruction;
+use miden_hir::Module;
+use miden_hir::Opcode;
 
 pub fn default_emitter(verbosity: Verbosity, color: ColorChoice) -> Arc<dyn Emitter> {
     match verbosity {
@@ -28,3 +36,79 @@ pub fn test_diagnostics() -> DiagnosticsHandler {
     );
     diagnostics
 }
+
+/// A diagnostics handler emitting to a [CaptureEmitter], to check the diagnostics reported
+pub fn capturing_diagnostics() -> (DiagnosticsHandler, Arc<CaptureEmitter>) {
+    let capture = Arc::new(CaptureEmitter::default());
+    let diagnostics = DiagnosticsHandler::new(
+        Default::default(),
+        Arc::new(CodeMap::new()),
+        capture.clone(),
+    );
+    (diagnostics, capture)
+}
+
+/// The instructions of all the blocks of `function`
+pub fn function_insts(function: &Function) -> impl Iterator<Item = Inst> + '_ {
+    function
+        .dfg
+        .blocks()
+        .flat_map(|(block, _)| function.dfg.block_insts(block))
+}
+
+/// The opcodes of the instructions of `function`
+pub fn function_opcodes(function: &Function) -> Vec<Opcode> {
+    function_insts(function)
+        .map(|inst| function.dfg[inst].opcode())
+        .collect()
+}
+
+/// The functions called by `function`, in the order of the calls
+pub fn callees(function: &Function) -> Vec<FunctionIdent> {
+    function_insts(function)
+        .filter_map(|inst| match function.dfg.inst(inst) {
+            Instruction::Call(call) => Some(call.callee),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The names of the functions called by the function `name` of `module`
+pub fn callee_names(module: &Module, name: &str) -> Vec<&'static str> {
+    let function = module.function(Ident::from(name)).unwrap();
+    callees(function)
+        .into_iter()
+        .map(|callee| callee.function.as_str())
+        .collect()
+}
+
+fn uleb128(mut value: u64, bytes: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            break;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// Encode a custom section named `name`, with `data` as its contents
+pub fn custom_section(name: &str, data: &[u8]) -> Vec<u8> {
+    let mut contents = vec![];
+    uleb128(name.len() as u64, &mut contents);
+    contents.extend(name.as_bytes());
+    contents.extend(data);
+    let mut section = vec![0];
+    uleb128(contents.len() as u64, &mut section);
+    section.extend(contents);
+    section
+}
+
+/// Prefix `contents` with its length, as a 32-bit DWARF unit length
+pub fn with_unit_length(contents: Vec<u8>) -> Vec<u8> {
+    let mut unit = (contents.len() as u32).to_le_bytes().to_vec();
+    unit.extend(contents);
+    unit
+}
@@ -110,6 +110,13 @@ pub fn emit_zero(ty: &Type, builder: &mut FunctionBuilderExt) -> WasmResult<Valu
         Type::U16 => builder.ins().u16(0, SourceSpan::default()),
         Type::U32 => builder.ins().u32(0, SourceSpan::default()),
         Type::U64 => builder.ins().u64(0, SourceSpan::default()),
+        // There are no `f32` immediates, but the bit pattern of zero is the same as an integer's
+        Type::F32 => {
+            let zero = builder.ins().u32(0, SourceSpan::default());
+            builder
+                .ins()
+                .bitcast(zero, Type::F32, SourceSpan::default())
+        }
         Type::F64 => builder.ins().f64(0.0, SourceSpan::default()),
         Type::Felt => builder.ins().felt(0u64.into(), SourceSpan::default()),
         Type::I128
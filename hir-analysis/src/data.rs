@@ -19,6 +19,15 @@ impl<T> Default for GlobalVariableAnalysis<T> {
     }
 }
 impl<T> GlobalVariableAnalysis<T> {
+    /// Construct the analysis from a previously computed `layout`, e.g. to share the results of
+    /// the analysis with an [AnalysisManager] on another thread
+    pub fn with_layout(layout: GlobalVariableLayout) -> Self {
+        Self {
+            layout,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
     pub fn layout(&self) -> &GlobalVariableLayout {
         &self.layout
     }
@@ -51,6 +51,9 @@ pub enum TypeError {
     /// An attempt was made to cast from a smaller integer type to a larger one via narrowing cast, e.g. `trunc`
     #[error("expected result to be an integral type smaller than {expected}, but got {actual}")]
     InvalidNarrowingCast { expected: Type, actual: Type },
+    /// An attempt was made to reinterpret a value as a type of a different size via `bitcast`
+    #[error("expected result to be a type of the same size as {expected}, but got {actual}")]
+    InvalidBitcast { expected: Type, actual: Type },
     /// The arguments of an instruction were supposed to be the same type, but at least one differs from the controlling type
     #[error("expected arguments to be the same type ({expected}), but argument at index {index} is {actual}")]
     MatchingArgumentTypeViolation {
@@ -377,6 +380,9 @@ pub enum InstPattern {
     UnaryWideningCast(TypePattern, TypePattern),
     /// The instruction matches if it has one argument of integral type, and one result of a smaller integral type
     UnaryNarrowingCast(TypePattern, TypePattern),
+    /// The instruction matches if it has one argument of the first type, and one result of the
+    /// second type, of the same size as the argument
+    UnaryBitcast(TypePattern, TypePattern),
     /// The instruction matches if it has two arguments of the given type, and one result which is the same type as the first argument
     Binary(TypePattern, TypePattern),
     /// The instruction matches if it has two arguments and one result, all of the same type
@@ -421,7 +427,8 @@ impl InstPattern {
             Self::Unary(_)
             | Self::UnaryMap(_, _)
             | Self::UnaryWideningCast(_, _)
-            | Self::UnaryNarrowingCast(_, _) => {
+            | Self::UnaryNarrowingCast(_, _)
+            | Self::UnaryBitcast(_, _) => {
                 if args.len() != 1 {
                     return Err(TypeError::IncorrectArgumentCount {
                         expected: 1,
@@ -569,7 +576,8 @@ impl InstPattern {
             Self::Unary(_)
             | Self::UnaryMap(_, _)
             | Self::UnaryWideningCast(_, _)
-            | Self::UnaryNarrowingCast(_, _) => {
+            | Self::UnaryNarrowingCast(_, _)
+            | Self::UnaryBitcast(_, _) => {
                 if !args.is_empty() {
                     return Err(TypeError::IncorrectArgumentCount {
                         expected: 1,
@@ -790,6 +798,29 @@ impl InstPattern {
                     });
                 }
             }
+            Self::UnaryBitcast(expected_in, expected_out) => {
+                if !expected_in.matches(actual_in) {
+                    return Err(TypeError::IncorrectArgumentType {
+                        expected: expected_in,
+                        actual: actual_in.clone(),
+                        index: 0,
+                    });
+                }
+                let actual_out = actual_out.expect("expected result type");
+                if !expected_out.matches(actual_out) {
+                    return Err(TypeError::InvalidResultType {
+                        expected: expected_out,
+                        actual: actual_out.clone(),
+                        index: 0,
+                    });
+                }
+                if actual_in.size_in_bits() != actual_out.size_in_bits() {
+                    return Err(TypeError::InvalidBitcast {
+                        expected: actual_in.clone(),
+                        actual: actual_out.clone(),
+                    });
+                }
+            }
             Self::Empty
             | Self::Binary(_, _)
             | Self::BinaryMatching(_)
@@ -887,6 +918,7 @@ impl InstPattern {
             | Self::UnaryMap(_, _)
             | Self::UnaryWideningCast(_, _)
             | Self::UnaryNarrowingCast(_, _)
+            | Self::UnaryBitcast(_, _)
             | Self::TernaryMatching(_, _)
             | Self::Exact(_, _)
             | Self::Any => unreachable!(),
@@ -938,6 +970,7 @@ impl InstPattern {
             | Self::UnaryMap(_, _)
             | Self::UnaryWideningCast(_, _)
             | Self::UnaryNarrowingCast(_, _)
+            | Self::UnaryBitcast(_, _)
             | Self::Binary(_, _)
             | Self::BinaryMatching(_)
             | Self::BinaryMatchingNoResult(_)
@@ -1010,6 +1043,9 @@ impl<'a> InstTypeChecker<'a> {
             Opcode::PtrToInt => InstPattern::UnaryMap(TypePattern::Pointer, TypePattern::Int),
             Opcode::IntToPtr => InstPattern::UnaryMap(TypePattern::Uint, TypePattern::Pointer),
             Opcode::Cast => InstPattern::UnaryMap(TypePattern::Int, TypePattern::Int),
+            Opcode::Bitcast => {
+                InstPattern::UnaryBitcast(TypePattern::Primitive, TypePattern::Primitive)
+            }
             Opcode::Trunc => InstPattern::UnaryNarrowingCast(TypePattern::Int, TypePattern::Int),
             Opcode::Zext => InstPattern::UnaryWideningCast(TypePattern::Int, TypePattern::Uint),
             Opcode::Sext => InstPattern::UnaryWideningCast(TypePattern::Int, TypePattern::Int),
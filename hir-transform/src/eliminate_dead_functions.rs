@@ -0,0 +1,150 @@
+use std::collections::VecDeque;
+
+use miden_hir::pass::{AnalysisManager, RewritePass, RewriteResult};
+use miden_hir::{self as hir, *};
+use midenc_session::Session;
+use rustc_hash::FxHashSet;
+
+/// This pass removes the functions of a module with internal linkage which are never called.
+///
+/// A function is live if it is visible outside of the module, i.e. it does not have internal
+/// linkage, if it is the entrypoint or the start function of the module, or if it is called by a
+/// live function. As internal functions can only be called from within the module, those which
+/// are not live can never be called, and are removed.
+///
+/// Functions which escape a Wasm module, i.e. which are exported, referenced by `ref.func`, or
+/// placed in a table, are never given internal linkage by the frontend, so they are always kept.
+#[derive(Default, PassInfo, RewritePassRegistration)]
+pub struct EliminateDeadFunctions;
+impl RewritePass for EliminateDeadFunctions {
+    type Entity = hir::Module;
+
+    fn apply(
+        &mut self,
+        module: &mut Self::Entity,
+        analyses: &mut AnalysisManager,
+        _session: &Session,
+    ) -> RewriteResult {
+        let mut worklist = module
+            .functions()
+            .filter(|function| !function.signature.is_private())
+            .map(|function| function.id.function)
+            .chain(module.entrypoint().map(|id| id.function))
+            .chain(module.start().map(|id| id.function))
+            .collect::<VecDeque<_>>();
+        let mut live = FxHashSet::default();
+        while let Some(id) = worklist.pop_front() {
+            if !live.insert(id) {
+                continue;
+            }
+            let Some(function) = module.function(id) else {
+                continue;
+            };
+            for (block, _) in function.dfg.blocks() {
+                for inst in function.dfg.block_insts(block) {
+                    if let Instruction::Call(call) = function.dfg.inst(inst) {
+                        if call.callee.module == module.name {
+                            worklist.push_back(call.callee.function);
+                        }
+                    }
+                }
+            }
+        }
+
+        let dead = module
+            .functions()
+            .filter(|function| !live.contains(&function.id.function))
+            .map(|function| function.id)
+            .collect::<Vec<_>>();
+        if dead.is_empty() {
+            analyses.mark_all_preserved::<hir::Module>(&module.name);
+            return Ok(());
+        }
+        for id in dead {
+            module.unlink(id.function);
+            analyses.invalidate::<hir::Function>(&id);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_hir::{
+        pass::{AnalysisManager, RewritePass},
+        testing::TestContext,
+        AbiParam, FunctionIdent, InstBuilder, Linkage, Module, ModuleBuilder, Signature,
+        SourceSpan, Type,
+    };
+
+    use crate::EliminateDeadFunctions;
+
+    /// Define `name` in `builder`, with the given linkage, calling each of `callees`
+    fn define(
+        builder: &mut ModuleBuilder,
+        context: &TestContext,
+        name: &str,
+        linkage: Linkage,
+        callees: &[&str],
+    ) -> FunctionIdent {
+        let mut signature = Signature::new([], [AbiParam::new(Type::U32)]);
+        signature.linkage = linkage;
+        let callee_signature = Signature::new([], [AbiParam::new(Type::U32)]);
+        let mut fb = builder
+            .function(name, signature)
+            .expect("unexpected symbol conflict");
+        let mut result = fb.ins().u32(0, SourceSpan::UNKNOWN);
+        for callee in callees {
+            let callee = fb
+                .import_function("test", *callee, callee_signature.clone())
+                .expect("unexpected symbol conflict");
+            let call = fb.ins().call(callee, &[], SourceSpan::UNKNOWN);
+            let value = fb.first_result(call);
+            result = fb.ins().add_wrapping(result, value, SourceSpan::UNKNOWN);
+        }
+        fb.ins().ret(Some(result), SourceSpan::UNKNOWN);
+        fb.build(&context.session.diagnostics)
+            .expect("unexpected validation error, see diagnostics output")
+    }
+
+    fn function_names(module: &Module) -> Vec<String> {
+        module
+            .functions()
+            .map(|function| function.id.function.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn eliminate_dead_functions_test() {
+        let context = TestContext::default();
+        let mut builder = ModuleBuilder::new("test");
+        // `dead` and `dead_callee` are only called by each other, while `live` is called by the
+        // public `main`, and `init` is only live as the start function of the module
+        define(&mut builder, &context, "main", Linkage::External, &["live"]);
+        define(&mut builder, &context, "live", Linkage::Internal, &[]);
+        define(
+            &mut builder,
+            &context,
+            "dead",
+            Linkage::Internal,
+            &["dead_callee"],
+        );
+        define(
+            &mut builder,
+            &context,
+            "dead_callee",
+            Linkage::Internal,
+            &["dead"],
+        );
+        let init = define(&mut builder, &context, "init", Linkage::Internal, &[]);
+        let mut module = builder.build();
+        module.set_start(init);
+
+        let mut analyses = AnalysisManager::default();
+        EliminateDeadFunctions
+            .apply(&mut module, &mut analyses, &context.session)
+            .expect("eliminating dead functions failed");
+        assert_eq!(function_names(&module), ["main", "live", "init"]);
+    }
+}
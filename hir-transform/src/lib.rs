@@ -1,8 +1,12 @@
 pub(crate) mod adt;
+mod eliminate_dead_functions;
 mod inline_blocks;
+mod lower_switch;
 mod split_critical_edges;
 mod treeify;
 
+pub use self::eliminate_dead_functions::EliminateDeadFunctions;
 pub use self::inline_blocks::InlineBlocks;
+pub use self::lower_switch::LowerSwitch;
 pub use self::split_critical_edges::SplitCriticalEdges;
 pub use self::treeify::Treeify;
@@ -0,0 +1,294 @@
+use miden_hir::pass::{AnalysisManager, RewritePass, RewriteResult};
+use miden_hir::{self as hir, *};
+use miden_hir_analysis::ControlFlowGraph;
+use midenc_session::Session;
+
+/// This pass rewrites `switch` instructions as a tree of conditional branches.
+///
+/// Miden Assembly has no form of computed jump, so a `switch` cannot be lowered to a jump table
+/// directly. Instead, when the switch is dense, i.e. its arms cover at least half of the values
+/// between the smallest and largest discriminant, the arms are grouped into contiguous ranges of
+/// values which share a destination, and we emit a balanced binary search over those ranges. The
+/// result is that any arm is reached in `O(log n)` comparisons, rather than the `O(n)` required by
+/// a chain of equality tests, while emitting roughly the same number of comparisons overall.
+///
+/// Sparse switches are lowered to a chain of equality tests, one per arm, as the gaps between
+/// arms would otherwise each need a range of their own in the search.
+///
+/// This pass must be run before [crate::SplitCriticalEdges], as neither it, nor the code generator,
+/// handle `switch` instructions.
+#[derive(Default, PassInfo, ModuleRewritePassAdapter)]
+pub struct LowerSwitch;
+impl RewritePass for LowerSwitch {
+    type Entity = hir::Function;
+
+    fn apply(
+        &mut self,
+        function: &mut Self::Entity,
+        analyses: &mut AnalysisManager,
+        _session: &Session,
+    ) -> RewriteResult {
+        let switches = function
+            .dfg
+            .blocks()
+            .filter_map(|(block, _)| {
+                let terminator = function.dfg.last_inst(block)?;
+                match function.dfg.inst(terminator) {
+                    Instruction::Switch(switch) => Some((block, terminator, switch.clone())),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if switches.is_empty() {
+            analyses.mark_preserved::<ControlFlowGraph>(&function.id);
+            return Ok(());
+        }
+
+        for (block, terminator, switch) in switches {
+            let span = function.dfg.inst_span(terminator);
+            // Remove the switch, the dispatch code emitted below takes its place
+            function.dfg.block_mut(block).insts.pop_back();
+
+            let mut builder = FunctionBuilder::new(function);
+            builder.switch_to_block(block);
+            lower_switch(&mut builder, switch, span);
+        }
+
+        Ok(())
+    }
+}
+
+/// A contiguous, inclusive range of discriminants which all transfer control to `dest`
+#[derive(Debug, Copy, Clone)]
+struct CaseRange {
+    start: u32,
+    end: u32,
+    dest: Block,
+}
+
+fn lower_switch(builder: &mut FunctionBuilder, switch: Switch, span: SourceSpan) {
+    let Switch {
+        arg,
+        mut arms,
+        default,
+        ..
+    } = switch;
+
+    // The sort is stable, so when a discriminant appears more than once, the first arm wins
+    arms.sort_by_key(|(discriminant, _)| *discriminant);
+    arms.dedup_by_key(|(discriminant, _)| *discriminant);
+    let (min, max) = match arms.as_slice() {
+        [] => {
+            builder.ins().br(default, &[], span);
+            return;
+        }
+        [(min, _), .., (max, _)] => (*min, *max),
+        [(discriminant, _)] => (*discriminant, *discriminant),
+    };
+
+    let num_values = (max - min) as u64 + 1;
+    if (arms.len() as u64) * 2 >= num_values {
+        lower_dense_switch(builder, arg, &arms, default, min, max, span);
+    } else {
+        lower_sparse_switch(builder, arg, &arms, default, span);
+    }
+}
+
+fn lower_dense_switch(
+    builder: &mut FunctionBuilder,
+    arg: Value,
+    arms: &[(u32, Block)],
+    default: Block,
+    min: u32,
+    max: u32,
+    span: SourceSpan,
+) {
+    // Discriminants outside of `min..=max` can only go to the default block
+    if min > 0 {
+        let in_range = builder.create_block();
+        let is_below = builder.ins().lt_imm(arg, Immediate::U32(min), span);
+        builder
+            .ins()
+            .cond_br(is_below, default, &[], in_range, &[], span);
+        builder.switch_to_block(in_range);
+    }
+    if max < u32::MAX {
+        let in_range = builder.create_block();
+        let is_above = builder.ins().gt_imm(arg, Immediate::U32(max), span);
+        builder
+            .ins()
+            .cond_br(is_above, default, &[], in_range, &[], span);
+        builder.switch_to_block(in_range);
+    }
+
+    let ranges = case_ranges(arms, default);
+    lower_binary_search(builder, arg, &ranges, span);
+}
+
+/// Group the (sorted) arms of a switch into ranges of consecutive discriminants with the same
+/// destination, with any gaps between arms being covered by ranges for the default block.
+fn case_ranges(arms: &[(u32, Block)], default: Block) -> Vec<CaseRange> {
+    fn push_range(ranges: &mut Vec<CaseRange>, start: u32, end: u32, dest: Block) {
+        match ranges.last_mut() {
+            Some(last) if last.dest == dest => last.end = end,
+            _ => ranges.push(CaseRange { start, end, dest }),
+        }
+    }
+
+    let mut ranges = Vec::<CaseRange>::with_capacity(arms.len());
+    for &(discriminant, dest) in arms {
+        if let Some(prev_end) = ranges.last().map(|range| range.end) {
+            if discriminant > prev_end + 1 {
+                push_range(&mut ranges, prev_end + 1, discriminant - 1, default);
+            }
+        }
+        push_range(&mut ranges, discriminant, discriminant, dest);
+    }
+    ranges
+}
+
+/// Emit a balanced binary search over `ranges`, which must be contiguous, and cover every
+/// possible value of `arg` at this point in the search.
+fn lower_binary_search(
+    builder: &mut FunctionBuilder,
+    arg: Value,
+    ranges: &[CaseRange],
+    span: SourceSpan,
+) {
+    if let [range] = ranges {
+        builder.ins().br(range.dest, &[], span);
+        return;
+    }
+
+    let (lower, upper) = ranges.split_at(ranges.len() / 2);
+    let lower_blk = builder.create_block();
+    let upper_blk = builder.create_block();
+    let is_lower = builder
+        .ins()
+        .lt_imm(arg, Immediate::U32(upper[0].start), span);
+    builder
+        .ins()
+        .cond_br(is_lower, lower_blk, &[], upper_blk, &[], span);
+
+    builder.switch_to_block(lower_blk);
+    lower_binary_search(builder, arg, lower, span);
+    builder.switch_to_block(upper_blk);
+    lower_binary_search(builder, arg, upper, span);
+}
+
+fn lower_sparse_switch(
+    builder: &mut FunctionBuilder,
+    arg: Value,
+    arms: &[(u32, Block)],
+    default: Block,
+    span: SourceSpan,
+) {
+    for &(discriminant, dest) in arms {
+        let next = builder.create_block();
+        let is_match = builder
+            .ins()
+            .eq_imm(arg, Immediate::U32(discriminant), span);
+        builder.ins().cond_br(is_match, dest, &[], next, &[], span);
+        builder.switch_to_block(next);
+    }
+    builder.ins().br(default, &[], span);
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_hir::{
+        pass::{AnalysisManager, RewritePass},
+        testing::TestContext,
+        AbiParam, Function, FunctionBuilder, InstBuilder, Signature, SourceSpan, Type,
+    };
+
+    use crate::LowerSwitch;
+
+    /// Build a function which switches over its argument, with an arm for each of
+    /// `discriminants`, each of which returns the discriminant, while the default returns 0
+    fn switch_function(discriminants: &[u32]) -> Function {
+        let id = "test::dispatch".parse().unwrap();
+        let mut function = Function::new(
+            id,
+            Signature::new([AbiParam::new(Type::U32)], [AbiParam::new(Type::U32)]),
+        );
+        {
+            let mut builder = FunctionBuilder::new(&mut function);
+            let entry = builder.current_block();
+            let arg = builder.block_params(entry)[0];
+
+            let mut arms = Vec::with_capacity(discriminants.len());
+            for discriminant in discriminants.iter().copied() {
+                let arm = builder.create_block();
+                builder.switch_to_block(arm);
+                let result = builder.ins().u32(discriminant, SourceSpan::UNKNOWN);
+                builder.ins().ret(Some(result), SourceSpan::UNKNOWN);
+                arms.push((discriminant, arm));
+            }
+            let default = builder.create_block();
+            builder.switch_to_block(default);
+            let result = builder.ins().u32(0, SourceSpan::UNKNOWN);
+            builder.ins().ret(Some(result), SourceSpan::UNKNOWN);
+
+            builder.switch_to_block(entry);
+            builder
+                .ins()
+                .switch(arg, arms, default, SourceSpan::UNKNOWN);
+        }
+        function
+    }
+
+    fn lower(function: &mut Function) -> String {
+        let context = TestContext::default();
+        let mut analyses = AnalysisManager::default();
+        let mut rewrite = LowerSwitch;
+        rewrite
+            .apply(function, &mut analyses, &context.session)
+            .expect("lowering switch failed");
+        function.to_string()
+    }
+
+    /// A dense switch is lowered to a binary search, which for 16 arms needs a bounds check,
+    /// plus 15 comparisons in the search itself, but only ever executes 5 of them
+    #[test]
+    fn lower_switch_dense_test() {
+        let discriminants = (0..16).collect::<Vec<_>>();
+        let mut function = switch_function(&discriminants);
+        let lowered = lower(&mut function);
+
+        assert!(!lowered.contains("switch"));
+        assert_eq!(lowered.matches("condbr").count(), 16);
+        assert_eq!(lowered.matches(" gt ").count(), 1);
+        assert_eq!(lowered.matches(" lt ").count(), 15);
+        assert_eq!(lowered.matches(" eq ").count(), 0);
+    }
+
+    /// Duplicate discriminants are reduced to their first arm, including `u32::MAX`, after which
+    /// there is no discriminant to start a range of the default block at
+    #[test]
+    fn lower_switch_duplicate_max_discriminant_test() {
+        let mut function = switch_function(&[u32::MAX - 1, u32::MAX, u32::MAX]);
+        let lowered = lower(&mut function);
+
+        assert!(!lowered.contains("switch"));
+        assert_eq!(lowered.matches("condbr").count(), 2);
+        assert_eq!(lowered.matches(" lt ").count(), 2);
+        assert_eq!(lowered.matches(" gt ").count(), 0);
+    }
+
+    /// A sparse switch is lowered to a chain of equality tests
+    #[test]
+    fn lower_switch_sparse_test() {
+        let mut function = switch_function(&[1, 100, 10_000]);
+        let lowered = lower(&mut function);
+
+        assert!(!lowered.contains("switch"));
+        assert_eq!(lowered.matches("condbr").count(), 3);
+        assert_eq!(lowered.matches(" eq ").count(), 3);
+        assert_eq!(
+            lowered.matches(" lt ").count() + lowered.matches(" gt ").count(),
+            0
+        );
+    }
+}
@@ -67,6 +67,7 @@ impl Type {
             | Self::Felt
             | Self::I32
             | Self::U32
+            | Self::F32
             | Self::Ptr(_)
             | Self::I16
             | Self::U16) => {
@@ -404,8 +405,8 @@ impl Type {
             Self::U256 | Self::I128 | Self::U128 => 16,
             // 64-bit integers and floats must be element-aligned
             Self::I64 | Self::U64 | Self::F64 => 4,
-            // 32-bit integers and pointers must be element-aligned
-            Self::I32 | Self::U32 | Self::Ptr(_) | Self::NativePtr(_, _) => 4,
+            // 32-bit integers, floats and pointers must be element-aligned
+            Self::I32 | Self::U32 | Self::F32 | Self::Ptr(_) | Self::NativePtr(_, _) => 4,
             // 16-bit integers can be naturally aligned
             Self::I16 | Self::U16 => 2,
             // 8-bit integers and booleans can be naturally aligned
@@ -433,6 +434,7 @@ impl Type {
             // has no effect on their available range, just how much memory they are
             // assumed to require for storage.
             Self::I32 | Self::U32 | Self::Felt => 32,
+            Self::F32 => 32,
             Self::I64 | Self::U64 | Self::F64 => 64,
             Self::I128 | Self::U128 => 128,
             Self::U256 => 256,
@@ -31,6 +31,7 @@ pub enum Type {
     I128,
     U128,
     U256,
+    F32,
     F64,
     /// Field element
     Felt,
@@ -92,6 +93,7 @@ impl Type {
             | Self::I128
             | Self::U128
             | Self::U256
+            | Self::F32
             | Self::F64
             | Self::Felt
             | Self::Ptr(_)
@@ -114,6 +116,7 @@ impl Type {
                 | Self::I128
                 | Self::U128
                 | Self::U256
+                | Self::F32
                 | Self::F64
                 | Self::Felt
         )
@@ -188,7 +191,7 @@ impl Type {
 
     #[inline]
     pub fn is_float(&self) -> bool {
-        matches!(self, Self::F64)
+        matches!(self, Self::F32 | Self::F64)
     }
 
     #[inline]
@@ -270,6 +273,7 @@ impl Type {
             ) => true,
             (Type::U128, Type::U8 | Type::U16 | Type::U32 | Type::U64 | Type::U128) => true,
             (Type::U256, rty) => rty.is_integer(),
+            (Type::F32, Type::F32) => true,
             (Type::F64, Type::F64) => true,
             (Type::Ptr(_) | Type::NativePtr(_, _), Type::U8 | Type::U16 | Type::U32) => true,
             _ => false,
@@ -311,6 +315,7 @@ impl fmt::Display for Type {
             Self::I128 => f.write_str("i128"),
             Self::U128 => f.write_str("u128"),
             Self::U256 => f.write_str("u256"),
+            Self::F32 => f.write_str("f32"),
             Self::F64 => f.write_str("f64"),
             Self::Felt => f.write_str("felt"),
             Self::Ptr(inner) => write!(f, "*mut {}", &inner),
@@ -215,6 +215,7 @@ impl<'a> fmt::Display for DisplayOp<'a> {
             }
             op @ (MasmOp::Exec(id) | MasmOp::Syscall(id) | MasmOp::ProcRef(id)) => {
                 let FunctionIdent { module, function } = id;
+                let function = super::mangle_procedure_name(function.as_str());
                 if self.is_local_module(module) {
                     write!(f, "{op}.{function}")
                 } else {
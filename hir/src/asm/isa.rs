@@ -4,6 +4,7 @@ use cranelift_entity::entity_impl;
 use rustc_hash::FxHashMap;
 use smallvec::{smallvec, SmallVec};
 
+use super::{demangle_procedure_name, mangle_procedure_name};
 use crate::{Felt, FunctionIdent, Ident, LocalId};
 
 /// A handle that refers to a MASM code block
@@ -807,7 +808,9 @@ impl MasmOp {
                     .expect("reference to import that doesn't exist");
                 Self::Exec(FunctionIdent {
                     module: Ident::with_empty_span(Symbol::intern(module)),
-                    function: Ident::with_empty_span(Symbol::intern(name.as_ref())),
+                    function: Ident::with_empty_span(Symbol::intern(&demangle_procedure_name(
+                        name.as_ref(),
+                    ))),
                 })
             }
             Instruction::CallLocal(_)
@@ -823,7 +826,9 @@ impl MasmOp {
                     .expect("reference to import that doesn't exist");
                 Self::Syscall(FunctionIdent {
                     module: Ident::with_empty_span(Symbol::intern(module)),
-                    function: Ident::with_empty_span(Symbol::intern(name.as_ref())),
+                    function: Ident::with_empty_span(Symbol::intern(&demangle_procedure_name(
+                        name.as_ref(),
+                    ))),
                 })
             }
             Instruction::DynExec => Self::DynExec,
@@ -839,7 +844,9 @@ impl MasmOp {
                     .expect("reference to import that doesn't exist");
                 Self::ProcRef(FunctionIdent {
                     module: Ident::with_empty_span(Symbol::intern(module)),
-                    function: Ident::with_empty_span(Symbol::intern(name.as_ref())),
+                    function: Ident::with_empty_span(Symbol::intern(&demangle_procedure_name(
+                        name.as_ref(),
+                    ))),
                 })
             }
             Instruction::Caller => Self::Caller,
@@ -1026,7 +1033,7 @@ impl MasmOp {
                     let id = proc_ids
                         .get(&aliased)
                         .copied()
-                        .unwrap_or_else(|| miden_assembly::ProcedureId::new(aliased.to_string()));
+                        .unwrap_or_else(|| procedure_id(&aliased));
                     Instruction::ExecImported(id)
                 }
             }
@@ -1047,7 +1054,7 @@ impl MasmOp {
                 let id = proc_ids
                     .get(&aliased)
                     .copied()
-                    .unwrap_or_else(|| miden_assembly::ProcedureId::new(aliased.to_string()));
+                    .unwrap_or_else(|| procedure_id(&aliased));
                 Instruction::SysCall(id)
             }
             Self::DynExec => Instruction::DynExec,
@@ -1072,7 +1079,7 @@ impl MasmOp {
                     let id = proc_ids
                         .get(&aliased)
                         .copied()
-                        .unwrap_or_else(|| miden_assembly::ProcedureId::new(aliased.to_string()));
+                        .unwrap_or_else(|| procedure_id(&aliased));
                     Instruction::ProcRefImported(id)
                 }
             }
@@ -1212,6 +1219,13 @@ impl MasmOp {
     }
 }
 
+/// Compute the [miden_assembly::ProcedureId] of `callee`, where `callee.module` is the alias of
+/// the module under which it is imported, from its name as it appears in Miden Assembly
+fn procedure_id(callee: &FunctionIdent) -> miden_assembly::ProcedureId {
+    let name = mangle_procedure_name(callee.function.as_str());
+    miden_assembly::ProcedureId::new(format!("{}::{name}", callee.module))
+}
+
 /// This implementation displays the opcode name for the given [MasmOp]
 impl fmt::Display for MasmOp {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -0,0 +1,122 @@
+use std::{borrow::Cow, fmt::Write};
+
+/// The prefix of a mangled procedure name.
+///
+/// Names which are already valid, but happen to start with this prefix, are mangled as well, so
+/// that a mangled name can always be told apart from one which was left as is.
+const MANGLED_PREFIX: &str = "M_";
+
+/// Returns true if `name` can be used as-is as the name of a procedure in Miden Assembly, i.e. it
+/// starts with an ASCII letter, and otherwise consists only of ASCII alphanumerics and `_`.
+pub fn is_valid_procedure_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        _ => name == miden_assembly::ProcedureName::MAIN_PROC_NAME,
+    }
+}
+
+/// Mangle `name`, the name of a function in the IR, into a valid Miden Assembly procedure name.
+///
+/// Function names are derived from names in the source program, e.g. a component export with a
+/// kebab-case WIT name, such as `note-script`, and so may contain characters which are not valid
+/// in Miden Assembly. Valid names are returned unchanged, otherwise the name is prefixed with
+/// `M_`, any `_` is doubled, and any other invalid character is replaced with its code point in
+/// hex between underscores, e.g. `note-script` becomes `M_note_2d_script`.
+///
+/// The mangling is deterministic, and can be reversed with [demangle_procedure_name].
+pub fn mangle_procedure_name(name: &str) -> Cow<'_, str> {
+    if is_valid_procedure_name(name) && !name.starts_with(MANGLED_PREFIX) {
+        return Cow::Borrowed(name);
+    }
+    let mut mangled = String::with_capacity(MANGLED_PREFIX.len() + name.len() * 2);
+    mangled.push_str(MANGLED_PREFIX);
+    for c in name.chars() {
+        match c {
+            '_' => mangled.push_str("__"),
+            c if c.is_ascii_alphanumeric() => mangled.push(c),
+            c => write!(mangled, "_{:x}_", c as u32).unwrap(),
+        }
+    }
+    Cow::Owned(mangled)
+}
+
+/// Recover the original name of a procedure whose name was mangled by [mangle_procedure_name].
+///
+/// Names which were not mangled are returned unchanged.
+pub fn demangle_procedure_name(name: &str) -> Cow<'_, str> {
+    let Some(mangled) = name.strip_prefix(MANGLED_PREFIX) else {
+        return Cow::Borrowed(name);
+    };
+    let mut demangled = String::with_capacity(mangled.len());
+    let mut chars = mangled.chars();
+    while let Some(c) = chars.next() {
+        if c != '_' {
+            demangled.push(c);
+            continue;
+        }
+        // Either `__`, an escaped `_`, or an escaped code point terminated by `_`
+        let mut code_point = String::new();
+        let mut terminated = false;
+        for c in chars.by_ref() {
+            if c == '_' {
+                terminated = true;
+                break;
+            }
+            code_point.push(c);
+        }
+        if !terminated {
+            return Cow::Borrowed(name);
+        }
+        if code_point.is_empty() {
+            demangled.push('_');
+            continue;
+        }
+        match u32::from_str_radix(&code_point, 16)
+            .ok()
+            .and_then(char::from_u32)
+        {
+            Some(c) => demangled.push(c),
+            None => return Cow::Borrowed(name),
+        }
+    }
+    Cow::Owned(demangled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn procedure_name_mangling_roundtrip() {
+        for name in [
+            "note-script",
+            "tx-kernel",
+            "miden:base/note@1.0.0#note-script",
+            "<T as core::any::Any>::type_id",
+            "M_already_prefixed",
+            "snake_case_name",
+            "_leading_underscore",
+            "0leading_digit",
+            "ünïcödé",
+            "",
+        ] {
+            let mangled = mangle_procedure_name(name);
+            assert!(
+                is_valid_procedure_name(&mangled),
+                "mangling {name:?} produced invalid name {mangled:?}"
+            );
+            assert_eq!(demangle_procedure_name(&mangled), name);
+        }
+    }
+
+    #[test]
+    fn valid_procedure_names_are_not_mangled() {
+        for name in ["entrypoint", "checked_add", "fib", "note_script"] {
+            assert_eq!(mangle_procedure_name(name), name);
+            assert_eq!(demangle_procedure_name(name), name);
+        }
+        assert_eq!(mangle_procedure_name("note-script"), "M_note_2d_script");
+        assert_eq!(mangle_procedure_name("tx-kernel"), "M_tx_2d_kernel");
+    }
+}
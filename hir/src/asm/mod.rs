@@ -2,12 +2,14 @@ mod builder;
 mod display;
 mod import;
 mod isa;
+mod mangle;
 mod stack;
 
 pub use self::builder::*;
 pub use self::display::{DisplayInlineAsm, DisplayMasmBlock};
 pub use self::import::{MasmImport, ModuleImportInfo};
 pub use self::isa::*;
+pub use self::mangle::{demangle_procedure_name, is_valid_procedure_name, mangle_procedure_name};
 pub use self::stack::{OperandStack, Stack, StackElement};
 
 use cranelift_entity::PrimaryMap;
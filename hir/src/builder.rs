@@ -996,6 +996,19 @@ pub trait InstBuilder<'f>: InstBuilderBase<'f> {
         into_first_result!(self.Unary(Opcode::Cast, ty, arg, span))
     }
 
+    /// Reinterpret the bits of `arg` as a value of type `ty`, e.g. `f64` as `i64`
+    ///
+    /// NOTE: This is only valid between numeric types of the same size
+    fn bitcast(self, arg: Value, ty: Type, span: SourceSpan) -> Value {
+        let arg_ty = self.data_flow_graph().value_type(arg);
+        assert!(
+            arg_ty.is_numeric() && ty.is_numeric() && arg_ty.size_in_bits() == ty.size_in_bits(),
+            "invalid bitcast, expected numeric types of the same size: value is of type {}, and target type is {}",
+            &arg_ty, &ty
+        );
+        into_first_result!(self.Unary(Opcode::Bitcast, ty, arg, span))
+    }
+
     /// Truncates an integral value as necessary to fit in `ty`.
     ///
     /// NOTE: Truncating a value into a larger type has undefined behavior, it is
@@ -48,6 +48,30 @@ pub struct ComponentExport {
     pub function_ty: LiftedFunctionType,
     /// The method of calling the function
     pub invoke_method: FunctionInvocationMethod,
+    /// The module function to call once the results of `function` have been lifted, if any
+    ///
+    /// This is the `post-return` canonical option, which per the canonical ABI is called with
+    /// the core (flattened) results of `function`, after the caller has read them, typically to
+    /// free any memory allocated for those results. When the component is linked, the export is
+    /// invoked through a wrapper which makes this call, see [Component::link].
+    pub post_return: Option<FunctionIdent>,
+}
+
+/// The name of the module holding the wrappers synthesized by [Component::link] for the exports
+/// with a `post-return` function
+pub const POST_RETURN_WRAPPERS_MODULE_NAME: &str = "post_returns";
+
+/// Determines which functions of a [Component] can be invoked by the host, once the component
+/// has been linked into a [Program] with [Component::link]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComponentEntrypoints {
+    /// Link an executable program with a single entrypoint, i.e. the one set with
+    /// [ComponentBuilder::with_entrypoint], or the function with the `entrypoint` attribute
+    #[default]
+    Single,
+    /// Link a library without an entrypoint, in which each export of the component is a public
+    /// procedure, e.g. the methods of a Miden account, such as `receive-asset` and `send-asset`
+    Exports,
 }
 
 /// A [Component] is a collection of [Module]s that are being compiled together as a package and have exports/imports.
@@ -61,6 +85,13 @@ pub struct Component {
 
     /// A list of this component's exports, indexed by export name
     exports: BTreeMap<FunctionExportName, ComponentExport>,
+
+    /// The entrypoint of this component, if one was set when building it
+    entrypoint: Option<FunctionIdent>,
+
+    /// The start functions of the modules of this component, in the order in which the modules
+    /// are instantiated
+    start_functions: Vec<FunctionIdent>,
 }
 
 impl Component {
@@ -98,6 +129,156 @@ impl Component {
     pub fn exports(&self) -> &BTreeMap<FunctionExportName, ComponentExport> {
         &self.exports
     }
+
+    /// Set the entrypoint of this component, i.e. the function invoked by the program it is linked
+    /// into with [ComponentEntrypoints::Single], e.g. one of its exports
+    pub fn set_entrypoint(&mut self, id: FunctionIdent) {
+        self.entrypoint = Some(id);
+    }
+
+    /// Returns the start functions of the modules of this component, in the order in which they
+    /// must be run
+    pub fn start_functions(&self) -> &[FunctionIdent] {
+        &self.start_functions
+    }
+
+    /// Link the modules of this component into a [Program], whose entrypoints are determined by
+    /// `entrypoints`.
+    ///
+    /// With [ComponentEntrypoints::Exports], every export must be defined by this component, with
+    /// external linkage, as it becomes a public procedure of the resulting library.
+    ///
+    /// An export with a `post-return` function is invoked through a wrapper, which calls the
+    /// post-return function once the results of the export have been lifted, see
+    /// [Component::wrap_post_returns]. The wrapper is the procedure of the library the host
+    /// invokes for that export, or the entrypoint of the program in place of the export.
+    pub fn link(mut self, entrypoints: ComponentEntrypoints) -> Result<Box<Program>, LinkerError> {
+        let wrappers = self.wrap_post_returns()?;
+        let mut linker = Linker::new();
+        match entrypoints {
+            ComponentEntrypoints::Single => {
+                let entrypoint = self
+                    .entrypoint
+                    .or_else(|| self.modules.iter().find_map(|m| m.entrypoint()))
+                    .map(|entry| wrappers.get(&entry).copied().unwrap_or(entry));
+                if let Some(entry) = entrypoint {
+                    linker.with_entrypoint(entry)?;
+                }
+            }
+            ComponentEntrypoints::Exports => {
+                for export in self.exports.values() {
+                    let id = export.function;
+                    let module = self
+                        .modules
+                        .find(&id.module)
+                        .get()
+                        .ok_or(LinkerError::MissingModule(id.module))?;
+                    let function = module
+                        .function(id.function)
+                        .ok_or(LinkerError::MissingFunction(id))?;
+                    if !function.is_public() {
+                        return Err(LinkerError::InvalidEntryLinkage(id));
+                    }
+                }
+            }
+        }
+
+        // The modules are added in the order of their names, so the start functions are added
+        // beforehand, in the order in which the modules are instantiated
+        for start in self.start_functions.iter().copied() {
+            linker.with_start(start);
+        }
+
+        for module in self.modules.take().into_iter() {
+            linker.add(module)?;
+        }
+
+        linker.link()
+    }
+
+    /// Synthesize a function in the [POST_RETURN_WRAPPERS_MODULE_NAME] module for each export
+    /// with a `post-return` function, named after the export, which calls the exported function,
+    /// then the post-return function with its results, and returns those results, and make it
+    /// the function of that export.
+    ///
+    /// Returns the wrapper of each exported function which has one.
+    fn wrap_post_returns(&mut self) -> Result<BTreeMap<FunctionIdent, FunctionIdent>, LinkerError> {
+        let mut wrappers = BTreeMap::new();
+        let module_name = Ident::with_empty_span(Symbol::intern(POST_RETURN_WRAPPERS_MODULE_NAME));
+        let mut module = Box::new(Module::new(module_name));
+        let modules = &self.modules;
+        let signature = |id: FunctionIdent| {
+            let module = modules
+                .find(&id.module)
+                .get()
+                .ok_or(LinkerError::MissingModule(id.module))?;
+            module
+                .function(id.function)
+                .map(|function| function.signature.clone())
+                .ok_or(LinkerError::MissingFunction(id))
+        };
+        for (name, export) in self.exports.iter_mut() {
+            let Some(post_return) = export.post_return else {
+                continue;
+            };
+            let export_signature = signature(export.function)?;
+            let post_return_signature = signature(post_return)?;
+            let results = export_signature
+                .results()
+                .iter()
+                .map(|result| &result.ty)
+                .collect::<Vec<_>>();
+            let post_return_params = post_return_signature
+                .params()
+                .iter()
+                .map(|param| &param.ty)
+                .collect::<Vec<_>>();
+            if results.len() > 1
+                || results != post_return_params
+                || !post_return_signature.results().is_empty()
+            {
+                return Err(LinkerError::InvalidPostReturn(post_return));
+            }
+
+            let id = FunctionIdent {
+                module: module_name,
+                function: Ident::with_empty_span(name.0),
+            };
+            let mut wrapper_signature = export_signature.clone();
+            wrapper_signature.linkage = Linkage::External;
+            let mut function = Box::new(Function::new(id, wrapper_signature));
+            let mut builder = FunctionBuilder::new(&mut function);
+            let span = SourceSpan::UNKNOWN;
+            let args = builder.block_params(builder.entry_block()).to_vec();
+            for (callee, signature) in [
+                (export.function, export_signature),
+                (post_return, post_return_signature),
+            ] {
+                builder
+                    .func
+                    .dfg
+                    .import_function(callee.module, callee.function, signature)
+                    .map_err(|_| LinkerError::SignatureMismatch(callee))?;
+            }
+            let call = builder.ins().call(export.function, &args, span);
+            let results = builder.inst_results(call).to_vec();
+            builder.ins().call(post_return, &results, span);
+            builder.ins().ret(results.first().copied(), span);
+            module
+                .push(function)
+                .map_err(|_| LinkerError::ModuleConflict(module_name))?;
+
+            wrappers.insert(export.function, id);
+            export.function = id;
+        }
+        if !wrappers.is_empty() {
+            if self.contains(module_name) {
+                return Err(LinkerError::ModuleConflict(module_name));
+            }
+            self.modules.insert(module);
+        }
+        Ok(wrappers)
+    }
 }
 
 /// This struct provides an ergonomic way to construct a [Component] in an imperative fashion.
@@ -108,6 +289,7 @@ pub struct ComponentBuilder<'a> {
     imports: BTreeMap<FunctionIdent, ComponentImport>,
     exports: BTreeMap<FunctionExportName, ComponentExport>,
     entry: Option<FunctionIdent>,
+    start_functions: Vec<FunctionIdent>,
     diagnostics: &'a miden_diagnostics::DiagnosticsHandler,
 }
 impl<'a> ComponentBuilder<'a> {
@@ -115,6 +297,7 @@ impl<'a> ComponentBuilder<'a> {
         Self {
             modules: Default::default(),
             entry: None,
+            start_functions: Default::default(),
             diagnostics,
             exports: Default::default(),
             imports: Default::default(),
@@ -176,6 +359,16 @@ impl<'a> ComponentBuilder<'a> {
         self.exports.insert(name, export);
     }
 
+    /// Add `id` to the start functions of the [Component] being built.
+    ///
+    /// Start functions are run in the order in which they are added, which should be the order
+    /// in which their modules are instantiated.
+    pub fn add_start(&mut self, id: FunctionIdent) {
+        if !self.start_functions.contains(&id) {
+            self.start_functions.push(id);
+        }
+    }
+
     pub fn build(self) -> Component {
         let mut c = Component::default();
         for module in self.modules.into_values() {
@@ -183,6 +376,8 @@ impl<'a> ComponentBuilder<'a> {
         }
         c.exports = self.exports;
         c.imports = self.imports;
+        c.entrypoint = self.entry;
+        c.start_functions = self.start_functions;
         c
     }
 }
@@ -16,6 +16,9 @@ pub struct DataFlowGraph {
     pub insts: ArenaMap<Inst, InstNode>,
     pub results: SecondaryMap<Inst, ValueList>,
     pub values: PrimaryMap<Value, ValueData>,
+    /// Names of values, e.g. the names of the source variables they hold, which are only used to
+    /// make the IR easier to read
+    pub value_names: FxHashMap<Value, Symbol>,
     pub value_lists: ValueListPool,
     pub imports: FxHashMap<FunctionIdent, ExternalFunction>,
     pub globals: PrimaryMap<GlobalValue, GlobalValueData>,
@@ -40,6 +43,7 @@ impl DataFlowGraph {
             insts: ArenaMap::new(),
             results: SecondaryMap::new(),
             values: PrimaryMap::new(),
+            value_names: Default::default(),
             value_lists: ValueListPool::new(),
             imports: Default::default(),
             globals: PrimaryMap::new(),
@@ -176,6 +180,16 @@ impl DataFlowGraph {
     }
 
     #[inline(always)]
+    /// Returns the name given to `v`, if any
+    pub fn value_name(&self, v: Value) -> Option<Symbol> {
+        self.value_names.get(&v).copied()
+    }
+
+    /// Give `v` the name `name`, replacing any name it was given before
+    pub fn set_value_name(&mut self, v: Value, name: Symbol) {
+        self.value_names.insert(v, name);
+    }
+
     pub fn value_data(&self, v: Value) -> &ValueData {
         &self.values[v]
     }
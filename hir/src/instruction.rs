@@ -379,6 +379,8 @@ pub enum Opcode {
     /// It is not valid to perform a cast on any value other than a field element, see
     /// `Trunc`, `Zext`, and `Sext` for casts between machine integer types.
     Cast,
+    /// Reinterprets the bits of a value as another type of the same size, e.g. f64 -> i64
+    Bitcast,
     /// Truncates a larger integral type to a smaller integral type, e.g. i64 -> i32
     Trunc,
     /// Zero-extends a smaller unsigned integral type to a larger unsigned integral type, e.g. u32 -> u64
@@ -500,6 +502,7 @@ impl Opcode {
             | Self::PtrToInt
             | Self::IntToPtr
             | Self::Cast
+            | Self::Bitcast
             | Self::Trunc
             | Self::Zext
             | Self::Sext
@@ -590,6 +593,7 @@ impl Opcode {
             | Self::PtrToInt
             | Self::IntToPtr
             | Self::Cast
+            | Self::Bitcast
             | Self::Trunc
             | Self::Zext
             | Self::Sext
@@ -666,6 +670,7 @@ impl Opcode {
             | Self::PtrToInt
             | Self::IntToPtr
             | Self::Cast
+            | Self::Bitcast
             | Self::Trunc
             | Self::Zext
             | Self::Sext
@@ -731,6 +736,7 @@ impl fmt::Display for Opcode {
             Self::PtrToInt => f.write_str("ptrtoint"),
             Self::IntToPtr => f.write_str("inttoptr"),
             Self::Cast => f.write_str("cast"),
+            Self::Bitcast => f.write_str("bitcast"),
             Self::Trunc => f.write_str("trunc"),
             Self::Zext => f.write_str("zext"),
             Self::Sext => f.write_str("sext"),
@@ -51,6 +51,15 @@ pub struct Module {
     /// The set of functions which belong to this module, in the order
     /// in which they were defined.
     pub(crate) functions: LinkedList<FunctionListAdapter>,
+    /// The function which must be run to initialize this module, before any of its other
+    /// functions are called, e.g. the `start` function of a WebAssembly module.
+    ///
+    /// Unlike the entrypoint of a program, a module may have a start function regardless of
+    /// whether it is linked into an executable or a library.
+    start: Option<FunctionIdent>,
+    /// The entities of this module which are exported to the host, but which have no symbol of
+    /// their own in the IR, e.g. the linear memory of a WebAssembly module.
+    entity_exports: Vec<EntityExport>,
     /// This flag indicates whether this module is a kernel module
     ///
     /// Kernel modules have additional constraints imposed on them that regular
@@ -168,6 +177,8 @@ impl fmt::Debug for Module {
             .field("segments", &self.segments)
             .field("globals", &self.globals)
             .field("functions", &self.functions)
+            .field("start", &self.start)
+            .field("entity_exports", &self.entity_exports)
             .finish()
     }
 }
@@ -188,6 +199,8 @@ impl PartialEq for Module {
         let is_eq = self.name == other.name
             && self.is_kernel == other.is_kernel
             && self.docs == other.docs
+            && self.start == other.start
+            && self.entity_exports == other.entity_exports
             && self.segments.iter().eq(other.segments.iter())
             && self.globals.len() == other.globals.len()
             && self.functions.iter().count() == other.functions.iter().count();
@@ -223,6 +236,24 @@ impl PartialEq for Module {
     }
 }
 
+/// An entity exported by a [Module] under `name`, which is not a function or global variable,
+/// and so has no symbol the host could find it by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityExport {
+    /// The linear memory, which is shared by all modules of a program
+    Memory { name: Ident },
+    /// The table with the given index in the module it was declared in
+    Table { name: Ident, index: u32 },
+}
+impl EntityExport {
+    /// The name this entity is exported under
+    pub fn name(&self) -> Ident {
+        match self {
+            Self::Memory { name } | Self::Table { name, .. } => *name,
+        }
+    }
+}
+
 /// This macro asserts that a function is valid for insertion into a given module.
 macro_rules! assert_valid_function {
     ($module:ident, $function:ident) => {
@@ -271,6 +302,8 @@ impl Module {
             segments: Default::default(),
             globals: GlobalVariableTable::new(ConflictResolutionStrategy::None),
             functions: Default::default(),
+            start: None,
+            entity_exports: vec![],
             is_kernel,
         }
     }
@@ -364,6 +397,98 @@ impl Module {
         })
     }
 
+    /// Get the start function of this module, if it has one
+    ///
+    /// The start function is run before any other function of the module is called, and is
+    /// distinct from the entrypoint of the program it is linked into, see [Module::entrypoint].
+    pub fn start(&self) -> Option<FunctionIdent> {
+        self.start
+    }
+
+    /// Set the start function of this module to `id`
+    ///
+    /// NOTE: This will panic if `id` does not refer to a function of this module.
+    pub fn set_start(&mut self, id: FunctionIdent) {
+        assert_eq!(
+            self.name, id.module,
+            "the start function of a module must be defined in that module"
+        );
+        self.start = Some(id);
+    }
+
+    /// Get the entities of this module which are exported without a symbol of their own, see
+    /// [EntityExport]
+    pub fn entity_exports(&self) -> &[EntityExport] {
+        self.entity_exports.as_slice()
+    }
+
+    /// Record that `export` is exported by this module
+    pub fn export_entity(&mut self, export: EntityExport) {
+        self.entity_exports.push(export);
+    }
+
+    /// Rename this module to `name`, updating the identifiers of its functions, and all references
+    /// to them from within this module, e.g. calls and inline assembly, to match.
+    ///
+    /// This can be used to avoid conflicts between modules which are linked together, e.g. when
+    /// their names were derived from the same source file name.
+    ///
+    /// NOTE: This will panic if the module is attached to a [Program], as the module is found there
+    /// by its name.
+    pub fn rename<S: Into<Ident>>(&mut self, name: S) {
+        assert!(
+            self.is_detached(),
+            "cannot rename module '{}' while it is attached to a program",
+            self.name
+        );
+        let old_name = core::mem::replace(&mut self.name, name.into());
+        let new_name = self.name;
+        let rename = |id: &mut FunctionIdent| {
+            if id.module == old_name {
+                id.module = new_name;
+            }
+        };
+
+        let mut functions = self.functions.take();
+        while let Some(mut function) = functions.pop_front() {
+            rename(&mut function.id);
+            let dfg = &mut function.dfg;
+            dfg.imports = core::mem::take(&mut dfg.imports)
+                .into_iter()
+                .map(|(mut id, mut import)| {
+                    rename(&mut id);
+                    rename(&mut import.id);
+                    (id, import)
+                })
+                .collect();
+            let insts = dfg
+                .blocks
+                .iter()
+                .flat_map(|(_, block)| block.insts())
+                .collect::<Vec<_>>();
+            for inst in insts {
+                match dfg.inst_mut(inst) {
+                    Instruction::Call(Call { callee, .. }) => rename(callee),
+                    Instruction::InlineAsm(InlineAsm { blocks, .. }) => {
+                        for op in blocks.values_mut().flat_map(|block| block.ops.iter_mut()) {
+                            match op {
+                                MasmOp::Exec(callee)
+                                | MasmOp::Syscall(callee)
+                                | MasmOp::ProcRef(callee) => rename(callee),
+                                _ => (),
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            self.functions.push_back(function);
+        }
+        if let Some(start) = self.start.as_mut() {
+            rename(start);
+        }
+    }
+
     /// Return an iterator over the functions in this module
     ///
     /// The iterator is double-ended, so can be used to traverse the module body in either direction
@@ -398,6 +523,44 @@ impl Module {
         imports
     }
 
+    /// Get the external functions referenced by this module, along with their signatures
+    ///
+    /// These are the symbols which must be satisfied by other modules at link time. Functions
+    /// defined in this module are never included, even if referenced via their fully-qualified
+    /// name. The result is sorted by function identifier.
+    pub fn imported_functions(&self) -> Vec<ExternalFunction> {
+        let locals = self
+            .functions
+            .iter()
+            .map(|f| f.id)
+            .collect::<FxHashSet<FunctionIdent>>();
+
+        let mut imports = BTreeMap::<FunctionIdent, ExternalFunction>::default();
+        for function in self.functions.iter() {
+            for import in function.imports() {
+                if !locals.contains(&import.id) {
+                    imports.entry(import.id).or_insert_with(|| import.clone());
+                }
+            }
+        }
+        imports.into_values().collect()
+    }
+
+    /// Get the functions with external linkage defined in this module, along with their signatures
+    ///
+    /// These are the symbols this module makes available to other modules at link time, in the
+    /// order in which they are defined.
+    pub fn exported_functions(&self) -> Vec<ExternalFunction> {
+        self.functions
+            .iter()
+            .filter(|f| f.is_public())
+            .map(|f| ExternalFunction {
+                id: f.id,
+                signature: f.signature.clone(),
+            })
+            .collect()
+    }
+
     /// Returns true if this module contains the function `name`
     pub fn contains(&self, name: Ident) -> bool {
         self.function(name).is_some()
@@ -614,23 +777,29 @@ impl<'a> ModuleCursor<'a> {
 
 pub struct ModuleBuilder {
     module: Box<Module>,
+    /// The names of all functions defined in `module`, so that symbol conflicts
+    /// can be detected without scanning the module's function list
+    function_names: FxHashSet<Symbol>,
 }
 impl From<Box<Module>> for ModuleBuilder {
     fn from(module: Box<Module>) -> Self {
-        Self { module }
+        let function_names = module
+            .functions()
+            .map(|f| f.id.function.as_symbol())
+            .collect();
+        Self {
+            module,
+            function_names,
+        }
     }
 }
 impl ModuleBuilder {
     pub fn new<S: Into<Ident>>(name: S) -> Self {
-        Self {
-            module: Box::new(Module::new(name)),
-        }
+        Self::from(Box::new(Module::new(name)))
     }
 
     pub fn new_kernel<S: Into<Ident>>(name: S) -> Self {
-        Self {
-            module: Box::new(Module::new_kernel(name)),
-        }
+        Self::from(Box::new(Module::new_kernel(name)))
     }
 
     pub fn with_span(&mut self, span: SourceSpan) -> &mut Self {
@@ -685,7 +854,8 @@ impl ModuleBuilder {
         signature: Signature,
     ) -> Result<ModuleFunctionBuilder<'a>, SymbolConflictError> {
         let name = name.into();
-        if let Some(prev) = self.module.function(name) {
+        if self.function_names.contains(&name.as_symbol()) {
+            let prev = self.module.function(name).unwrap();
             return Err(SymbolConflictError(prev.id));
         }
 
@@ -853,6 +1023,7 @@ impl<'m> ModuleFunctionBuilder<'m> {
         }
 
         let id = self.function.id;
+        self.builder.function_names.insert(id.function.as_symbol());
         self.builder.module.functions.push_back(self.function);
 
         Ok(id)
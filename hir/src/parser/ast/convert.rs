@@ -850,7 +850,7 @@ fn smallint_to_immediate(
         Type::I64 => Some(Immediate::I64(i as i64)),
         Type::U64 => Some(Immediate::U64(i as u64)),
         Type::I128 => Some(Immediate::I128(i as i128)),
-        Type::U128 | Type::U256 | Type::F64 => {
+        Type::U128 | Type::U256 | Type::F32 | Type::F64 => {
             diagnostics
                 .diagnostic(Severity::Error)
                 .with_message("invalid immediate operand")
@@ -890,7 +890,7 @@ fn bigint_to_immediate(
         Type::I64 => i.to_i64().map(Immediate::I64),
         Type::U64 if !is_negative => i.to_u64().map(Immediate::U64),
         Type::I128 => i.to_i128().map(Immediate::I128),
-        Type::U128 | Type::U256 | Type::F64 => {
+        Type::U128 | Type::U256 | Type::F32 | Type::F64 => {
             diagnostics
                 .diagnostic(Severity::Error)
                 .with_message("invalid immediate operand")
@@ -105,6 +105,7 @@ pub enum Token {
     PopCnt,
     IsOdd,
     Cast,
+    Bitcast,
     PtrToInt,
     IntToPtr,
     Neg,
@@ -144,6 +145,7 @@ pub enum Token {
     I128,
     U128,
     U256,
+    F32,
     F64,
     Felt,
     Const,
@@ -255,6 +257,7 @@ impl Token {
             "popcnt" => Self::PopCnt,
             "is_odd" => Self::IsOdd,
             "cast" => Self::Cast,
+            "bitcast" => Self::Bitcast,
             "ptrtoint" => Self::PtrToInt,
             "inttoprt" => Self::IntToPtr,
             "neg" => Self::Neg,
@@ -293,6 +296,7 @@ impl Token {
             "i128" => Self::I128,
             "u128" => Self::U128,
             "u256" => Self::U256,
+            "f32" => Self::F32,
             "f64" => Self::F64,
             "felt" => Self::Felt,
             "mut" => Self::Mut,
@@ -443,6 +447,7 @@ impl fmt::Display for Token {
             Self::PopCnt => write!(f, "popcnt"),
             Self::IsOdd => write!(f, "is_odd"),
             Self::Cast => write!(f, "cast"),
+            Self::Bitcast => write!(f, "bitcast"),
             Self::PtrToInt => write!(f, "ptrtoint"),
             Self::IntToPtr => write!(f, "inttoptr"),
             Self::Neg => write!(f, "neg"),
@@ -482,6 +487,7 @@ impl fmt::Display for Token {
             Self::I128 => write!(f, "i128"),
             Self::U128 => write!(f, "u128"),
             Self::U256 => write!(f, "u256"),
+            Self::F32 => write!(f, "f32"),
             Self::F64 => write!(f, "f64"),
             Self::Felt => write!(f, "felt"),
             Self::Mut => write!(f, "mut"),
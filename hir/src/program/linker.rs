@@ -55,6 +55,19 @@ pub enum LinkerError {
     /// Occurs when the declared entrypoint does not have external linkage
     #[error("invalid entrypoint '{0}': must have external linkage")]
     InvalidEntryLinkage(FunctionIdent),
+    /// Occurs when a start function does not have external linkage, or takes arguments or
+    /// produces results, as it is invoked on its own before the entrypoint
+    #[error(
+        "invalid start function '{0}': must have external linkage, no parameters and no results"
+    )]
+    InvalidStart(FunctionIdent),
+    /// Occurs when the post-return function of a component export does not take the results of
+    /// the export as arguments, produces results, or the export returns more than one result
+    #[error(
+        "invalid post-return function '{0}': must take the results of its export, of which there \
+         can be at most one, and produce no results"
+    )]
+    InvalidPostReturn(FunctionIdent),
     /// Occurs when attempting to set the program entrypoint when it has already been set
     #[error("conflicting entrypoints: '{current}' conflicts with previously declared entrypoint '{prev}'")]
     InvalidMultipleEntry {
@@ -202,6 +215,18 @@ impl Linker {
         Ok(())
     }
 
+    /// Add `id` to the start functions of the linked program
+    ///
+    /// Start functions are run in the order in which they were added, before the entrypoint.
+    /// The start function of each module added with [Linker::add] is added automatically when
+    /// the module is added, unless it was already added, so this is only needed to run the start
+    /// functions of several modules in a specific order.
+    pub fn with_start(&mut self, id: FunctionIdent) {
+        if !self.program.start_functions.contains(&id) {
+            self.program.start_functions.push(id);
+        }
+    }
+
     /// Add `module` to the set of modules to be linked
     ///
     /// This preprocesses the module for the linker, and will catch the following issues:
@@ -224,6 +249,10 @@ impl Linker {
             return Err(LinkerError::ModuleConflict(id));
         }
 
+        if let Some(start) = module.start() {
+            self.with_start(start);
+        }
+
         // Import all data segments
         while let Some(segment) = module.segments.pop_front() {
             self.program.segments.insert(segment)?;
@@ -335,6 +364,7 @@ impl Linker {
     /// * Verify that all referenced functions exist, or are known to be provided at runtime,
     ///   and that the signature known to the caller matches the actual definition.
     /// * Verifies that the entrypoint, if set, is valid
+    /// * Verifies that the start functions are valid
     /// * Verify that there are no cycles in the call graph, i.e. that there is no recursion present
     /// * Verify that all references to global symbols have corresponding definitions
     /// * Perform garbage collection of unreferenced globals
@@ -364,6 +394,21 @@ impl Linker {
             }
         }
 
+        // Verify the start functions, which are invoked without arguments
+        for start in self.program.start_functions.iter().copied() {
+            let module = self
+                .pending
+                .get(&start.module)
+                .ok_or(LinkerError::MissingModule(start.module))?;
+            let function = module
+                .function(start.function)
+                .ok_or(LinkerError::MissingFunction(start))?;
+            let signature = &function.signature;
+            if !function.is_public() || signature.arity() > 0 || !signature.results().is_empty() {
+                return Err(LinkerError::InvalidStart(start));
+            }
+        }
+
         // Verify module/function references
         for node in self.callgraph.nodes() {
             // If the module is pending, it is being linked
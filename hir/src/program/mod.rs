@@ -40,6 +40,10 @@ pub struct Program {
     /// that function will be used instead. If there are multiple functions with the `entrypoint`
     /// attribute, and this field is `None`, the linker will raise an error.
     entrypoint: Option<FunctionIdent>,
+    /// The start functions of the modules in this program, in the order in which they must run.
+    ///
+    /// These are run before the entrypoint, if this program is executable.
+    start_functions: Vec<FunctionIdent>,
     /// The data segments gathered from all modules in the program, and laid out in address order.
     segments: DataSegmentTable,
     /// The global variable table produced by linking the global variable tables of all
@@ -74,6 +78,12 @@ impl Program {
             .or_else(|| self.modules.iter().find_map(|m| m.entrypoint()))
     }
 
+    /// Returns the start functions of the modules in this program, in the order in which they must
+    /// be run, i.e. before the entrypoint, and before any other function of their module
+    pub fn start_functions(&self) -> &[FunctionIdent] {
+        &self.start_functions
+    }
+
     /// Return a reference to the module table for this program
     pub fn modules(&self) -> &RBTree<ModuleTreeAdapter> {
         &self.modules
@@ -187,6 +197,9 @@ impl<'a> ProgramBuilder<'a> {
     }
 
     /// Link a [Program] from the current [ProgramBuilder] state
+    ///
+    /// The start functions of the modules, if any, are run before the entrypoint, in the order
+    /// of the names of their modules.
     pub fn link(self) -> Result<Box<Program>, LinkerError> {
         let mut linker = Linker::new();
         let entrypoint = self
@@ -154,3 +154,187 @@ fn linker_test() {
         .link()
         .expect("failed to link program");
 }
+
+/// Test that renaming a module updates the identifiers of its functions, and the references to
+/// them from within the module, but not references to other modules
+#[test]
+fn module_rename_test() {
+    let context = TestContext::default();
+
+    let mut builder = ModuleBuilder::new("test");
+    let fib = testing::fib1(&mut builder, &context);
+    let sig = Signature::new([AbiParam::new(Type::U32)], [AbiParam::new(Type::U32)]);
+    {
+        let mut fb = builder
+            .function("main", sig.clone())
+            .expect("unexpected symbol conflict");
+        let fib = fb
+            .import_function(fib.module, fib.function, sig.clone())
+            .expect("unexpected symbol conflict");
+        let popcnt = fb
+            .import_function("other", "popcnt", sig.clone())
+            .expect("unexpected symbol conflict");
+        let n = fb.block_params(fb.entry_block())[0];
+        let call = fb.ins().call(fib, &[n], SourceSpan::UNKNOWN);
+        let result = fb.first_result(call);
+        let call = fb.ins().call(popcnt, &[result], SourceSpan::UNKNOWN);
+        let result = fb.first_result(call);
+        fb.ins().ret(Some(result), SourceSpan::UNKNOWN);
+        fb.build(&context.session.diagnostics)
+            .expect("unexpected validation error, see diagnostics output");
+    }
+    let mut module = builder.build();
+
+    module.rename("renamed");
+
+    assert_eq!(module.name.as_str(), "renamed");
+    let ids = module
+        .functions()
+        .map(|function| function.id.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["renamed::fib", "renamed::main"]);
+    let main = module
+        .function(Ident::with_empty_span(Symbol::intern("main")))
+        .unwrap();
+    let mut callees = main
+        .dfg
+        .block_insts(main.dfg.entry)
+        .filter_map(|inst| match main.dfg.inst(inst) {
+            Instruction::Call(call) => Some(call.callee.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    callees.sort();
+    assert_eq!(callees, ["other::popcnt", "renamed::fib"]);
+    let mut imports = main
+        .dfg
+        .imports()
+        .map(|import| import.id.to_string())
+        .collect::<Vec<_>>();
+    imports.sort();
+    assert_eq!(imports, ["other::popcnt", "renamed::fib"]);
+}
+
+/// Build a module `name` with an `init` function taking no arguments, marked as its start function
+fn module_with_start(name: &str, context: &TestContext) -> Box<Module> {
+    let mut builder = ModuleBuilder::new(name);
+    let mut fb = builder
+        .function("init", Signature::new([], []))
+        .expect("unexpected symbol conflict");
+    fb.ins().ret(None, SourceSpan::UNKNOWN);
+    let init = fb
+        .build(&context.session.diagnostics)
+        .expect("unexpected validation error, see diagnostics output");
+    let mut module = builder.build();
+    module.set_start(init);
+    module
+}
+
+/// Test that the start functions of the linked modules are carried over to the program, in the
+/// order given by the component, rather than the order of the module names
+#[test]
+fn linker_start_functions_test() {
+    let context = TestContext::default();
+
+    let mut builder = ComponentBuilder::new(&context.session.diagnostics);
+    for name in ["a", "b"] {
+        builder
+            .add_module(module_with_start(name, &context))
+            .expect("unexpected module conflict");
+    }
+    builder.add_start("b::init".parse().unwrap());
+    builder.add_start("a::init".parse().unwrap());
+    let component = builder.build();
+    let program = component
+        .link(ComponentEntrypoints::Exports)
+        .expect("failed to link program");
+    let start_functions = program
+        .start_functions()
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(start_functions, ["b::init", "a::init"]);
+    assert_eq!(program.entrypoint(), None);
+
+    // A start function must not expect any arguments
+    let mut builder = ModuleBuilder::new("test");
+    let fib = testing::fib1(&mut builder, &context);
+    let mut module = builder.build();
+    module.set_start(fib);
+    let err = ProgramBuilder::new(&context.session.diagnostics)
+        .with_module(module)
+        .expect("unexpected module conflict")
+        .link()
+        .expect_err("expected an invalid start function");
+    assert_matches!(err, LinkerError::InvalidStart(id) if id == fib);
+}
+
+/// Test that an export with a post-return function is invoked through a wrapper, which calls the
+/// post-return function with the results of the export
+#[test]
+fn linker_post_return_wrapper_test() {
+    let context = TestContext::default();
+
+    let mut builder = ModuleBuilder::new("m");
+    let mut fb = builder
+        .function(
+            "get",
+            Signature::new([AbiParam::new(Type::I32)], [AbiParam::new(Type::I32)]),
+        )
+        .expect("unexpected symbol conflict");
+    let arg = fb.block_params(fb.entry_block())[0];
+    fb.ins().ret(Some(arg), SourceSpan::UNKNOWN);
+    let get = fb
+        .build(&context.session.diagnostics)
+        .expect("unexpected validation error, see diagnostics output");
+    let mut fb = builder
+        .function("free", Signature::new([AbiParam::new(Type::I32)], []))
+        .expect("unexpected symbol conflict");
+    fb.ins().ret(None, SourceSpan::UNKNOWN);
+    let free = fb
+        .build(&context.session.diagnostics)
+        .expect("unexpected validation error, see diagnostics output");
+
+    let mut component_builder = ComponentBuilder::new(&context.session.diagnostics);
+    component_builder
+        .add_module(builder.build())
+        .expect("unexpected module conflict");
+    component_builder.add_export(
+        Symbol::intern("get").into(),
+        ComponentExport {
+            function: get,
+            function_ty: LiftedFunctionType {
+                params: vec![Type::I32],
+                results: vec![Type::I32],
+            },
+            invoke_method: FunctionInvocationMethod::Call,
+            post_return: Some(free),
+        },
+    );
+    let program = component_builder
+        .build()
+        .link(ComponentEntrypoints::Exports)
+        .expect("failed to link program");
+
+    let wrappers = program
+        .modules()
+        .find(&Ident::with_empty_span(Symbol::intern(
+            POST_RETURN_WRAPPERS_MODULE_NAME,
+        )))
+        .get()
+        .expect("expected a module holding the post-return wrappers");
+    let wrapper = wrappers
+        .function(Ident::with_empty_span(Symbol::intern("get")))
+        .expect("expected a wrapper for the export");
+    assert!(wrapper.is_public());
+    let callees = wrapper
+        .dfg
+        .blocks()
+        .flat_map(|(block, _)| wrapper.dfg.block_insts(block))
+        .filter_map(|inst| match wrapper.dfg.inst(inst) {
+            Instruction::Call(call) => Some(call.callee),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(callees, [get, free]);
+}
@@ -114,7 +114,28 @@ pub fn write_block_header(
         write!(w, ", ")?;
         write_arg(w, func, arg)?;
     }
-    writeln!(w, "):")
+    write!(w, "):")?;
+    write_value_names(w, func, func.dfg.block_params(block))?;
+    writeln!(w)
+}
+
+/// Write the names of those of `values` which have one, as a comment
+fn write_value_names(w: &mut dyn Write, func: &Function, values: &[Value]) -> fmt::Result {
+    let mut named = values
+        .iter()
+        .filter_map(|&v| func.dfg.value_name(v).map(|name| (v, name)))
+        .peekable();
+    if named.peek().is_none() {
+        return Ok(());
+    }
+    w.write_str(" //")?;
+    for (i, (v, name)) in named.enumerate() {
+        if i > 0 {
+            w.write_str(",")?;
+        }
+        write!(w, " {v}: {name}")?;
+    }
+    Ok(())
 }
 
 pub fn write_instruction(
@@ -154,9 +175,9 @@ pub fn write_instruction(
         }
     }
 
-    writeln!(w, ";")?;
-
-    Ok(())
+    write!(w, ";")?;
+    write_value_names(w, func, func.dfg.inst_results(inst))?;
+    writeln!(w)
 }
 
 fn write_operands(
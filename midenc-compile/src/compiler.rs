@@ -5,8 +5,8 @@ use clap::{Args, ColorChoice};
 use miden_diagnostics::term::termcolor::ColorChoice as MDColorChoice;
 use miden_diagnostics::Emitter;
 use midenc_session::{
-    InputFile, Options, OutputFile, OutputType, OutputTypeSpec, OutputTypes, ProjectType, Session,
-    TargetEnv, VerbosityFlag, Warnings,
+    Endianness, InitTrapPolicy, InputFile, Options, OutputFile, OutputType, OutputTypeSpec,
+    OutputTypes, ProcedureOrder, ProjectType, Session, TargetEnv, VerbosityFlag, Warnings,
 };
 
 /// Compile a program from WebAssembly or Miden IR, to Miden Assembly.
@@ -41,6 +41,9 @@ pub struct Compiler {
         help_heading = "Diagnostics"
     )]
     warn: Warnings,
+    /// Stop emitting errors after `<N>` have been reported
+    #[arg(long, value_name = "N", help_heading = "Diagnostics")]
+    max_errors: Option<usize>,
     /// Whether, and how, to color terminal output
     #[arg(long, value_enum, default_value_t = ColorChoice::Auto, default_missing_value = "auto", help_heading = "Diagnostics")]
     color: ColorChoice,
@@ -68,6 +71,78 @@ pub struct Compiler {
         help_heading = "Compiler"
     )]
     is_library: bool,
+    /// The byte order in which multi-byte values are laid out in memory
+    ///
+    /// Only little-endian, the byte order of WebAssembly, is currently implemented, so compiling
+    /// with `--endianness=big` fails with an error.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "ORDER",
+        default_value_t = Endianness::Little,
+        help_heading = "Compiler"
+    )]
+    endianness: Endianness,
+    /// Report every unsupported feature used by the input, then stop without compiling it
+    ///
+    /// Unlike a normal compilation, this does not stop at the first unsupported feature, and
+    /// prints the number of uses of each one, along with the location of the first use.
+    #[arg(long, default_value_t = false, help_heading = "Compiler")]
+    list_unsupported: bool,
+    /// Replace functions which use an unsupported feature with a stub which traps when called
+    ///
+    /// A warning is emitted for each such function, rather than failing compilation, so the
+    /// problem only manifests if the function is actually called.
+    #[arg(long, default_value_t = false, help_heading = "Compiler")]
+    stub_unsupported: bool,
+    /// How a trap raised by the static constructors of a module is surfaced
+    ///
+    /// By default, the trap aborts the program. When set to `report`, an explicit trap in the
+    /// constructors is caught instead, and recorded in the `__wasm_init_trapped` global, so that
+    /// the embedder can decide how the initialization failure is surfaced.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "POLICY",
+        default_value_t = InitTrapPolicy::Propagate,
+        help_heading = "Compiler"
+    )]
+    init_traps: InitTrapPolicy,
+    /// Trap on loads and stores of unaligned addresses, rather than emulating them
+    ///
+    /// Alignment hints are not taken into account, as they may be wrong.
+    #[arg(long, default_value_t = false, help_heading = "Compiler")]
+    trap_on_unaligned: bool,
+    /// Make integer division and remainder by zero produce zero, rather than trapping
+    ///
+    /// This is meant for code which checks its divisors before dividing. By default, as in Wasm,
+    /// division by zero traps.
+    #[arg(long, default_value_t = false, help_heading = "Compiler")]
+    wrapping_division: bool,
+    /// Lower atomic operations as if the program is single-threaded, rather than rejecting them
+    ///
+    /// Atomic loads, stores and read-modify-writes become plain memory accesses,
+    /// `memory.atomic.wait` returns immediately on a shared memory, and traps on any other, and
+    /// `memory.atomic.notify` wakes no waiters. A warning is emitted for each atomic operation.
+    #[arg(long, default_value_t = false, help_heading = "Compiler")]
+    single_threaded_atomics: bool,
+    /// Link the Miden Assembly module at `PATH` wherever a module named `NAME` is imported
+    ///
+    /// This takes precedence over the built-in intrinsics module of the same name, if there is
+    /// one, e.g. `--intrinsic intrinsics::mem=mem.masm` replaces the memory intrinsics. May be
+    /// given more than once.
+    #[arg(
+        long = "intrinsic",
+        value_name = "NAME=PATH",
+        value_parser = parse_intrinsic_module,
+        help_heading = "Compiler"
+    )]
+    intrinsic_modules: Vec<(String, PathBuf)>,
+    /// Retain the DWARF debug info of the input, relating the compiled code to its source
+    ///
+    /// This is implied when emitting a debug map, i.e. `--emit=debug-map`.
+    #[arg(long, short = 'g', default_value_t = false, help_heading = "Compiler")]
+    debug_info: bool,
     /// Write all intermediate compiler artifacts to `<dir>`
     ///
     /// Defaults to a directory named `target` in the current working directory
@@ -106,6 +181,24 @@ pub struct Compiler {
         help_heading = "Output"
     )]
     output_types: Vec<OutputTypeSpec>,
+    /// Print the number of functions and instructions of each module, in both the IR and the
+    /// generated Miden Assembly, once compilation has finished
+    ///
+    /// This is meant for tracking the size of the generated code across compiler versions.
+    #[arg(long, default_value_t = false, help_heading = "Output")]
+    stats: bool,
+    /// The order in which the procedures of each module are emitted in Miden Assembly
+    ///
+    /// By default, procedures follow the order of the functions in the input. When set to `name`,
+    /// they are sorted by name instead, for output which is easier to compare across compilations.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "ORDER",
+        default_value_t = ProcedureOrder::Source,
+        help_heading = "Output"
+    )]
+    procedure_order: ProcedureOrder,
     /// Print the IR after each pass is applied
     #[arg(long, default_value_t = false, help_heading = "Passes")]
     print_ir_after_all: bool,
@@ -140,9 +233,22 @@ impl Compiler {
             .with_color(color)
             .with_verbosity(self.verbosity.into())
             .with_warnings(self.warn)
-            .with_output_types(output_types);
+            .with_max_errors(self.max_errors)
+            .with_output_types(output_types)
+            .with_endianness(self.endianness);
         options.print_ir_after_all = self.print_ir_after_all;
         options.print_ir_after_pass = self.print_ir_after_pass;
+        options.print_stats = self.stats;
+        options.procedure_order = self.procedure_order;
+        options.list_unsupported = self.list_unsupported;
+        options.stub_unsupported = self.stub_unsupported;
+        options.init_trap_policy = self.init_traps;
+        options.trap_on_unaligned_access = self.trap_on_unaligned;
+        options.wrapping_division = self.wrapping_division;
+        options.single_threaded_atomics = self.single_threaded_atomics;
+        options.intrinsic_modules = self.intrinsic_modules.into_iter().collect();
+        options.debug_info =
+            self.debug_info || options.output_types.contains_key(&OutputType::DebugMap);
 
         let output_file = match self.output_file {
             Some(path) => Some(OutputFile::Real(path)),
@@ -162,3 +268,15 @@ impl Compiler {
         .with_project_type(project_type)
     }
 }
+
+/// Parse the value of `--intrinsic`, i.e. `NAME=PATH`
+fn parse_intrinsic_module(spec: &str) -> Result<(String, PathBuf), String> {
+    match spec.split_once('=') {
+        Some((name, path)) if !name.is_empty() && !path.is_empty() => {
+            Ok((name.to_string(), PathBuf::from(path)))
+        }
+        _ => Err(format!(
+            "invalid intrinsic module '{spec}': expected NAME=PATH"
+        )),
+    }
+}
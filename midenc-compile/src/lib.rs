@@ -9,7 +9,8 @@ use self::stages::*;
 use std::sync::Arc;
 
 use miden_codegen_masm as masm;
-use miden_hir::{pass::AnalysisManager, Symbol};
+use miden_frontend_wasm::WasmTranslationConfig;
+use miden_hir::{pass::AnalysisManager, FunctionIdent, Symbol};
 use midenc_session::{OutputType, Session};
 
 pub use self::stages::Compiled;
@@ -48,12 +49,18 @@ pub enum CompilerError {
     /// An error occurred while emitting a MASL library
     #[error(transparent)]
     Masl(#[from] miden_assembly::LibraryError),
+    /// An error occurred while assembling a program for the Miden VM
+    #[error(transparent)]
+    Assembly(#[from] miden_assembly::AssemblyError),
     /// An error occurred when reading a file
     #[error(transparent)]
     Io(#[from] std::io::Error),
     /// An error occurred while compiling a program
     #[error(transparent)]
     Failed(#[from] anyhow::Error),
+    /// A Wasm component was given along with other inputs, which it cannot be linked with
+    #[error("a wasm component must be the only input, as it is linked from its own modules")]
+    ComponentWithOtherInputs,
     /// An error was emitted as a diagnostic, so we don't need to emit info to stdout
     #[error("exited due to error: see diagnostics for details")]
     Reported,
@@ -133,6 +140,10 @@ pub fn compile(session: Arc<Session>) -> CompilerResult<()> {
                 let bytes = masl.to_bytes();
                 std::fs::write(&path, bytes)?;
             }
+            session.emit(program)?;
+            if session.should_emit(OutputType::DebugMap) {
+                session.emit(&masm::DebugMap::new(program))?;
+            }
             if session.should_emit(OutputType::Masm) {
                 for module in program.modules() {
                     session.emit(module)?;
@@ -162,6 +173,10 @@ pub fn compile(session: Arc<Session>) -> CompilerResult<()> {
                 let bytes = masl.to_bytes();
                 std::fs::write(&path, bytes)?;
             }
+            session.emit(&program)?;
+            if session.should_emit(OutputType::DebugMap) {
+                session.emit(&masm::DebugMap::new(&program))?;
+            }
             if session.should_emit(OutputType::Masm) {
                 for module in program.modules() {
                     session.emit(module)?;
@@ -173,9 +188,14 @@ pub fn compile(session: Arc<Session>) -> CompilerResult<()> {
         Err(err) => {
             session.diagnostics.error(err);
             session.diagnostics.abort_if_errors();
+            return Ok(());
         }
     }
 
+    if session.options.print_stats {
+        print!("{}", session.statistics.size_report());
+    }
+
     Ok(())
 }
 
@@ -193,11 +213,64 @@ pub fn compile_to_memory(session: Arc<Session>) -> CompilerResult<Compiled> {
     }
 }
 
+/// Compile the Wasm module `wasm`, translated according to `config`, to a program for the Miden
+/// VM which executes `entrypoint`.
+///
+/// This runs the same stages as [compile_to_memory], from HIR rewrites to code generation, and
+/// then assembles the resulting Miden Assembly, along with the standard library, so that Wasm
+/// bytes can be turned into a runnable program in a single call.
+pub fn compile_wasm_to_core_program(
+    wasm: &[u8],
+    config: &WasmTranslationConfig,
+    entrypoint: FunctionIdent,
+    session: &Session,
+) -> CompilerResult<miden_core::Program> {
+    use miden_assembly::{Assembler, AssemblyContext};
+
+    let mut analyses = AnalysisManager::new();
+    let module = miden_frontend_wasm::translate_module(wasm, config, &session.diagnostics)?;
+    let module = ApplyRewritesStage.run(Box::new(module), &mut analyses, session)?;
+    let program = miden_hir::ProgramBuilder::new(&session.diagnostics)
+        .with_module(module)?
+        .with_entrypoint(entrypoint)
+        .link()?;
+    let Compiled::Program(program) =
+        CodegenStage.run(MaybeLinked::Linked(program), &mut analyses, session)?
+    else {
+        unreachable!("code generation for a linked program must produce a program")
+    };
+
+    let assembler = Assembler::default().with_library(&miden_stdlib::StdLibrary::default())?;
+    for module in program.modules() {
+        let module = module.to_module_ast(&session.codemap);
+        assembler.compile_module(
+            &module.ast,
+            Some(&module.path),
+            &mut AssemblyContext::for_module(false),
+        )?;
+    }
+    Ok(assembler.compile_ast(&program.to_program_ast(&session.codemap))?)
+}
+
 fn compile_inputs(
     inputs: Vec<midenc_session::InputFile>,
     analyses: &mut AnalysisManager,
     session: &Session,
 ) -> CompilerResult<Compiled> {
+    // A component is made up of its own modules, which are linked according to its exports
+    let is_component = |input: &midenc_session::InputFile| {
+        input.file_type() == midenc_session::FileType::WasmComponent
+    };
+    if let [input] = inputs.as_slice() {
+        if is_component(input) {
+            let mut stages = ParseStage.next(LinkComponentStage).next(CodegenStage);
+            return stages.run(input.clone(), analyses, session);
+        }
+    }
+    if inputs.iter().any(is_component) {
+        return Err(CompilerError::ComponentWithOtherInputs);
+    }
+
     let mut stages = ParseStage
         .next(SemanticAnalysisStage)
         .next_optional(ApplyRewritesStage)
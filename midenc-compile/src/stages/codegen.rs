@@ -25,15 +25,31 @@ impl Stage for CodegenStage {
     ) -> CompilerResult<Self::Output> {
         match input {
             MaybeLinked::Linked(program) => {
+                if session.options.print_stats {
+                    for module in program.modules().iter() {
+                        record_hir_size(module, session);
+                    }
+                }
                 let mut convert_to_masm = masm::ConvertHirToMasm::<hir::Program>::default();
                 let program = convert_to_masm.convert(program, analyses, session)?;
+                if session.options.print_stats {
+                    for module in program.modules() {
+                        record_masm_size(module, session);
+                    }
+                }
                 Ok(Compiled::Program(program))
             }
             MaybeLinked::Unlinked(modules) => {
                 let mut convert_to_masm = masm::ConvertHirToMasm::<hir::Module>::default();
                 let mut masm_modules = Vec::with_capacity(modules.len());
                 for module in modules.into_iter() {
+                    if session.options.print_stats {
+                        record_hir_size(&module, session);
+                    }
                     let masm_module = convert_to_masm.convert(module, analyses, session)?;
+                    if session.options.print_stats {
+                        record_masm_size(&masm_module, session);
+                    }
                     masm_modules.push(masm_module);
                 }
                 Ok(Compiled::Modules(masm_modules))
@@ -41,3 +57,37 @@ impl Stage for CodegenStage {
         }
     }
 }
+
+/// Record the number of functions and instructions of `module` in the session statistics
+fn record_hir_size(module: &hir::Module, session: &Session) {
+    let mut functions = 0;
+    let mut instructions = 0;
+    for function in module.functions() {
+        functions += 1;
+        let dfg = &function.dfg;
+        for (block, _) in dfg.blocks() {
+            instructions += dfg.block_insts(block).count();
+        }
+    }
+    session
+        .statistics
+        .record_hir_size(module.name.as_str(), functions, instructions);
+}
+
+/// Record the number of procedures and instructions of `module` in the session statistics
+fn record_masm_size(module: &masm::Module, session: &Session) {
+    let mut procedures = 0;
+    let mut instructions = 0;
+    for function in module.functions() {
+        procedures += 1;
+        instructions += function
+            .body
+            .blocks
+            .values()
+            .map(|block| block.ops.len())
+            .sum::<usize>();
+    }
+    session
+        .statistics
+        .record_masm_size(module.name.as_str(), procedures, instructions);
+}
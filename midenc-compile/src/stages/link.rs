@@ -1,3 +1,5 @@
+use midenc_session::ProjectType;
+
 use super::*;
 
 /// This type is used to represent the fact that depending on
@@ -32,3 +34,44 @@ impl Stage for LinkerStage {
         }
     }
 }
+
+/// Link together the modules of a Wasm component into an HIR program, applying the module-scoped
+/// rewrites to each of them first.
+///
+/// A library exports each of the exports of the component, while a program is entered via the
+/// entrypoint of the component.
+pub struct LinkComponentStage;
+impl Stage for LinkComponentStage {
+    type Input = ParseOutput;
+    type Output = MaybeLinked;
+
+    fn enabled(&self, session: &Session) -> bool {
+        !session.parse_only()
+    }
+
+    fn run(
+        &mut self,
+        input: Self::Input,
+        analyses: &mut AnalysisManager,
+        session: &Session,
+    ) -> CompilerResult<Self::Output> {
+        let ParseOutput::Component(mut component) = input else {
+            unreachable!("only components are linked by this stage")
+        };
+        let mut modules = vec![];
+        for module in component.modules_mut().take().into_iter() {
+            modules.push(ApplyRewritesStage.run(module, analyses, session)?);
+        }
+        if !session.should_link() {
+            return Ok(MaybeLinked::Unlinked(modules));
+        }
+        for module in modules.into_iter() {
+            component.modules_mut().insert(module);
+        }
+        let entrypoints = match session.project_type {
+            ProjectType::Program => hir::ComponentEntrypoints::Single,
+            ProjectType::Library => hir::ComponentEntrypoints::Exports,
+        };
+        Ok(MaybeLinked::Linked(component.link(entrypoints)?))
+    }
+}
@@ -13,7 +13,7 @@ mod rewrite;
 mod sema;
 
 pub use self::codegen::{CodegenStage, Compiled};
-pub use self::link::{LinkerStage, MaybeLinked};
+pub use self::link::{LinkComponentStage, LinkerStage, MaybeLinked};
 pub use self::parse::{ParseOutput, ParseStage};
 pub use self::rewrite::ApplyRewritesStage;
 pub use self::sema::SemanticAnalysisStage;
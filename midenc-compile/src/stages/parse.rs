@@ -1,4 +1,6 @@
-use midenc_session::InputFile;
+use miden_hir::{pass::PassInfo, FunctionInvocationMethod, Symbol};
+use miden_hir_transform::EliminateDeadFunctions;
+use midenc_session::{Emit, InputFile, OutputType};
 use std::path::Path;
 use wasm::WasmTranslationConfig;
 
@@ -11,6 +13,8 @@ pub enum ParseOutput {
     Ast(Box<ast::Module>),
     /// We parsed HIR from a Wasm module or other binary format
     Hir(Box<hir::Module>),
+    /// We parsed HIR from a Wasm component, whose modules are linked together
+    Component(Box<hir::Component>),
 }
 
 /// This stage of compilation is where we parse input files into the
@@ -34,6 +38,9 @@ impl Stage for ParseStage {
             InputType::Real(ref path) => match file_type {
                 FileType::Hir => self.parse_ast_from_file(path.as_ref(), &session),
                 FileType::Wasm => self.parse_hir_from_wasm_file(path.as_ref(), &session),
+                FileType::WasmComponent => {
+                    self.parse_hir_from_wasm_component_file(path.as_ref(), &session)
+                }
                 unsupported => unreachable!("unsupported file type: {unsupported}"),
             },
             InputType::Stdin { name, ref input } => match file_type {
@@ -41,10 +48,12 @@ impl Stage for ParseStage {
                 FileType::Wasm => self.parse_hir_from_wasm_bytes(
                     &input,
                     &session,
-                    &WasmTranslationConfig {
-                        source_name: name.to_string().clone(),
-                        ..Default::default()
-                    },
+                    &translation_config(name.to_string(), session),
+                ),
+                FileType::WasmComponent => self.parse_hir_from_wasm_component_bytes(
+                    &input,
+                    &session,
+                    &translation_config(name.to_string(), session),
                 ),
                 unsupported => unreachable!("unsupported file type: {unsupported}"),
             },
@@ -89,21 +98,114 @@ impl ParseStage {
         let mut bytes = Vec::with_capacity(1024);
         file.read_to_end(&mut bytes)?;
         let file_name = path.file_stem().unwrap().to_str().unwrap().to_owned();
-        let config = wasm::WasmTranslationConfig {
-            source_name: file_name,
-            ..Default::default()
-        };
+        let config = translation_config(file_name, session);
         self.parse_hir_from_wasm_bytes(&bytes, session, &config)
     }
 
+    fn parse_hir_from_wasm_component_file(
+        &self,
+        path: &Path,
+        session: &Session,
+    ) -> CompilerResult<ParseOutput> {
+        let bytes = std::fs::read(path)?;
+        let file_name = path.file_stem().unwrap().to_str().unwrap().to_owned();
+        let config = translation_config(file_name, session);
+        self.parse_hir_from_wasm_component_bytes(&bytes, session, &config)
+    }
+
+    fn parse_hir_from_wasm_component_bytes(
+        &self,
+        bytes: &[u8],
+        session: &Session,
+        config: &WasmTranslationConfig,
+    ) -> CompilerResult<ParseOutput> {
+        if session.options.list_unsupported {
+            let report = wasm::list_unsupported_features(bytes, config)?;
+            print!("{report}");
+            return Err(CompilerError::Stopped);
+        }
+        if session.should_emit(OutputType::Wat) {
+            session.emit(&Wat {
+                name: Symbol::intern(config.source_name()),
+                text: wasmprinter::print_bytes(bytes)?,
+            })?;
+        }
+        let component = wasm::translate_component(bytes, config, &session.diagnostics)?;
+        for module in component.modules().iter() {
+            session.emit(module)?;
+        }
+
+        Ok(ParseOutput::Component(Box::new(component)))
+    }
+
     fn parse_hir_from_wasm_bytes(
         &self,
         bytes: &[u8],
         session: &Session,
         config: &WasmTranslationConfig,
     ) -> CompilerResult<ParseOutput> {
+        if session.options.list_unsupported {
+            let report = wasm::list_unsupported_features(bytes, config)?;
+            print!("{report}");
+            return Err(CompilerError::Stopped);
+        }
+        if session.should_emit(OutputType::Wat) {
+            session.emit(&Wat {
+                name: Symbol::intern(config.source_name()),
+                text: wasmprinter::print_bytes(bytes)?,
+            })?;
+        }
+        if session.should_emit(OutputType::CallGraph) {
+            session.emit(&wasm::call_graph(bytes, config, &session.diagnostics)?)?;
+        }
         let module = wasm::translate_module(bytes, config, &session.diagnostics)?;
+        session.emit(&module)?;
 
         Ok(ParseOutput::Hir(Box::new(module)))
     }
 }
+
+/// The configuration with which the Wasm input `source_name` is translated in `session`
+fn translation_config(source_name: String, session: &Session) -> WasmTranslationConfig {
+    WasmTranslationConfig::builder()
+        .source_name(source_name)
+        .endianness(session.options.endianness)
+        .stub_unsupported_functions(session.options.stub_unsupported)
+        .init_trap_policy(session.options.init_trap_policy)
+        .trap_on_unaligned_access(session.options.trap_on_unaligned_access)
+        .wrapping_division(session.options.wrapping_division)
+        .single_threaded_atomics(session.options.single_threaded_atomics)
+        .parse_wasm_debuginfo(session.options.debug_info)
+        // Only functions with internal linkage can be eliminated, so the functions which do not
+        // escape their module are given internal linkage when dead functions are eliminated
+        .internalize_non_escaping_functions(matches!(
+            session
+                .matches()
+                .try_get_one::<bool>(EliminateDeadFunctions::FLAG),
+            Ok(Some(true))
+        ))
+        .codemap(session.codemap.clone())
+        // There is no way to give the metadata of the exports of a component on the command
+        // line, so they are invoked with `call`, as the methods of an account would be
+        .default_export_metadata(wasm::ExportMetadata {
+            invoke_method: FunctionInvocationMethod::Call,
+        })
+        .build()
+}
+
+/// The WebAssembly text format of a Wasm input
+struct Wat {
+    name: Symbol,
+    text: String,
+}
+impl Emit for Wat {
+    fn name(&self) -> Option<Symbol> {
+        Some(self.name)
+    }
+    fn output_type(&self) -> OutputType {
+        OutputType::Wat
+    }
+    fn write_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(self.text.as_bytes())
+    }
+}
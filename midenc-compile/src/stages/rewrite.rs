@@ -44,6 +44,7 @@ impl Stage for ApplyRewritesStage {
         let mut rewrites = RewriteSet::default();
         if registered.is_empty() {
             if session.should_codegen() {
+                rewrites.push(ModuleRewritePassAdapter::new(transforms::LowerSwitch));
                 rewrites.push(ModuleRewritePassAdapter::new(
                     transforms::SplitCriticalEdges,
                 ));
@@ -27,6 +27,9 @@ impl Stage for SemanticAnalysisStage {
                 Ok(module)
             }
             ParseOutput::Hir(module) => Ok(module),
+            // Components are linked by LinkComponentStage, so they only get here when given
+            // along with other inputs
+            ParseOutput::Component(_) => Err(CompilerError::ComponentWithOtherInputs),
         }
     }
 }
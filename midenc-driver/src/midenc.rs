@@ -2,13 +2,15 @@ use std::ffi::OsString;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use anyhow::anyhow;
 use clap::{ColorChoice, Parser, Subcommand};
 use miden_diagnostics::Emitter;
+use miden_frontend_wasm::UnsupportedFeature;
 use miden_hir::FunctionIdent;
 use midenc_compile as compile;
 use midenc_session::{InputFile, TargetEnv, VerbosityFlag, Warnings};
 
-use super::DriverError;
+use super::{DriverError, DriverResult};
 
 /// This struct provides the command-line interface used by `midenc`
 #[derive(Debug, Parser)]
@@ -147,6 +149,15 @@ enum Commands {
         #[arg(long, short = 'e', value_name = "NAME")]
         entrypoint: Option<FunctionIdent>,
     },
+    /// Print a detailed explanation of a diagnostic code
+    ///
+    /// Diagnostics for unsupported features refer to a code such as `MIDEN0001`. This
+    /// describes the limitation in more detail, and suggests possible workarounds.
+    Explain {
+        /// The diagnostic code to explain, e.g. `MIDEN0001`
+        #[arg(required(true), value_name = "CODE")]
+        code: String,
+    },
 }
 
 impl Midenc {
@@ -199,12 +210,89 @@ impl Midenc {
                     Err(err) => Err(DriverError::Compile(err)),
                 }
             }
+            Commands::Explain { code } => {
+                println!("{}", explain(&code)?);
+                Ok(())
+            }
             _ => unimplemented!(),
         }
     }
 }
 
+/// Get the detailed explanation for the diagnostic code `code`
+fn explain(code: &str) -> DriverResult<String> {
+    let feature = UnsupportedFeature::from_code(code)
+        .ok_or_else(|| anyhow!("unknown diagnostic code '{code}'"))?;
+    Ok(format!("{feature}: {}", feature.explain()))
+}
+
 fn format_error<I: clap::CommandFactory>(err: clap::Error) -> clap::Error {
     let mut cmd = I::command();
     err.format(&mut cmd)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explain_known_code() {
+        let text = explain("MIDEN0001").unwrap();
+        assert!(text.starts_with("MIDEN0001: "));
+        assert!(text.contains("single linear memory"));
+
+        for feature in UnsupportedFeature::ALL {
+            assert!(explain(feature.code()).is_ok());
+        }
+
+        let args = ["midenc", "explain", "MIDEN0001"].map(OsString::from);
+        assert!(Midenc::run(std::env::temp_dir(), args).is_ok());
+    }
+
+    #[test]
+    fn explain_unknown_code() {
+        let err = explain("MIDEN9999").unwrap_err();
+        assert_eq!(err.to_string(), "unknown diagnostic code 'MIDEN9999'");
+
+        let args = ["midenc", "explain", "MIDEN9999"].map(OsString::from);
+        assert!(Midenc::run(std::env::temp_dir(), args).is_err());
+    }
+
+    #[test]
+    fn emit_masm_ast() {
+        // (module
+        //     (func (export "add") (param i32 i32) (result i32)
+        //         local.get 0
+        //         local.get 1
+        //         i32.add))
+        const WASM: &[u8] = &[
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+            0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7f, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x07, 0x07, 0x01, 0x03, b'a', b'd', b'd', 0x00, 0x00, // export section
+            0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, // code section
+        ];
+
+        let dir = std::env::temp_dir().join("midenc-emit-masm-ast");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("add.wasm");
+        let output = dir.join("add.masm-ast");
+        std::fs::write(&input, WASM).unwrap();
+        let _ = std::fs::remove_file(&output);
+
+        let emit = format!("masm-ast={}", output.display());
+        let args = [
+            OsString::from("midenc"),
+            OsString::from("compile"),
+            input.into_os_string(),
+            OsString::from("--emit"),
+            OsString::from(emit),
+        ];
+        Midenc::run(&dir, args).expect("compilation failed");
+
+        let text = std::fs::read_to_string(&output).expect("masm-ast output was not written");
+        assert!(text.contains("mod "));
+        assert!(text.contains("export.add"));
+        assert!(!dir.join("add.masl").exists());
+    }
+}
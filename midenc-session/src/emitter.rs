@@ -0,0 +1,144 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use miden_diagnostics::term::termcolor::Buffer;
+use miden_diagnostics::{Emitter, Severity};
+
+/// An [Emitter] which forwards to another [Emitter], but stops printing error
+/// diagnostics once a configured limit has been reached.
+///
+/// When the first error beyond the limit is encountered, a single note is printed
+/// in its place informing the user that further errors have been suppressed. All
+/// subsequent errors are silently dropped. Diagnostics of other severities are
+/// always forwarded.
+///
+/// This is used to implement `--max-errors`, so that a badly broken input does not
+/// produce an unbounded amount of output.
+pub struct CappedEmitter {
+    inner: Arc<dyn Emitter>,
+    max_errors: usize,
+    errors: AtomicUsize,
+}
+impl CappedEmitter {
+    pub fn new(inner: Arc<dyn Emitter>, max_errors: usize) -> Self {
+        Self {
+            inner,
+            max_errors,
+            errors: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of error diagnostics seen by this emitter, including suppressed ones
+    pub fn error_count(&self) -> usize {
+        self.errors.load(Ordering::Relaxed)
+    }
+}
+impl Emitter for CappedEmitter {
+    #[inline]
+    fn buffer(&self) -> Buffer {
+        self.inner.buffer()
+    }
+
+    fn print(&self, buffer: Buffer) -> io::Result<()> {
+        if severity(buffer.as_slice()).map_or(true, |severity| severity < Severity::Error) {
+            return self.inner.print(buffer);
+        }
+
+        let seen = self.errors.fetch_add(1, Ordering::Relaxed);
+        match seen.cmp(&self.max_errors) {
+            core::cmp::Ordering::Less => self.inner.print(buffer),
+            core::cmp::Ordering::Equal => {
+                let mut note = self.inner.buffer();
+                writeln!(
+                    note,
+                    "note: further errors suppressed (limit of {} reached)",
+                    self.max_errors
+                )?;
+                self.inner.print(note)
+            }
+            core::cmp::Ordering::Greater => Ok(()),
+        }
+    }
+}
+
+/// Returns the severity of the rendered diagnostic in `bytes`, if it has one
+///
+/// The emitter only ever sees rendered output, so we recover the severity from
+/// the label of the diagnostic's header, i.e. the word preceding its code or message,
+/// ignoring any color escape sequences.
+fn severity(bytes: &[u8]) -> Option<Severity> {
+    let header = strip_leading_escapes(bytes);
+    let end = header
+        .iter()
+        .position(|b| matches!(b, b'[' | b':' | 0x1b))?;
+    match &header[..end] {
+        b"bug" => Some(Severity::Bug),
+        b"error" => Some(Severity::Error),
+        b"warning" => Some(Severity::Warning),
+        b"note" => Some(Severity::Note),
+        b"help" => Some(Severity::Help),
+        _ => None,
+    }
+}
+
+fn strip_leading_escapes(mut bytes: &[u8]) -> &[u8] {
+    while let [0x1b, b'[', rest @ ..] = bytes {
+        // CSI sequences are terminated by a byte in the range 0x40..=0x7e
+        match rest.iter().position(|b| (0x40..=0x7e).contains(b)) {
+            Some(end) => bytes = &rest[(end + 1)..],
+            None => return &[],
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_diagnostics::{CaptureEmitter, CodeMap, DiagnosticsHandler, Severity};
+
+    use super::*;
+
+    #[test]
+    fn max_errors_suppresses_excess_errors() {
+        let capture = Arc::new(CaptureEmitter::default());
+        let emitter = Arc::new(CappedEmitter::new(capture.clone(), 3));
+        let diagnostics =
+            DiagnosticsHandler::new(Default::default(), Arc::new(CodeMap::new()), emitter.clone());
+
+        for i in 0..100 {
+            diagnostics
+                .diagnostic(Severity::Error)
+                .with_message(format!("invalid item {i}"))
+                .emit();
+        }
+        diagnostics
+            .diagnostic(Severity::Warning)
+            .with_message("unused item")
+            .emit();
+
+        let captured = capture.captured();
+        assert_eq!(emitter.error_count(), 100);
+        assert_eq!(captured.matches("invalid item").count(), 3);
+        assert!(captured.contains("invalid item 2"));
+        assert!(!captured.contains("invalid item 3"));
+        assert_eq!(captured.matches("further errors suppressed").count(), 1);
+        assert!(captured.contains("unused item"));
+    }
+
+    #[test]
+    fn severity_is_recovered_from_colored_output() {
+        assert_eq!(
+            severity(b"\x1b[0m\x1b[1m\x1b[38;5;9merror\x1b[0m: oops"),
+            Some(Severity::Error)
+        );
+        assert_eq!(severity(b"error[E001]: oops"), Some(Severity::Error));
+        assert_eq!(severity(b"bug: oops"), Some(Severity::Bug));
+        assert_eq!(
+            severity(b"\x1b[1m\x1b[33mwarning\x1b[0m: hmm"),
+            Some(Severity::Warning)
+        );
+        // A message which merely starts with the word is not mistaken for an error
+        assert_eq!(severity(b"errors were found"), None);
+    }
+}
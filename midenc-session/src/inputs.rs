@@ -36,9 +36,23 @@ impl InputFile {
     /// This function returns an error if the contents are not a valid supported file type.
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, InvalidInputError> {
         let path = path.as_ref();
-        let file_type = FileType::try_from(path)?;
+        let mut file_type = FileType::try_from(path)?;
+        if file_type == FileType::Wasm {
+            // Components share their extension with core modules, so they are told apart by
+            // their preamble. A file which cannot be read is reported when it is parsed.
+            let mut preamble = [0; 8];
+            let read = std::fs::File::open(path)
+                .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut preamble));
+            if read.is_ok() && is_wasm_component(&preamble) {
+                file_type = FileType::WasmComponent;
+            }
+        }
         match file_type {
-            FileType::Hir | FileType::Wasm | FileType::Masm | FileType::Masl => Ok(Self {
+            FileType::Hir
+            | FileType::Wasm
+            | FileType::WasmComponent
+            | FileType::Masm
+            | FileType::Masl => Ok(Self {
                 file: InputType::Real(path.to_path_buf()),
                 file_type,
             }),
@@ -57,7 +71,7 @@ impl InputFile {
         std::io::stdin().read_to_end(&mut input)?;
         let file_type = FileType::detect(&input)?;
         match file_type {
-            FileType::Hir | FileType::Wasm => Ok(Self {
+            FileType::Hir | FileType::Wasm | FileType::WasmComponent => Ok(Self {
                 file: InputType::Stdin { name, input },
                 file_type,
             }),
@@ -157,6 +171,8 @@ pub enum FileType {
     Masm,
     Masl,
     Wasm,
+    /// A Wasm component, rather than a core module
+    WasmComponent,
     Wat,
 }
 impl fmt::Display for FileType {
@@ -166,12 +182,16 @@ impl fmt::Display for FileType {
             Self::Masm => f.write_str("masm"),
             Self::Masl => f.write_str("masl"),
             Self::Wasm => f.write_str("wasm"),
+            Self::WasmComponent => f.write_str("wasm component"),
             Self::Wat => f.write_str("wat"),
         }
     }
 }
 impl FileType {
     pub fn detect(bytes: &[u8]) -> Result<Self, InvalidInputError> {
+        if is_wasm_component(bytes) {
+            return Ok(FileType::WasmComponent);
+        }
         if bytes.starts_with(b"\0asm") {
             return Ok(FileType::Wasm);
         }
@@ -195,6 +215,12 @@ impl FileType {
         Err(InvalidInputError::UnrecognizedFileType)
     }
 }
+/// Returns true if `bytes` start with the preamble of a Wasm component, whose version is followed
+/// by a layer of 1, where that of a core module is 0
+fn is_wasm_component(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"\0asm") && bytes.get(6..8) == Some(&[1, 0][..])
+}
+
 impl TryFrom<&Path> for FileType {
     type Error = InvalidInputError;
 
@@ -1,5 +1,6 @@
 mod duration;
 mod emit;
+mod emitter;
 mod flags;
 mod inputs;
 mod options;
@@ -8,11 +9,12 @@ mod statistics;
 
 pub use self::duration::HumanDuration;
 pub use self::emit::Emit;
+pub use self::emitter::CappedEmitter;
 pub use self::flags::{CompileFlag, FlagAction};
 pub use self::inputs::{FileType, InputFile, InputType, InvalidInputError};
 pub use self::options::*;
 pub use self::outputs::{OutputFile, OutputFiles, OutputType, OutputTypeSpec, OutputTypes};
-pub use self::statistics::Statistics;
+pub use self::statistics::{ModuleSize, SizeReport, Statistics};
 
 use std::fmt;
 use std::path::{Path, PathBuf};
@@ -87,10 +89,15 @@ impl Session {
         };
         let codemap = Arc::new(CodeMap::new());
 
+        let emitter = emitter.unwrap_or_else(|| options.default_emitter());
+        let emitter: Arc<dyn Emitter> = match options.max_errors {
+            Some(max_errors) => Arc::new(CappedEmitter::new(emitter, max_errors)),
+            None => emitter,
+        };
         let diagnostics = Arc::new(DiagnosticsHandler::new(
             options.diagnostics.clone(),
             codemap.clone(),
-            emitter.unwrap_or_else(|| options.default_emitter()),
+            emitter,
         ));
 
         let output_files = match output_file {
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -22,16 +23,42 @@ pub struct Options {
     pub search_paths: Vec<PathBuf>,
     /// The location of the libraries which are shipped with the compiler
     pub sysroot: Option<PathBuf>,
+    /// User-provided Miden Assembly modules, by the name of the module they are imported as,
+    /// e.g. `intrinsics::hash`, which are linked in place of the built-in intrinsics module of
+    /// that name, if there is one
+    pub intrinsic_modules: BTreeMap<String, PathBuf>,
     /// Whether, and how, to color terminal output
     pub color: ColorChoice,
     /// The current diagnostics configuration
     pub diagnostics: DiagnosticsConfig,
+    /// The maximum number of errors to emit before suppressing the rest
+    pub max_errors: Option<usize>,
     /// The current working directory of the compiler
     pub current_dir: PathBuf,
     /// Print IR to stdout after each pass
     pub print_ir_after_all: bool,
     /// Print IR to stdout each time the named pass is applied
     pub print_ir_after_pass: Option<String>,
+    /// Print the size of the IR, and of the Miden Assembly, of each module after compilation
+    pub print_stats: bool,
+    /// The order in which the procedures of each module are emitted in Miden Assembly
+    pub procedure_order: ProcedureOrder,
+    /// The byte order in which multi-byte values are laid out in memory
+    pub endianness: Endianness,
+    /// Only report the unsupported features used by the input, without compiling it
+    pub list_unsupported: bool,
+    /// Replace functions using unsupported features with stubs which trap when called
+    pub stub_unsupported: bool,
+    /// How a trap raised while running the static constructors of a module is surfaced
+    pub init_trap_policy: InitTrapPolicy,
+    /// Trap on loads and stores of unaligned addresses, rather than emulating them
+    pub trap_on_unaligned_access: bool,
+    /// Make integer division and remainder by zero produce zero, rather than trapping
+    pub wrapping_division: bool,
+    /// Lower atomic operations as if the program is single-threaded, rather than rejecting them
+    pub single_threaded_atomics: bool,
+    /// Retain the debug info of the input, relating the compiled code to its source
+    pub debug_info: bool,
 }
 impl Default for Options {
     fn default() -> Self {
@@ -47,11 +74,23 @@ impl Options {
             output_types: Default::default(),
             search_paths: vec![],
             sysroot: None,
+            intrinsic_modules: BTreeMap::new(),
             color: Default::default(),
             diagnostics: Default::default(),
+            max_errors: None,
             current_dir,
             print_ir_after_all: false,
             print_ir_after_pass: None,
+            print_stats: false,
+            procedure_order: ProcedureOrder::default(),
+            endianness: Endianness::default(),
+            list_unsupported: false,
+            stub_unsupported: false,
+            init_trap_policy: InitTrapPolicy::default(),
+            trap_on_unaligned_access: false,
+            wrapping_division: false,
+            single_threaded_atomics: false,
+            debug_info: false,
         }
     }
 
@@ -83,11 +122,21 @@ impl Options {
         self
     }
 
+    pub fn with_max_errors(mut self, max_errors: Option<usize>) -> Self {
+        self.max_errors = max_errors;
+        self
+    }
+
     pub fn with_output_types(mut self, output_types: OutputTypes) -> Self {
         self.output_types = output_types;
         self
     }
 
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
     /// Get a new [miden_diagnostics::Emitter] based on the current options.
     pub fn default_emitter(&self) -> Arc<dyn Emitter> {
         use miden_diagnostics::{DefaultEmitter, NullEmitter};
@@ -117,6 +166,108 @@ pub enum OptLevel {
     SizeMin,
 }
 
+/// This enum represents the byte order in which multi-byte values are laid out in memory
+///
+/// Little-endian is the default, as it is the byte order used by WebAssembly, so data produced
+/// by a Wasm compiler, e.g. global initializers and data segments, can be used as-is.
+///
+/// NOTE: The code generator currently only implements little-endian memory accesses, so programs
+/// compiled with any other byte order are rejected.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+pub enum Endianness {
+    /// The least significant byte of a value is stored at the lowest address
+    #[default]
+    Little,
+    /// The most significant byte of a value is stored at the lowest address
+    Big,
+}
+impl fmt::Display for Endianness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Little => f.write_str("little"),
+            Self::Big => f.write_str("big"),
+        }
+    }
+}
+impl FromStr for Endianness {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "little" => Ok(Self::Little),
+            "big" => Ok(Self::Big),
+            _ => Err(()),
+        }
+    }
+}
+
+/// This enum represents how a trap raised while initializing a module, i.e. while running its
+/// static constructors, is surfaced
+///
+/// NOTE: The Miden VM has no means of recovering from a trap, so only explicit traps, i.e. Wasm
+/// `unreachable` instructions, in the body of the constructors themselves can be caught. A trap
+/// raised by a function they call, e.g. a failed assertion, always propagates.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+pub enum InitTrapPolicy {
+    /// The trap aborts the program, as any other trap would
+    #[default]
+    Propagate,
+    /// The trap is caught, and the rest of the constructors skipped, by returning from them
+    /// early, and it is reported by setting the `__wasm_init_trapped` global to a non-zero value,
+    /// which the embedder can inspect to decide how the failure is surfaced
+    Report,
+}
+impl fmt::Display for InitTrapPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Propagate => f.write_str("propagate"),
+            Self::Report => f.write_str("report"),
+        }
+    }
+}
+impl FromStr for InitTrapPolicy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "propagate" => Ok(Self::Propagate),
+            "report" => Ok(Self::Report),
+            _ => Err(()),
+        }
+    }
+}
+
+/// This enum represents the order in which the procedures of a module are emitted in Miden Assembly
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProcedureOrder {
+    /// Procedures are emitted in the order their functions appear in the IR module, which for a
+    /// Wasm input is the order of the functions in the Wasm module
+    #[default]
+    Source,
+    /// Procedures are emitted sorted by name, so that the output does not depend on the order in
+    /// which the functions of the IR module were built
+    Name,
+}
+impl fmt::Display for ProcedureOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Source => f.write_str("source"),
+            Self::Name => f.write_str("name"),
+        }
+    }
+}
+impl FromStr for ProcedureOrder {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "source" => Ok(Self::Source),
+            "name" => Ok(Self::Name),
+            _ => Err(()),
+        }
+    }
+}
+
 /// This enum represents the behavior of the compiler with regard to warnings
 #[derive(Debug, Copy, Clone, Default, ValueEnum)]
 pub enum Warnings {
@@ -11,31 +11,49 @@ use clap::ValueEnum;
 pub enum OutputType {
     /// The compiler will emit the abstract syntax tree of the input, if applicable
     Ast,
+    /// The compiler will emit the WebAssembly text format of the input, if it is a Wasm module
+    Wat,
     /// The compiler will emit Miden IR
     Hir,
     /// The compiler will emit Miden Assembly
     Masm,
+    /// The compiler will emit the textual form of the Miden Assembly program, before it is assembled
+    MasmAst,
     /// The compiler will emit a Miden Assembly program or library
     #[default]
     Masl,
+    /// The compiler will emit a JSON map relating the compiled procedures to the Wasm functions,
+    /// and source locations, they were compiled from
+    DebugMap,
+    /// The compiler will emit the call graph of the input, in the DOT format, if it is a Wasm
+    /// module
+    CallGraph,
 }
 impl OutputType {
     pub fn extension(&self) -> &'static str {
         match self {
             Self::Ast => "ast",
+            Self::Wat => "wat",
             Self::Hir => "hir",
             Self::Masm => "masm",
+            Self::MasmAst => "masm-ast",
             Self::Masl => "masl",
+            Self::DebugMap => "debug-map.json",
+            Self::CallGraph => "callgraph.dot",
         }
     }
 
     pub fn shorthand_display() -> String {
         format!(
-            "`{}`, `{}`, `{}`, `{}`",
+            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
             Self::Ast,
+            Self::Wat,
             Self::Hir,
             Self::Masm,
+            Self::MasmAst,
             Self::Masl,
+            Self::DebugMap,
+            Self::CallGraph,
         )
     }
 }
@@ -43,9 +61,13 @@ impl fmt::Display for OutputType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Ast => f.write_str("ast"),
+            Self::Wat => f.write_str("wat"),
             Self::Hir => f.write_str("hir"),
             Self::Masm => f.write_str("masm"),
+            Self::MasmAst => f.write_str("masm-ast"),
             Self::Masl => f.write_str("masl"),
+            Self::DebugMap => f.write_str("debug-map"),
+            Self::CallGraph => f.write_str("callgraph"),
         }
     }
 }
@@ -55,9 +77,13 @@ impl FromStr for OutputType {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "ast" => Ok(Self::Ast),
+            "wat" => Ok(Self::Wat),
             "hir" => Ok(Self::Hir),
             "masm" => Ok(Self::Masm),
+            "masm-ast" => Ok(Self::MasmAst),
             "masl" => Ok(Self::Masl),
+            "debug-map" => Ok(Self::DebugMap),
+            "callgraph" => Ok(Self::CallGraph),
             _ => Err(()),
         }
     }
@@ -252,13 +278,13 @@ impl OutputTypes {
     pub fn should_codegen(&self) -> bool {
         self.0
             .keys()
-            .any(|k| matches!(k, OutputType::Masm | OutputType::Masl))
+            .any(|k| matches!(k, OutputType::Masm | OutputType::MasmAst | OutputType::Masl))
     }
 
     pub fn should_link(&self) -> bool {
         self.0
             .keys()
-            .any(|k| matches!(k, OutputType::Masm | OutputType::Masl))
+            .any(|k| matches!(k, OutputType::Masm | OutputType::MasmAst | OutputType::Masl))
     }
 }
 
@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+use std::fmt;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 use crate::HumanDuration;
@@ -20,6 +23,8 @@ pub struct Statistics {
     opt_time: AtomicU64,
     /// The elapsed time at which codegen started
     codegen_time: AtomicU64,
+    /// The size of each module compiled, by module name
+    module_sizes: Mutex<BTreeMap<String, ModuleSize>>,
 }
 impl Default for Statistics {
     fn default() -> Statistics {
@@ -33,6 +38,7 @@ impl Statistics {
             parse_time: AtomicU64::new(NOT_STARTED),
             opt_time: AtomicU64::new(NOT_STARTED),
             codegen_time: AtomicU64::new(NOT_STARTED),
+            module_sizes: Default::default(),
         }
     }
 
@@ -70,6 +76,97 @@ impl Statistics {
     pub fn codegen_completed(&self) {
         store_duration(&self.codegen_time, self.elapsed())
     }
+
+    /// Record the size of the HIR of `module`, i.e. its number of functions and instructions
+    pub fn record_hir_size(&self, module: &str, functions: usize, instructions: usize) {
+        let mut sizes = self.module_sizes.lock().unwrap();
+        let size = sizes.entry(module.to_string()).or_default();
+        size.hir_functions = functions;
+        size.hir_instructions = instructions;
+    }
+
+    /// Record the size of the Miden Assembly of `module`, i.e. its number of procedures and
+    /// instructions
+    pub fn record_masm_size(&self, module: &str, procedures: usize, instructions: usize) {
+        let mut sizes = self.module_sizes.lock().unwrap();
+        let size = sizes.entry(module.to_string()).or_default();
+        size.masm_procedures = procedures;
+        size.masm_instructions = instructions;
+    }
+
+    /// Get the sizes recorded for each module so far, by module name
+    pub fn module_sizes(&self) -> BTreeMap<String, ModuleSize> {
+        self.module_sizes.lock().unwrap().clone()
+    }
+
+    /// Get a report of the sizes recorded for each module so far, along with their total
+    pub fn size_report(&self) -> SizeReport {
+        SizeReport(self.module_sizes())
+    }
+}
+
+/// The size of a compiled module, in terms of both its HIR, and the Miden Assembly generated
+/// from it. Sizes which were not recorded, e.g. of an intrinsics module, which has no HIR, are 0.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ModuleSize {
+    /// The number of functions in the HIR
+    pub hir_functions: usize,
+    /// The number of instructions in the HIR
+    pub hir_instructions: usize,
+    /// The number of procedures in the Miden Assembly
+    pub masm_procedures: usize,
+    /// The number of instructions in the Miden Assembly
+    pub masm_instructions: usize,
+}
+impl core::ops::AddAssign for ModuleSize {
+    fn add_assign(&mut self, rhs: Self) {
+        self.hir_functions += rhs.hir_functions;
+        self.hir_instructions += rhs.hir_instructions;
+        self.masm_procedures += rhs.masm_procedures;
+        self.masm_instructions += rhs.masm_instructions;
+    }
+}
+
+/// A table of the size of each compiled module, and their total, as printed by `--stats`
+pub struct SizeReport(BTreeMap<String, ModuleSize>);
+impl SizeReport {
+    /// Get the total size of all modules
+    pub fn total(&self) -> ModuleSize {
+        let mut total = ModuleSize::default();
+        for size in self.0.values() {
+            total += *size;
+        }
+        total
+    }
+}
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .0
+            .keys()
+            .map(|name| name.len())
+            .chain(["module".len(), "total".len()])
+            .max()
+            .unwrap();
+        writeln!(
+            f,
+            "{:<width$}  {:>13}  {:>16}  {:>15}  {:>17}",
+            "module", "hir functions", "hir instructions", "masm procedures", "masm instructions"
+        )?;
+        let total = self.total();
+        for (name, size) in self.0.iter().chain([(&"total".to_string(), &total)]) {
+            writeln!(
+                f,
+                "{:<width$}  {:>13}  {:>16}  {:>15}  {:>17}",
+                name,
+                size.hir_functions,
+                size.hir_instructions,
+                size.masm_procedures,
+                size.masm_instructions
+            )?;
+        }
+        Ok(())
+    }
 }
 
 fn store_duration(raw_secs_f64: &AtomicU64, duration: HumanDuration) {
@@ -3,6 +3,7 @@
 use std::fs;
 use std::io::Read;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command;
 use std::process::Stdio;
 use std::sync::Arc;
@@ -10,6 +11,7 @@ use std::sync::Arc;
 use miden_assembly::Assembler;
 use miden_assembly::AssemblyContext;
 use miden_codegen_masm::MasmCompiler;
+use miden_core::crypto::hash::RpoDigest;
 use miden_diagnostics::term::termcolor::ColorChoice;
 use miden_diagnostics::CodeMap;
 use miden_diagnostics::DefaultEmitter;
@@ -19,28 +21,48 @@ use miden_diagnostics::Emitter;
 use miden_diagnostics::NullEmitter;
 use miden_diagnostics::SourceSpan;
 use miden_diagnostics::Verbosity;
+use miden_frontend_wasm::translate_component;
 use miden_frontend_wasm::translate_module;
+use miden_frontend_wasm::ExportMetadata;
 use miden_frontend_wasm::WasmTranslationConfig;
 
 use miden_hir::pass::AnalysisManager;
 use miden_hir::pass::RewritePass;
 use miden_hir::pass::RewriteSet;
+use miden_hir::ComponentEntrypoints;
+use miden_hir::Felt;
 use miden_hir::FunctionIdent;
+use miden_hir::FunctionInvocationMethod;
 use miden_hir::Ident;
 use miden_hir::ModuleRewritePassAdapter;
 use miden_hir::ProgramBuilder;
 use miden_hir::Symbol;
+use miden_processor::AdviceInputs;
 use miden_stdlib::StdLibrary;
+use midenc_session::InitTrapPolicy;
 use midenc_session::InputFile;
 use midenc_session::Session;
 
+use crate::exec_vm::execute_vm_with_advice;
+use crate::felt_conversion::TestFelt;
+
 pub enum CompilerTestSource {
     Rust(String),
     RustCargo {
         cargo_project_folder_name: String,
         artifact_name: String,
     },
-    // Wasm(String),
+    Wat(String),
+    /// Wasm text format source of a component, which is executed by calling its export `export`
+    WatComponent {
+        source: String,
+        export: String,
+    },
+    /// A prebuilt Wasm module, e.g. a checked-in fixture, whose bytes are given directly
+    Wasm,
+    /// Miden Assembly source, which is assembled directly for execution on the VM, bypassing the
+    /// rest of the compiler
+    Masm(String),
     // Ir(String),
 }
 
@@ -56,6 +78,45 @@ impl CompilerTestSource {
     }
 }
 
+/// The reasons a [CompilerTest] could not be constructed from a Rust Cargo project
+#[derive(Debug, thiserror::Error)]
+pub enum CompilerTestError {
+    /// Cargo could not be run at all, e.g. because it, or the `component` subcommand, is missing
+    #[error("failed to execute {command}: {source}")]
+    Spawn {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    /// Cargo ran, but failed to compile the project
+    #[error("Rust to Wasm compilation failed:\n{stderr}")]
+    Build { stderr: String },
+    /// The project was compiled, but produced no Wasm artifact
+    #[error("expected one Wasm artifact, but none were produced:\n{stderr}")]
+    NoArtifacts { stderr: String },
+    /// The project was compiled, but produced more than one Wasm artifact
+    #[error(
+        "expected one Wasm artifact, but {} were produced: {artifacts:?}\n{stderr}",
+        .artifacts.len()
+    )]
+    MultipleArtifacts {
+        artifacts: Vec<PathBuf>,
+        stderr: String,
+    },
+    /// The output of cargo, or an artifact, could not be read
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// The command line of `command`, for use in error messages
+fn display_command(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| format!("'{}'", arg.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Compile to different stages (e.g. Wasm, IR, MASM) and compare the results against expected output
 pub struct CompilerTest {
     /// The compiler session
@@ -70,11 +131,26 @@ pub struct CompilerTest {
     pub hir: Option<Box<miden_hir::Program>>,
     /// The compiled MASM
     pub ir_masm: Option<Arc<miden_codegen_masm::Program>>,
+    /// The values placed on the operand stack when executing the program on the VM
+    stack_inputs: Vec<Felt>,
+    /// The advice inputs available to the program when executing it on the VM
+    advice_inputs: AdviceInputs,
 }
 
 impl CompilerTest {
     /// Compile the Wasm component from a Rust Cargo project using cargo-component
+    ///
+    /// Panics if the project could not be compiled, see
+    /// [CompilerTest::try_rust_source_cargo_component]
     pub fn rust_source_cargo_component(cargo_project_folder: &str) -> Self {
+        Self::try_rust_source_cargo_component(cargo_project_folder).unwrap()
+    }
+
+    /// Compile the Wasm component from a Rust Cargo project using cargo-component, which must
+    /// produce exactly one Wasm artifact
+    pub fn try_rust_source_cargo_component(
+        cargo_project_folder: &str,
+    ) -> Result<Self, CompilerTestError> {
         let manifest_path = format!("../rust-apps-wasm/{}/Cargo.toml", cargo_project_folder);
         // dbg!(&pwd);
         let mut cargo_build_cmd = Command::new("cargo");
@@ -92,83 +168,97 @@ impl CompilerTest {
             .arg("build-std=std,core,alloc,panic_abort")
             .arg("-Z")
             // abort on panic without message formatting (core::fmt uses call_indirect)
-            .arg("build-std-features=panic_immediate_abort");
+            .arg("build-std-features=panic_immediate_abort")
+            .arg("--message-format=json-render-diagnostics");
         let mut child = cargo_build_cmd
-            .arg("--message-format=json-render-diagnostics")
             .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
             .spawn()
-            .expect(
-                format!(
-                    "Failed to execute cargo build {}.",
-                    cargo_build_cmd
-                        .get_args()
-                        .map(|arg| format!("'{}'", arg.to_str().unwrap()))
-                        .collect::<Vec<_>>()
-                        .join(" ")
-                )
-                .as_str(),
-            );
+            .map_err(|source| CompilerTestError::Spawn {
+                command: display_command(&cargo_build_cmd),
+                source,
+            })?;
+        // Diagnostics are rendered to stderr, which is drained concurrently with stdout, so that
+        // cargo never blocks on a full pipe
+        let mut stderr_pipe = child.stderr.take().unwrap();
+        let stderr_reader = std::thread::spawn(move || {
+            let mut stderr = Vec::new();
+            stderr_pipe.read_to_end(&mut stderr).map(|_| stderr)
+        });
         let reader = std::io::BufReader::new(child.stdout.take().unwrap());
         let mut wasm_artifacts = Vec::new();
         for message in cargo_metadata::Message::parse_stream(reader) {
-            match message.expect("Failed to parse cargo metadata") {
-                cargo_metadata::Message::CompilerArtifact(artifact) => {
-                    // find the Wasm artifact in artifact.filenames
-                    for filename in artifact.filenames {
-                        if filename.as_str().ends_with(".wasm") {
-                            wasm_artifacts.push(filename.into_std_path_buf());
-                        }
+            if let cargo_metadata::Message::CompilerArtifact(artifact) = message? {
+                // find the Wasm artifact in artifact.filenames
+                for filename in artifact.filenames {
+                    if filename.as_str().ends_with(".wasm") {
+                        wasm_artifacts.push(filename.into_std_path_buf());
                     }
                 }
-                _ => (),
             }
         }
-        let output = child.wait().expect("Couldn't get cargo's exit status");
-        if !output.success() {
-            eprintln!("pwd: {:?}", std::env::current_dir().unwrap());
-            let mut stderr = Vec::new();
-            child
-                .stderr
-                .unwrap()
-                .read(&mut stderr)
-                .expect("Failed to read stderr");
-            let stderr = String::from_utf8(stderr).expect("Failed to parse stderr");
-            eprintln!("stderr: {}", stderr);
-            panic!("Rust to Wasm compilation failed!");
+        let status = child.wait()?;
+        let stderr = stderr_reader
+            .join()
+            .expect("the thread reading stderr panicked")?;
+        let stderr = String::from_utf8_lossy(&stderr).into_owned();
+        if !status.success() {
+            return Err(CompilerTestError::Build { stderr });
         }
-        assert!(output.success());
-        assert_eq!(wasm_artifacts.len(), 1, "Expected one Wasm artifact");
-        let wasm_comp_path = &wasm_artifacts.first().unwrap();
+        let wasm_comp_path = match wasm_artifacts.as_slice() {
+            [] => return Err(CompilerTestError::NoArtifacts { stderr }),
+            [path] => path,
+            _ => {
+                return Err(CompilerTestError::MultipleArtifacts {
+                    artifacts: wasm_artifacts,
+                    stderr,
+                })
+            }
+        };
         let artifact_name = wasm_comp_path
             .file_stem()
             .unwrap()
             .to_str()
             .unwrap()
             .to_string();
-        Self {
+        Ok(Self {
             session: default_session(),
             source: CompilerTestSource::RustCargo {
                 cargo_project_folder_name: cargo_project_folder.to_string(),
                 artifact_name,
             },
             entrypoint: None,
-            wasm_bytes: fs::read(wasm_artifacts.first().unwrap()).unwrap(),
+            wasm_bytes: fs::read(wasm_comp_path)?,
             hir: None,
             ir_masm: None,
-        }
+            stack_inputs: vec![],
+            advice_inputs: AdviceInputs::default(),
+        })
     }
 
     /// Set the Rust source code to compile using a Cargo project and binary bundle name
+    ///
+    /// Panics if the project could not be compiled, see [CompilerTest::try_rust_source_cargo]
     pub fn rust_source_cargo(
         cargo_project_folder: &str,
         artifact_name: &str,
         entrypoint: &str,
     ) -> Self {
+        Self::try_rust_source_cargo(cargo_project_folder, artifact_name, entrypoint).unwrap()
+    }
+
+    /// Set the Rust source code to compile using a Cargo project and binary bundle name
+    pub fn try_rust_source_cargo(
+        cargo_project_folder: &str,
+        artifact_name: &str,
+        entrypoint: &str,
+    ) -> Result<Self, CompilerTestError> {
         let manifest_path = format!("../rust-apps-wasm/{}/Cargo.toml", cargo_project_folder);
         // dbg!(&pwd);
         let temp_dir = std::env::temp_dir();
         let target_dir = temp_dir.join(cargo_project_folder);
-        let output = Command::new("cargo")
+        let mut cargo_build_cmd = Command::new("cargo");
+        cargo_build_cmd
             .arg("build")
             .arg("--manifest-path")
             .arg(manifest_path)
@@ -184,13 +274,17 @@ impl CompilerTest {
             .arg("build-std=core,alloc")
             .arg("-Z")
             // abort on panic without message formatting (core::fmt uses call_indirect)
-            .arg("build-std-features=panic_immediate_abort")
+            .arg("build-std-features=panic_immediate_abort");
+        let output = cargo_build_cmd
             .output()
-            .expect("Failed to execute cargo build.");
+            .map_err(|source| CompilerTestError::Spawn {
+                command: display_command(&cargo_build_cmd),
+                source,
+            })?;
         if !output.status.success() {
-            eprintln!("pwd: {:?}", std::env::current_dir().unwrap());
-            eprintln!("{}", String::from_utf8_lossy(&output.stderr));
-            panic!("Rust to Wasm compilation failed!");
+            return Err(CompilerTestError::Build {
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            });
         }
         let target_bin_file_path = Path::new(&target_dir)
             .join("wasm32-unknown-unknown")
@@ -198,10 +292,8 @@ impl CompilerTest {
             .join(artifact_name)
             .with_extension("wasm");
         // dbg!(&target_bin_file_path);
-        let mut target_bin_file = fs::File::open(target_bin_file_path).unwrap();
-        let mut wasm_bytes = vec![];
-        Read::read_to_end(&mut target_bin_file, &mut wasm_bytes).unwrap();
-        fs::remove_dir_all(target_dir).unwrap();
+        let wasm_bytes = fs::read(target_bin_file_path)?;
+        fs::remove_dir_all(target_dir)?;
 
         let session = default_session();
         let entrypoint = FunctionIdent {
@@ -211,7 +303,7 @@ impl CompilerTest {
                 SourceSpan::default(),
             ),
         };
-        CompilerTest {
+        Ok(CompilerTest {
             session,
             source: CompilerTestSource::RustCargo {
                 cargo_project_folder_name: cargo_project_folder.to_string(),
@@ -221,7 +313,9 @@ impl CompilerTest {
             entrypoint: Some(entrypoint),
             hir: None,
             ir_masm: None,
-        }
+            stack_inputs: vec![],
+            advice_inputs: AdviceInputs::default(),
+        })
     }
 
     /// Set the Rust source code to compile
@@ -235,9 +329,150 @@ impl CompilerTest {
             entrypoint: None,
             hir: None,
             ir_masm: None,
+            stack_inputs: vec![],
+            advice_inputs: AdviceInputs::default(),
+        }
+    }
+
+    /// Set the Wasm text format source code to compile, using `entrypoint` as the entrypoint
+    pub fn wat_module(wat_source: &str, entrypoint: &str) -> Self {
+        let wasm_bytes = wat::parse_str(wat_source).expect("Failed to parse WAT");
+        let session = default_session();
+        let entrypoint = FunctionIdent {
+            module: Ident::new(Symbol::intern("noname"), SourceSpan::default()),
+            function: Ident::new(
+                Symbol::intern(entrypoint.to_string()),
+                SourceSpan::default(),
+            ),
+        };
+        CompilerTest {
+            session,
+            source: CompilerTestSource::Wat(wat_source.to_string()),
+            wasm_bytes,
+            entrypoint: Some(entrypoint),
+            hir: None,
+            ir_masm: None,
+            stack_inputs: vec![],
+            advice_inputs: AdviceInputs::default(),
+        }
+    }
+
+    /// Set the Wasm text format source of a component to compile, using its export `export` as
+    /// the entrypoint
+    ///
+    /// The export is called directly, so it must take and return only values which are passed as
+    /// they are, e.g. integers.
+    pub fn wat_component(wat_source: &str, export: &str) -> Self {
+        let wasm_bytes = wat::parse_str(wat_source).expect("Failed to parse WAT");
+        CompilerTest {
+            session: default_session(),
+            source: CompilerTestSource::WatComponent {
+                source: wat_source.to_string(),
+                export: export.to_string(),
+            },
+            wasm_bytes,
+            entrypoint: None,
+            hir: None,
+            ir_masm: None,
+            stack_inputs: vec![],
+            advice_inputs: AdviceInputs::default(),
+        }
+    }
+
+    /// Set the prebuilt Wasm module to compile, e.g. a checked-in `.wasm` fixture, which needs no
+    /// Rust toolchain to build
+    ///
+    /// The `entrypoint`, if any, is the name of a function in the module, whatever the module is
+    /// named.
+    pub fn wasm_bytes(bytes: Vec<u8>, entrypoint: Option<&str>) -> Self {
+        let entrypoint = entrypoint.map(|entrypoint| FunctionIdent {
+            module: Ident::new(Symbol::intern("noname"), SourceSpan::default()),
+            function: Ident::new(Symbol::intern(entrypoint), SourceSpan::default()),
+        });
+        CompilerTest {
+            session: default_session(),
+            source: CompilerTestSource::Wasm,
+            wasm_bytes: bytes,
+            entrypoint,
+            hir: None,
+            ir_masm: None,
+            stack_inputs: vec![],
+            advice_inputs: AdviceInputs::default(),
+        }
+    }
+
+    /// Set the Miden Assembly source of a program to execute on the VM
+    ///
+    /// This is useful for testing the VM execution setup itself, e.g. programs which read from the
+    /// advice provider, as the program is not compiled from Wasm, so none of the Wasm, IR or MASM
+    /// compilation stages are available for such a test.
+    pub fn masm_program(masm_source: &str) -> Self {
+        CompilerTest {
+            session: default_session(),
+            source: CompilerTestSource::Masm(masm_source.to_string()),
+            wasm_bytes: vec![],
+            entrypoint: None,
+            hir: None,
+            ir_masm: None,
+            stack_inputs: vec![],
+            advice_inputs: AdviceInputs::default(),
         }
     }
 
+    /// Set the values placed on the operand stack when executing the program on the VM
+    ///
+    /// The values are passed to the VM in the same order as the arguments of [crate::execute_vm].
+    pub fn with_stack_inputs(mut self, inputs: &[u64]) -> Self {
+        self.stack_inputs = inputs.iter().copied().map(Felt::new).collect();
+        self
+    }
+
+    /// Set how a trap raised by the static constructors of the compiled module is surfaced
+    pub fn with_init_trap_policy(mut self, policy: InitTrapPolicy) -> Self {
+        self.session.options.init_trap_policy = policy;
+        self
+    }
+
+    /// Trap on loads and stores of unaligned addresses, rather than emulating them
+    pub fn with_trap_on_unaligned_access(mut self) -> Self {
+        self.session.options.trap_on_unaligned_access = true;
+        self
+    }
+
+    /// Make integer division and remainder by zero produce zero, rather than trapping
+    pub fn with_wrapping_division(mut self) -> Self {
+        self.session.options.wrapping_division = true;
+        self
+    }
+
+    /// Lower atomic operations as if the program is single-threaded, rather than rejecting them
+    pub fn with_single_threaded_atomics(mut self) -> Self {
+        self.session.options.single_threaded_atomics = true;
+        self
+    }
+
+    /// Retain the debug info of the compiled Wasm, relating the compiled code to its source
+    pub fn with_debug_info(mut self) -> Self {
+        self.session.options.debug_info = true;
+        self
+    }
+
+    /// Add the given key/values pairs to the advice map available to the program when executing it
+    /// on the VM
+    pub fn with_advice_map<I>(mut self, entries: I) -> Self
+    where
+        I: IntoIterator<Item = (RpoDigest, Vec<u64>)>,
+    {
+        let entries = entries.into_iter().map(|(key, values)| {
+            (
+                key.as_bytes(),
+                values.into_iter().map(Felt::new).collect::<Vec<_>>(),
+            )
+        });
+        self.advice_inputs = core::mem::take(&mut self.advice_inputs).with_map(entries);
+        self
+    }
+
     /// Set the Rust source code to compile and add a binary operation test
     pub fn rust_fn_body(rust_source: &str) -> Self {
         let rust_source = format!(
@@ -255,7 +490,12 @@ impl CompilerTest {
             "#,
             rust_source
         );
-        let wasm_bytes = compile_rust_file(&rust_source);
+        Self::rust_fn_body_inner(&rust_source)
+    }
+
+    /// Compile `rust_source`, which is expected to define an exported `entrypoint` function
+    fn rust_fn_body_inner(rust_source: &str) -> Self {
+        let wasm_bytes = compile_rust_file(rust_source);
         let session = default_session();
         let entrypoint = FunctionIdent {
             module: Ident {
@@ -275,9 +515,62 @@ impl CompilerTest {
             entrypoint: Some(entrypoint),
             hir: None,
             ir_masm: None,
+            stack_inputs: vec![],
+            advice_inputs: AdviceInputs::default(),
         }
     }
 
+    /// Compile the body of the Rust function `entrypoint`, linking in a simple bump allocator so
+    /// that the code may use `alloc` types such as `Vec` and `Box`
+    ///
+    /// Memory is never freed by the allocator, so this is only suitable for short-lived programs.
+    pub fn rust_fn_body_with_alloc(rust_source: &str) -> Self {
+        let rust_source = format!(
+            r#"
+            #![no_std]
+            #![no_main]
+
+            extern crate alloc;
+
+            #[panic_handler]
+            fn my_panic(_info: &core::panic::PanicInfo) -> ! {{
+                loop {{}}
+            }}
+
+            const HEAP_SIZE: usize = 64 * 1024;
+            static mut HEAP: [u8; HEAP_SIZE] = [0; HEAP_SIZE];
+            static mut HEAP_TOP: usize = 0;
+
+            struct BumpAlloc;
+
+            unsafe impl core::alloc::GlobalAlloc for BumpAlloc {{
+                unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {{
+                    // The heap itself is only aligned to a byte, so it is the address of the
+                    // allocation which is aligned, rather than its offset in the heap
+                    let base = HEAP.as_mut_ptr() as usize;
+                    let addr = (base + HEAP_TOP + layout.align() - 1) & !(layout.align() - 1);
+                    let end = addr - base + layout.size();
+                    if end > HEAP_SIZE {{
+                        return core::ptr::null_mut();
+                    }}
+                    HEAP_TOP = end;
+                    addr as *mut u8
+                }}
+
+                unsafe fn dealloc(&self, _ptr: *mut u8, _layout: core::alloc::Layout) {{}}
+            }}
+
+            #[global_allocator]
+            static ALLOC: BumpAlloc = BumpAlloc;
+
+            #[no_mangle]
+            pub extern "C" fn entrypoint{}
+            "#,
+            rust_source
+        );
+        Self::rust_fn_body_inner(&rust_source)
+    }
+
     /// Compare the compiled Wasm against the expected output
     pub fn expect_wasm(&self, expected_wat_file: expect_test::ExpectFile) {
         let wasm_bytes = self.wasm_bytes.as_ref();
@@ -287,20 +580,7 @@ impl CompilerTest {
 
     /// Compare the compiled IR against the expected output
     pub fn expect_ir(&mut self, expected_hir_file: expect_test::ExpectFile) {
-        let hir_program = if let Some(hir) = self.hir.as_ref() {
-            hir
-        } else {
-            let hir_module = wasm_to_ir(&self.wasm_bytes, &self.session);
-            let mut builder = ProgramBuilder::new(&self.session.diagnostics)
-                .with_module(hir_module.into())
-                .unwrap();
-            if let Some(entrypoint) = self.entrypoint.as_ref() {
-                builder = builder.with_entrypoint(entrypoint.clone());
-            }
-            let hir_program = builder.link().expect("Failed to link IR program");
-            self.hir = Some(hir_program);
-            self.hir.as_ref().unwrap()
-        };
+        let hir_program = self.hir_program();
         // Program does not implement pretty printer yet, use the first module
         let ir_module = demangle(
             &hir_program
@@ -316,17 +596,75 @@ impl CompilerTest {
         expected_hir_file.assert_eq(&ir_module);
     }
 
+    /// Get the compiled IR program, translating and linking the Wasm on first use
+    fn hir_program(&mut self) -> &miden_hir::Program {
+        if let CompilerTestSource::WatComponent { export, .. } = &self.source {
+            if self.hir.is_none() {
+                let hir_program = wasm_component_to_ir(&self.wasm_bytes, export, &self.session);
+                self.hir = Some(hir_program);
+            }
+        }
+        if self.hir.is_none() {
+            let hir_module = wasm_to_ir(&self.wasm_bytes, &self.session);
+            let module_name = hir_module.name;
+            let mut builder = ProgramBuilder::new(&self.session.diagnostics)
+                .with_module(hir_module.into())
+                .unwrap();
+            // The program consists of this one module, which is named after its name section, if
+            // it has one, rather than the `noname` the entrypoint was given
+            if let Some(entrypoint) = self.entrypoint.as_ref() {
+                builder = builder.with_entrypoint(FunctionIdent {
+                    module: module_name,
+                    function: entrypoint.function,
+                });
+            }
+            let hir_program = builder.link().expect("Failed to link IR program");
+            self.hir = Some(hir_program);
+        }
+        self.hir.as_ref().unwrap()
+    }
+
     /// Compare the compiled MASM against the expected output
     pub fn expect_masm(&mut self, expected_masm_file: expect_test::ExpectFile) {
         let program = self.ir_masm_program();
         expected_masm_file.assert_eq(&program.to_string());
     }
 
+    /// Execute the program on the VM, with the inputs set via [Self::with_stack_inputs] and
+    /// [Self::with_advice_map], returning the contents of the operand stack on exit
+    pub fn execute(&mut self) -> Vec<TestFelt> {
+        let program = self.vm_masm_program();
+        execute_vm_with_advice(&program, &self.stack_inputs, self.advice_inputs.clone())
+    }
+
+    /// Execute the program on the VM, and check that the top of the operand stack on exit
+    /// matches `expected`, the first element of which is expected on top of the stack
+    pub fn expect_vm_output(&mut self, expected: &[u64]) {
+        let output = self.execute();
+        assert!(
+            output.len() >= expected.len(),
+            "expected at least {} elements on the operand stack, got {}",
+            expected.len(),
+            output.len()
+        );
+        let actual = output
+            .into_iter()
+            .take(expected.len())
+            .map(u64::from)
+            .collect::<Vec<_>>();
+        assert_eq!(actual.as_slice(), expected);
+    }
+
     /// Get the compiled MASM as [`miden_assembly::Program`]
     pub fn vm_masm_program(&mut self) -> miden_core::Program {
         let assembler = Assembler::default()
             .with_library(&StdLibrary::default())
             .expect("Failed to load stdlib");
+        if let CompilerTestSource::Masm(masm_source) = &self.source {
+            return assembler
+                .compile(masm_source)
+                .expect("VM Assembler failed to compile program");
+        }
         let program = self.ir_masm_program();
         // TODO: get code map from the self.diagnostics
         let codemap = CodeMap::new();
@@ -355,6 +693,7 @@ impl CompilerTest {
     pub fn ir_masm_program(&mut self) -> Arc<miden_codegen_masm::Program> {
         if self.ir_masm.is_none() {
             let mut compiler = MasmCompiler::new(&self.session);
+            self.hir_program();
             let hir = self.hir.take().expect("IR is not compiled");
             let ir_masm = compiler.compile(hir).unwrap();
             let frozen = ir_masm.freeze();
@@ -455,17 +794,46 @@ fn hash_string(inputs: &str) -> String {
     format!("{:x}", hash)
 }
 
+/// Translate the Wasm component, and link it into a program whose entrypoint is its export `export`
+fn wasm_component_to_ir(
+    wasm_bytes: &[u8],
+    export: &str,
+    session: &Session,
+) -> Box<miden_hir::Program> {
+    let config = WasmTranslationConfig::builder()
+        .default_export_metadata(ExportMetadata {
+            invoke_method: FunctionInvocationMethod::Exec,
+        })
+        .build();
+    let mut component = translate_component(wasm_bytes, &config, &session.diagnostics)
+        .expect("Failed to translate Wasm component");
+    let entrypoint = component
+        .exports()
+        .get(&Symbol::intern(export).into())
+        .unwrap_or_else(|| panic!("the component has no export named `{export}`"))
+        .function;
+    component.set_entrypoint(entrypoint);
+    component
+        .link(ComponentEntrypoints::Single)
+        .expect("Failed to link IR program")
+}
+
 fn wasm_to_ir(wasm_bytes: &[u8], session: &Session) -> miden_hir::Module {
     use miden_hir_transform as transforms;
-    let mut ir_module = translate_module(
-        wasm_bytes,
-        &WasmTranslationConfig::default(),
-        &session.diagnostics,
-    )
-    .expect("Failed to translate Wasm to IR module");
+    let config = WasmTranslationConfig::builder()
+        .endianness(session.options.endianness)
+        .init_trap_policy(session.options.init_trap_policy)
+        .trap_on_unaligned_access(session.options.trap_on_unaligned_access)
+        .wrapping_division(session.options.wrapping_division)
+        .single_threaded_atomics(session.options.single_threaded_atomics)
+        .parse_wasm_debuginfo(session.options.debug_info)
+        .build();
+    let mut ir_module = translate_module(wasm_bytes, &config, &session.diagnostics)
+        .expect("Failed to translate Wasm to IR module");
 
     let mut analyses = AnalysisManager::new();
     let mut rewrites = RewriteSet::default();
+    rewrites.push(ModuleRewritePassAdapter::new(transforms::LowerSwitch));
     rewrites.push(ModuleRewritePassAdapter::new(
         transforms::SplitCriticalEdges,
     ));
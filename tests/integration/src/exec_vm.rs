@@ -1,21 +1,29 @@
 use miden_core::Program;
 use miden_core::StackInputs;
 use miden_hir::Felt;
+use miden_processor::AdviceInputs;
 use miden_processor::DefaultHost;
 use miden_processor::ExecutionOptions;
+use miden_processor::MemAdviceProvider;
 
 use crate::felt_conversion::TestFelt;
 
 /// Execute the module using the VM with the given arguments
 pub fn execute_vm(program: &Program, args: &[Felt]) -> Vec<TestFelt> {
+    execute_vm_with_advice(program, args, AdviceInputs::default())
+}
+
+/// Execute the module using the VM with the given arguments, with `advice_inputs` available to
+/// the program via the advice provider
+pub fn execute_vm_with_advice(
+    program: &Program,
+    args: &[Felt],
+    advice_inputs: AdviceInputs,
+) -> Vec<TestFelt> {
     let stack_inputs = StackInputs::new(args.to_vec());
-    let trace = miden_processor::execute(
-        program,
-        stack_inputs,
-        DefaultHost::default(),
-        ExecutionOptions::default(),
-    )
-    .expect("failed to execute program on VM");
+    let host = DefaultHost::new(MemAdviceProvider::from(advice_inputs));
+    let trace = miden_processor::execute(program, stack_inputs, host, ExecutionOptions::default())
+        .expect("failed to execute program on VM");
     trace
         .stack_outputs()
         .stack()
@@ -15,3 +15,7 @@ pub use exec_vm::execute_vm;
 
 #[cfg(test)]
 mod rust_masm_tests;
+#[cfg(test)]
+mod vm_inputs_tests;
+#[cfg(test)]
+mod wasm_masm_tests;
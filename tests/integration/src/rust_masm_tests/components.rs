@@ -27,17 +27,14 @@ fn wcm_add() {
 
     let session = default_session();
     let export_metadata = [(
-        Symbol::intern("add").into(),
+        Symbol::intern("miden:add/add@1.0.0#add").into(),
         ExportMetadata {
             invoke_method: miden_hir::FunctionInvocationMethod::Call,
         },
-    )]
-    .into_iter()
-    .collect();
-    let config = WasmTranslationConfig {
-        export_metadata,
-        ..Default::default()
-    };
+    )];
+    let config = WasmTranslationConfig::builder()
+        .export_metadata(export_metadata)
+        .build();
     let component = translate_component(&wasm_bytes, &config, &session.diagnostics)
         .expect("Failed to translate Wasm to IR module");
     assert!(!component.modules().is_empty());
@@ -68,22 +65,17 @@ fn wcm_inc() {
             digest: RpoDigest::default(),
             invoke_method: miden_hir::FunctionInvocationMethod::Call,
         },
-    )]
-    .into_iter()
-    .collect();
+    )];
     let export_metadata = [(
         Symbol::intern("inc").into(),
         ExportMetadata {
             invoke_method: miden_hir::FunctionInvocationMethod::Call,
         },
-    )]
-    .into_iter()
-    .collect();
-    let config = WasmTranslationConfig {
-        import_metadata,
-        export_metadata,
-        ..Default::default()
-    };
+    )];
+    let config = WasmTranslationConfig::builder()
+        .import_metadata(import_metadata)
+        .export_metadata(export_metadata)
+        .build();
     let ir = translate_component(&wasm_bytes, &config, &session.diagnostics)
         .expect("Failed to translate Wasm to IR module");
     assert!(!ir.modules().is_empty());
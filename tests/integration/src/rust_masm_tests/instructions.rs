@@ -337,3 +337,28 @@ test_unary_op!(not, !, u16);
 test_unary_op!(not, !, u8);
 
 test_unary_op!(not, !, bool);
+
+#[test]
+fn no_std_alloc_vec() {
+    let main_fn = r#"(n: u32) -> u32 {
+                let mut v = alloc::vec::Vec::new();
+                for i in 0..n {
+                    v.push(i * 2);
+                }
+                let len = alloc::boxed::Box::new(v.len() as u32);
+                v.iter().sum::<u32>() + *len
+            }"#;
+    let mut test = CompilerTest::rust_fn_body_with_alloc(main_fn);
+    let ir_masm = test.ir_masm_program();
+    let vm_program = test.vm_masm_program();
+
+    let args = [Felt::from(10u32)];
+    let vm_out: u32 = execute_vm(&vm_program, &args).first().unwrap().clone().into();
+    assert_eq!(vm_out, 100);
+    let emul_out: u32 = execute_emulator(ir_masm, &args)
+        .first()
+        .unwrap()
+        .clone()
+        .into();
+    assert_eq!(emul_out, 100);
+}
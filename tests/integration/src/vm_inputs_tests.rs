@@ -0,0 +1,23 @@
+use miden_core::crypto::hash::RpoDigest;
+use miden_hir::Felt;
+
+use crate::CompilerTest;
+
+#[test]
+fn read_advice_map_value() {
+    // The key is read from the top word of the operand stack, and the values stored under it are
+    // moved to the advice stack, from which they are then read by the program
+    let masm = r#"
+        begin
+            adv.push_mapval
+            dropw
+            adv_push.2
+            add
+        end
+    "#;
+    let key = RpoDigest::new([Felt::new(7); 4]);
+    CompilerTest::masm_program(masm)
+        .with_stack_inputs(&[7, 7, 7, 7])
+        .with_advice_map([(key, vec![40, 2])])
+        .expect_vm_output(&[42]);
+}
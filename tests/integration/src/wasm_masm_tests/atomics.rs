@@ -0,0 +1,67 @@
+use crate::CompilerTest;
+
+// Each function returns the sum of the result of an atomic read-modify-write, i.e. the old value,
+// and the value left in memory by it
+const ATOMICS_MODULE: &str = r#"
+    (module
+        (memory (;0;) 1)
+        (func $fetch_add (export "fetch_add") (result i32)
+            i32.const 1024
+            i32.const 40
+            i32.store
+            i32.const 1024
+            i32.const 2
+            i32.atomic.rmw.add
+            i32.const 1024
+            i32.atomic.load
+            i32.add
+        )
+        (func $fetch_add_u8 (export "fetch_add_u8") (result i32)
+            i32.const 1024
+            i32.const 0x1ff
+            i32.store
+            i32.const 1024
+            i32.const 1
+            i32.atomic.rmw8.add_u
+            i32.const 1024
+            i32.load
+            i32.add
+        )
+        (func $compare_exchange (export "compare_exchange") (result i32)
+            i32.const 1024
+            i32.const 7
+            i32.store
+            i32.const 1024
+            i32.const 7
+            i32.const 9
+            i32.atomic.rmw.cmpxchg
+            i32.const 1024
+            i32.load
+            i32.add
+        )
+    )
+"#;
+
+#[test]
+fn atomic_fetch_add_is_a_plain_add() {
+    let mut test =
+        CompilerTest::wat_module(ATOMICS_MODULE, "fetch_add").with_single_threaded_atomics();
+    // 40 + 42
+    test.expect_vm_output(&[82]);
+}
+
+#[test]
+fn atomic_narrow_fetch_add_wraps_within_the_accessed_bytes() {
+    let mut test =
+        CompilerTest::wat_module(ATOMICS_MODULE, "fetch_add_u8").with_single_threaded_atomics();
+    // The low byte wraps from 0xff to 0, leaving the byte above it as is: 0xff + 0x100
+    test.expect_vm_output(&[0x1ff]);
+}
+
+#[test]
+fn atomic_compare_exchange_stores_the_replacement() {
+    let mut test =
+        CompilerTest::wat_module(ATOMICS_MODULE, "compare_exchange").with_single_threaded_atomics();
+    // 7 + 9
+    test.expect_vm_output(&[16]);
+}
@@ -0,0 +1,232 @@
+use crate::CompilerTest;
+
+/// The strings passed between the component instances: ASCII, characters taking two and three
+/// bytes in UTF-8, and a character outside of the basic multilingual plane, which takes four
+/// bytes in UTF-8, and a surrogate pair in UTF-16
+const STRINGS: [&str; 3] = ["hello", "é世", "a🦀"];
+
+/// The address the caller writes the string it passes to
+const CALLER_STRING: u32 = 4096;
+/// The address of the return area the caller passes to receive a string
+const CALLER_RET_AREA: u32 = 4352;
+/// The address the caller allocates the strings it receives at
+const CALLER_ALLOC: u32 = 24576;
+/// The address of the return area of the callee
+const CALLEE_RET_AREA: u32 = 8192;
+/// The address the callee allocates the strings it receives at
+const CALLEE_ALLOC: u32 = 16384;
+
+/// The checksum computed by the components, over the code units of a string of `len` code units
+fn checksum(len: usize, units: impl Iterator<Item = u32>) -> u32 {
+    units.fold(len as u32, |h, unit| h.wrapping_mul(31).wrapping_add(unit))
+}
+
+fn utf8_checksum(s: &str) -> u32 {
+    checksum(s.len(), s.bytes().map(u32::from))
+}
+
+fn utf16_checksum(s: &str) -> u32 {
+    checksum(s.encode_utf16().count(), s.encode_utf16().map(u32::from))
+}
+
+/// The code units of `s` in `encoding`, along with the instruction storing one of them
+fn code_units(s: &str, encoding: &str) -> (Vec<u32>, &'static str) {
+    match encoding {
+        "utf8" => (s.bytes().map(u32::from).collect(), "i32.store8"),
+        "utf16" => (s.encode_utf16().map(u32::from).collect(), "i32.store16"),
+        _ => unreachable!("unexpected string encoding {encoding}"),
+    }
+}
+
+/// A core function computing [checksum] over the string of `len` code units at `ptr`, encoded
+/// with `encoding`
+fn checksum_func(encoding: &str) -> String {
+    let (load, stride) = match encoding {
+        "utf8" => ("i32.load8_u", 1),
+        "utf16" => ("i32.load16_u", 2),
+        _ => unreachable!("unexpected string encoding {encoding}"),
+    };
+    format!(
+        r#"
+        (func $checksum (param $ptr i32) (param $len i32) (result i32)
+            (local $h i32) (local $i i32)
+            local.get $len
+            local.set $h
+            block $done
+                loop $next
+                    local.get $i
+                    local.get $len
+                    i32.ge_u
+                    br_if $done
+                    local.get $h
+                    i32.const 31
+                    i32.mul
+                    local.get $ptr
+                    local.get $i
+                    i32.const {stride}
+                    i32.mul
+                    i32.add
+                    {load}
+                    i32.add
+                    local.set $h
+                    local.get $i
+                    i32.const 1
+                    i32.add
+                    local.set $i
+                    br $next
+                end
+            end
+            local.get $h
+        )
+        "#
+    )
+}
+
+/// A component in which a core module, encoding strings with `caller_encoding`, writes `s` to
+/// its memory and passes it to a function lifted in another component instance, which encodes
+/// strings with `callee_encoding`.
+///
+/// The function either returns the checksum of the string it receives, or, if `echo` is set,
+/// returns the string itself, of which the caller then computes the checksum. The component
+/// exports the function of the caller, `run`, which returns the checksum.
+fn component_wat(s: &str, caller_encoding: &str, callee_encoding: &str, echo: bool) -> String {
+    let (units, store) = code_units(s, caller_encoding);
+    let unit_size = if caller_encoding == "utf8" { 1 } else { 2 };
+    let stores = units
+        .iter()
+        .enumerate()
+        .map(|(i, unit)| {
+            let addr = CALLER_STRING + i as u32 * unit_size;
+            format!("i32.const {addr} i32.const {unit} {store}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let len = units.len();
+    let caller_checksum = checksum_func(caller_encoding);
+    let callee_checksum = checksum_func(callee_encoding);
+    let (result_ty, callee_core_ty, callee_body, caller_import_ty, caller_body) = if echo {
+        (
+            "string",
+            "(param i32 i32) (result i32)",
+            format!(
+                "i32.const {CALLEE_RET_AREA} local.get 0 i32.store
+                 i32.const {ret_len} local.get 1 i32.store
+                 i32.const {CALLEE_RET_AREA}",
+                ret_len = CALLEE_RET_AREA + 4,
+            ),
+            "(param i32 i32 i32)",
+            format!(
+                "i32.const {CALLER_STRING} i32.const {len} i32.const {CALLER_RET_AREA} call $f
+                 i32.const {CALLER_RET_AREA} i32.load
+                 i32.const {ret_len} i32.load
+                 call $checksum",
+                ret_len = CALLER_RET_AREA + 4,
+            ),
+        )
+    } else {
+        (
+            "u32",
+            "(param i32 i32) (result i32)",
+            "local.get 0 local.get 1 call $checksum".to_string(),
+            "(param i32 i32) (result i32)",
+            format!("i32.const {CALLER_STRING} i32.const {len} call $f"),
+        )
+    };
+    format!(
+        r#"
+        (component
+            (component $callee_component
+                (core module $callee
+                    (memory (export "memory") 1)
+                    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                        i32.const {CALLEE_ALLOC}
+                    )
+                    {callee_checksum}
+                    (func (export "f") {callee_core_ty}
+                        {callee_body}
+                    )
+                )
+                (core instance $callee_instance (instantiate $callee))
+                (alias core export $callee_instance "memory" (core memory $memory))
+                (alias core export $callee_instance "realloc" (core func $realloc))
+                (alias core export $callee_instance "f" (core func $f))
+                (type $f_ty (func (param "s" string) (result {result_ty})))
+                (func $f_lifted (type $f_ty)
+                    (canon lift (core func $f) (memory $memory) (realloc $realloc)
+                        string-encoding={callee_encoding})
+                )
+                (export "f" (func $f_lifted))
+            )
+            (instance $callee_component_instance (instantiate $callee_component))
+            (alias export $callee_component_instance "f" (func $f))
+            (core module $libc
+                (memory (export "memory") 1)
+                (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                    i32.const {CALLER_ALLOC}
+                )
+            )
+            (core instance $libc_instance (instantiate $libc))
+            (alias core export $libc_instance "memory" (core memory $memory))
+            (alias core export $libc_instance "realloc" (core func $realloc))
+            (core func $f_lowered
+                (canon lower (func $f) (memory $memory) (realloc $realloc)
+                    string-encoding={caller_encoding})
+            )
+            (core instance $callee_exports (export "f" (func $f_lowered)))
+            (core module $caller
+                (import "libc" "memory" (memory 1))
+                (import "callee" "f" (func $f {caller_import_ty}))
+                {caller_checksum}
+                (func (export "run") (result i32)
+                    {stores}
+                    {caller_body}
+                )
+            )
+            (core instance $caller_instance (instantiate $caller
+                (with "libc" (instance $libc_instance))
+                (with "callee" (instance $callee_exports))
+            ))
+            (type $run_ty (func (result u32)))
+            (alias core export $caller_instance "run" (core func $run))
+            (func $run_lifted (type $run_ty) (canon lift (core func $run)))
+            (export "run" (func $run_lifted))
+        )
+        "#
+    )
+}
+
+fn run(s: &str, caller_encoding: &str, callee_encoding: &str, echo: bool, expected: u32) {
+    let wat = component_wat(s, caller_encoding, callee_encoding, echo);
+    let mut test = CompilerTest::wat_component(&wat, "run");
+    test.expect_vm_output(&[expected as u64]);
+}
+
+#[test]
+fn utf16_strings_are_transcoded_for_a_utf8_callee() {
+    for s in STRINGS {
+        run(s, "utf16", "utf8", false, utf8_checksum(s));
+    }
+}
+
+#[test]
+fn utf8_strings_are_transcoded_for_a_utf16_callee() {
+    for s in STRINGS {
+        run(s, "utf8", "utf16", false, utf16_checksum(s));
+    }
+}
+
+#[test]
+fn strings_round_trip_through_a_callee_with_another_encoding() {
+    for s in STRINGS {
+        run(s, "utf16", "utf8", true, utf16_checksum(s));
+        run(s, "utf8", "utf16", true, utf8_checksum(s));
+    }
+}
+
+#[test]
+fn strings_are_copied_for_a_callee_with_the_same_encoding() {
+    for s in STRINGS {
+        run(s, "utf8", "utf8", true, utf8_checksum(s));
+        run(s, "utf16", "utf16", true, utf16_checksum(s));
+    }
+}
@@ -0,0 +1,309 @@
+use std::sync::Arc;
+
+use miden_codegen_masm::MasmCompiler;
+use miden_frontend_wasm::{translate_component, ExportMetadata, WasmTranslationConfig};
+use miden_hir::{ComponentEntrypoints, FunctionExportName, FunctionInvocationMethod, Symbol};
+use midenc_compile::Compiled;
+use midenc_session::{FileType, InputFile, Options, Session, TargetEnv};
+
+use crate::{compiler_test::default_session, CompilerTest};
+
+/// A component exporting two methods, in the style of the basic wallet account
+const COMPONENT: &str = r#"
+    (component
+        (core module (;0;)
+            (type (;0;) (func (param i32) (result i32)))
+            (func $receive_asset (;0;) (type 0) (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                i32.add
+            )
+            (func $send_asset (;1;) (type 0) (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                i32.sub
+            )
+            (memory (;0;) 17)
+            (export "memory" (memory 0))
+            (export "receive-asset" (func $receive_asset))
+            (export "send-asset" (func $send_asset))
+        )
+        (core instance (;0;) (instantiate 0))
+        (alias core export 0 "memory" (core memory (;0;)))
+        (type (;0;) (func (param "a" u32) (result u32)))
+        (alias core export 0 "receive-asset" (core func (;0;)))
+        (func (;0;) (type 0) (canon lift (core func 0)))
+        (export (;1;) "receive-asset" (func 0))
+        (alias core export 0 "send-asset" (core func (;1;)))
+        (func (;2;) (type 0) (canon lift (core func 1)))
+        (export (;3;) "send-asset" (func 2))
+    )
+"#;
+
+#[test]
+fn component_exports_as_entrypoints() {
+    let wasm = wat::parse_str(COMPONENT).unwrap();
+    let session = default_session();
+    let export_metadata = ["receive-asset", "send-asset"].into_iter().map(|name| {
+        let metadata = ExportMetadata {
+            invoke_method: FunctionInvocationMethod::Call,
+        };
+        (Symbol::intern(name).into(), metadata)
+    });
+    let config = WasmTranslationConfig::builder()
+        .export_metadata(export_metadata)
+        .build();
+    let component = translate_component(&wasm, &config, &session.diagnostics)
+        .expect("failed to translate component");
+    let mut exported = component
+        .exports()
+        .values()
+        .map(|export| export.function)
+        .collect::<Vec<_>>();
+    exported.sort();
+
+    let program = MasmCompiler::new(&session)
+        .compile_component(component, ComponentEntrypoints::Exports)
+        .expect("failed to compile component");
+    assert!(program.is_library());
+    // The intrinsics modules linked into every program are not part of the component
+    let mut public = program
+        .modules()
+        .filter(|module| !module.name.as_str().starts_with("intrinsics::"))
+        .flat_map(|module| module.functions())
+        .filter(|function| function.signature.is_public())
+        .map(|function| function.name)
+        .collect::<Vec<_>>();
+    public.sort();
+    assert_eq!(public.len(), 2);
+    assert_eq!(public, exported);
+}
+
+/// A component exporting the `note-script` function of the `miden:base/note@1.0.0` interface, in
+/// the style of `cargo component`, except that the core function is not named after the
+/// interface, so that its name cannot be mistaken for the name it is exported under
+const INTERFACE_COMPONENT: &str = r#"
+    (component
+        (core module (;0;)
+            (type (;0;) (func (param i32) (result i32)))
+            (func $run_note_script (;0;) (type 0) (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                i32.add
+            )
+            (memory (;0;) 17)
+            (export "memory" (memory 0))
+            (export "run-note-script" (func $run_note_script))
+        )
+        (core instance (;0;) (instantiate 0))
+        (alias core export 0 "memory" (core memory (;0;)))
+        (type (;0;) (func (param "a" u32) (result u32)))
+        (alias core export 0 "run-note-script" (core func (;0;)))
+        (func (;0;) (type 0) (canon lift (core func 0)))
+        (component (;0;)
+            (type (;0;) (func (param "a" u32) (result u32)))
+            (import "import-func-note-script" (func (;0;) (type 0)))
+            (type (;1;) (func (param "a" u32) (result u32)))
+            (export (;1;) "note-script" (func 0) (func (type 1)))
+        )
+        (instance (;0;) (instantiate 0
+            (with "import-func-note-script" (func 0))
+            )
+        )
+        (export (;1;) "miden:base/note@1.0.0" (instance 0))
+    )
+"#;
+
+#[test]
+fn interface_exports_are_namespaced() {
+    let wasm = wat::parse_str(INTERFACE_COMPONENT).unwrap();
+    let session = default_session();
+    let export_name = Symbol::intern("miden:base/note@1.0.0#note-script");
+    let export_metadata = [(
+        export_name.into(),
+        ExportMetadata {
+            invoke_method: FunctionInvocationMethod::Call,
+        },
+    )];
+    let config = WasmTranslationConfig::builder()
+        .export_metadata(export_metadata)
+        .build();
+    let component = translate_component(&wasm, &config, &session.diagnostics)
+        .expect("failed to translate component");
+    let qualified_name = FunctionExportName::from(export_name);
+    let exports = component
+        .exports()
+        .iter()
+        .map(|(name, export)| (name, export.function.function.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        exports,
+        [(&qualified_name, "run_note_script")],
+        "expected the core function to be exported under the name of its interface"
+    );
+    let exported = component.exports()[&qualified_name].function;
+
+    let program = MasmCompiler::new(&session)
+        .compile_component(component, ComponentEntrypoints::Exports)
+        .expect("failed to compile component");
+    let public = program
+        .modules()
+        .filter(|module| !module.name.as_str().starts_with("intrinsics::"))
+        .flat_map(|module| module.functions())
+        .filter(|function| function.signature.is_public())
+        .map(|function| function.name)
+        .collect::<Vec<_>>();
+    assert_eq!(public, [exported]);
+}
+
+#[test]
+fn component_compiled_from_a_file() {
+    let wasm = wat::parse_str(COMPONENT).unwrap();
+    let path = std::env::temp_dir().join(format!("component_{}.wasm", std::process::id()));
+    std::fs::write(&path, wasm).unwrap();
+
+    let input = InputFile::from_path(&path).unwrap();
+    assert_eq!(input.file_type(), FileType::WasmComponent);
+    // The emulator target compiles a library, whose procedures are the exports of the component
+    let session = Arc::new(Session::new(
+        TargetEnv::Emu,
+        input,
+        None,
+        None,
+        None,
+        Options::default(),
+        None,
+    ));
+    let compiled = midenc_compile::compile_to_memory(session);
+    std::fs::remove_file(&path).ok();
+    let Compiled::Program(program) = compiled.expect("compilation failed") else {
+        panic!("expected the component to be linked into a program");
+    };
+    assert!(program.is_library());
+    let mut public = program
+        .modules()
+        .filter(|module| !module.name.as_str().starts_with("intrinsics::"))
+        .flat_map(|module| module.functions())
+        .filter(|function| function.signature.is_public())
+        .map(|function| function.name.function.as_str().to_string())
+        .collect::<Vec<_>>();
+    public.sort();
+    assert_eq!(public, ["receive-asset", "send-asset"]);
+}
+
+/// A component exporting `inc`, lifted with a `post-return` function which traps unless it is
+/// called with `expected`, the result of `inc`
+fn post_return_component(expected: u32) -> String {
+    format!(
+        r#"
+        (component
+            (core module (;0;)
+                (func (export "inc") (param i32) (result i32)
+                    local.get 0
+                    i32.const 1
+                    i32.add
+                )
+                (func (export "cabi_post_inc") (param i32)
+                    local.get 0
+                    i32.const {expected}
+                    i32.ne
+                    if
+                        unreachable
+                    end
+                )
+            )
+            (core instance (;0;) (instantiate 0))
+            (alias core export 0 "inc" (core func (;0;)))
+            (alias core export 0 "cabi_post_inc" (core func (;1;)))
+            (type (;0;) (func (param "a" u32) (result u32)))
+            (func (;0;) (type 0) (canon lift (core func 0) (post-return 1)))
+            (export (;1;) "inc" (func 0))
+        )
+        "#
+    )
+}
+
+#[test]
+fn post_return_is_called_with_the_results_of_an_export() {
+    let mut test =
+        CompilerTest::wat_component(&post_return_component(6), "inc").with_stack_inputs(&[5]);
+    test.expect_vm_output(&[6]);
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn post_return_runs_after_an_export_returns() {
+    // The post-return function traps, as the result of `inc` is not the one it expects
+    let mut test =
+        CompilerTest::wat_component(&post_return_component(7), "inc").with_stack_inputs(&[5]);
+    test.execute();
+}
+
+/// The arguments `run` passes to the imported `stub` function
+const STUB_ARGS: [u64; 10] = [3, 1 << 32, 5, u64::MAX, 7, 11 << 40, 13, 17, 19, 23 << 33];
+
+/// A component in which a core module calls `stub`, a function with ten `i64` parameters and no
+/// results, imported from the instance of another core module. The stub folds its parameters into
+/// a checksum kept in a global, which `run` reads back after the call, so that the result depends
+/// on every argument being passed in the right place
+fn void_import_component() -> String {
+    let fold = (0..STUB_ARGS.len())
+        .map(|i| format!("global.get $h i64.const 31 i64.mul local.get {i} i64.add global.set $h"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let args = STUB_ARGS
+        .iter()
+        .map(|arg| format!("i64.const {}", *arg as i64))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        r#"
+        (component
+            (core module $stub
+                (global $h (mut i64) (i64.const 0))
+                (func (export "stub") (param i64 i64 i64 i64 i64 i64 i64 i64 i64 i64)
+                    {fold}
+                )
+                (func (export "checksum") (result i32)
+                    global.get $h
+                    i64.const 32
+                    i64.shr_u
+                    global.get $h
+                    i64.xor
+                    i32.wrap_i64
+                )
+            )
+            (core instance $stub_instance (instantiate $stub))
+            (core module $main
+                (import "env" "stub"
+                    (func $stub (param i64 i64 i64 i64 i64 i64 i64 i64 i64 i64)))
+                (import "env" "checksum" (func $checksum (result i32)))
+                (func (export "run") (result i32)
+                    {args}
+                    call $stub
+                    call $checksum
+                )
+            )
+            (core instance $main_instance (instantiate $main
+                (with "env" (instance $stub_instance))
+            ))
+            (type $run_ty (func (result u32)))
+            (alias core export $main_instance "run" (core func $run))
+            (func $run_lifted (type $run_ty) (canon lift (core func $run)))
+            (export "run" (func $run_lifted))
+        )
+        "#
+    )
+}
+
+#[test]
+fn void_import_with_many_i64_params() {
+    let h = STUB_ARGS
+        .iter()
+        .fold(0u64, |h, arg| h.wrapping_mul(31).wrapping_add(*arg));
+    let expected = ((h >> 32) ^ h) as u32;
+    // The VM rejects programs which leave the operand stack deeper than it was on entry, so a
+    // successful run implies that the call consumed all 20 felts of its arguments
+    let mut test = CompilerTest::wat_component(&void_import_component(), "run");
+    test.expect_vm_output(&[expected as u64]);
+}
@@ -0,0 +1,37 @@
+use miden_frontend_wasm::WasmTranslationConfig;
+use miden_hir::{Felt, FunctionIdent, Ident};
+
+use crate::{default_session, execute_vm};
+
+#[test]
+fn wasm_compiles_to_runnable_core_program() {
+    let wat = r#"
+        (module $arith
+            (func $mul_add (export "mul_add") (param i32 i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.mul
+                local.get 2
+                i32.add
+            )
+        )
+    "#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let session = default_session();
+    let entrypoint = FunctionIdent {
+        module: Ident::from("arith"),
+        function: Ident::from("mul_add"),
+    };
+    let program = midenc_compile::compile_wasm_to_core_program(
+        &wasm,
+        &WasmTranslationConfig::default(),
+        entrypoint,
+        &session,
+    )
+    .expect("compilation failed");
+
+    // Every argument is the same, so the result does not depend on the order they are passed in
+    let args = [Felt::new(6), Felt::new(6), Felt::new(6)];
+    let out: u32 = execute_vm(&program, &args).first().unwrap().clone().into();
+    assert_eq!(out, 42);
+}
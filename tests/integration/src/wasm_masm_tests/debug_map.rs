@@ -0,0 +1,142 @@
+use miden_codegen_masm::{DebugMap, DebugMapEntry, Program, SourceLocation};
+use miden_hir::Symbol;
+
+use crate::CompilerTest;
+
+const MODULE: &str = r#"
+    (module
+        (func $double (param i32) (result i32)
+            local.get 0
+            local.get 0
+            i32.add
+        )
+        (func $entrypoint (export "entrypoint") (result i32)
+            i32.const 21
+            call $double
+        )
+    )
+"#;
+
+/// The source file every address of the module is attributed to by its line program
+const SOURCE_FILE: &str = "main.rs";
+/// The line every address of the module is attributed to by its line program
+const SOURCE_LINE: u8 = 42;
+
+fn uleb128(mut value: u64, bytes: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            break;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// Prefix `contents` with its length, as a 32-bit DWARF unit length
+fn with_unit_length(contents: Vec<u8>) -> Vec<u8> {
+    let mut unit = (contents.len() as u32).to_le_bytes().to_vec();
+    unit.extend(contents);
+    unit
+}
+
+fn custom_section(name: &str, data: &[u8]) -> Vec<u8> {
+    let mut contents = vec![];
+    uleb128(name.len() as u64, &mut contents);
+    contents.extend(name.as_bytes());
+    contents.extend(data);
+    let mut section = vec![0];
+    uleb128(contents.len() as u64, &mut section);
+    section.extend(contents);
+    section
+}
+
+/// The DWARF 4 sections of a single compilation unit, whose line program attributes every
+/// address of the code section to [SOURCE_LINE] of [SOURCE_FILE]
+fn dwarf_sections() -> Vec<u8> {
+    let file_name = format!("{SOURCE_FILE}\0");
+
+    // A compile unit, without children, with a name and a line program
+    let debug_abbrev = [1, 0x11, 0, 0x03, 0x08, 0x10, 0x17, 0, 0, 0];
+
+    // Version, abbreviations offset and address size, followed by the compile unit
+    let mut debug_info = vec![4, 0, 0, 0, 0, 0, 4, 1];
+    debug_info.extend(file_name.as_bytes());
+    debug_info.extend(0u32.to_le_bytes());
+
+    // Minimum instruction length, maximum operations per instruction, default `is_stmt`, line
+    // base, line range, opcode base and the lengths of the standard opcodes, followed by the
+    // empty list of include directories, and the list of file names
+    let mut header = vec![
+        1, 1, 1, -5i8 as u8, 14, 13, 0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1, 0,
+    ];
+    header.extend(file_name.as_bytes());
+    header.extend([0, 0, 0, 0]);
+    // DW_LNE_set_address 0, DW_LNS_advance_line, DW_LNS_copy, DW_LNS_advance_pc 0x1000, and
+    // DW_LNE_end_sequence
+    let mut program = vec![0, 5, 2, 0, 0, 0, 0, 3, SOURCE_LINE - 1, 1, 2];
+    uleb128(0x1000, &mut program);
+    program.extend([0, 1, 1]);
+    let mut debug_line = vec![4, 0];
+    debug_line.extend((header.len() as u32).to_le_bytes());
+    debug_line.extend(header);
+    debug_line.extend(program);
+
+    let mut sections = custom_section(".debug_abbrev", &debug_abbrev);
+    sections.extend(custom_section(".debug_info", &with_unit_length(debug_info)));
+    sections.extend(custom_section(".debug_line", &with_unit_length(debug_line)));
+    sections
+}
+
+#[test]
+fn debug_map_relates_procedures_to_source() {
+    let mut test = CompilerTest::wat_module(MODULE, "entrypoint").with_debug_info();
+    test.wasm_bytes.extend(dwarf_sections());
+    let program = test.ir_masm_program();
+    let debug_map = DebugMap::new(&program);
+    let entry = debug_map
+        .entries
+        .iter()
+        .find(|entry| entry.procedure.function.as_str() == "entrypoint")
+        .expect("expected the entrypoint to have a debug map entry");
+    assert_eq!(
+        entry.source,
+        Some(SourceLocation {
+            file: Symbol::intern(SOURCE_FILE),
+            line: SOURCE_LINE as u32,
+        })
+    );
+    // The entrypoint is emitted after `double`
+    assert_ne!(entry.masm_offset, 0);
+    assert_eq!(entry.masm_offset, masm_offset(&program, entry));
+
+    let json: serde_json::Value = serde_json::from_str(&debug_map.to_string()).unwrap();
+    let json_entry = json["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|json_entry| json_entry["masm"]["procedure"] == "entrypoint")
+        .expect("expected the entrypoint to be serialized");
+    assert_eq!(
+        json_entry["source"],
+        serde_json::json!({"file": SOURCE_FILE, "line": SOURCE_LINE})
+    );
+    assert_eq!(json_entry["masm"]["offset"], entry.masm_offset);
+}
+
+/// The number of top-level ops emitted in the module of `entry` before its procedure
+///
+/// The procedures compiled from this module have no control flow, so their ops are all at the
+/// top level of their body.
+fn masm_offset(program: &Program, entry: &DebugMapEntry) -> u32 {
+    let module = program
+        .modules()
+        .find(|module| module.name == entry.procedure.module)
+        .unwrap();
+    module
+        .functions()
+        .take_while(|function| function.name != entry.procedure)
+        .map(|function| function.body.block(function.body.id()).ops.len() as u32)
+        .sum()
+}
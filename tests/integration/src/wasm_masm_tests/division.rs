@@ -0,0 +1,62 @@
+use crate::CompilerTest;
+
+const DIVISION_BY_ZERO_MODULE: &str = r#"
+    (module
+        (func $div_u (export "div_u") (result i32)
+            i32.const 7
+            i32.const 0
+            i32.div_u
+        )
+        (func $rem_s (export "rem_s") (result i32)
+            i32.const -7
+            i32.const 0
+            i32.rem_s
+        )
+        (func $div_s_i64 (export "div_s_i64") (result i32)
+            i64.const -7
+            i64.const 0
+            i64.div_s
+            i64.eqz
+        )
+        (func $div_u_nonzero (export "div_u_nonzero") (result i32)
+            i32.const 7
+            i32.const 2
+            i32.div_u
+        )
+    )
+"#;
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn division_by_zero_traps_by_default() {
+    let mut test = CompilerTest::wat_module(DIVISION_BY_ZERO_MODULE, "div_u");
+    test.execute();
+}
+
+#[test]
+fn i32_div_u_by_zero_is_zero_when_wrapping() {
+    let mut test =
+        CompilerTest::wat_module(DIVISION_BY_ZERO_MODULE, "div_u").with_wrapping_division();
+    test.expect_vm_output(&[0]);
+}
+
+#[test]
+fn i32_rem_s_by_zero_is_zero_when_wrapping() {
+    let mut test =
+        CompilerTest::wat_module(DIVISION_BY_ZERO_MODULE, "rem_s").with_wrapping_division();
+    test.expect_vm_output(&[0]);
+}
+
+#[test]
+fn i64_div_s_by_zero_is_zero_when_wrapping() {
+    let mut test =
+        CompilerTest::wat_module(DIVISION_BY_ZERO_MODULE, "div_s_i64").with_wrapping_division();
+    test.expect_vm_output(&[1]);
+}
+
+#[test]
+fn division_by_nonzero_is_unchanged_when_wrapping() {
+    let mut test =
+        CompilerTest::wat_module(DIVISION_BY_ZERO_MODULE, "div_u_nonzero").with_wrapping_division();
+    test.expect_vm_output(&[3]);
+}
@@ -0,0 +1,39 @@
+use crate::CompilerTest;
+
+#[test]
+fn multi_byte_store_narrow_load() {
+    // Stores the argument, 0x01020304, as an `i32`, and 0x1112131415161718 as an `i64`, then
+    // reads individual bytes and halves back, so the result only matches if the bytes of each
+    // value are laid out in little-endian order:
+    //
+    // * the first byte of the `i32` is its least significant byte, 0x04
+    // * the last byte of the `i32` is its most significant byte, 0x01
+    // * the first half of the `i64` holds its least significant bits, 0x1718
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (func $entrypoint (export "entrypoint") (param i32) (result i32)
+                i32.const 4096
+                local.get 0
+                i32.store
+                i32.const 4104
+                i64.const 0x1112131415161718
+                i64.store
+                i32.const 4096
+                i32.load8_u
+                i32.const 4099
+                i32.load8_u
+                i32.const 8
+                i32.shl
+                i32.or
+                i32.const 4104
+                i32.load16_u
+                i32.const 16
+                i32.shl
+                i32.or
+            )
+        )
+    "#;
+    let mut test = CompilerTest::wat_module(wat, "entrypoint").with_stack_inputs(&[0x01020304]);
+    test.expect_vm_output(&[0x17180104]);
+}
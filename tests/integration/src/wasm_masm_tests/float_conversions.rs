@@ -0,0 +1,60 @@
+use crate::CompilerTest;
+
+// The bit patterns of `core::f32::consts::PI`, i.e. `f32::from_bits(0x40490fdb)`, which promotes
+// to `0x400921fb60000000`, of the smallest positive subnormal `f32`, which promotes to
+// `0x36a0000000000000`, and of `core::f64::consts::PI`, which rounds to the `f32` PI on demotion
+const FLOAT_CONVERSIONS_MODULE: &str = r#"
+    (module
+        (func $promote_hi (export "promote_hi") (result i32)
+            i32.const 0x40490fdb
+            f32.reinterpret_i32
+            f64.promote_f32
+            i64.reinterpret_f64
+            i64.const 32
+            i64.shr_u
+            i32.wrap_i64
+        )
+        (func $promote_lo (export "promote_lo") (result i32)
+            i32.const 0x40490fdb
+            f32.reinterpret_i32
+            f64.promote_f32
+            i64.reinterpret_f64
+            i32.wrap_i64
+        )
+        (func $promote_subnormal_hi (export "promote_subnormal_hi") (result i32)
+            i32.const 1
+            f32.reinterpret_i32
+            f64.promote_f32
+            i64.reinterpret_f64
+            i64.const 32
+            i64.shr_u
+            i32.wrap_i64
+        )
+        (func $demote (export "demote") (result i32)
+            i64.const 0x400921fb54442d18
+            f64.reinterpret_i64
+            f32.demote_f64
+            i32.reinterpret_f32
+        )
+    )
+"#;
+
+#[test]
+fn f64_promote_f32_bit_pattern() {
+    let mut test = CompilerTest::wat_module(FLOAT_CONVERSIONS_MODULE, "promote_hi");
+    test.expect_vm_output(&[0x400921fb]);
+    let mut test = CompilerTest::wat_module(FLOAT_CONVERSIONS_MODULE, "promote_lo");
+    test.expect_vm_output(&[0x60000000]);
+}
+
+#[test]
+fn f64_promote_f32_subnormal() {
+    let mut test = CompilerTest::wat_module(FLOAT_CONVERSIONS_MODULE, "promote_subnormal_hi");
+    test.expect_vm_output(&[0x36a00000]);
+}
+
+#[test]
+fn f32_demote_f64_rounds_to_nearest() {
+    let mut test = CompilerTest::wat_module(FLOAT_CONVERSIONS_MODULE, "demote");
+    test.expect_vm_output(&[0x40490fdb]);
+}
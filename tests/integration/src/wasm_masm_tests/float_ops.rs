@@ -0,0 +1,209 @@
+use crate::CompilerTest;
+
+/// Compile and run the `f64` operator `op`, applied to `args`, which are built from their bit
+/// patterns, returning the bit pattern of the result
+fn run_f64(op: &str, args: &[f64]) -> u64 {
+    let operands = args
+        .iter()
+        .map(|arg| format!("i64.const {:#x} f64.reinterpret_i64 ", arg.to_bits()))
+        .collect::<String>();
+    let wat = format!(
+        r#"
+        (module
+            (func $hi (export "hi") (result i32)
+                {operands}
+                {op}
+                i64.reinterpret_f64
+                i64.const 32
+                i64.shr_u
+                i32.wrap_i64
+            )
+            (func $lo (export "lo") (result i32)
+                {operands}
+                {op}
+                i64.reinterpret_f64
+                i32.wrap_i64
+            )
+        )
+    "#
+    );
+    let hi = u64::from(CompilerTest::wat_module(&wat, "hi").execute()[0]);
+    let lo = u64::from(CompilerTest::wat_module(&wat, "lo").execute()[0]);
+    (hi << 32) | lo
+}
+
+/// Like [run_f64], but for an `f32` operator
+fn run_f32(op: &str, args: &[f32]) -> u32 {
+    let operands = args
+        .iter()
+        .map(|arg| format!("i32.const {:#x} f32.reinterpret_i32 ", arg.to_bits()))
+        .collect::<String>();
+    let wat = format!(
+        r#"
+        (module
+            (func $entrypoint (export "entrypoint") (result i32)
+                {operands}
+                {op}
+                i32.reinterpret_f32
+            )
+        )
+    "#
+    );
+    u64::from(CompilerTest::wat_module(&wat, "entrypoint").execute()[0]) as u32
+}
+
+/// Rounds `x` to the nearest integral value, with ties to even, as `f64.nearest` does
+fn round_ties_even(x: f64) -> f64 {
+    if (x - x.trunc()).abs() == 0.5 {
+        2.0 * (x / 2.0).round()
+    } else {
+        x.round()
+    }
+}
+
+#[test]
+fn f64_min_max_match_rust_on_numbers() {
+    let cases = [
+        (1.5, -2.25),
+        (-2.25, -3.0),
+        (7.0, 5e-324),
+        (f64::INFINITY, f64::MAX),
+        (f64::NEG_INFINITY, -1.0),
+    ];
+    for (a, b) in cases {
+        for (lhs, rhs) in [(a, b), (b, a)] {
+            assert_eq!(run_f64("f64.min", &[lhs, rhs]), lhs.min(rhs).to_bits());
+            assert_eq!(run_f64("f64.max", &[lhs, rhs]), lhs.max(rhs).to_bits());
+        }
+    }
+}
+
+#[test]
+fn f64_min_max_order_zeros_by_sign() {
+    for (lhs, rhs) in [(0.0, -0.0), (-0.0, 0.0)] {
+        assert_eq!(run_f64("f64.min", &[lhs, rhs]), (-0.0f64).to_bits());
+        assert_eq!(run_f64("f64.max", &[lhs, rhs]), 0.0f64.to_bits());
+    }
+}
+
+#[test]
+fn f64_min_max_propagate_nan() {
+    for (lhs, rhs) in [(f64::NAN, 1.0), (-4.0, f64::NAN), (f64::NAN, f64::NAN)] {
+        for op in ["f64.min", "f64.max"] {
+            assert!(f64::from_bits(run_f64(op, &[lhs, rhs])).is_nan());
+        }
+        // Unlike Rust, which ignores a NaN operand
+        if !(lhs.is_nan() && rhs.is_nan()) {
+            assert!(!lhs.min(rhs).is_nan());
+            assert!(!lhs.max(rhs).is_nan());
+        }
+    }
+}
+
+#[test]
+fn f32_min_max_propagate_nan() {
+    assert!(f32::from_bits(run_f32("f32.min", &[2.0, f32::NAN])).is_nan());
+    assert!(f32::from_bits(run_f32("f32.max", &[f32::NAN, 2.0])).is_nan());
+    assert_eq!(run_f32("f32.min", &[2.0, -0.5]), (-0.5f32).to_bits());
+    assert_eq!(run_f32("f32.max", &[-0.0, 0.0]), 0.0f32.to_bits());
+}
+
+#[test]
+fn sign_operators() {
+    assert_eq!(run_f64("f64.copysign", &[2.5, -0.0]), (-2.5f64).to_bits());
+    assert_eq!(run_f64("f64.copysign", &[-2.5, 1.0]), 2.5f64.to_bits());
+    assert_eq!(
+        run_f32("f32.copysign", &[f32::INFINITY, -1.0]),
+        f32::NEG_INFINITY.to_bits()
+    );
+    assert_eq!(run_f32("f32.abs", &[-3.5]), 3.5f32.to_bits());
+    assert_eq!(run_f32("f32.neg", &[0.0]), (-0.0f32).to_bits());
+    // Only the sign bit is changed, so the payload of NaNs is kept
+    let nan = f64::from_bits(0x7ff0_0000_0000_0001);
+    assert_eq!(run_f64("f64.neg", &[nan]), 0xfff0_0000_0000_0001);
+    assert_eq!(run_f64("f64.abs", &[-nan]), nan.to_bits());
+}
+
+#[test]
+fn f64_rounding_matches_rust() {
+    let values = [
+        -2.5,
+        -1.5,
+        -0.5,
+        -0.3,
+        0.7,
+        2.5,
+        2.7,
+        4503599627370497.0,
+        f64::NEG_INFINITY,
+    ];
+    for x in values {
+        assert_eq!(
+            run_f64("f64.trunc", &[x]),
+            x.trunc().to_bits(),
+            "trunc({x})"
+        );
+        assert_eq!(
+            run_f64("f64.floor", &[x]),
+            x.floor().to_bits(),
+            "floor({x})"
+        );
+        assert_eq!(run_f64("f64.ceil", &[x]), x.ceil().to_bits(), "ceil({x})");
+        assert_eq!(
+            run_f64("f64.nearest", &[x]),
+            round_ties_even(x).to_bits(),
+            "nearest({x})"
+        );
+    }
+    assert!(f64::from_bits(run_f64("f64.floor", &[f64::NAN])).is_nan());
+}
+
+#[test]
+fn f32_rounding_matches_rust() {
+    for x in [-1.5f32, -0.2, 0.5, 1.5, 3.25, 8388609.0] {
+        assert_eq!(
+            run_f32("f32.trunc", &[x]),
+            x.trunc().to_bits(),
+            "trunc({x})"
+        );
+        assert_eq!(
+            run_f32("f32.floor", &[x]),
+            x.floor().to_bits(),
+            "floor({x})"
+        );
+        assert_eq!(run_f32("f32.ceil", &[x]), x.ceil().to_bits(), "ceil({x})");
+        let nearest = round_ties_even(x as f64) as f32;
+        assert_eq!(
+            run_f32("f32.nearest", &[x]),
+            nearest.to_bits(),
+            "nearest({x})"
+        );
+    }
+}
+
+#[test]
+fn float_constants() {
+    let wat = r#"
+        (module
+            (func $entrypoint (export "entrypoint") (result i32)
+                f32.const -1.25
+                i32.reinterpret_f32
+            )
+        )
+    "#;
+    let mut test = CompilerTest::wat_module(wat, "entrypoint");
+    test.expect_vm_output(&[(-1.25f32).to_bits() as u64]);
+    let wat = r#"
+        (module
+            (func $entrypoint (export "entrypoint") (result i32)
+                f64.const 0x1p-1
+                i64.reinterpret_f64
+                i64.const 32
+                i64.shr_u
+                i32.wrap_i64
+            )
+        )
+    "#;
+    let mut test = CompilerTest::wat_module(wat, "entrypoint");
+    test.expect_vm_output(&[0x3fe0_0000]);
+}
@@ -0,0 +1,32 @@
+use crate::execute_emulator;
+use crate::execute_vm;
+use crate::CompilerTest;
+
+#[test]
+fn global_set_then_get() {
+    // The global is deliberately left unnamed, so that both the declaration and the accesses
+    // rely on the same fallback name to refer to its storage
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (global (mut i32) (i32.const 7))
+            (func $entrypoint (export "entrypoint") (result i32)
+                i32.const 42
+                global.set 0
+                global.get 0
+            )
+        )
+    "#;
+    let mut test = CompilerTest::wat_module(wat, "entrypoint");
+    let ir_masm = test.ir_masm_program();
+    let vm_program = test.vm_masm_program();
+
+    let vm_out: u32 = execute_vm(&vm_program, &[]).first().unwrap().clone().into();
+    assert_eq!(vm_out, 42);
+    let emul_out: u32 = execute_emulator(ir_masm, &[])
+        .first()
+        .unwrap()
+        .clone()
+        .into();
+    assert_eq!(emul_out, 42);
+}
@@ -0,0 +1,47 @@
+use crate::CompilerTest;
+
+// Each result is compared to the one expected under Wasm's wraparound semantics, which differs
+// from the one obtained by reducing modulo the field modulus `2^64 - 2^32 + 1`, e.g. `2^64` is
+// `2^32 - 1` in the field, rather than `0`
+const I64_ARITHMETIC_MODULE: &str = r#"
+    (module
+        (func $add_wraps (export "add_wraps") (result i32)
+            i64.const 0xffffffffffffffff
+            i64.const 1
+            i64.add
+            i64.eqz
+        )
+        (func $sub_wraps (export "sub_wraps") (result i32)
+            i64.const 0
+            i64.const 1
+            i64.sub
+            i64.const 0xffffffffffffffff
+            i64.eq
+        )
+        (func $mul_wraps (export "mul_wraps") (result i32)
+            i64.const 0x100000000
+            i64.const 0x100000001
+            i64.mul
+            i64.const 0x100000000
+            i64.eq
+        )
+    )
+"#;
+
+#[test]
+fn i64_add_wraps_around() {
+    let mut test = CompilerTest::wat_module(I64_ARITHMETIC_MODULE, "add_wraps");
+    test.expect_vm_output(&[1]);
+}
+
+#[test]
+fn i64_sub_wraps_around() {
+    let mut test = CompilerTest::wat_module(I64_ARITHMETIC_MODULE, "sub_wraps");
+    test.expect_vm_output(&[1]);
+}
+
+#[test]
+fn i64_mul_wraps_around() {
+    let mut test = CompilerTest::wat_module(I64_ARITHMETIC_MODULE, "mul_wraps");
+    test.expect_vm_output(&[1]);
+}
@@ -0,0 +1,35 @@
+use midenc_session::InitTrapPolicy;
+
+use crate::CompilerTest;
+
+// The ctors trap after making progress, which is observed by the export once they return
+const TRAPPING_CTORS_MODULE: &str = r#"
+    (module
+        (global $stage (mut i32) i32.const 0)
+        (func $__wasm_call_ctors
+            i32.const 1
+            global.set $stage
+            unreachable
+        )
+        (func $main (export "main") (result i32)
+            call $__wasm_call_ctors
+            global.get $stage
+            i32.const 41
+            i32.add
+        )
+    )
+"#;
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn trapping_ctors_fail_the_program_by_default() {
+    let mut test = CompilerTest::wat_module(TRAPPING_CTORS_MODULE, "main");
+    test.execute();
+}
+
+#[test]
+fn trapping_ctors_are_reported_when_caught() {
+    let mut test = CompilerTest::wat_module(TRAPPING_CTORS_MODULE, "main")
+        .with_init_trap_policy(InitTrapPolicy::Report);
+    test.expect_vm_output(&[42]);
+}
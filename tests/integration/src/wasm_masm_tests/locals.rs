@@ -0,0 +1,37 @@
+use miden_hir::Felt;
+
+use crate::execute_emulator;
+use crate::CompilerTest;
+
+#[test]
+fn local_tee_feeds_store_and_add() {
+    // `local.tee` must leave its operand on the stack, as it is consumed by the store, while the
+    // copy saved in the local is consumed by the add
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (func $entrypoint (export "entrypoint") (param i32) (result i32) (local i32)
+                i32.const 1024
+                local.get 0
+                i32.const 3
+                i32.mul
+                local.tee 1
+                i32.store
+                local.get 1
+                i32.const 1024
+                i32.load
+                i32.add
+            )
+        )
+    "#;
+    let mut test = CompilerTest::wat_module(wat, "entrypoint").with_stack_inputs(&[7]);
+    let ir_masm = test.ir_masm_program();
+    test.expect_vm_output(&[42]);
+
+    let emul_out: u32 = execute_emulator(ir_masm, &[Felt::new(7)])
+        .first()
+        .unwrap()
+        .clone()
+        .into();
+    assert_eq!(emul_out, 42);
+}
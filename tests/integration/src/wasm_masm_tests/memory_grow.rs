@@ -0,0 +1,52 @@
+use crate::CompilerTest;
+
+/// A module with a memory of 1 page, which may grow to 4 pages, which grows it by the number of
+/// pages given as the argument, and returns the result of `memory.grow`, plus 1000 times the
+/// resulting `memory.size`
+const MEMORY_GROW_MODULE: &str = r#"
+    (module
+        (memory (;0;) 1 4)
+        (func $entrypoint (export "entrypoint") (param i32) (result i32)
+            local.get 0
+            memory.grow
+            memory.size
+            i32.const 1000
+            i32.mul
+            i32.add
+        )
+    )
+"#;
+
+#[test]
+fn memory_grows_up_to_its_maximum() {
+    // Growth succeeds, returning the previous size of 1 page, until it would exceed the maximum,
+    // where it returns -1, and leaves the size unchanged
+    for (pages, expected) in [(0, 1001), (1, 2001), (3, 4001), (4, 999)] {
+        let mut test =
+            CompilerTest::wat_module(MEMORY_GROW_MODULE, "entrypoint").with_stack_inputs(&[pages]);
+        test.expect_vm_output(&[expected]);
+    }
+}
+
+#[test]
+fn memory_grows_in_several_steps() {
+    // Each growth starts from the size the previous one left the memory at
+    let wat = r#"
+        (module
+            (memory (;0;) 1 4)
+            (func $entrypoint (export "entrypoint") (result i32)
+                i32.const 2
+                memory.grow
+                i32.const 1
+                memory.grow
+                i32.add
+                i32.const 1
+                memory.grow
+                i32.add
+            )
+        )
+    "#;
+    // 1 + 3, after which there is no room for the last page, which fails with -1
+    let mut test = CompilerTest::wat_module(wat, "entrypoint");
+    test.expect_vm_output(&[3]);
+}
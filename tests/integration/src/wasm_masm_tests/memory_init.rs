@@ -0,0 +1,97 @@
+use crate::CompilerTest;
+
+/// A module which copies `len` bytes of a passive data segment, starting at the offset given as
+/// the argument, to address `dst` in memory, and returns the 4 bytes at `dst` as an `i32`. If
+/// `drop` is set, the segment is dropped before it is copied from.
+fn memory_init_module(memory: &str, dst: u32, len: u32, drop: bool) -> String {
+    let drop = if drop { "data.drop $d" } else { "" };
+    format!(
+        r#"
+        (module
+            (memory (;0;) {memory})
+            (data $d "\01\02\03\04\05\06\07\08")
+            (func $entrypoint (export "entrypoint") (param i32) (result i32)
+                {drop}
+                i32.const {dst}
+                local.get 0
+                i32.const {len}
+                memory.init $d
+                i32.const {dst}
+                i32.load
+            )
+        )
+    "#
+    )
+}
+
+#[test]
+fn memory_init_in_bounds() {
+    let wat = memory_init_module("16", 4096, 4, false);
+    for (offset, expected) in [(0, 0x04030201), (4, 0x08070605)] {
+        let mut test = CompilerTest::wat_module(&wat, "entrypoint").with_stack_inputs(&[offset]);
+        test.expect_vm_output(&[expected]);
+    }
+}
+
+#[test]
+fn memory_init_copies_only_the_requested_bytes() {
+    // The bytes following the 2 copied ones are left as they were, i.e. zeroed
+    let wat = memory_init_module("16", 4096, 2, false);
+    let mut test = CompilerTest::wat_module(&wat, "entrypoint").with_stack_inputs(&[1]);
+    test.expect_vm_output(&[0x0302]);
+}
+
+#[test]
+fn memory_init_from_several_call_sites() {
+    // The segment is placed by the first `memory.init`, and copied from again by the second
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (data $d "\01\02\03\04\05\06\07\08")
+            (func $entrypoint (export "entrypoint") (result i32)
+                i32.const 4096
+                i32.const 0
+                i32.const 4
+                memory.init $d
+                i32.const 4100
+                i32.const 4
+                i32.const 4
+                memory.init $d
+                i32.const 4096
+                i32.load
+                i32.const 4100
+                i32.load
+                i32.add
+            )
+        )
+    "#;
+    let mut test = CompilerTest::wat_module(wat, "entrypoint");
+    test.expect_vm_output(&[0x04030201 + 0x08070605]);
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn memory_init_out_of_bounds_source_traps() {
+    // The last 4 bytes of the 8 byte segment start at offset 4, so this reads 1 byte past its end
+    let wat = memory_init_module("16", 4096, 4, false);
+    let mut test = CompilerTest::wat_module(&wat, "entrypoint").with_stack_inputs(&[5]);
+    test.execute();
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn memory_init_out_of_bounds_destination_traps() {
+    // The memory is a single page, which the copy runs 2 bytes past the end of
+    let wat = memory_init_module("1 1", 65534, 4, false);
+    let mut test = CompilerTest::wat_module(&wat, "entrypoint").with_stack_inputs(&[0]);
+    test.execute();
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn memory_init_after_drop_traps() {
+    // Once dropped, the segment is treated as empty, so any non-empty copy is out of bounds
+    let wat = memory_init_module("16", 4096, 4, true);
+    let mut test = CompilerTest::wat_module(&wat, "entrypoint").with_stack_inputs(&[0]);
+    test.execute();
+}
@@ -0,0 +1,22 @@
+mod atomics;
+mod component_strings;
+mod components;
+mod core_program;
+mod debug_map;
+mod division;
+mod endianness;
+mod float_conversions;
+mod float_ops;
+mod globals;
+mod i64_arithmetic;
+mod init_traps;
+mod locals;
+mod memory_grow;
+mod memory_init;
+mod prebuilt;
+mod references;
+mod reinterpret;
+mod start;
+mod stats;
+mod truncation;
+mod unaligned;
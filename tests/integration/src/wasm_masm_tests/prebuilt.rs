@@ -0,0 +1,23 @@
+use crate::CompilerTest;
+
+#[test]
+fn prebuilt_module_runs_from_its_bytes() {
+    // The module is named, so its entrypoint is not in the `noname` module of unnamed ones
+    let wasm = wat::parse_str(
+        r#"
+        (module $arith
+            (func $mul_add (export "mul_add") (param i32 i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.mul
+                local.get 2
+                i32.add
+            )
+        )
+    "#,
+    )
+    .unwrap();
+    // The arguments are distinct, and no other order of them gives the same result
+    let mut test = CompilerTest::wasm_bytes(wasm, Some("mul_add")).with_stack_inputs(&[6, 7, 5]);
+    test.expect_vm_output(&[47]);
+}
@@ -0,0 +1,136 @@
+use crate::CompilerTest;
+
+const REFERENCES_MODULE: &str = r#"
+    (module
+        (func $null_eq_null (export "null_eq_null") (result i32)
+            ref.null eq
+            ref.null eq
+            ref.eq
+        )
+        (func $null_eq_i31 (export "null_eq_i31") (param i32) (result i32)
+            ref.null eq
+            local.get 0
+            ref.i31
+            ref.eq
+        )
+        (func $i31_eq_i31 (export "i31_eq_i31") (param i32 i32) (result i32)
+            local.get 0
+            ref.i31
+            local.get 1
+            ref.i31
+            ref.eq
+        )
+    )
+"#;
+
+#[test]
+fn ref_eq_null() {
+    let mut test = CompilerTest::wat_module(REFERENCES_MODULE, "null_eq_null");
+    test.expect_vm_output(&[1]);
+}
+
+#[test]
+fn ref_eq_null_is_not_i31() {
+    // `ref.i31 0` must not be confused with a null reference
+    let mut test =
+        CompilerTest::wat_module(REFERENCES_MODULE, "null_eq_i31").with_stack_inputs(&[0]);
+    test.expect_vm_output(&[0]);
+}
+
+#[test]
+fn ref_eq_i31() {
+    for (a, b, expected) in [(5, 5, 1), (5, 6, 0), (0x8000_0005, 5, 1)] {
+        let mut test =
+            CompilerTest::wat_module(REFERENCES_MODULE, "i31_eq_i31").with_stack_inputs(&[a, b]);
+        test.expect_vm_output(&[expected]);
+    }
+}
+
+#[test]
+fn call_through_imported_funcref_global() {
+    // The imported global is backed by the external symbol `env::callback`, which is assigned a
+    // reference here, standing in for a host providing a function pointer
+    let wat = r#"
+        (module
+            (type $t (func (param i32) (result i32)))
+            (import "env" "callback" (global $callback (mut (ref null $t))))
+            (elem declare func $double)
+            (func $double (type $t) (param i32) (result i32)
+                local.get 0
+                local.get 0
+                i32.add
+            )
+            (func $entrypoint (export "entrypoint") (result i32)
+                ref.func $double
+                global.set $callback
+                i32.const 21
+                global.get $callback
+                call_ref $t
+            )
+        )
+    "#;
+    let mut test = CompilerTest::wat_module(wat, "entrypoint");
+    test.expect_vm_output(&[42]);
+}
+
+const DISPATCH_TABLE_MODULE: &str = r#"
+    (module
+        (type $binop (func (param i32 i32) (result i32)))
+        (type $unop (func (param i32) (result i32)))
+        (table 5 funcref)
+        (elem (i32.const 0) func $add $sub $mul)
+        (elem (i32.const 4) func $neg)
+        (func $add (type $binop)
+            local.get 0
+            local.get 1
+            i32.add
+        )
+        (func $sub (type $binop)
+            local.get 0
+            local.get 1
+            i32.sub
+        )
+        (func $mul (type $binop)
+            local.get 0
+            local.get 1
+            i32.mul
+        )
+        (func $neg (type $unop)
+            i32.const 0
+            local.get 0
+            i32.sub
+        )
+        (func $dispatch (export "dispatch") (param $op i32) (param $a i32) (param $b i32)
+            (result i32)
+            local.get $a
+            local.get $b
+            local.get $op
+            call_indirect (type $binop)
+        )
+    )
+"#;
+
+#[test]
+fn call_indirect_through_dispatch_table() {
+    for (op, a, b, expected) in [(0, 20, 3, 23), (1, 20, 3, 17), (2, 20, 3, 60)] {
+        let mut test = CompilerTest::wat_module(DISPATCH_TABLE_MODULE, "dispatch")
+            .with_stack_inputs(&[op, a, b]);
+        test.expect_vm_output(&[expected]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn call_indirect_through_null_entry_traps() {
+    let mut test =
+        CompilerTest::wat_module(DISPATCH_TABLE_MODULE, "dispatch").with_stack_inputs(&[3, 20, 3]);
+    test.execute();
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn call_indirect_with_mismatched_signature_traps() {
+    let mut test =
+        CompilerTest::wat_module(DISPATCH_TABLE_MODULE, "dispatch").with_stack_inputs(&[4, 20, 3]);
+    test.execute();
+}
@@ -0,0 +1,42 @@
+use crate::CompilerTest;
+
+// The bit pattern of `core::f64::consts::PI`, i.e. `f64::from_bits(0x400921fb54442d18)`
+const REINTERPRET_MODULE: &str = r#"
+    (module
+        (func $round_trip (export "round_trip") (result i32)
+            i64.const 0x400921fb54442d18
+            f64.reinterpret_i64
+            i64.reinterpret_f64
+            i64.const 0x400921fb54442d18
+            i64.eq
+        )
+        (func $round_trip_hi (export "round_trip_hi") (result i32)
+            i64.const 0x400921fb54442d18
+            f64.reinterpret_i64
+            i64.reinterpret_f64
+            i64.const 32
+            i64.shr_u
+            i32.wrap_i64
+        )
+        (func $round_trip_lo (export "round_trip_lo") (result i32)
+            i64.const 0x400921fb54442d18
+            f64.reinterpret_i64
+            i64.reinterpret_f64
+            i32.wrap_i64
+        )
+    )
+"#;
+
+#[test]
+fn f64_from_bits_round_trip() {
+    let mut test = CompilerTest::wat_module(REINTERPRET_MODULE, "round_trip");
+    test.expect_vm_output(&[1]);
+}
+
+#[test]
+fn f64_from_bits_preserves_bit_pattern() {
+    let mut test = CompilerTest::wat_module(REINTERPRET_MODULE, "round_trip_hi");
+    test.expect_vm_output(&[0x400921fb]);
+    let mut test = CompilerTest::wat_module(REINTERPRET_MODULE, "round_trip_lo");
+    test.expect_vm_output(&[0x54442d18]);
+}
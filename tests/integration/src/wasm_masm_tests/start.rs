@@ -0,0 +1,24 @@
+use crate::CompilerTest;
+
+/// The start function is run before the entrypoint, which, as an export, also runs it if it has
+/// not been run yet, so it must not be run a second time
+#[test]
+fn start_function_runs_once_before_the_entrypoint() {
+    let wat = r#"
+        (module
+            (global $runs (mut i32) i32.const 0)
+            (func $init
+                global.get $runs
+                i32.const 1
+                i32.add
+                global.set $runs
+            )
+            (func $entrypoint (export "entrypoint") (result i32)
+                global.get $runs
+            )
+            (start $init)
+        )
+    "#;
+    let mut test = CompilerTest::wat_module(wat, "entrypoint");
+    test.expect_vm_output(&[1]);
+}
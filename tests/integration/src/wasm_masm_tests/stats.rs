@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use midenc_session::{
+    InputFile, Options, OutputType, OutputTypeSpec, OutputTypes, Session, TargetEnv,
+};
+
+const STATS_MODULE: &str = r#"
+    (module $stats
+        (func $add (export "add") (param i32 i32) (result i32)
+            local.get 0
+            local.get 1
+            i32.add
+        )
+        (func $double (export "double") (param i32) (result i32)
+            local.get 0
+            local.get 0
+            call $add
+        )
+    )
+"#;
+
+#[test]
+fn stats_report_module_sizes() {
+    let wasm = wat::parse_str(STATS_MODULE).unwrap();
+    let path = std::env::temp_dir().join(format!("stats_{}.wasm", std::process::id()));
+    std::fs::write(&path, wasm).unwrap();
+
+    let mut options = Options::default().with_output_types(OutputTypes::new([OutputTypeSpec {
+        output_type: OutputType::Masm,
+        path: None,
+    }]));
+    options.print_stats = true;
+    let session = Arc::new(Session::new(
+        TargetEnv::Base,
+        InputFile::from_path(&path).unwrap(),
+        None,
+        None,
+        None,
+        options,
+        None,
+    ));
+    let compiled = midenc_compile::compile_to_memory(session.clone());
+    std::fs::remove_file(&path).ok();
+    compiled.expect("compilation failed");
+
+    let sizes = session.statistics.module_sizes();
+    let size = sizes
+        .get("stats")
+        .expect("expected the size of the module to be recorded");
+    assert_eq!(size.hir_functions, 2);
+    assert_eq!(size.masm_procedures, 2);
+    assert!(size.hir_instructions > 0);
+    assert!(size.masm_instructions > 0);
+
+    let report = session.statistics.size_report();
+    assert_eq!(report.total().hir_functions, 2);
+    let report = report.to_string();
+    assert!(
+        report.lines().any(|line| line.starts_with("stats ")),
+        "{report}"
+    );
+    assert!(
+        report.lines().any(|line| line.starts_with("total ")),
+        "{report}"
+    );
+}
@@ -0,0 +1,166 @@
+use crate::CompilerTest;
+
+// There are no floating-point constants in Miden IR, so the operands are built from their bit
+// patterns, i.e. `-3.75`, `3000000000.5`, `1e19`, NaN and `2^31` respectively
+const TRUNCATION_MODULE: &str = r#"
+    (module
+        (func $trunc_s (export "trunc_s") (result i32)
+            i64.const 0xc00e000000000000
+            f64.reinterpret_i64
+            i32.trunc_f64_s
+            i32.const 10
+            i32.add
+        )
+        (func $trunc_u (export "trunc_u") (result i32)
+            i64.const 0x41e65a0bc0100000
+            f64.reinterpret_i64
+            i32.trunc_f64_u
+        )
+        (func $trunc_u64_hi (export "trunc_u64_hi") (result i32)
+            i64.const 0x43e158e460913d00
+            f64.reinterpret_i64
+            i64.trunc_f64_u
+            i64.const 32
+            i64.shr_u
+            i32.wrap_i64
+        )
+        (func $trunc_u64_lo (export "trunc_u64_lo") (result i32)
+            i64.const 0x43e158e460913d00
+            f64.reinterpret_i64
+            i64.trunc_f64_u
+            i32.wrap_i64
+        )
+        (func $trunc_nan (export "trunc_nan") (result i32)
+            i64.const 0x7ff8000000000000
+            f64.reinterpret_i64
+            i32.trunc_f64_s
+        )
+        (func $trunc_overflow (export "trunc_overflow") (result i32)
+            i64.const 0x41e0000000000000
+            f64.reinterpret_i64
+            i32.trunc_f64_s
+        )
+    )
+"#;
+
+// The operands are `-3.75`, `3000000000`, `-1e10`, `1e19`, which is `9999999980506447872` as an
+// `f32`, NaN, `2^31` and `-1` respectively
+const F32_TRUNCATION_MODULE: &str = r#"
+    (module
+        (func $trunc_s (export "trunc_s") (result i32)
+            f32.const -3.75
+            i32.trunc_f32_s
+            i32.const 10
+            i32.add
+        )
+        (func $trunc_u (export "trunc_u") (result i32)
+            f32.const 3000000000
+            i32.trunc_f32_u
+        )
+        (func $trunc_s64 (export "trunc_s64") (result i32)
+            f32.const -1e10
+            i64.trunc_f32_s
+            i64.const 10000000007
+            i64.add
+            i32.wrap_i64
+        )
+        (func $trunc_u64_hi (export "trunc_u64_hi") (result i32)
+            f32.const 1e19
+            i64.trunc_f32_u
+            i64.const 32
+            i64.shr_u
+            i32.wrap_i64
+        )
+        (func $trunc_nan (export "trunc_nan") (result i32)
+            f32.const nan
+            i32.trunc_f32_u
+        )
+        (func $trunc_overflow (export "trunc_overflow") (result i32)
+            f32.const 2147483648
+            i32.trunc_f32_s
+        )
+        (func $trunc_negative_u (export "trunc_negative_u") (result i64)
+            f32.const -1
+            i64.trunc_f32_u
+        )
+    )
+"#;
+
+#[test]
+fn i32_trunc_f64_s_rounds_towards_zero() {
+    let mut test = CompilerTest::wat_module(TRUNCATION_MODULE, "trunc_s");
+    test.expect_vm_output(&[7]);
+}
+
+#[test]
+fn i32_trunc_f64_u_uses_the_full_range() {
+    let mut test = CompilerTest::wat_module(TRUNCATION_MODULE, "trunc_u");
+    test.expect_vm_output(&[3000000000]);
+}
+
+#[test]
+fn i64_trunc_f64_u_uses_the_full_range() {
+    let mut test = CompilerTest::wat_module(TRUNCATION_MODULE, "trunc_u64_hi");
+    test.expect_vm_output(&[2328306436]);
+    let mut test = CompilerTest::wat_module(TRUNCATION_MODULE, "trunc_u64_lo");
+    test.expect_vm_output(&[2313682944]);
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn trunc_of_nan_traps() {
+    let mut test = CompilerTest::wat_module(TRUNCATION_MODULE, "trunc_nan");
+    test.execute();
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn trunc_out_of_range_traps() {
+    let mut test = CompilerTest::wat_module(TRUNCATION_MODULE, "trunc_overflow");
+    test.execute();
+}
+
+#[test]
+fn i32_trunc_f32_s_rounds_towards_zero() {
+    let mut test = CompilerTest::wat_module(F32_TRUNCATION_MODULE, "trunc_s");
+    test.expect_vm_output(&[7]);
+}
+
+#[test]
+fn i32_trunc_f32_u_uses_the_full_range() {
+    let mut test = CompilerTest::wat_module(F32_TRUNCATION_MODULE, "trunc_u");
+    test.expect_vm_output(&[3000000000]);
+}
+
+#[test]
+fn i64_trunc_f32_s_of_a_negative_value() {
+    let mut test = CompilerTest::wat_module(F32_TRUNCATION_MODULE, "trunc_s64");
+    test.expect_vm_output(&[7]);
+}
+
+#[test]
+fn i64_trunc_f32_u_uses_the_full_range() {
+    let mut test = CompilerTest::wat_module(F32_TRUNCATION_MODULE, "trunc_u64_hi");
+    test.expect_vm_output(&[2328306432]);
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn trunc_f32_of_nan_traps() {
+    let mut test = CompilerTest::wat_module(F32_TRUNCATION_MODULE, "trunc_nan");
+    test.execute();
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn trunc_f32_out_of_range_traps() {
+    let mut test = CompilerTest::wat_module(F32_TRUNCATION_MODULE, "trunc_overflow");
+    test.execute();
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn trunc_f32_of_a_negative_value_to_unsigned_traps() {
+    let mut test = CompilerTest::wat_module(F32_TRUNCATION_MODULE, "trunc_negative_u");
+    test.execute();
+}
@@ -0,0 +1,79 @@
+use crate::CompilerTest;
+
+/// A module which writes the bytes `01 02 .. 08` to memory at address 4096, optionally replaces
+/// four of them with the bytes of `0xaabbccdd` using an unaligned store to address 4097, and then
+/// returns the result of an unaligned load of four bytes from the address given as the argument.
+fn unaligned_module(store: bool) -> String {
+    let store = if store {
+        "i32.const 4097 i32.const 0xaabbccdd i32.store align=1"
+    } else {
+        ""
+    };
+    format!(
+        r#"
+        (module
+            (memory (;0;) 16)
+            (func $entrypoint (export "entrypoint") (param i32) (result i32)
+                i32.const 4096
+                i32.const 0x04030201
+                i32.store
+                i32.const 4100
+                i32.const 0x08070605
+                i32.store
+                {store}
+                local.get 0
+                i32.load offset=1 align=1
+            )
+        )
+    "#
+    )
+}
+
+#[test]
+fn unaligned_load() {
+    let mut test =
+        CompilerTest::wat_module(&unaligned_module(false), "entrypoint").with_stack_inputs(&[4096]);
+    test.expect_vm_output(&[0x05040302]);
+}
+
+#[test]
+fn unaligned_store() {
+    let wat = unaligned_module(true);
+    // Loading from 4097 reads exactly the bytes written by the store, while loading from 4098
+    // reads the last three of them, followed by the unmodified byte at 4101
+    for (addr, expected) in [(4096, 0xaabbccdd), (4097, 0x06aabbcc)] {
+        let mut test = CompilerTest::wat_module(&wat, "entrypoint").with_stack_inputs(&[addr]);
+        test.expect_vm_output(&[expected]);
+    }
+}
+
+#[test]
+fn unaligned_access_with_natural_alignment_hint() {
+    // The store and load claim natural alignment, as the address is not known to the producer,
+    // and must still succeed at every address
+    let wat = r#"
+        (module
+            (memory (;0;) 16)
+            (func $entrypoint (export "entrypoint") (param i32) (result i32)
+                local.get 0
+                i32.const 0xaabbccdd
+                i32.store
+                local.get 0
+                i32.load
+            )
+        )
+    "#;
+    for addr in 4096..4100 {
+        let mut test = CompilerTest::wat_module(wat, "entrypoint").with_stack_inputs(&[addr]);
+        test.expect_vm_output(&[0xaabbccdd]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "failed to execute program on VM")]
+fn unaligned_load_traps_when_configured() {
+    let mut test = CompilerTest::wat_module(&unaligned_module(false), "entrypoint")
+        .with_stack_inputs(&[4096])
+        .with_trap_on_unaligned_access();
+    test.execute();
+}
@@ -9,11 +9,48 @@ use midenc_session::{
     InputFile, OutputFile, OutputType, OutputTypeSpec, OutputTypes, ProjectType, Session, TargetEnv,
 };
 
+use crate::fingerprint::Fingerprint;
+
+/// The environment variable which, when set to a non-empty value, forces recompilation of every
+/// Wasm module to MASM, as `--force` does
+pub const FORCE_REBUILD_ENV: &str = "CARGO_MIDEN_FORCE_REBUILD";
+
+/// Compiles `wasm_file_path` to the artifacts of `output_types` in `output_folder`, unless a
+/// previous compilation of the same Wasm module, with the same compiler and options, is already
+/// present there.
+///
+/// Passing `force`, or setting [FORCE_REBUILD_ENV], always recompiles, regardless of whether the
+/// outputs are up to date.
+pub fn build_masm_if_changed(
+    wasm_file_path: &Path,
+    output_folder: &Path,
+    is_bin: bool,
+    output_types: &[OutputType],
+    force: bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let output_paths = output_paths(wasm_file_path, output_folder, output_types);
+    let fingerprint = Fingerprint::new(wasm_file_path, is_bin, TargetEnv::default(), output_types)?;
+    let force = force || std::env::var_os(FORCE_REBUILD_ENV).is_some_and(|v| !v.is_empty());
+    if !force && fingerprint.is_fresh(&output_paths) {
+        log::debug!(
+            "outputs of '{}' are up to date, skipping compilation",
+            wasm_file_path.display()
+        );
+        return Ok(output_paths);
+    }
+    let output_paths = build_masm(wasm_file_path, output_folder, is_bin, output_types)?;
+    fingerprint.write(&output_paths)?;
+    Ok(output_paths)
+}
+
+/// Compiles `wasm_file_path` to the artifacts of `output_types` in `output_folder`, returning
+/// their paths in the same order.
 pub fn build_masm(
     wasm_file_path: &Path,
     output_folder: &Path,
     is_bin: bool,
-) -> anyhow::Result<PathBuf> {
+    output_types: &[OutputType],
+) -> anyhow::Result<Vec<PathBuf>> {
     let project_type = if is_bin {
         ProjectType::Program
     } else {
@@ -23,27 +60,29 @@ pub fn build_masm(
     if !output_folder.exists() {
         bail!(
             "MASM output folder '{}' does not exist.",
-            output_folder.to_str().unwrap()
+            output_folder.display()
         );
     }
     log::debug!(
         "Compiling '{}' Wasm to '{}' directory with midenc ...",
-        wasm_file_path.to_str().unwrap(),
-        &output_folder.to_str().unwrap()
+        wasm_file_path.display(),
+        output_folder.display()
     );
     let input = InputFile::from_path(wasm_file_path).context("Invalid input file")?;
+    // MASM modules are written to the output folder under their own names, while the other
+    // outputs are named after the input, in the output folder the session is given below
     let output_file_folder = OutputFile::Real(output_folder.to_path_buf());
-    let output_type = OutputType::Masm;
-    let output_types = OutputTypes::new(vec![OutputTypeSpec {
+    let output_type_specs = output_types.iter().map(|&output_type| OutputTypeSpec {
         output_type,
-        path: Some(output_file_folder.clone()),
-    }]);
+        path: (output_type == OutputType::Masm).then(|| output_file_folder.clone()),
+    });
     let cwd = std::env::current_dir().context("Failed to get current working directory")?;
-    let options = midenc_session::Options::new(cwd)
+    let mut options = midenc_session::Options::new(cwd)
         // .with_color(color)
         .with_verbosity(Verbosity::Debug)
         // .with_warnings(self.warn)
-        .with_output_types(output_types);
+        .with_output_types(OutputTypes::new(output_type_specs));
+    options.debug_info = output_types.contains(&OutputType::DebugMap);
     let target = TargetEnv::default();
     let session = Arc::new(
         Session::new(
@@ -57,8 +96,29 @@ pub fn build_masm(
         )
         .with_project_type(project_type),
     );
-    midenc_compile::compile(session.clone()).context("Wasm to MASM compilation failed!")?;
-    let mut output_path = output_folder.join(wasm_file_path.file_stem().unwrap());
-    output_path.set_extension(output_type.extension());
-    Ok(output_path)
+    // The error of the compiler is kept as the source of the returned error, so that it is
+    // reported along with the module which failed to compile
+    midenc_compile::compile(session.clone()).with_context(|| {
+        format!(
+            "Wasm to MASM compilation of '{}' failed",
+            wasm_file_path.display()
+        )
+    })?;
+    Ok(output_paths(wasm_file_path, output_folder, output_types))
+}
+
+fn output_paths(
+    wasm_file_path: &Path,
+    output_folder: &Path,
+    output_types: &[OutputType],
+) -> Vec<PathBuf> {
+    let file_stem = wasm_file_path.file_stem().unwrap_or_default();
+    output_types
+        .iter()
+        .map(|output_type| {
+            let mut output_path = output_folder.join(file_stem);
+            output_path.set_extension(output_type.extension());
+            output_path
+        })
+        .collect()
 }
@@ -20,6 +20,7 @@
 
 use anyhow::{anyhow, bail, Context, Result};
 use cargo_component_core::terminal::{Color, Terminal};
+use midenc_session::OutputType;
 use parse_arg::{iter_short, match_arg};
 use semver::Version;
 use std::fmt;
@@ -62,6 +63,15 @@ impl CargoPackageSpec {
             },
         })
     }
+
+    /// Returns true if `package` is the package this specifier refers to.
+    pub fn matches(&self, package: &cargo_metadata::Package) -> bool {
+        self.name == package.name
+            && self
+                .version
+                .as_ref()
+                .map_or(true, |version| *version == package.version)
+    }
 }
 
 impl FromStr for CargoPackageSpec {
@@ -366,6 +376,23 @@ pub struct CargoArguments {
     pub workspace: bool,
     /// The --package argument.
     pub packages: Vec<CargoPackageSpec>,
+    /// The --bin argument.
+    pub bins: Vec<String>,
+    /// The --bins argument.
+    pub all_bins: bool,
+    /// The --force argument.
+    ///
+    /// This is specific to cargo-miden, and is not passed through to cargo.
+    pub force: bool,
+    /// The output types selected by the --emit argument, e.g. `--emit masm,wat`.
+    ///
+    /// This is specific to cargo-miden, and is not passed through to cargo.
+    pub emit: Vec<OutputType>,
+    /// The formats selected by the --message-format argument, e.g.
+    /// `--message-format short,json-diagnostic-short`.
+    ///
+    /// These are merged with the JSON messages cargo-miden reads the artifacts of a build from.
+    pub message_formats: Vec<String>,
 }
 
 impl CargoArguments {
@@ -394,6 +421,8 @@ impl CargoArguments {
             .single("--manifest-path", "PATH", None)
             .multiple("--package", "SPEC", Some('p'))
             .multiple("--target", "TRIPLE", None)
+            .multiple("--bin", "NAME", None)
+            .flag("--bins", None)
             .flag("--release", Some('r'))
             .flag("--frozen", None)
             .flag("--locked", None)
@@ -401,7 +430,10 @@ impl CargoArguments {
             .flag("--all", None)
             .flag("--workspace", None)
             .counting("--verbose", Some('v'))
-            .flag("--quiet", Some('q'));
+            .flag("--quiet", Some('q'))
+            .flag("--force", None)
+            .multiple("--emit", "TYPES", None)
+            .multiple("--message-format", "FMT", None);
 
         let mut iter = iter.map(Into::into).peekable();
 
@@ -452,10 +484,38 @@ impl CargoArguments {
                 .into_iter()
                 .map(CargoPackageSpec::new)
                 .collect::<Result<_>>()?,
+            bins: args.get_mut("--bin").unwrap().take_multiple(),
+            all_bins: args.get("--bins").unwrap().count() > 0,
+            force: args.get("--force").unwrap().count() > 0,
+            emit: args
+                .get_mut("--emit")
+                .unwrap()
+                .take_multiple()
+                .iter()
+                .flat_map(|types| types.split(','))
+                .map(parse_output_type)
+                .collect::<Result<_>>()?,
+            message_formats: args
+                .get_mut("--message-format")
+                .unwrap()
+                .take_multiple()
+                .iter()
+                .flat_map(|formats| formats.split(','))
+                .map(String::from)
+                .collect(),
         })
     }
 }
 
+fn parse_output_type(s: &str) -> Result<OutputType> {
+    s.parse().map_err(|_| {
+        anyhow!(
+            "invalid output type `{s}` - expected one of: {display}",
+            display = OutputType::shorthand_display()
+        )
+    })
+}
+
 /// Configuration information for cargo-miden.
 ///
 /// This is used to configure the behavior of cargo-miden.
@@ -752,6 +812,11 @@ mod test {
                 offline: false,
                 workspace: true,
                 packages: Vec::new(),
+                bins: Vec::new(),
+                all_bins: false,
+                force: false,
+                emit: Vec::new(),
+                message_formats: Vec::new(),
             }
         );
 
@@ -775,6 +840,17 @@ mod test {
                 "--locked",
                 "--offline",
                 "--all",
+                "--bin",
+                "bin1",
+                "--bin=bin2",
+                "--bins",
+                "--force",
+                "--emit",
+                "masm,wat",
+                "--emit=hir",
+                "--message-format",
+                "short",
+                "--message-format=json,json-diagnostic-short",
                 "--not-an-option",
             ]
             .into_iter(),
@@ -803,6 +879,15 @@ mod test {
                         version: Some(Version::parse("1.1.1").unwrap())
                     }
                 ],
+                bins: vec!["bin1".to_string(), "bin2".to_string()],
+                all_bins: true,
+                force: true,
+                emit: vec![OutputType::Masm, OutputType::Wat, OutputType::Hir],
+                message_formats: vec![
+                    "short".to_string(),
+                    "json".to_string(),
+                    "json-diagnostic-short".to_string()
+                ],
             }
         );
     }
@@ -0,0 +1,85 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use midenc_session::{OutputType, TargetEnv};
+use sha2::{Digest, Sha256};
+
+/// A fingerprint of everything which determines the output of compiling a Wasm module to MASM.
+///
+/// This consists of the SHA-256 of the Wasm module contents, the version of the compiler, and the
+/// options used for compilation. The fingerprint is stored next to the first of the outputs, so
+/// that subsequent builds can skip recompiling a module when none of its inputs have changed.
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    wasm_hash: String,
+    compiler_version: &'static str,
+    is_bin: bool,
+    target: TargetEnv,
+    output_types: Vec<OutputType>,
+}
+impl Fingerprint {
+    /// Compute the fingerprint for compiling `wasm_file_path` with the given options
+    pub fn new(
+        wasm_file_path: &Path,
+        is_bin: bool,
+        target: TargetEnv,
+        output_types: &[OutputType],
+    ) -> anyhow::Result<Self> {
+        let wasm = std::fs::read(wasm_file_path).with_context(|| {
+            format!(
+                "Failed to read Wasm file '{}' for fingerprinting",
+                wasm_file_path.display()
+            )
+        })?;
+        // The hash is stored across builds, so it must not depend on the toolchain, which rules
+        // out the hasher of the standard library
+        Ok(Self {
+            wasm_hash: format!("{:x}", Sha256::digest(&wasm)),
+            compiler_version: crate::version(),
+            is_bin,
+            target,
+            output_types: output_types.to_vec(),
+        })
+    }
+
+    /// Returns true if all of `outputs` exist, and were produced from inputs with this fingerprint
+    pub fn is_fresh(&self, outputs: &[PathBuf]) -> bool {
+        if outputs.is_empty() || !outputs.iter().all(|output| output.exists()) {
+            return false;
+        }
+        match std::fs::read_to_string(Self::path_for(&outputs[0])) {
+            Ok(stored) => stored == self.to_string(),
+            Err(_) => false,
+        }
+    }
+
+    /// Store this fingerprint next to the first of `outputs`
+    pub fn write(&self, outputs: &[PathBuf]) -> anyhow::Result<()> {
+        let path = Self::path_for(&outputs[0]);
+        std::fs::write(&path, self.to_string())
+            .with_context(|| format!("Failed to write fingerprint '{}'", path.display()))
+    }
+
+    fn path_for(output: &Path) -> PathBuf {
+        output.with_extension("fingerprint")
+    }
+}
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "wasm = {}", self.wasm_hash)?;
+        writeln!(f, "compiler = {}", self.compiler_version)?;
+        writeln!(f, "bin = {}", self.is_bin)?;
+        writeln!(f, "target = {}", self.target)?;
+        write!(f, "output = ")?;
+        for (i, output_type) in self.output_types.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{output_type}")?;
+        }
+        writeln!(f)
+    }
+}
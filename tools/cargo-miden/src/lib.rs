@@ -7,12 +7,18 @@ use cargo_component_core::terminal::Terminal;
 use clap::{CommandFactory, Parser};
 use config::CargoArguments;
 use new_project::NewCommand;
+use wit_info::WitInfoCommand;
 
 mod build;
 pub mod config;
+mod fingerprint;
 mod new_project;
 mod run_cargo_command;
 mod target;
+mod wit_info;
+
+pub use self::build::FORCE_REBUILD_ENV;
+pub use self::wit_info::WitInfo;
 
 fn version() -> &'static str {
     option_env!("CARGO_VERSION_INFO").unwrap_or(env!("CARGO_PKG_VERSION"))
@@ -21,7 +27,7 @@ fn version() -> &'static str {
 /// The list of commands that are built-in to `cargo-miden`.
 const BUILTIN_COMMANDS: &[&str] = &[
     "miden", // for indirection via `cargo miden`
-    "new",
+    "new", "wit-info",
 ];
 
 const AFTER_HELP: &str = "Unrecognized subcommands will be passed to cargo verbatim 
@@ -50,6 +56,7 @@ enum CargoMiden {
 #[derive(Parser)]
 enum Command {
     New(NewCommand),
+    WitInfo(WitInfoCommand),
 }
 
 fn detect_subcommand<I, T>(args: I) -> Option<String>
@@ -93,6 +100,7 @@ where
             match CargoMiden::parse_from(args.clone()) {
                 CargoMiden::Miden(cmd) | CargoMiden::Command(cmd) => match cmd {
                     Command::New(cmd) => vec![cmd.exec()?],
+                    Command::WitInfo(cmd) => vec![cmd.exec()?],
                 },
             }
         }
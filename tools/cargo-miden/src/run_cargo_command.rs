@@ -1,14 +1,32 @@
 use anyhow::bail;
-use cargo_metadata::Metadata;
+use cargo_metadata::camino::Utf8PathBuf;
+use cargo_metadata::{Message, Metadata, PackageId};
+use midenc_session::OutputType;
+use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
-use crate::build::build_masm;
+use crate::build::build_masm_if_changed;
 use crate::config::CargoArguments;
-use crate::target::{install_wasm32_wasi, WASM32_WASI_TARGET};
+use crate::target::{install_wasm_target, is_wasm_target, WASM32_WASI_TARGET};
 
-fn is_wasm_target(target: &str) -> bool {
-    target == WASM32_WASI_TARGET
+/// Returns the Wasm targets built by a build command given `cargo_args`, the artifacts of which
+/// are compiled to MASM.
+///
+/// The targets given with `--target` are passed through to cargo as-is, and only if there are
+/// none, the implicit `wasm32-wasi` target is built, in which case the second element returned
+/// is true, as it needs to be passed to cargo.
+fn wasm_targets(cargo_args: &CargoArguments) -> (Vec<&str>, bool) {
+    if cargo_args.targets.is_empty() {
+        return (vec![WASM32_WASI_TARGET], true);
+    }
+    let targets = cargo_args
+        .targets
+        .iter()
+        .map(String::as_str)
+        .filter(|t| is_wasm_target(t))
+        .collect();
+    (targets, false)
 }
 
 /// Runs the cargo command as specified in the configuration.
@@ -39,89 +57,386 @@ pub fn run_cargo_command(
         args = args.clone().collect::<Vec<_>>(),
     );
 
+    let is_build = matches!(subcommand, Some("b") | Some("build"));
+    // `--force` and `--emit` are handled by us, cargo does not understand them, while the
+    // `--message-format` of a build is merged with the one we need, see [message_format]
+    let mut handled_options = vec!["--emit"];
+    if is_build {
+        handled_options.push("--message-format");
+    }
     let mut cmd = Command::new(&cargo);
-    cmd.args(args);
+    let mut seen_separator = false;
+    let mut is_option_value = false;
+    cmd.args(args.filter(|arg| {
+        seen_separator |= *arg == "--";
+        if seen_separator {
+            return true;
+        }
+        // The value of an option may be given as the following argument
+        if std::mem::take(&mut is_option_value) {
+            return false;
+        }
+        is_option_value = handled_options.contains(&arg.as_str());
+        let has_inline_value = handled_options.iter().any(|option| {
+            arg.strip_prefix(option)
+                .is_some_and(|rest| rest.starts_with('='))
+        });
+        !is_option_value && !has_inline_value && *arg != "--force"
+    }));
 
-    let is_build = matches!(subcommand, Some("b") | Some("build"));
+    if !is_build {
+        match cmd.status() {
+            Ok(status) => {
+                if !status.success() {
+                    bail!("cargo failed with exit code {}", status.code().unwrap_or(1));
+                }
+            }
+            Err(e) => {
+                bail!("failed to spawn `{cargo}`: {e}", cargo = cargo.display());
+            }
+        }
+        return Ok(Vec::new());
+    }
 
     // Handle the target for build commands
-    if is_build {
-        install_wasm32_wasi()?;
+    let (targets, is_implicit_target) = wasm_targets(cargo_args);
+    for target in targets.iter().copied() {
+        install_wasm_target(target)?;
+    }
+    if is_implicit_target {
+        cmd.arg("--target").arg(WASM32_WASI_TARGET);
+    } else if targets.is_empty() {
+        log::warn!(
+            "none of the targets {:?} is a Wasm target, so nothing will be compiled to MASM",
+            cargo_args.targets
+        );
+    }
 
-        // Add an implicit wasm32-wasi target if there isn't a wasm target present
-        if !cargo_args.targets.iter().any(|t| is_wasm_target(t)) {
-            cmd.arg("--target").arg(WASM32_WASI_TARGET);
-        }
+    // The artifacts of the build are reported by cargo on stdout
+    let (message_format, echo_messages) = message_format(&cargo_args.message_formats);
+    cmd.arg(format!("--message-format={message_format}"))
+        .stdout(Stdio::piped());
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => bail!("failed to spawn `{cargo}`: {e}", cargo = cargo.display()),
+    };
+    let reader = BufReader::new(child.stdout.take().unwrap());
+
+    // When packages are selected with `--package`, cargo only builds those, but the artifacts of
+    // their dependencies are reported as well, so only those of the selected packages are
+    // compiled to MASM
+    let packages = metadata
+        .packages
+        .iter()
+        .filter(|package| metadata.workspace_members.contains(&package.id))
+        .filter(|package| {
+            cargo_args.workspace
+                || cargo_args.packages.is_empty()
+                || cargo_args.packages.iter().any(|spec| spec.matches(package))
+        })
+        .map(|package| &package.id)
+        .collect::<Vec<_>>();
+    let artifacts = wasm_artifacts(reader, &packages, echo_messages);
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("cargo failed with exit code {}", status.code().unwrap_or(1));
+    }
+    let artifacts = artifacts?;
+    if artifacts.is_empty() && !targets.is_empty() {
+        log::warn!("cargo did not produce any WebAssembly module to compile to MASM");
     }
 
-    match cmd.status() {
-        Ok(status) => {
-            if !status.success() {
-                bail!("cargo failed with exit code {}", status.code().unwrap_or(1));
-            }
-        }
-        Err(e) => {
-            bail!("failed to spawn `{cargo}`: {e}", cargo = cargo.display());
-        }
+    let output_types = if cargo_args.emit.is_empty() {
+        &[OutputType::Masm][..]
+    } else {
+        &cargo_args.emit[..]
+    };
+    let miden_out_dir = metadata
+        .target_directory
+        .join("miden")
+        .join(if cargo_args.release {
+            "release"
+        } else {
+            "debug"
+        });
+    if !miden_out_dir.exists() {
+        std::fs::create_dir_all(&miden_out_dir)?;
     }
+
     let mut outputs = Vec::new();
-    if is_build {
-        log::debug!("searching for WebAssembly modules to compile to MASM");
-        let targets = cargo_args
-            .targets
-            .iter()
-            .map(String::as_str)
-            .filter(|t| is_wasm_target(t))
-            .chain(cargo_args.targets.is_empty().then_some(WASM32_WASI_TARGET));
-
-        for target in targets {
-            let out_dir = metadata
-                .target_directory
-                .join(target)
-                .join(if cargo_args.release {
-                    "release"
-                } else {
-                    "debug"
-                });
-
-            let miden_out_dir =
-                metadata
-                    .target_directory
-                    .join("miden")
-                    .join(if cargo_args.release {
-                        "release"
-                    } else {
-                        "debug"
-                    });
-            if !miden_out_dir.exists() {
-                std::fs::create_dir_all(&miden_out_dir)?;
-            }
+    for artifact in artifacts {
+        log::debug!("compiling `{}` to MASM", artifact.path);
+        outputs.extend(build_masm_if_changed(
+            artifact.path.as_std_path(),
+            miden_out_dir.as_std_path(),
+            artifact.is_bin,
+            output_types,
+            cargo_args.force,
+        )?);
+    }
 
-            for package in &metadata.packages {
-                let is_bin = package.targets.iter().any(|t| t.is_bin());
-
-                // First try for <name>.wasm
-                let path = out_dir.join(&package.name).with_extension("wasm");
-                if path.exists() {
-                    let output =
-                        build_masm(path.as_std_path(), miden_out_dir.as_std_path(), is_bin)?;
-                    outputs.push(output);
-                } else {
-                    let path = out_dir
-                        .join(package.name.replace('-', "_"))
-                        .with_extension("wasm");
-                    if path.exists() {
-                        let output =
-                            build_masm(path.as_std_path(), miden_out_dir.as_std_path(), is_bin)?;
-                        outputs.push(output);
-                    } else {
-                        log::debug!("no output found for package `{name}`", name = package.name);
-                        bail!("Cargo build failed, no Wasm artifact found");
-                    }
-                }
+    Ok(outputs)
+}
+
+/// Returns the value of `--message-format` given to cargo for a build, for the `formats` given
+/// by the user, along with whether the JSON messages of cargo are written to stdout.
+///
+/// The artifacts of a build are read from the JSON messages of cargo, so these are always
+/// requested. If the user asked for JSON messages too, their formats are passed through as-is,
+/// and the messages are written to stdout as cargo would. Otherwise, cargo still renders the
+/// diagnostics to stderr, in the short format if `short` is given.
+fn message_format(formats: &[String]) -> (String, bool) {
+    if formats.iter().any(|format| format.starts_with("json")) {
+        return (formats.join(","), true);
+    }
+    let mut message_format = "json-render-diagnostics".to_string();
+    if formats.iter().any(|format| format == "short") {
+        message_format.push_str(",json-diagnostic-short");
+    }
+    (message_format, false)
+}
+
+/// A WebAssembly module produced by cargo
+#[derive(Debug, PartialEq, Eq)]
+struct WasmArtifact {
+    /// The path of the module
+    path: Utf8PathBuf,
+    /// Whether the module was built from a binary target, i.e. is compiled to a program, rather
+    /// than a library
+    is_bin: bool,
+}
+
+/// The kinds of the targets whose artifacts are never compiled to MASM, as they are built for the
+/// host, rather than for Wasm
+const HOST_TARGET_KINDS: &[&str] = &["custom-build", "proc-macro"];
+
+/// Returns the WebAssembly modules produced for the targets of `packages`, among the messages
+/// written by cargo to `reader` with `--message-format=json`.
+///
+/// Every target of a package is compiled to a module of its own, e.g. each binary or example,
+/// and a library built as a `cdylib`, the artifact of which is named after the library target,
+/// rather than the package. The messages which are not JSON are written to stdout as-is, as are
+/// the JSON messages if `echo_messages` is set.
+fn wasm_artifacts(
+    reader: impl BufRead,
+    packages: &[&PackageId],
+    echo_messages: bool,
+) -> anyhow::Result<Vec<WasmArtifact>> {
+    let mut artifacts = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let Some(message) = Message::parse_stream(line.as_bytes()).next() else {
+            continue;
+        };
+        let message = message?;
+        if echo_messages && !matches!(message, Message::TextLine(_)) {
+            println!("{line}");
+        }
+        let artifact = match message {
+            Message::CompilerArtifact(artifact) => artifact,
+            Message::TextLine(line) => {
+                println!("{line}");
+                continue;
             }
+            _ => continue,
+        };
+        if !packages.contains(&&artifact.package_id)
+            || artifact
+                .target
+                .kind
+                .iter()
+                .any(|kind| HOST_TARGET_KINDS.contains(&kind.as_str()))
+        {
+            continue;
         }
+        let is_bin = artifact.target.crate_types.iter().any(|ty| ty == "bin");
+        artifacts.extend(
+            artifact
+                .filenames
+                .into_iter()
+                .filter(|path| path.extension() == Some("wasm"))
+                .map(|path| WasmArtifact { path, is_bin }),
+        );
+    }
+    Ok(artifacts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::target::WASM32_UNKNOWN_TARGET;
+
+    fn targets_of(args: &[&str]) -> (Vec<String>, bool) {
+        let cargo_args =
+            CargoArguments::parse_from(["miden", "build"].iter().chain(args).copied()).unwrap();
+        let (targets, is_implicit_target) = wasm_targets(&cargo_args);
+        (
+            targets.into_iter().map(String::from).collect(),
+            is_implicit_target,
+        )
     }
 
-    Ok(outputs)
+    /// A `compiler-artifact` message for the target `name` of kind `kind` of `package`
+    fn artifact_message(package: &str, name: &str, kind: &str, filenames: &[&str]) -> String {
+        let crate_type = if kind == "custom-build" { "bin" } else { kind };
+        let filenames = filenames
+            .iter()
+            .map(|filename| format!("\"/ws/target/{filename}\""))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            concat!(
+                r#"{{"reason":"compiler-artifact","#,
+                r#""package_id":"{package} 0.1.0 (path+file:///ws/{package})","#,
+                r#""manifest_path":"/ws/{package}/Cargo.toml","#,
+                r#""target":{{"kind":["{kind}"],"crate_types":["{crate_type}"],"name":"{name}","#,
+                r#""src_path":"/ws/{package}/src/{name}.rs","edition":"2021","doc":true,"#,
+                r#""doctest":false,"test":true}},"#,
+                r#""profile":{{"opt_level":"3","debuginfo":0,"debug_assertions":false,"#,
+                r#""overflow_checks":false,"test":false}},"#,
+                r#""features":[],"filenames":[{filenames}],"executable":null,"fresh":false}}"#,
+            ),
+            package = package,
+            kind = kind,
+            crate_type = crate_type,
+            name = name,
+            filenames = filenames,
+        )
+    }
+
+    #[test]
+    fn wasm_artifacts_of_selected_packages_are_found() {
+        let messages = [
+            artifact_message("dep", "dep", "cdylib", &["wasm32-wasi/release/dep.wasm"]),
+            artifact_message(
+                "foo",
+                "build-script-build",
+                "custom-build",
+                &["build/foo.wasm"],
+            ),
+            artifact_message("foo", "add", "bin", &["wasm32-wasi/release/add.wasm"]),
+            "Compiling foo v0.1.0".to_string(),
+            artifact_message("foo", "sub", "bin", &["wasm32-wasi/release/sub.wasm"]),
+            artifact_message(
+                "bar",
+                "bar",
+                "cdylib",
+                &[
+                    "wasm32-wasi/release/libbar.rlib",
+                    "wasm32-wasi/release/bar.wasm",
+                ],
+            ),
+            r#"{"reason":"build-finished","success":true}"#.to_string(),
+        ]
+        .join("\n");
+        let foo = PackageId {
+            repr: "foo 0.1.0 (path+file:///ws/foo)".to_string(),
+        };
+        let bar = PackageId {
+            repr: "bar 0.1.0 (path+file:///ws/bar)".to_string(),
+        };
+        let artifacts = wasm_artifacts(messages.as_bytes(), &[&foo, &bar], false).unwrap();
+        let artifact = |path: &str, is_bin| WasmArtifact {
+            path: Utf8PathBuf::from(format!("/ws/target/wasm32-wasi/release/{path}")),
+            is_bin,
+        };
+        assert_eq!(
+            artifacts,
+            [
+                artifact("add.wasm", true),
+                artifact("sub.wasm", true),
+                artifact("bar.wasm", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn wasm_artifacts_are_found_with_normalized_names() {
+        // The library target of `my-lib` is named `my_lib`, as cargo does for a package with
+        // dashes in its name, or as `lib.name` may set it to, while binaries keep their dashes
+        let messages = [
+            artifact_message(
+                "my-lib",
+                "my_lib",
+                "cdylib",
+                &["wasm32-wasi/release/my_lib.wasm"],
+            ),
+            artifact_message(
+                "my-lib",
+                "my-bin",
+                "bin",
+                &["wasm32-wasi/release/my-bin.wasm"],
+            ),
+        ]
+        .join("\n");
+        let my_lib = PackageId {
+            repr: "my-lib 0.1.0 (path+file:///ws/my-lib)".to_string(),
+        };
+        let artifacts = wasm_artifacts(messages.as_bytes(), &[&my_lib], false).unwrap();
+        let artifact = |path: &str, is_bin| WasmArtifact {
+            path: Utf8PathBuf::from(format!("/ws/target/wasm32-wasi/release/{path}")),
+            is_bin,
+        };
+        assert_eq!(
+            artifacts,
+            [
+                artifact("my_lib.wasm", false),
+                artifact("my-bin.wasm", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn message_format_is_merged_with_the_user_formats() {
+        let message_format_of = |formats: &[&str]| {
+            message_format(&formats.iter().map(|f| f.to_string()).collect::<Vec<_>>())
+        };
+        assert_eq!(
+            message_format_of(&[]),
+            ("json-render-diagnostics".to_string(), false)
+        );
+        assert_eq!(
+            message_format_of(&["human"]),
+            ("json-render-diagnostics".to_string(), false)
+        );
+        assert_eq!(
+            message_format_of(&["short"]),
+            (
+                "json-render-diagnostics,json-diagnostic-short".to_string(),
+                false
+            )
+        );
+        // JSON messages asked for by the user are passed through, and written to stdout
+        assert_eq!(
+            message_format_of(&["json", "json-diagnostic-short"]),
+            ("json,json-diagnostic-short".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn implicit_target_is_only_used_without_targets() {
+        assert_eq!(
+            targets_of(&[]),
+            (vec![WASM32_WASI_TARGET.to_string()], true)
+        );
+        assert_eq!(
+            targets_of(&["--target", WASM32_UNKNOWN_TARGET]),
+            (vec![WASM32_UNKNOWN_TARGET.to_string()], false)
+        );
+        assert_eq!(
+            targets_of(&["--target=wasm32-wasi", "--target", "wasm32-unknown-unknown"]),
+            (
+                vec![
+                    WASM32_WASI_TARGET.to_string(),
+                    WASM32_UNKNOWN_TARGET.to_string()
+                ],
+                false
+            )
+        );
+        // Other targets are passed through to cargo, but there is nothing to compile to MASM
+        assert_eq!(
+            targets_of(&["--target", "x86_64-unknown-linux-gnu"]),
+            (vec![], false)
+        );
+    }
 }
@@ -6,33 +6,40 @@ use std::{
 };
 
 pub const WASM32_WASI_TARGET: &str = "wasm32-wasi";
+pub const WASM32_UNKNOWN_TARGET: &str = "wasm32-unknown-unknown";
 
-pub fn install_wasm32_wasi() -> Result<()> {
-    log::info!("Installing {WASM32_WASI_TARGET} target");
+/// Returns true if `target` is a Wasm target, the artifacts of which are compiled to MASM
+pub fn is_wasm_target(target: &str) -> bool {
+    matches!(target, WASM32_WASI_TARGET | WASM32_UNKNOWN_TARGET)
+}
+
+/// Install the Rust standard library for `target`, unless it is installed already
+pub fn install_wasm_target(target: &str) -> Result<()> {
+    log::info!("Installing {target} target");
     let sysroot = get_sysroot()?;
-    if sysroot.join("lib/rustlib/wasm32-wasi").exists() {
+    if sysroot.join("lib/rustlib").join(target).exists() {
         return Ok(());
     }
 
     if env::var_os("RUSTUP_TOOLCHAIN").is_none() {
         bail!(
-            "failed to find the `wasm32-wasi` target \
+            "failed to find the `{target}` target \
              and `rustup` is not available. If you're using rustup \
              make sure that it's correctly installed; if not, make sure to \
-             install the `wasm32-wasi` target before using this command"
+             install the `{target}` target before using this command"
         );
     }
 
     let output = Command::new("rustup")
         .arg("target")
         .arg("add")
-        .arg(WASM32_WASI_TARGET)
+        .arg(target)
         .stderr(Stdio::inherit())
         .stdout(Stdio::inherit())
         .output()?;
 
     if !output.status.success() {
-        bail!("failed to install the `wasm32-wasi` target");
+        bail!("failed to install the `{target}` target");
     }
 
     Ok(())
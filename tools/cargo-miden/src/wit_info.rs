@@ -0,0 +1,97 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use clap::Args;
+use wit_component::DecodedWasm;
+use wit_parser::{Resolve, WorldItem, WorldKey};
+
+/// Print the WIT imports and exports of the Wasm component at <path>
+#[derive(Args)]
+#[clap(disable_version_flag = true)]
+pub struct WitInfoCommand {
+    /// The path to the Wasm component to inspect.
+    #[clap(value_name = "path")]
+    pub path: PathBuf,
+}
+
+impl WitInfoCommand {
+    pub fn exec(self) -> anyhow::Result<PathBuf> {
+        let info = WitInfo::from_file(&self.path)?;
+        print!("{info}");
+        Ok(self.path)
+    }
+}
+
+/// The WIT world of a Wasm component, as decoded from the component type information embedded
+/// in it by `wit-bindgen`
+#[derive(Debug, Clone)]
+pub struct WitInfo {
+    /// The name of the world
+    pub world: String,
+    /// The items imported by the component, e.g. `miden:base/tx@1.0.0`
+    pub imports: Vec<String>,
+    /// The items exported by the component, e.g. `miden:base/note-script@1.0.0`
+    pub exports: Vec<String>,
+}
+
+impl WitInfo {
+    /// Decode the WIT world of the Wasm component at `path`
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let wasm = std::fs::read(path)
+            .with_context(|| format!("Failed to read Wasm component '{}'", path.display()))?;
+        Self::from_component(&wasm)
+            .with_context(|| format!("Failed to decode WIT from '{}'", path.display()))
+    }
+
+    /// Decode the WIT world of the given Wasm component binary
+    pub fn from_component(wasm: &[u8]) -> anyhow::Result<Self> {
+        let (resolve, world_id) = match wit_component::decode(wasm)? {
+            DecodedWasm::Component(resolve, world_id) => (resolve, world_id),
+            DecodedWasm::WitPackage(..) => {
+                bail!("expected a Wasm component, but got an encoded WIT package")
+            }
+        };
+        let world = &resolve.worlds[world_id];
+        let name = match world.package {
+            Some(package) => format!("{}/{}", resolve.packages[package].name, world.name),
+            None => world.name.clone(),
+        };
+        Ok(Self {
+            world: name,
+            imports: world
+                .imports
+                .iter()
+                .map(|(key, item)| describe_item(&resolve, key, item))
+                .collect(),
+            exports: world
+                .exports
+                .iter()
+                .map(|(key, item)| describe_item(&resolve, key, item))
+                .collect(),
+        })
+    }
+}
+
+impl fmt::Display for WitInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "world {}", self.world)?;
+        for import in self.imports.iter() {
+            writeln!(f, "  import {import}")?;
+        }
+        for export in self.exports.iter() {
+            writeln!(f, "  export {export}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Describe a world item, using the fully-qualified (and versioned) name for interfaces
+fn describe_item(resolve: &Resolve, key: &WorldKey, item: &WorldItem) -> String {
+    let name = resolve.name_world_key(key);
+    match item {
+        WorldItem::Interface(_) => name,
+        WorldItem::Function(_) => format!("func {name}"),
+        WorldItem::Type(_) => format!("type {name}"),
+    }
+}
@@ -1,7 +1,8 @@
 use cargo_component_core::terminal;
-use cargo_miden::run;
+use cargo_miden::{run, FORCE_REBUILD_ENV};
 use std::env;
 use std::fs;
+use std::process::Command;
 
 // NOTE: This test sets the current working directory so don't run it in parallel with tests
 // that depend on the current directory
@@ -40,3 +41,379 @@ fn build_new_project_from_template() {
     env::set_current_dir(restore_dir).unwrap();
     fs::remove_dir_all(new_project_path).unwrap();
 }
+
+#[test]
+fn build_skips_unchanged_wasm() {
+    let project_name = "test-proj-incremental";
+    let project_dir = env::temp_dir().join(project_name);
+    if project_dir.exists() {
+        fs::remove_dir_all(&project_dir).unwrap();
+    }
+    let terminal = terminal::Terminal::new(terminal::Verbosity::Verbose, terminal::Color::Auto);
+    let args = ["cargo", "miden", "new", project_dir.to_str().unwrap()]
+        .into_iter()
+        .map(|s| s.to_string());
+    run(args, &terminal).expect("Failed to create new project");
+    let manifest_path = project_dir.join("Cargo.toml");
+    let build = |extra_args: &[&str]| {
+        let args = [
+            "cargo",
+            "miden",
+            "build",
+            "--release",
+            "--manifest-path",
+            manifest_path.to_str().unwrap(),
+        ]
+        .iter()
+        .chain(extra_args)
+        .map(|s| s.to_string());
+        let outputs = run(args, &terminal).expect("Failed to compile");
+        outputs.first().unwrap().clone()
+    };
+    let modified = |path: &std::path::Path| path.metadata().unwrap().modified().unwrap();
+
+    let masm_path = build(&[]);
+    let first_build = modified(&masm_path);
+
+    // Nothing changed, so the MASM output should not have been rewritten
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    assert_eq!(build(&[]), masm_path);
+    assert_eq!(modified(&masm_path), first_build);
+
+    // Unless we ask for it explicitly
+    assert_eq!(build(&["--force"]), masm_path);
+    let forced_build = modified(&masm_path);
+    assert!(forced_build > first_build);
+
+    // Or through the environment, which is only set for a build in its own process, so as not
+    // to leak into the other tests
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    let status = Command::new(env!("CARGO_BIN_EXE_cargo-miden"))
+        .args(["miden", "build", "--release", "--manifest-path"])
+        .arg(&manifest_path)
+        .env(FORCE_REBUILD_ENV, "1")
+        .status()
+        .expect("Failed to run cargo-miden");
+    assert!(status.success());
+    assert!(modified(&masm_path) > forced_build);
+
+    fs::remove_dir_all(project_dir).unwrap();
+}
+
+#[test]
+fn build_multiple_bins() {
+    let project_dir = env::temp_dir().join("test-proj-multiple-bins");
+    if project_dir.exists() {
+        fs::remove_dir_all(&project_dir).unwrap();
+    }
+    fs::create_dir_all(project_dir.join("src").join("bin")).unwrap();
+    fs::create_dir_all(project_dir.join(".cargo")).unwrap();
+    fs::write(
+        project_dir.join("Cargo.toml"),
+        r#"
+        [package]
+        name = "test-proj-multiple-bins"
+        version = "0.1.0"
+        edition = "2021"
+
+        [profile.release]
+        panic = "abort"
+        "#,
+    )
+    .unwrap();
+    // The binaries have no `main`, so they must not be linked against the WASI command runtime,
+    // and their entrypoint must be exported explicitly
+    fs::write(
+        project_dir.join(".cargo").join("config.toml"),
+        r#"
+        [target.wasm32-wasi]
+        rustflags = [
+            "-C", "link-self-contained=no",
+            "-C", "link-arg=--no-entry",
+            "-C", "link-arg=--export=entrypoint",
+        ]
+        "#,
+    )
+    .unwrap();
+    for (bin, op) in [("add", "+"), ("sub", "-")] {
+        fs::write(
+            project_dir
+                .join("src")
+                .join("bin")
+                .join(format!("{bin}.rs")),
+            format!(
+                r#"
+                #![no_std]
+                #![no_main]
+
+                #[panic_handler]
+                fn my_panic(_info: &core::panic::PanicInfo) -> ! {{
+                    loop {{}}
+                }}
+
+                #[no_mangle]
+                pub extern "C" fn entrypoint(a: u32, b: u32) -> u32 {{
+                    a {op} b
+                }}
+                "#
+            ),
+        )
+        .unwrap();
+    }
+
+    // Cargo only picks up `.cargo/config.toml` from the current directory
+    let restore_dir = env::current_dir().unwrap();
+    env::set_current_dir(&project_dir).unwrap();
+    let terminal = terminal::Terminal::new(terminal::Verbosity::Verbose, terminal::Color::Auto);
+    let args = ["cargo", "miden", "build", "--release", "--bins"]
+        .iter()
+        .map(|s| s.to_string());
+    let outputs = run(args, &terminal);
+    env::set_current_dir(restore_dir).unwrap();
+
+    let mut outputs = outputs.expect("Failed to compile");
+    outputs.sort();
+    let names = outputs
+        .iter()
+        .map(|path| path.file_name().unwrap().to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["add.masm", "sub.masm"]);
+    for output in outputs.iter() {
+        assert!(output.exists());
+        assert!(output.metadata().unwrap().len() > 0);
+    }
+    fs::remove_dir_all(project_dir).unwrap();
+}
+
+#[test]
+fn build_workspace_with_multiple_bins() {
+    let project_dir = env::temp_dir().join("test-proj-workspace-multiple-bins");
+    if project_dir.exists() {
+        fs::remove_dir_all(&project_dir).unwrap();
+    }
+    fs::create_dir_all(project_dir.join(".cargo")).unwrap();
+    fs::write(
+        project_dir.join("Cargo.toml"),
+        r#"
+        [workspace]
+        members = ["calc", "bar"]
+        resolver = "2"
+
+        [profile.release]
+        panic = "abort"
+        "#,
+    )
+    .unwrap();
+    fs::write(
+        project_dir.join(".cargo").join("config.toml"),
+        r#"
+        [target.wasm32-wasi]
+        rustflags = [
+            "-C", "link-self-contained=no",
+            "-C", "link-arg=--no-entry",
+            "-C", "link-arg=--export=entrypoint",
+        ]
+        "#,
+    )
+    .unwrap();
+    // The binaries have no `main`, which libraries do not need in the first place
+    let source = |op: &str, is_bin: bool| {
+        let no_main = if is_bin { "#![no_main]" } else { "" };
+        format!(
+            r#"
+            #![no_std]
+            {no_main}
+
+            #[panic_handler]
+            fn my_panic(_info: &core::panic::PanicInfo) -> ! {{
+                loop {{}}
+            }}
+
+            #[no_mangle]
+            pub extern "C" fn entrypoint(a: u32, b: u32) -> u32 {{
+                a {op} b
+            }}
+            "#
+        )
+    };
+    // The `calc` package has two binaries, and `bar` a library built as a `cdylib`
+    let calc_dir = project_dir.join("calc");
+    fs::create_dir_all(calc_dir.join("src").join("bin")).unwrap();
+    fs::write(
+        calc_dir.join("Cargo.toml"),
+        r#"
+        [package]
+        name = "calc"
+        version = "0.1.0"
+        edition = "2021"
+        "#,
+    )
+    .unwrap();
+    for (bin, op) in [("add", "+"), ("sub", "-")] {
+        fs::write(
+            calc_dir.join("src").join("bin").join(format!("{bin}.rs")),
+            source(op, true),
+        )
+        .unwrap();
+    }
+    let bar_dir = project_dir.join("bar");
+    fs::create_dir_all(bar_dir.join("src")).unwrap();
+    fs::write(
+        bar_dir.join("Cargo.toml"),
+        r#"
+        [package]
+        name = "bar"
+        version = "0.1.0"
+        edition = "2021"
+
+        [lib]
+        crate-type = ["cdylib"]
+        "#,
+    )
+    .unwrap();
+    fs::write(bar_dir.join("src").join("lib.rs"), source("*", false)).unwrap();
+
+    // Cargo only picks up `.cargo/config.toml` from the current directory
+    let restore_dir = env::current_dir().unwrap();
+    env::set_current_dir(&project_dir).unwrap();
+    let terminal = terminal::Terminal::new(terminal::Verbosity::Verbose, terminal::Color::Auto);
+    let args = ["cargo", "miden", "build", "--release", "--workspace"]
+        .iter()
+        .map(|s| s.to_string());
+    let outputs = run(args, &terminal);
+    env::set_current_dir(restore_dir).unwrap();
+
+    // Every Wasm artifact of the workspace is compiled, whichever package it belongs to
+    let mut outputs = outputs.expect("Failed to compile");
+    outputs.sort();
+    let names = outputs
+        .iter()
+        .map(|path| path.file_name().unwrap().to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["add.masm", "bar.masm", "sub.masm"]);
+    for output in outputs.iter() {
+        assert!(output.exists());
+        assert!(output.metadata().unwrap().len() > 0);
+    }
+    fs::remove_dir_all(project_dir).unwrap();
+}
+
+#[test]
+fn build_emits_selected_output_types() {
+    let project_dir = env::temp_dir().join("test-proj-emit");
+    if project_dir.exists() {
+        fs::remove_dir_all(&project_dir).unwrap();
+    }
+    let terminal = terminal::Terminal::new(terminal::Verbosity::Verbose, terminal::Color::Auto);
+    let args = ["cargo", "miden", "new", project_dir.to_str().unwrap()]
+        .into_iter()
+        .map(|s| s.to_string());
+    run(args, &terminal).expect("Failed to create new project");
+    let manifest_path = project_dir.join("Cargo.toml");
+    let args = [
+        "cargo",
+        "miden",
+        "build",
+        "--release",
+        "--manifest-path",
+        manifest_path.to_str().unwrap(),
+        "--emit",
+        "wat,masm",
+    ]
+    .iter()
+    .map(|s| s.to_string());
+    let outputs = run(args, &terminal).expect("Failed to compile");
+    let extensions = outputs
+        .iter()
+        .map(|path| path.extension().unwrap().to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(extensions, ["wat", "masm"]);
+    for output in outputs.iter() {
+        assert!(output.exists());
+        assert!(output.metadata().unwrap().len() > 0);
+    }
+    let wat = fs::read_to_string(&outputs[0]).unwrap();
+    assert!(wat.starts_with("(module"), "unexpected WAT output: {wat}");
+    fs::remove_dir_all(project_dir).unwrap();
+}
+
+#[test]
+fn build_selected_package_only() {
+    let project_dir = env::temp_dir().join("test-proj-workspace");
+    if project_dir.exists() {
+        fs::remove_dir_all(&project_dir).unwrap();
+    }
+    fs::create_dir_all(&project_dir).unwrap();
+    fs::write(
+        project_dir.join("Cargo.toml"),
+        r#"
+        [workspace]
+        members = ["foo", "bar"]
+        resolver = "2"
+
+        [profile.release]
+        panic = "abort"
+        "#,
+    )
+    .unwrap();
+    for name in ["foo", "bar"] {
+        let package_dir = project_dir.join(name);
+        fs::create_dir_all(package_dir.join("src")).unwrap();
+        fs::write(
+            package_dir.join("Cargo.toml"),
+            format!(
+                r#"
+                [package]
+                name = "{name}"
+                version = "0.1.0"
+                edition = "2021"
+
+                [lib]
+                crate-type = ["cdylib"]
+                "#
+            ),
+        )
+        .unwrap();
+        fs::write(
+            package_dir.join("src").join("lib.rs"),
+            r#"
+            #![no_std]
+
+            #[panic_handler]
+            fn my_panic(_info: &core::panic::PanicInfo) -> ! {
+                loop {}
+            }
+
+            #[no_mangle]
+            pub extern "C" fn entrypoint(a: u32, b: u32) -> u32 {
+                a + b
+            }
+            "#,
+        )
+        .unwrap();
+    }
+
+    let terminal = terminal::Terminal::new(terminal::Verbosity::Verbose, terminal::Color::Auto);
+    let manifest_path = project_dir.join("Cargo.toml");
+    let args = [
+        "cargo",
+        "miden",
+        "build",
+        "--release",
+        "--manifest-path",
+        manifest_path.to_str().unwrap(),
+        "-p",
+        "foo",
+    ]
+    .iter()
+    .map(|s| s.to_string());
+    let outputs = run(args, &terminal).expect("Failed to compile");
+    let names = outputs
+        .iter()
+        .map(|path| path.file_name().unwrap().to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["foo.masm"]);
+    assert!(outputs[0].exists());
+    assert!(!outputs[0].with_file_name("bar.masm").exists());
+    fs::remove_dir_all(project_dir).unwrap();
+}
@@ -1,2 +1,3 @@
 mod build;
 mod utils;
+mod wit_info;
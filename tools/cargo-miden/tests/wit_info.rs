@@ -0,0 +1,40 @@
+use cargo_component_core::terminal;
+use cargo_miden::{run, WitInfo};
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn wit_info_lists_component_exports() {
+    let project_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("../../tests/rust-apps-wasm/sdk/p2id-note");
+    let target_dir = env::temp_dir().join("p2id-note-wit-info");
+    let status = Command::new("cargo")
+        .arg("component")
+        .arg("build")
+        .arg("--release")
+        .arg("--manifest-path")
+        .arg(project_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("Failed to execute cargo component build");
+    assert!(status.success(), "Failed to build the p2id-note component");
+    let component = target_dir
+        .join("wasm32-wasi")
+        .join("release")
+        .join("basic_wallet_p2id_note.wasm");
+
+    let info = WitInfo::from_file(&component).expect("Failed to decode WIT");
+    assert!(info
+        .exports
+        .contains(&"miden:base/note-script@1.0.0".to_string()));
+    assert!(info.imports.contains(&"miden:base/tx@1.0.0".to_string()));
+
+    let args = ["cargo", "miden", "wit-info", component.to_str().unwrap()]
+        .into_iter()
+        .map(|s| s.to_string());
+    let terminal = terminal::Terminal::new(terminal::Verbosity::Verbose, terminal::Color::Auto);
+    let outputs = run(args, &terminal).expect("Failed to print WIT info");
+    assert_eq!(outputs, vec![component]);
+}